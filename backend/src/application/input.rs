@@ -0,0 +1,19 @@
+use serde::{Deserialize, Deserializer};
+
+/// Trimmed text, or nothing if it was left blank
+pub fn optional_text(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// For an update field that can be left out to keep what's saved or sent as
+/// null to clear it: left out is `None`, null is `Some(None)`. Use with
+/// `#[serde(default, deserialize_with = "patch")]`.
+pub fn patch<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -1,3 +1,5 @@
 // Application layer modules will be defined here
+pub mod input;
+pub mod scheduler;
 pub mod state;
 pub mod use_cases;
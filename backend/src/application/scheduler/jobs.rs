@@ -0,0 +1,170 @@
+use super::Job;
+use crate::application::use_cases::check_stock_consistency::CheckStockConsistencyUseCase;
+use crate::application::use_cases::expire_stale_orders::ExpireStaleOrdersUseCase;
+use crate::application::use_cases::low_stock::LowStockUseCase;
+use crate::application::use_cases::loyalty::LoyaltyUseCase;
+use crate::application::use_cases::send_maintenance_reminders::SendMaintenanceRemindersUseCase;
+use crate::infrastructure::db::repositories::refresh_token::RefreshTokenRepository;
+use async_trait::async_trait;
+use chrono::Utc;
+
+/// Removes refresh tokens that can no longer be used
+pub struct RefreshTokenCleanupJob {
+    refresh_token_repo: RefreshTokenRepository,
+}
+
+impl RefreshTokenCleanupJob {
+    pub fn new(refresh_token_repo: RefreshTokenRepository) -> Self {
+        Self { refresh_token_repo }
+    }
+}
+
+#[async_trait]
+impl Job for RefreshTokenCleanupJob {
+    fn name(&self) -> &'static str {
+        "refresh_token_cleanup"
+    }
+
+    async fn run(&self) -> Result<String, String> {
+        let deleted = self.refresh_token_repo.delete_expired(Utc::now()).await?;
+        Ok(format!("Deleted {} expired refresh tokens", deleted))
+    }
+}
+
+pub struct ExpireStaleOrdersJob {
+    use_case: ExpireStaleOrdersUseCase,
+}
+
+impl ExpireStaleOrdersJob {
+    pub fn new(use_case: ExpireStaleOrdersUseCase) -> Self {
+        Self { use_case }
+    }
+}
+
+#[async_trait]
+impl Job for ExpireStaleOrdersJob {
+    fn name(&self) -> &'static str {
+        "expire_stale_orders"
+    }
+
+    async fn run(&self) -> Result<String, String> {
+        let expired = self.use_case.execute().await?;
+        Ok(format!(
+            "Cancelled {} unanswered quotes and {} stale bookings",
+            expired.quotes, expired.bookings
+        ))
+    }
+}
+
+pub struct MaintenanceRemindersJob {
+    use_case: SendMaintenanceRemindersUseCase,
+}
+
+impl MaintenanceRemindersJob {
+    pub fn new(use_case: SendMaintenanceRemindersUseCase) -> Self {
+        Self { use_case }
+    }
+}
+
+#[async_trait]
+impl Job for MaintenanceRemindersJob {
+    fn name(&self) -> &'static str {
+        "maintenance_reminders"
+    }
+
+    async fn run(&self) -> Result<String, String> {
+        let sent = self.use_case.execute().await?;
+        Ok(format!("Sent {} maintenance reminders", sent))
+    }
+}
+
+/// Writes off loyalty points that have passed their expiry
+pub struct LoyaltyExpiryJob {
+    use_case: LoyaltyUseCase,
+}
+
+impl LoyaltyExpiryJob {
+    pub fn new(use_case: LoyaltyUseCase) -> Self {
+        Self { use_case }
+    }
+}
+
+#[async_trait]
+impl Job for LoyaltyExpiryJob {
+    fn name(&self) -> &'static str {
+        "loyalty_expiry"
+    }
+
+    async fn run(&self) -> Result<String, String> {
+        let expired = self.use_case.expire_points().await?;
+        Ok(format!("Expired {} loyalty points", expired))
+    }
+}
+
+/// Flags items whose quantity no longer matches their stock movements. Fails
+/// when it finds any, so the mismatch shows up on the jobs page.
+pub struct StockConsistencyJob {
+    use_case: CheckStockConsistencyUseCase,
+}
+
+impl StockConsistencyJob {
+    pub fn new(use_case: CheckStockConsistencyUseCase) -> Self {
+        Self { use_case }
+    }
+}
+
+#[async_trait]
+impl Job for StockConsistencyJob {
+    fn name(&self) -> &'static str {
+        "stock_consistency"
+    }
+
+    async fn run(&self) -> Result<String, String> {
+        let discrepancies = self.use_case.execute().await?;
+        if discrepancies.is_empty() {
+            return Ok("Stock quantities match the movement ledger".to_string());
+        }
+        let items: Vec<String> = discrepancies
+            .iter()
+            .map(|d| {
+                format!(
+                    "#{} {} (on record {}, ledger {})",
+                    d.item_id, d.name, d.quantity, d.ledger_quantity
+                )
+            })
+            .collect();
+        Err(format!(
+            "{} items disagree with the ledger: {}",
+            discrepancies.len(),
+            items.join(", ")
+        ))
+    }
+}
+
+/// Daily list of everything at or below its reorder point. Also sends any
+/// low-stock alerts that didn't go out when the stock changed.
+pub struct LowStockDigestJob {
+    use_case: LowStockUseCase,
+}
+
+impl LowStockDigestJob {
+    pub fn new(use_case: LowStockUseCase) -> Self {
+        Self { use_case }
+    }
+}
+
+#[async_trait]
+impl Job for LowStockDigestJob {
+    fn name(&self) -> &'static str {
+        "low_stock_digest"
+    }
+
+    async fn run(&self) -> Result<String, String> {
+        let alerts = self.use_case.dispatch_alerts().await?;
+        let low = self.use_case.send_digest().await?;
+        Ok(format!(
+            "{} items at or below their reorder point; sent {} pending alerts",
+            low, alerts
+        ))
+    }
+}
@@ -0,0 +1,118 @@
+pub mod jobs;
+
+use crate::infrastructure::db::repositories::scheduled_job::ScheduledJobRepository;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// How often each replica checks for due jobs
+const POLL_INTERVAL_SECS: u64 = 30;
+
+/// A unit of background work. The returned string is a short summary kept as
+/// the job's last message.
+#[async_trait]
+pub trait Job: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn run(&self) -> Result<String, String>;
+}
+
+struct ScheduledJob {
+    expression: String,
+    schedule: Schedule,
+    job: Arc<dyn Job>,
+}
+
+impl ScheduledJob {
+    fn next_after(&self, after: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+        self.schedule
+            .after(&after)
+            .next()
+            .ok_or_else(|| format!("Schedule for {} has no upcoming runs", self.job.name()))
+    }
+}
+
+/// Runs jobs on cron schedules (seconds field first, evaluated in UTC). Run
+/// state lives in the scheduled_jobs table so that every replica can run the
+/// scheduler and each due run still happens exactly once.
+pub struct JobScheduler {
+    job_repo: ScheduledJobRepository,
+    jobs: Vec<ScheduledJob>,
+}
+
+impl JobScheduler {
+    pub fn new(job_repo: ScheduledJobRepository) -> Self {
+        Self {
+            job_repo,
+            jobs: Vec::new(),
+        }
+    }
+
+    pub fn add(mut self, expression: &str, job: Arc<dyn Job>) -> Result<Self, String> {
+        let schedule = Schedule::from_str(expression)
+            .map_err(|e| format!("Invalid schedule for {}: {}", job.name(), e))?;
+        self.jobs.push(ScheduledJob {
+            expression: expression.to_string(),
+            schedule,
+            job,
+        });
+        Ok(self)
+    }
+
+    pub fn start(self) {
+        tokio::spawn(async move {
+            for entry in &self.jobs {
+                let registered = match entry.next_after(Utc::now()) {
+                    Ok(next) => {
+                        self.job_repo
+                            .register(entry.job.name(), &entry.expression, next)
+                            .await
+                    }
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = registered {
+                    tracing::error!("Failed to register job {}: {}", entry.job.name(), e);
+                }
+            }
+
+            let mut ticker =
+                tokio::time::interval(std::time::Duration::from_secs(POLL_INTERVAL_SECS));
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.run_due().await {
+                    tracing::error!("Job scheduler poll failed: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn run_due(&self) -> Result<(), String> {
+        let now = Utc::now();
+        let rows = self.job_repo.list().await?;
+
+        for entry in &self.jobs {
+            let name = entry.job.name();
+            let due = rows
+                .iter()
+                .any(|row| row.job_name == name && row.next_run_at <= now);
+            if !due {
+                continue;
+            }
+
+            let next = entry.next_after(now)?;
+            if !self.job_repo.try_claim(name, now, next).await? {
+                continue;
+            }
+
+            tracing::info!("Running job {}", name);
+            let result = entry.job.run().await;
+            match &result {
+                Ok(summary) => tracing::info!("Job {} finished: {}", name, summary),
+                Err(e) => tracing::error!("Job {} failed: {}", name, e),
+            }
+            self.job_repo.record_result(name, &result).await?;
+        }
+        Ok(())
+    }
+}
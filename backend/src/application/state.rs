@@ -1,31 +1,66 @@
 use crate::application::use_cases::add_service_item::AddServiceItemUseCase;
 use crate::application::use_cases::add_stock_item::AddStockItemUseCase;
+use crate::application::use_cases::branch::BranchUseCase;
+use crate::application::use_cases::check_stock_consistency::CheckStockConsistencyUseCase;
+use crate::application::use_cases::confirm_mfa::ConfirmMfaUseCase;
 use crate::application::use_cases::connect_line::ConnectLineUseCase;
+use crate::application::use_cases::create_guest_customer::CreateGuestCustomerUseCase;
 use crate::application::use_cases::create_service_order::CreateServiceOrderUseCase;
+use crate::application::use_cases::create_staff::CreateStaffUseCase;
 use crate::application::use_cases::delete_feedback::DeleteFeedbackUseCase;
+use crate::application::use_cases::delete_motorcycle::DeleteMotorcycleUseCase;
 use crate::application::use_cases::delete_service_order::DeleteServiceOrderUseCase;
 use crate::application::use_cases::delete_stock_item::DeleteStockItemUseCase;
+use crate::application::use_cases::disable_mfa::DisableMfaUseCase;
 use crate::application::use_cases::disconnect_line::DisconnectLineUseCase;
+use crate::application::use_cases::enroll_mfa::EnrollMfaUseCase;
+use crate::application::use_cases::export_personal_data::ExportPersonalDataUseCase;
 use crate::application::use_cases::get_dashboard_stats::GetDashboardStatsUseCase;
+use crate::application::use_cases::get_motorcycle_history::GetMotorcycleHistoryUseCase;
 use crate::application::use_cases::get_profile::GetProfileUseCase;
 use crate::application::use_cases::get_service_order_detail::GetServiceOrderDetailUseCase;
+use crate::application::use_cases::get_stock_item_by_barcode::GetStockItemByBarcodeUseCase;
+use crate::application::use_cases::list_audit_events::ListAuditEventsUseCase;
+use crate::application::use_cases::list_erasure_requests::ListErasureRequestsUseCase;
 use crate::application::use_cases::list_feedbacks::ListFeedbacksUseCase;
+use crate::application::use_cases::list_maintenance_reminders::ListMaintenanceRemindersUseCase;
+use crate::application::use_cases::list_motorcycles::ListMotorcyclesUseCase;
+use crate::application::use_cases::list_scheduled_jobs::ListScheduledJobsUseCase;
 use crate::application::use_cases::list_service_orders::ListServiceOrdersUseCase;
 use crate::application::use_cases::list_stock_items::ListStockItemsUseCase;
+use crate::application::use_cases::list_stock_movements::ListStockMovementsUseCase;
 use crate::application::use_cases::list_users::ListUsersUseCase;
 use crate::application::use_cases::login::LoginUseCase;
 use crate::application::use_cases::logout::LogoutUseCase;
+use crate::application::use_cases::low_stock::LowStockUseCase;
+use crate::application::use_cases::loyalty::LoyaltyUseCase;
+use crate::application::use_cases::manage_maintenance_rules::ManageMaintenanceRulesUseCase;
+use crate::application::use_cases::manage_suppliers::ManageSuppliersUseCase;
+use crate::application::use_cases::part_compatibility::PartCompatibilityUseCase;
 use crate::application::use_cases::process_payment::ProcessPaymentUseCase;
 use crate::application::use_cases::promote_user::PromoteUserUseCase;
+use crate::application::use_cases::purchase_order::PurchaseOrderUseCase;
+use crate::application::use_cases::quotation::QuotationUseCase;
 use crate::application::use_cases::refresh_token::RefreshTokenUseCase;
+use crate::application::use_cases::register_motorcycle::RegisterMotorcycleUseCase;
 use crate::application::use_cases::register_user::RegisterUserUseCase;
 use crate::application::use_cases::remove_service_item::RemoveServiceItemUseCase;
+use crate::application::use_cases::request_erasure::RequestErasureUseCase;
+use crate::application::use_cases::review_erasure_request::ReviewErasureRequestUseCase;
+use crate::application::use_cases::search::SearchUseCase;
+use crate::application::use_cases::set_user_active::SetUserActiveUseCase;
+use crate::application::use_cases::stock_catalogue::StockCatalogueUseCase;
+use crate::application::use_cases::stock_take::StockTakeUseCase;
+use crate::application::use_cases::stock_transfer::StockTransferUseCase;
 use crate::application::use_cases::submit_feedback::SubmitFeedbackUseCase;
+use crate::application::use_cases::transfer_motorcycle::TransferMotorcycleUseCase;
+use crate::application::use_cases::update_motorcycle::UpdateMotorcycleUseCase;
 use crate::application::use_cases::update_order_photos::UpdateOrderPhotosUseCase;
 use crate::application::use_cases::update_order_status::UpdateOrderStatusUseCase;
 use crate::application::use_cases::update_profile::UpdateProfileUseCase;
 use crate::application::use_cases::update_stock_item::UpdateStockItemUseCase;
 use crate::application::use_cases::use_stock_item::UseStockItemUseCase;
+use crate::application::use_cases::verify_phone::VerifyPhoneUseCase;
 use crate::infrastructure::security::jwt::service::JwtService;
 
 pub struct AppState {
@@ -62,5 +97,40 @@ pub struct AppState {
         crate::application::use_cases::mark_notification_read::MarkNotificationReadUseCase,
     pub delete_service_order_use_case: DeleteServiceOrderUseCase,
     pub remove_service_item_use_case: RemoveServiceItemUseCase,
+    pub enroll_mfa_use_case: EnrollMfaUseCase,
+    pub confirm_mfa_use_case: ConfirmMfaUseCase,
+    pub disable_mfa_use_case: DisableMfaUseCase,
+    pub list_audit_events_use_case: ListAuditEventsUseCase,
+    pub export_personal_data_use_case: ExportPersonalDataUseCase,
+    pub request_erasure_use_case: RequestErasureUseCase,
+    pub list_erasure_requests_use_case: ListErasureRequestsUseCase,
+    pub review_erasure_request_use_case: ReviewErasureRequestUseCase,
+    pub create_staff_use_case: CreateStaffUseCase,
+    pub set_user_active_use_case: SetUserActiveUseCase,
+    pub list_motorcycles_use_case: ListMotorcyclesUseCase,
+    pub register_motorcycle_use_case: RegisterMotorcycleUseCase,
+    pub update_motorcycle_use_case: UpdateMotorcycleUseCase,
+    pub delete_motorcycle_use_case: DeleteMotorcycleUseCase,
+    pub get_motorcycle_history_use_case: GetMotorcycleHistoryUseCase,
+    pub manage_maintenance_rules_use_case: ManageMaintenanceRulesUseCase,
+    pub list_maintenance_reminders_use_case: ListMaintenanceRemindersUseCase,
+    pub transfer_motorcycle_use_case: TransferMotorcycleUseCase,
+    pub list_scheduled_jobs_use_case: ListScheduledJobsUseCase,
+    pub search_use_case: SearchUseCase,
+    pub create_guest_customer_use_case: CreateGuestCustomerUseCase,
+    pub verify_phone_use_case: VerifyPhoneUseCase,
+    pub loyalty_use_case: LoyaltyUseCase,
+    pub quotation_use_case: QuotationUseCase,
+    pub list_stock_movements_use_case: ListStockMovementsUseCase,
+    pub check_stock_consistency_use_case: CheckStockConsistencyUseCase,
+    pub manage_suppliers_use_case: ManageSuppliersUseCase,
+    pub purchase_order_use_case: PurchaseOrderUseCase,
+    pub low_stock_use_case: LowStockUseCase,
+    pub get_stock_item_by_barcode_use_case: GetStockItemByBarcodeUseCase,
+    pub stock_catalogue_use_case: StockCatalogueUseCase,
+    pub stock_take_use_case: StockTakeUseCase,
+    pub stock_transfer_use_case: StockTransferUseCase,
+    pub part_compatibility_use_case: PartCompatibilityUseCase,
+    pub branch_use_case: BranchUseCase,
     pub jwt_service: JwtService,
 }
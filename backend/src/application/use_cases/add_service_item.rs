@@ -24,7 +24,11 @@ impl AddServiceItemUseCase {
         }
     }
 
-    pub async fn execute(&self, command: AddServiceItemCommand) -> Result<ServiceItem, String> {
+    pub async fn execute(
+        &self,
+        command: AddServiceItemCommand,
+        user_id: i32,
+    ) -> Result<ServiceItem, String> {
         // 1. Verify order exists
         let mut order = self
             .order_repo
@@ -46,7 +50,7 @@ impl AddServiceItemUseCase {
 
         // 3. Update order total price
         order.total_price += command.price;
-        self.order_repo.update_order(order).await?;
+        self.order_repo.update_order(order, Some(user_id)).await?;
 
         Ok(added_item)
     }
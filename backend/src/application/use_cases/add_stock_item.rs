@@ -1,4 +1,6 @@
-use crate::domain::service::stock_entity::StockItem;
+use crate::application::use_cases::branch::BranchUseCase;
+use crate::domain::service::stock_entity::{DEFAULT_UNIT, StockItem};
+use crate::domain::user::entity::Role;
 use crate::infrastructure::db::repositories::stock::StockItemRepository;
 use serde::Deserialize;
 
@@ -7,24 +9,96 @@ pub struct AddStockItemCommand {
     pub name: String,
     pub price: f64,
     pub quantity: i32,
+    pub reorder_point: Option<i32>,
+    pub reorder_quantity: Option<i32>,
+    pub sku: Option<String>,
+    pub barcode: Option<String>,
+    pub category: Option<String>,
+    pub brand: Option<String>,
+    pub unit: Option<String>,
+    pub shelf_location: Option<String>,
+    /// What each unit of the opening quantity cost
+    pub cost_price: Option<f64>,
+    /// Where the opening quantity is; leave out for your own branch
+    pub branch_id: Option<i32>,
+}
+
+/// Rejects a SKU or barcode that already belongs to another item
+pub async fn ensure_unique_identifiers(
+    stock_repo: &StockItemRepository,
+    item: &StockItem,
+) -> Result<(), String> {
+    if let Some(sku) = &item.sku
+        && let Some(other) = stock_repo.find_by_sku(sku).await?
+        && other.id != item.id
+    {
+        return Err(format!("SKU {} is already used by {}", sku, other.name));
+    }
+    if let Some(barcode) = &item.barcode
+        && let Some(other) = stock_repo.find_by_barcode(barcode).await?
+        && other.id != item.id
+    {
+        return Err(format!(
+            "Barcode {} is already used by {}",
+            barcode, other.name
+        ));
+    }
+    Ok(())
 }
 
 pub struct AddStockItemUseCase {
     stock_repo: StockItemRepository,
+    branch_use_case: BranchUseCase,
 }
 
 impl AddStockItemUseCase {
-    pub fn new(stock_repo: StockItemRepository) -> Self {
-        Self { stock_repo }
+    pub fn new(stock_repo: StockItemRepository, branch_use_case: BranchUseCase) -> Self {
+        Self {
+            stock_repo,
+            branch_use_case,
+        }
     }
 
-    pub async fn execute(&self, command: AddStockItemCommand) -> Result<StockItem, String> {
+    pub async fn execute(
+        &self,
+        command: AddStockItemCommand,
+        user_id: i32,
+        role: &Role,
+    ) -> Result<StockItem, String> {
+        let branch_id = self
+            .branch_use_case
+            .resolve(user_id, role, command.branch_id)
+            .await?;
         let item = StockItem {
             id: None,
             name: command.name,
             price: command.price,
             quantity: command.quantity,
-        };
-        self.stock_repo.create_stock_item(item).await
+            reserved_quantity: 0,
+            available_quantity: command.quantity,
+            reorder_point: command.reorder_point,
+            reorder_quantity: command.reorder_quantity,
+            sku: command.sku,
+            barcode: command.barcode,
+            category: command.category,
+            brand: command.brand,
+            unit: command.unit.unwrap_or_else(|| DEFAULT_UNIT.to_string()),
+            shelf_location: command.shelf_location,
+            average_cost: command.cost_price.unwrap_or(0.0),
+        }
+        .normalized()?;
+        ensure_unique_identifiers(&self.stock_repo, &item).await?;
+        match self
+            .stock_repo
+            .create_stock_item(item.clone(), user_id, branch_id)
+            .await
+        {
+            Ok(created) => Ok(created),
+            Err(e) => {
+                // Lost a race for the SKU or barcode
+                ensure_unique_identifiers(&self.stock_repo, &item).await?;
+                Err(e)
+            }
+        }
     }
 }
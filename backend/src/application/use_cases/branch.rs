@@ -0,0 +1,306 @@
+use crate::application::input::optional_text;
+use crate::domain::audit::entity::{BRANCH_CREATED, BRANCH_UPDATED, USER_BRANCHES_CHANGED};
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::models::{BranchChangeset, BranchModel};
+use crate::infrastructure::db::repositories::branch::BranchRepository;
+use crate::infrastructure::db::repositories::user::UserRepository;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct BranchCommand {
+    pub name: String,
+    pub address: Option<String>,
+    pub phone: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetUserBranchesCommand {
+    /// The branches the user works at
+    pub branch_ids: Vec<i32>,
+    /// Let the user act on every branch, whichever they work at
+    #[serde(default)]
+    pub all_branches: bool,
+}
+
+/// Narrows a listing or dashboard to one branch
+#[derive(Debug, Deserialize)]
+pub struct BranchFilter {
+    pub branch_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserBranchesResponse {
+    pub user_id: i32,
+    pub branch_ids: Vec<i32>,
+    pub all_branches: bool,
+}
+
+/// The branches a user may see and act on. Customers and staff allowed every
+/// branch aren't restricted; other staff only reach the branches they work
+/// at, and nothing if they work at none.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BranchAccess {
+    All,
+    Only(Vec<i32>),
+}
+
+impl BranchAccess {
+    pub fn allows(&self, branch_id: i32) -> bool {
+        match self {
+            BranchAccess::All => true,
+            BranchAccess::Only(ids) => ids.contains(&branch_id),
+        }
+    }
+
+    /// The branches to filter by, or `None` for no filter
+    pub fn branch_ids(&self) -> Option<Vec<i32>> {
+        match self {
+            BranchAccess::All => None,
+            BranchAccess::Only(ids) => Some(ids.clone()),
+        }
+    }
+}
+
+impl BranchCommand {
+    fn into_changeset(self) -> Result<BranchChangeset, String> {
+        let name = self.name.trim().to_string();
+        if name.is_empty() {
+            return Err("Branch name is required".to_string());
+        }
+
+        Ok(BranchChangeset {
+            name,
+            address: optional_text(self.address),
+            phone: optional_text(self.phone),
+            is_active: self.is_active.unwrap_or(true),
+        })
+    }
+}
+
+/// The shop's branches, who works where, and which branch a request acts on
+#[derive(Clone)]
+pub struct BranchUseCase {
+    branch_repo: BranchRepository,
+    user_repo: UserRepository,
+}
+
+impl BranchUseCase {
+    pub fn new(branch_repo: BranchRepository, user_repo: UserRepository) -> Self {
+        Self {
+            branch_repo,
+            user_repo,
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<BranchModel>, String> {
+        self.branch_repo.list().await
+    }
+
+    pub async fn access(&self, user_id: i32, role: &Role) -> Result<BranchAccess, String> {
+        if *role == Role::Customer || self.branch_repo.has_all_branches(user_id).await? {
+            return Ok(BranchAccess::All);
+        }
+        Ok(BranchAccess::Only(
+            self.branch_repo.user_branch_ids(user_id).await?,
+        ))
+    }
+
+    /// Fails unless the user may act on the branch
+    pub async fn check(&self, user_id: i32, role: &Role, branch_id: i32) -> Result<(), String> {
+        if self.access(user_id, role).await?.allows(branch_id) {
+            Ok(())
+        } else {
+            Err("You don't have access to this branch".to_string())
+        }
+    }
+
+    /// The branches a listing should show: the one asked for, or every
+    /// branch the user may see. `None` means no filter.
+    pub async fn scope(
+        &self,
+        user_id: i32,
+        role: &Role,
+        requested: Option<i32>,
+    ) -> Result<Option<Vec<i32>>, String> {
+        match requested {
+            Some(branch_id) => {
+                self.branch_repo
+                    .find_by_id(branch_id)
+                    .await?
+                    .ok_or("Branch not found")?;
+                self.check(user_id, role, branch_id).await?;
+                Ok(Some(vec![branch_id]))
+            }
+            None => Ok(self.access(user_id, role).await?.branch_ids()),
+        }
+    }
+
+    /// The branch something new happens at: the one asked for, which has to
+    /// be open and one the user may act on, otherwise the user's own open
+    /// branch, otherwise the default branch for those allowed every branch
+    pub async fn resolve(
+        &self,
+        user_id: i32,
+        role: &Role,
+        requested: Option<i32>,
+    ) -> Result<i32, String> {
+        let access = self.access(user_id, role).await?;
+        let Some(branch_id) = requested else {
+            let BranchAccess::Only(ids) = &access else {
+                return self.branch_repo.default_branch_id().await;
+            };
+            for &branch_id in ids {
+                if self
+                    .branch_repo
+                    .find_by_id(branch_id)
+                    .await?
+                    .is_some_and(|b| b.is_active)
+                {
+                    return Ok(branch_id);
+                }
+            }
+            return Err(if ids.is_empty() {
+                "You aren't assigned to a branch".to_string()
+            } else {
+                "None of your branches is open".to_string()
+            });
+        };
+
+        self.find_open(branch_id).await?;
+        if !access.allows(branch_id) {
+            return Err("You don't have access to this branch".to_string());
+        }
+        Ok(branch_id)
+    }
+
+    /// The branch, as long as it's open for business
+    pub async fn find_open(&self, branch_id: i32) -> Result<BranchModel, String> {
+        let branch = self
+            .branch_repo
+            .find_by_id(branch_id)
+            .await?
+            .ok_or("Branch not found")?;
+        if !branch.is_active {
+            return Err(format!("{} is closed", branch.name));
+        }
+        Ok(branch)
+    }
+
+    pub async fn create(
+        &self,
+        command: BranchCommand,
+        actor: &AuditActor,
+    ) -> Result<BranchModel, String> {
+        let changes = command.into_changeset()?;
+        if self
+            .branch_repo
+            .find_by_name(&changes.name)
+            .await?
+            .is_some()
+        {
+            return Err("A branch with this name already exists".to_string());
+        }
+        self.branch_repo
+            .create(changes, |branch| {
+                AuditEvent::new(actor, BRANCH_CREATED, "branch", branch.branch_id)
+                    .with_change(&serde_json::json!({}), &serde_json::json!(branch))
+            })
+            .await
+    }
+
+    pub async fn update(
+        &self,
+        branch_id: i32,
+        command: BranchCommand,
+        actor: &AuditActor,
+    ) -> Result<BranchModel, String> {
+        let changes = command.into_changeset()?;
+        let previous = self
+            .branch_repo
+            .find_by_id(branch_id)
+            .await?
+            .ok_or("Branch not found")?;
+        if self
+            .branch_repo
+            .find_by_name(&changes.name)
+            .await?
+            .is_some_and(|other| other.branch_id != branch_id)
+        {
+            return Err("A branch with this name already exists".to_string());
+        }
+        if previous.is_active
+            && !changes.is_active
+            && !self
+                .branch_repo
+                .list()
+                .await?
+                .iter()
+                .any(|b| b.branch_id != branch_id && b.is_active)
+        {
+            return Err("At least one branch has to stay open".to_string());
+        }
+
+        self.branch_repo
+            .update(branch_id, changes, |updated| {
+                AuditEvent::new(actor, BRANCH_UPDATED, "branch", branch_id)
+                    .with_change(&previous, updated)
+            })
+            .await
+    }
+
+    pub async fn user_branches(&self, user_id: i32) -> Result<UserBranchesResponse, String> {
+        Ok(UserBranchesResponse {
+            user_id,
+            branch_ids: self.branch_repo.user_branch_ids(user_id).await?,
+            all_branches: self.branch_repo.has_all_branches(user_id).await?,
+        })
+    }
+
+    pub async fn set_user_branches(
+        &self,
+        user_id: i32,
+        command: SetUserBranchesCommand,
+        actor: &AuditActor,
+    ) -> Result<UserBranchesResponse, String> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or("User not found")?;
+        if user.role == Role::Customer {
+            return Err("Only staff work at a branch".to_string());
+        }
+
+        let mut branch_ids = command.branch_ids;
+        branch_ids.sort_unstable();
+        branch_ids.dedup();
+        for &branch_id in &branch_ids {
+            if self.branch_repo.find_by_id(branch_id).await?.is_none() {
+                return Err(format!("Branch {} not found", branch_id));
+            }
+        }
+
+        let previous = self.user_branches(user_id).await?;
+        let event = AuditEvent::new(actor, USER_BRANCHES_CHANGED, "user", user_id).with_change(
+            &serde_json::json!({
+                "branch_ids": previous.branch_ids,
+                "all_branches": previous.all_branches,
+            }),
+            &serde_json::json!({
+                "branch_ids": branch_ids,
+                "all_branches": command.all_branches,
+            }),
+        );
+        self.branch_repo
+            .set_user_branches(user_id, &branch_ids, command.all_branches, event)
+            .await?;
+
+        Ok(UserBranchesResponse {
+            user_id,
+            branch_ids,
+            all_branches: command.all_branches,
+        })
+    }
+}
@@ -0,0 +1,19 @@
+use crate::infrastructure::db::repositories::stock_movement::{
+    StockDiscrepancy, StockMovementRepository,
+};
+
+/// Compares each item's quantity with the total of its stock movements
+#[derive(Clone)]
+pub struct CheckStockConsistencyUseCase {
+    movement_repo: StockMovementRepository,
+}
+
+impl CheckStockConsistencyUseCase {
+    pub fn new(movement_repo: StockMovementRepository) -> Self {
+        Self { movement_repo }
+    }
+
+    pub async fn execute(&self) -> Result<Vec<StockDiscrepancy>, String> {
+        self.movement_repo.find_discrepancies().await
+    }
+}
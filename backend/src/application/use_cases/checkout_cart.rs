@@ -1,4 +1,7 @@
+use crate::application::use_cases::branch::BranchUseCase;
+use crate::application::use_cases::low_stock::LowStockUseCase;
 use crate::domain::service::entity::{OrderStatus, ServiceOrder};
+use crate::domain::user::entity::Role;
 use crate::infrastructure::db::repositories::inventory::InventoryRepository;
 use crate::infrastructure::db::repositories::service_order::ServiceOrderRepository;
 use serde::Deserialize;
@@ -12,18 +15,29 @@ pub struct CartItem {
 #[derive(Deserialize)]
 pub struct CheckoutCommand {
     pub items: Vec<CartItem>,
+    /// Where the parts are collected from; leave out for the default branch
+    pub branch_id: Option<i32>,
 }
 
 pub struct CheckoutUseCase {
     order_repo: ServiceOrderRepository,
     inventory_repo: InventoryRepository,
+    low_stock: LowStockUseCase,
+    branch_use_case: BranchUseCase,
 }
 
 impl CheckoutUseCase {
-    pub fn new(order_repo: ServiceOrderRepository, inventory_repo: InventoryRepository) -> Self {
+    pub fn new(
+        order_repo: ServiceOrderRepository,
+        inventory_repo: InventoryRepository,
+        low_stock: LowStockUseCase,
+        branch_use_case: BranchUseCase,
+    ) -> Self {
         Self {
             order_repo,
             inventory_repo,
+            low_stock,
+            branch_use_case,
         }
     }
 
@@ -31,29 +45,40 @@ impl CheckoutUseCase {
         if command.items.is_empty() {
             return Err("Cart is empty".to_string());
         }
+        let branch_id = self
+            .branch_use_case
+            .resolve(customer_id, &Role::Customer, command.branch_id)
+            .await?;
 
         // 1. Create a base order for the purchase
         let order = ServiceOrder {
             id: None,
             bike_id: None,
             customer_id,
+            branch_id,
             status: OrderStatus::Booked,
             total_price: 0.0,
             items: Vec::new(),
             created_at: Some(chrono::Utc::now()),
             before_picture_url: None,
             after_picture_url: None,
+            odometer_km: None,
         };
 
         let created_order = self.order_repo.create_order(order, customer_id).await?;
         let order_id = created_order.id.ok_or("Failed to create order")?;
 
         // 2. Add each item from cart
+        let mut taken = false;
         for item in command.items {
-            self.inventory_repo
-                .use_stock_item(order_id, item.stock_item_id, item.quantity)
+            taken |= self
+                .inventory_repo
+                .use_stock_item(order_id, item.stock_item_id, item.quantity, customer_id)
                 .await?;
         }
+        if taken {
+            self.low_stock.dispatch_alerts_in_background();
+        }
 
         Ok(order_id)
     }
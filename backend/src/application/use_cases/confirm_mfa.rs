@@ -0,0 +1,55 @@
+use crate::infrastructure::db::repositories::user_mfa::UserMfaRepository;
+use crate::infrastructure::security::mfa::{generate_recovery_codes, verify_totp_code};
+use crate::infrastructure::security::password::hash_password;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmMfaCommand {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MfaRecoveryCodesResult {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct ConfirmMfaUseCase {
+    mfa_repository: UserMfaRepository,
+}
+
+impl ConfirmMfaUseCase {
+    pub fn new(mfa_repository: UserMfaRepository) -> Self {
+        Self { mfa_repository }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: i32,
+        command: ConfirmMfaCommand,
+    ) -> Result<MfaRecoveryCodesResult, String> {
+        let mfa = self
+            .mfa_repository
+            .find_by_user_id(user_id)
+            .await?
+            .ok_or("Start two-factor enrollment first".to_string())?;
+
+        if mfa.enabled {
+            return Err("Two-factor authentication is already enabled".to_string());
+        }
+
+        let step = verify_totp_code(&mfa.totp_secret, &command.code)?
+            .ok_or("Invalid verification code".to_string())?;
+
+        // Plaintext codes leave the server exactly once, in this response
+        let recovery_codes = generate_recovery_codes();
+        let hashes = recovery_codes
+            .iter()
+            .map(|code| hash_password(code))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.mfa_repository.enable(user_id, step, hashes).await?;
+
+        Ok(MfaRecoveryCodesResult { recovery_codes })
+    }
+}
@@ -1,4 +1,8 @@
+use crate::application::use_cases::verify_phone::VerifyPhoneUseCase;
 use crate::domain::notification::gateway::{NotificationGateway, NotificationMessage};
+use crate::domain::user::entity::Role;
+use crate::domain::value_objects::PhoneNumber;
+use crate::infrastructure::db::repositories::user::UserRepository;
 use crate::infrastructure::db::repositories::user_line_account::UserLineAccountRepository;
 use crate::infrastructure::external::notification::line::LineNotificationGateway;
 use serde::{Deserialize, Serialize};
@@ -20,16 +24,22 @@ pub struct ConnectLineResult {
 pub struct ConnectLineUseCase {
     line_repo: UserLineAccountRepository,
     line_gateway: Arc<LineNotificationGateway>,
+    user_repo: UserRepository,
+    verify_phone: VerifyPhoneUseCase,
 }
 
 impl ConnectLineUseCase {
     pub fn new(
         line_repo: UserLineAccountRepository,
         line_gateway: Arc<LineNotificationGateway>,
+        user_repo: UserRepository,
+        verify_phone: VerifyPhoneUseCase,
     ) -> Self {
         Self {
             line_repo,
             line_gateway,
+            user_repo,
+            verify_phone,
         }
     }
 
@@ -75,6 +85,15 @@ impl ConnectLineUseCase {
             )
             .await?;
 
+        // Customers who already verified their phone pick up any walk-in records
+        if let Err(e) = self.claim_guests(user_id_val).await {
+            tracing::error!(
+                "Failed to merge guest records into user {}: {}",
+                user_id_val,
+                e
+            );
+        }
+
         // Send welcome message
         let welcome_message = NotificationMessage {
             user_id: user_id_val,
@@ -97,4 +116,15 @@ impl ConnectLineUseCase {
             message: "LINE account connected successfully with profile".to_string(),
         })
     }
+
+    async fn claim_guests(&self, user_id: i32) -> Result<usize, String> {
+        let Some(user) = self.user_repo.find_by_id(user_id).await? else {
+            return Ok(0);
+        };
+        if user.role != Role::Customer || user.phone_verified_at.is_none() {
+            return Ok(0);
+        }
+        let phone = PhoneNumber::new(user.phone)?;
+        self.verify_phone.claim_guests(user_id, &phone).await
+    }
 }
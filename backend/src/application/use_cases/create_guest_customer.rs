@@ -0,0 +1,68 @@
+use crate::application::use_cases::list_users::UserResponse;
+use crate::domain::audit::entity::GUEST_CREATED;
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::user::entity::User;
+use crate::domain::value_objects::PhoneNumber;
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::user::UserRepository;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateGuestCustomerCommand {
+    pub name: String,
+    pub phone: String,
+}
+
+#[derive(Clone)]
+pub struct CreateGuestCustomerUseCase {
+    user_repo: UserRepository,
+    audit_repo: AuditEventRepository,
+}
+
+impl CreateGuestCustomerUseCase {
+    pub fn new(user_repo: UserRepository, audit_repo: AuditEventRepository) -> Self {
+        Self {
+            user_repo,
+            audit_repo,
+        }
+    }
+
+    /// Records a walk-in customer by name and phone so orders can be opened for
+    /// them. Returns the existing guest when the phone is already on file.
+    pub async fn execute(
+        &self,
+        actor: &AuditActor,
+        command: CreateGuestCustomerCommand,
+    ) -> Result<UserResponse, String> {
+        let name = command.name.trim().to_string();
+        if name.is_empty() {
+            return Err("Name is required".to_string());
+        }
+        let phone = PhoneNumber::new(command.phone)?;
+
+        if let Some(account) = self.user_repo.find_account_by_phone(&phone).await? {
+            return Err(format!(
+                "This phone belongs to the registered account '{}'",
+                account.username
+            ));
+        }
+        if let Some(guest) = self.user_repo.find_open_guest_by_phone(&phone).await? {
+            return Ok(UserResponse::from(guest));
+        }
+
+        let username = format!("guest-{}", uuid::Uuid::new_v4().simple());
+        let guest = self
+            .user_repo
+            .create_user(User::new_guest(username, name, phone.value().to_string()))
+            .await?;
+        let guest_id = guest.id.unwrap_or(0);
+
+        // The guest's id is enough; their name and phone stay out of the log
+        let event = AuditEvent::new(actor, GUEST_CREATED, "user", guest_id);
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        Ok(UserResponse::from(guest))
+    }
+}
@@ -1,5 +1,10 @@
+use crate::application::use_cases::branch::BranchUseCase;
 use crate::domain::notification::gateway::{NotificationGateway, NotificationMessage};
 use crate::domain::service::entity::{OrderStatus, ServiceOrder};
+use crate::domain::user::entity::Role;
+use crate::domain::value_objects::LicensePlate;
+use crate::infrastructure::db::models::NewMotorcycle;
+use crate::infrastructure::db::repositories::maintenance::MaintenanceRepository;
 use crate::infrastructure::db::repositories::motorcycle::MotorcycleRepository;
 use crate::infrastructure::db::repositories::service_order::ServiceOrderRepository;
 use crate::infrastructure::db::repositories::user::UserRepository;
@@ -16,11 +21,15 @@ pub struct CreateServiceOrderCommand {
     pub customer_id: Option<i32>,
     pub problem_description: Option<String>,
     pub walk_in_date: Option<String>,
+    pub odometer_km: Option<i32>,
+    /// Leave out to book at the creator's own branch
+    pub branch_id: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct CreateServiceOrderResult {
     pub order_id: i32,
+    pub branch_id: i32,
     pub status: OrderStatus,
     pub total_price: f64,
 }
@@ -32,6 +41,8 @@ pub struct CreateServiceOrderUseCase {
     line_repo: UserLineAccountRepository,
     user_repo: UserRepository,
     notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
+    maintenance_repo: MaintenanceRepository,
+    branch_use_case: BranchUseCase,
 }
 
 impl CreateServiceOrderUseCase {
@@ -41,6 +52,8 @@ impl CreateServiceOrderUseCase {
         line_repo: UserLineAccountRepository,
         user_repo: UserRepository,
         notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
+        maintenance_repo: MaintenanceRepository,
+        branch_use_case: BranchUseCase,
     ) -> Self {
         Self {
             order_repository,
@@ -48,32 +61,65 @@ impl CreateServiceOrderUseCase {
             line_repo,
             user_repo,
             notification_gateway,
+            maintenance_repo,
+            branch_use_case,
         }
     }
 
+    /// Staff whose branches include this one
+    async fn works_at(&self, user_id: i32, role: &Role, branch_id: i32) -> bool {
+        self.branch_use_case
+            .access(user_id, role)
+            .await
+            .is_ok_and(|access| access.allows(branch_id))
+    }
+
     pub async fn execute(
         &self,
         command: CreateServiceOrderCommand,
         creator_id: i32,
+        creator_role: &Role,
     ) -> Result<CreateServiceOrderResult, String> {
+        let branch_id = self
+            .branch_use_case
+            .resolve(creator_id, creator_role, command.branch_id)
+            .await?;
+
         // 1. Determine Customer ID
         let customer_id = command.customer_id.unwrap_or(creator_id);
+        if command.customer_id.is_some() {
+            let customer = self
+                .user_repo
+                .find_by_id(customer_id)
+                .await?
+                .ok_or("Customer not found")?;
+            if customer.is_guest && !customer.is_active {
+                return Err(
+                    "This guest has been merged into a registered account; use that account instead"
+                        .to_string(),
+                );
+            }
+        }
 
         // 2. Determine or Create Bike ID
         let bike_id = if let Some(id) = command.bike_id {
-            Some(id)
-        } else if command.brand.is_some()
-            && command.model.is_some()
-            && command.license_plate.is_some()
+            // Booking picks one of the customer's registered bikes
+            let bike = self
+                .bike_repository
+                .find_by_id(id)
+                .await?
+                .filter(|b| b.user_id == customer_id && b.archived_at.is_none())
+                .ok_or("Motorcycle is not registered to this customer".to_string())?;
+            Some(bike.bike_id)
+        } else if let (Some(brand), Some(model), Some(license)) =
+            (command.brand, command.model, command.license_plate)
         {
-            let brand = command.brand.unwrap();
-            let model = command.model.unwrap();
-            let license = command.license_plate.unwrap();
+            let plate = LicensePlate::new(license)?;
 
-            // Try to find existing bike for this user with same license
+            // Reuse the customer's bike however the plate was typed
             let existing_bike = self
                 .bike_repository
-                .find_by_license_and_user(&license, customer_id)
+                .find_by_license_and_user(&plate, customer_id)
                 .await?;
 
             if let Some(bike) = existing_bike {
@@ -81,7 +127,19 @@ impl CreateServiceOrderUseCase {
             } else {
                 let bike = self
                     .bike_repository
-                    .create_motorcycle(brand, model, license, customer_id)
+                    .create_motorcycle(NewMotorcycle {
+                        brand,
+                        model,
+                        license_plate: plate.value(),
+                        user_id: customer_id,
+                        year: None,
+                        colour: None,
+                        engine_number: None,
+                        vin: None,
+                        mileage: 0,
+                        plate_key: Some(plate.key()),
+                        plate_province: plate.province().map(str::to_string),
+                    })
                     .await?;
                 Some(bike.bike_id)
             }
@@ -89,17 +147,35 @@ impl CreateServiceOrderUseCase {
             None
         };
 
-        // 3. Create Service Order
+        // 3. Check the odometer reading against what we last saw for this bike
+        if let Some(odometer_km) = command.odometer_km {
+            if odometer_km < 0 {
+                return Err("Odometer reading cannot be negative".to_string());
+            }
+            if let Some(id) = bike_id
+                && let Some(bike) = self.bike_repository.find_by_id(id).await?
+                && odometer_km < bike.mileage
+            {
+                return Err(format!(
+                    "Odometer reading is below the recorded {} km for this motorcycle",
+                    bike.mileage
+                ));
+            }
+        }
+
+        // 4. Create Service Order
         let order = ServiceOrder {
             id: None,
             bike_id,
             customer_id,
+            branch_id,
             status: OrderStatus::Booked,
             total_price: 0.0,
             items: Vec::new(),
             created_at: Some(chrono::Utc::now()),
             before_picture_url: None,
             after_picture_url: None,
+            odometer_km: command.odometer_km,
         };
 
         let created_order = self
@@ -107,7 +183,21 @@ impl CreateServiceOrderUseCase {
             .create_order(order, creator_id)
             .await?;
 
-        // 4. Notify Admins and Mechanics
+        if let (Some(id), Some(odometer_km)) = (bike_id, command.odometer_km) {
+            self.bike_repository.record_mileage(id, odometer_km).await?;
+        }
+
+        // Booking the bike answers any maintenance reminder we sent for it
+        if let (Some(id), Some(order_id)) = (bike_id, created_order.id)
+            && let Err(e) = self
+                .maintenance_repo
+                .convert_open_for_bike(id, order_id)
+                .await
+        {
+            tracing::error!("Failed to convert maintenance reminders: {}", e);
+        }
+
+        // 5. Notify Admins and Mechanics
         let order_id = created_order.id.unwrap_or(0);
         let problem = command
             .problem_description
@@ -122,12 +212,14 @@ impl CreateServiceOrderUseCase {
         let walk_in_info_clone = walk_in_info.clone();
         let problem_clone = problem.clone();
 
-        // Fire notifications in background
+        // Fire notifications in background, to the staff at the order's branch
         tokio::spawn(async move {
             // Notify Admins
             if let Ok(admins) = self_clone.user_repo.find_admins().await {
                 for admin in admins {
-                    if let Some(admin_id) = admin.id {
+                    if let Some(admin_id) = admin.id
+                        && self_clone.works_at(admin_id, &Role::Admin, branch_id).await
+                    {
                         let admin_line_id = self_clone
                             .line_repo
                             .find_by_user_id(admin_id)
@@ -229,7 +321,11 @@ impl CreateServiceOrderUseCase {
             // Notify Mechanics
             if let Ok(mechanics) = self_clone.user_repo.find_mechanics().await {
                 for mechanic in mechanics {
-                    if let Some(mech_id) = mechanic.id {
+                    if let Some(mech_id) = mechanic.id
+                        && self_clone
+                            .works_at(mech_id, &Role::Mechanic, branch_id)
+                            .await
+                    {
                         let mech_line_id = self_clone
                             .line_repo
                             .find_by_user_id(mech_id)
@@ -367,6 +463,7 @@ impl CreateServiceOrderUseCase {
 
         Ok(CreateServiceOrderResult {
             order_id,
+            branch_id,
             status: created_order.status,
             total_price: created_order.total_price,
         })
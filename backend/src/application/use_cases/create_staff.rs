@@ -0,0 +1,104 @@
+use crate::domain::audit::entity::USER_CREATED;
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::user::entity::{Role, User};
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::branch::BranchRepository;
+use crate::infrastructure::db::repositories::user::UserRepository;
+use crate::infrastructure::security::password::{generate_one_time_password, hash_password};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateStaffCommand {
+    pub username: String,
+    pub name: String,
+    pub phone: String,
+    pub role: Role,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateStaffResult {
+    pub user_id: i32,
+    pub username: String,
+    pub role: Role,
+    /// Where they start work; the default branch
+    pub branch_id: Option<i32>,
+    // Shown once; the staff member has to replace it at first login
+    pub temporary_password: String,
+}
+
+#[derive(Clone)]
+pub struct CreateStaffUseCase {
+    user_repository: UserRepository,
+    audit_repository: AuditEventRepository,
+    branch_repository: BranchRepository,
+}
+
+impl CreateStaffUseCase {
+    pub fn new(
+        user_repository: UserRepository,
+        audit_repository: AuditEventRepository,
+        branch_repository: BranchRepository,
+    ) -> Self {
+        Self {
+            user_repository,
+            audit_repository,
+            branch_repository,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        command: CreateStaffCommand,
+        actor: &AuditActor,
+    ) -> Result<CreateStaffResult, String> {
+        if command.role == Role::Customer {
+            return Err("Customers register themselves; choose Admin or Mechanic".to_string());
+        }
+
+        if self
+            .user_repository
+            .find_by_username(&command.username)
+            .await?
+            .is_some()
+        {
+            return Err("Username already exists".to_string());
+        }
+
+        let temporary_password = generate_one_time_password();
+        let password_hash = hash_password(&temporary_password)?;
+
+        let new_user = User::new_staff(
+            command.username,
+            password_hash,
+            command.name,
+            command.phone,
+            command.role,
+        );
+        let created_user = self.user_repository.create_user(new_user).await?;
+        let user_id = created_user.id.unwrap_or(0);
+        let branch_id = self
+            .branch_repository
+            .assign_default_branch(user_id)
+            .await?;
+
+        let event = AuditEvent::new(actor, USER_CREATED, "user", user_id).with_change(
+            &serde_json::json!({}),
+            &serde_json::json!({
+                "username": created_user.username,
+                "role": created_user.role,
+                "branch_id": branch_id,
+            }),
+        );
+        if let Err(e) = self.audit_repository.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        Ok(CreateStaffResult {
+            user_id,
+            username: created_user.username,
+            role: created_user.role,
+            branch_id,
+            temporary_password,
+        })
+    }
+}
@@ -1,16 +1,43 @@
+use crate::domain::audit::entity::FEEDBACK_DELETED;
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
 use crate::infrastructure::db::repositories::feedback::FeedbackRepository;
 
 #[derive(Clone)]
 pub struct DeleteFeedbackUseCase {
     feedback_repo: FeedbackRepository,
+    audit_repo: AuditEventRepository,
 }
 
 impl DeleteFeedbackUseCase {
-    pub fn new(feedback_repo: FeedbackRepository) -> Self {
-        Self { feedback_repo }
+    pub fn new(feedback_repo: FeedbackRepository, audit_repo: AuditEventRepository) -> Self {
+        Self {
+            feedback_repo,
+            audit_repo,
+        }
     }
 
-    pub async fn execute(&self, id: i32) -> Result<(), String> {
-        self.feedback_repo.delete_feedback(id).await
+    pub async fn execute(&self, id: i32, actor: &AuditActor) -> Result<(), String> {
+        let feedback = self
+            .feedback_repo
+            .find_by_id(id)
+            .await?
+            .ok_or("Feedback not found")?;
+
+        self.feedback_repo.delete_feedback(id).await?;
+
+        // Only ids go in the snapshot, so the audit log keeps no personal data
+        // once the feedback itself is gone
+        let event = AuditEvent::new(actor, FEEDBACK_DELETED, "feedback", id).with_snapshot(
+            &serde_json::json!({
+                "feedback_id": feedback.feedback_id,
+                "user_id": feedback.user_id,
+            }),
+        );
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,23 @@
+use crate::infrastructure::db::repositories::motorcycle::{
+    MotorcycleRemoval, MotorcycleRepository,
+};
+
+pub struct DeleteMotorcycleUseCase {
+    bike_repo: MotorcycleRepository,
+}
+
+impl DeleteMotorcycleUseCase {
+    pub fn new(bike_repo: MotorcycleRepository) -> Self {
+        Self { bike_repo }
+    }
+
+    pub async fn execute(&self, user_id: i32, bike_id: i32) -> Result<MotorcycleRemoval, String> {
+        self.bike_repo
+            .find_by_id(bike_id)
+            .await?
+            .filter(|b| b.user_id == user_id && b.archived_at.is_none())
+            .ok_or("Motorcycle not found".to_string())?;
+
+        self.bike_repo.remove_motorcycle(bike_id).await
+    }
+}
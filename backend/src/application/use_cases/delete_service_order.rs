@@ -1,3 +1,5 @@
+use crate::domain::audit::entity::SERVICE_ORDER_DELETED;
+use crate::domain::audit::{AuditActor, AuditEvent};
 use crate::domain::notification::gateway::{NotificationGateway, NotificationMessage};
 use crate::infrastructure::db::repositories::service_order::ServiceOrderRepository;
 use crate::infrastructure::db::repositories::user_line_account::UserLineAccountRepository;
@@ -23,7 +25,12 @@ impl DeleteServiceOrderUseCase {
         }
     }
 
-    pub async fn execute(&self, order_id: i32, reason: String) -> Result<(), String> {
+    pub async fn execute(
+        &self,
+        order_id: i32,
+        reason: String,
+        actor: &AuditActor,
+    ) -> Result<(), String> {
         // 1. Fetch order to get customer_id before deleting
         let order = self
             .order_repo
@@ -33,7 +40,19 @@ impl DeleteServiceOrderUseCase {
 
         let customer_id = order.customer_id;
 
-        // 2. Clone for async background task
+        // 2. Delete the order (notifications + items + order), returning points
+        // spent on it and taking back what it earned. Refused while it still
+        // holds parts that were already fitted. The audit event keeps a
+        // snapshot since the order itself is gone.
+        let event = AuditEvent::new(actor, SERVICE_ORDER_DELETED, "service_order", order_id)
+            .with_snapshot(&order)
+            .with_reason(&reason);
+        self.order_repo
+            .delete_order(order_id, actor.user_id, event)
+            .await?;
+
+        // 3. Tell the customer; the order row is gone, so the notification
+        // isn't linked to it
         let self_clone = self.clone();
         let reason_clone = reason.clone();
 
@@ -82,7 +101,7 @@ impl DeleteServiceOrderUseCase {
                 .notification_gateway
                 .send_notification(NotificationMessage {
                     user_id: customer_id,
-                    order_id: Some(order_id),
+                    order_id: None,
                     recipient: customer_line_id,
                     title: format!("❌ Order Cancelled #SO-{}", order_id),
                     body: format!(
@@ -94,7 +113,6 @@ impl DeleteServiceOrderUseCase {
                 .await;
         });
 
-        // 3. Delete the order (notifications + items + order)
-        self.order_repo.delete_order(order_id).await
+        Ok(())
     }
 }
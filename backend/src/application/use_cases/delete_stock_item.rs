@@ -1,5 +1,8 @@
 use crate::infrastructure::db::repositories::stock::StockItemRepository;
 
+/// Deleting an item archives it: it leaves the catalogue, lookups and
+/// reports, while its movements and the orders that used it keep pointing
+/// at it
 pub struct DeleteStockItemUseCase {
     stock_repo: StockItemRepository,
 }
@@ -10,6 +13,6 @@ impl DeleteStockItemUseCase {
     }
 
     pub async fn execute(&self, item_id: i32) -> Result<(), String> {
-        self.stock_repo.delete_stock_item(item_id).await
+        self.stock_repo.archive_stock_item(item_id).await
     }
 }
@@ -0,0 +1,59 @@
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::repositories::user::UserRepository;
+use crate::infrastructure::db::repositories::user_mfa::UserMfaRepository;
+use crate::infrastructure::security::mfa::verify_totp_code;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct DisableMfaCommand {
+    pub code: String,
+}
+
+#[derive(Clone)]
+pub struct DisableMfaUseCase {
+    user_repository: UserRepository,
+    mfa_repository: UserMfaRepository,
+    mfa_required_for_admins: bool,
+}
+
+impl DisableMfaUseCase {
+    pub fn new(
+        user_repository: UserRepository,
+        mfa_repository: UserMfaRepository,
+        mfa_required_for_admins: bool,
+    ) -> Self {
+        Self {
+            user_repository,
+            mfa_repository,
+            mfa_required_for_admins,
+        }
+    }
+
+    pub async fn execute(&self, user_id: i32, command: DisableMfaCommand) -> Result<(), String> {
+        let user = self
+            .user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or("User not found".to_string())?;
+
+        if self.mfa_required_for_admins && user.role == Role::Admin {
+            return Err("Two-factor authentication is mandatory for admin accounts".to_string());
+        }
+
+        let mfa = self
+            .mfa_repository
+            .find_by_user_id(user_id)
+            .await?
+            .filter(|m| m.enabled)
+            .ok_or("Two-factor authentication is not enabled".to_string())?;
+
+        // Require a live code so a stolen access token alone can't strip MFA
+        let step = verify_totp_code(&mfa.totp_secret, &command.code)?
+            .ok_or("Invalid verification code".to_string())?;
+        if !self.mfa_repository.consume_step(user_id, step).await? {
+            return Err("Verification code has already been used".to_string());
+        }
+
+        self.mfa_repository.delete(user_id).await
+    }
+}
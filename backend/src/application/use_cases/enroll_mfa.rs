@@ -0,0 +1,57 @@
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::repositories::user::UserRepository;
+use crate::infrastructure::db::repositories::user_mfa::UserMfaRepository;
+use crate::infrastructure::security::mfa::{generate_totp_secret, provisioning_uri};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct MfaEnrollmentResult {
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+#[derive(Clone)]
+pub struct EnrollMfaUseCase {
+    user_repository: UserRepository,
+    mfa_repository: UserMfaRepository,
+}
+
+impl EnrollMfaUseCase {
+    pub fn new(user_repository: UserRepository, mfa_repository: UserMfaRepository) -> Self {
+        Self {
+            user_repository,
+            mfa_repository,
+        }
+    }
+
+    pub async fn execute(&self, user_id: i32) -> Result<MfaEnrollmentResult, String> {
+        let user = self
+            .user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or("User not found".to_string())?;
+
+        if user.role == Role::Customer {
+            return Err(
+                "Two-factor authentication is only available for staff accounts".to_string(),
+            );
+        }
+
+        if let Some(existing) = self.mfa_repository.find_by_user_id(user_id).await?
+            && existing.enabled
+        {
+            return Err("Two-factor authentication is already enabled".to_string());
+        }
+
+        // Not active until the user proves their app works via ConfirmMfaUseCase
+        let secret = generate_totp_secret();
+        self.mfa_repository
+            .upsert_pending(user_id, secret.clone())
+            .await?;
+
+        Ok(MfaEnrollmentResult {
+            provisioning_uri: provisioning_uri(&secret, &user.username)?,
+            secret,
+        })
+    }
+}
@@ -0,0 +1,144 @@
+use crate::application::use_cases::loyalty::LoyaltyUseCase;
+use crate::domain::audit::AuditEvent;
+use crate::domain::audit::entity::SERVICE_ORDER_EXPIRED;
+use crate::domain::notification::gateway::{NotificationGateway, NotificationMessage};
+use crate::infrastructure::db::models::{QuotationStatusEnum, ServiceOrderStatusEnum};
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::quotation::QuotationRepository;
+use crate::infrastructure::db::repositories::service_order::ServiceOrderRepository;
+use crate::infrastructure::db::repositories::user_line_account::UserLineAccountRepository;
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+
+#[derive(Debug, Default, Serialize)]
+pub struct ExpiredOrders {
+    pub quotes: usize,
+    pub bookings: usize,
+}
+
+/// How long an order may sit in each waiting status before it is cancelled
+#[derive(Debug, Clone, Copy)]
+pub struct ExpiryWindows {
+    pub quote_days: i64,
+    pub booking_days: i64,
+}
+
+pub struct ExpireStaleOrdersUseCase {
+    order_repo: ServiceOrderRepository,
+    line_repo: UserLineAccountRepository,
+    notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
+    audit_repo: AuditEventRepository,
+    loyalty: LoyaltyUseCase,
+    quotation_repo: QuotationRepository,
+    windows: ExpiryWindows,
+}
+
+impl ExpireStaleOrdersUseCase {
+    pub fn new(
+        order_repo: ServiceOrderRepository,
+        line_repo: UserLineAccountRepository,
+        notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
+        audit_repo: AuditEventRepository,
+        loyalty: LoyaltyUseCase,
+        quotation_repo: QuotationRepository,
+        windows: ExpiryWindows,
+    ) -> Self {
+        Self {
+            order_repo,
+            line_repo,
+            notification_gateway,
+            audit_repo,
+            loyalty,
+            quotation_repo,
+            windows,
+        }
+    }
+
+    /// Cancels quotes the customer never answered and bookings that never
+    /// made it into the workshop.
+    pub async fn execute(&self) -> Result<ExpiredOrders, String> {
+        let now = Utc::now();
+        Ok(ExpiredOrders {
+            quotes: self
+                .expire(
+                    ServiceOrderStatusEnum::OfferSent,
+                    now - Duration::days(self.windows.quote_days),
+                    "the quote was not confirmed",
+                )
+                .await?,
+            bookings: self
+                .expire(
+                    ServiceOrderStatusEnum::Booked,
+                    now - Duration::days(self.windows.booking_days),
+                    "the booking was never brought in",
+                )
+                .await?,
+        })
+    }
+
+    async fn expire(
+        &self,
+        status: ServiceOrderStatusEnum,
+        cutoff: chrono::DateTime<Utc>,
+        reason: &str,
+    ) -> Result<usize, String> {
+        let mut expired = 0;
+        for order in self.order_repo.find_stale(status.clone(), cutoff).await? {
+            let Some(order_id) = order.id else { continue };
+            if !self
+                .order_repo
+                .cancel_if_status(order_id, status.clone())
+                .await?
+            {
+                continue;
+            }
+            expired += 1;
+
+            if let Err(e) = self.loyalty.refund_order(order_id).await {
+                tracing::error!("Failed to refund loyalty points: {}", e);
+            }
+            if let Err(e) = self
+                .quotation_repo
+                .close_pending(order_id, QuotationStatusEnum::Expired)
+                .await
+            {
+                tracing::error!("Failed to expire quotation: {}", e);
+            }
+
+            let event = AuditEvent::system(SERVICE_ORDER_EXPIRED, "service_order", order_id)
+                .with_change(
+                    &serde_json::json!({ "status": status }),
+                    &serde_json::json!({ "status": ServiceOrderStatusEnum::Cancelled }),
+                )
+                .with_reason(reason);
+            if let Err(e) = self.audit_repo.record(event).await {
+                tracing::error!("Failed to record audit event: {}", e);
+            }
+
+            let recipient = self
+                .line_repo
+                .find_by_user_id(order.customer_id)
+                .await
+                .ok()
+                .flatten()
+                .map(|l| l.line_user_id)
+                .unwrap_or_default();
+            let _ = self
+                .notification_gateway
+                .send_notification(NotificationMessage {
+                    user_id: order.customer_id,
+                    order_id: Some(order_id),
+                    recipient,
+                    title: format!("❌ Order Expired #SO-{}", order_id),
+                    body: format!(
+                        "Your order #SO-{} was cancelled automatically because {}.\nYou are welcome to book again at any time.",
+                        order_id, reason
+                    ),
+                    custom_payload: None,
+                })
+                .await;
+        }
+        Ok(expired)
+    }
+}
@@ -0,0 +1,212 @@
+use crate::infrastructure::db::repositories::personal_data::PersonalDataRepository;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::io::Write;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+/// A PDPA data-subject access export. Each top-level field becomes one file in the ZIP form.
+#[derive(Serialize)]
+pub struct PersonalDataExport {
+    pub exported_at: DateTime<Utc>,
+    pub profile: Value,
+    pub line_account: Value,
+    pub motorcycles: Value,
+    pub service_orders: Value,
+    pub feedbacks: Value,
+    pub notifications: Value,
+    pub sessions: Value,
+    pub maintenance_reminders: Value,
+    pub motorcycle_transfers: Value,
+    pub merged_guest_profiles: Value,
+    pub phone_verifications: Value,
+    pub loyalty_points: Value,
+    pub quotations: Value,
+}
+
+#[derive(Clone)]
+pub struct ExportPersonalDataUseCase {
+    personal_data_repo: PersonalDataRepository,
+}
+
+impl ExportPersonalDataUseCase {
+    pub fn new(personal_data_repo: PersonalDataRepository) -> Self {
+        Self { personal_data_repo }
+    }
+
+    pub async fn execute(&self, user_id: i32) -> Result<PersonalDataExport, String> {
+        let records = self
+            .personal_data_repo
+            .collect(user_id)
+            .await?
+            .ok_or("User not found".to_string())?;
+
+        // Credentials (password hash, token values) are never part of an export
+        let user = records.user;
+        let profile = json!({
+            "id": user.user_id,
+            "username": user.username,
+            "name": user.name,
+            "phone": user.phone,
+            "role": user.role,
+            "created_at": user.created_at,
+        });
+
+        let line_account = records
+            .line_account
+            .map(|acc| {
+                json!({
+                    "line_user_id": acc.line_user_id,
+                    "display_name": acc.display_name,
+                    "picture_url": acc.picture_url,
+                    "linked_at": acc.linked_at,
+                })
+            })
+            .unwrap_or(Value::Null);
+
+        let service_orders = records
+            .service_orders
+            .iter()
+            .map(|order| {
+                // What the shop paid for the parts is internal, so the
+                // lines are listed field by field
+                let items: Vec<_> = records
+                    .service_items
+                    .iter()
+                    .filter(|item| item.order_id == order.order_id)
+                    .map(|item| {
+                        json!({
+                            "item_id": item.item_id,
+                            "description": item.description,
+                            "price": item.price.to_string(),
+                            "quantity": item.quantity,
+                            "stock_item_id": item.stock_item_id,
+                        })
+                    })
+                    .collect();
+                json!({
+                    "order_id": order.order_id,
+                    "bike_id": order.bike_id,
+                    "status": order.status,
+                    "total_price": order.total_price.to_string(),
+                    "created_at": order.created_at,
+                    "before_picture_url": order.before_picture_url,
+                    "after_picture_url": order.after_picture_url,
+                    "items": items,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let sessions = records
+            .refresh_tokens
+            .iter()
+            .map(|t| {
+                json!({
+                    "created_at": t.created_at,
+                    "expires_at": t.expires_at,
+                    "is_revoked": t.is_revoked,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let merged_guest_profiles = records
+            .merged_guests
+            .iter()
+            .map(|guest| {
+                json!({
+                    "id": guest.user_id,
+                    "name": guest.name,
+                    "phone": guest.phone,
+                    "created_at": guest.created_at,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // The code itself is only ever stored hashed
+        let phone_verifications = records
+            .phone_verifications
+            .iter()
+            .map(|v| {
+                json!({
+                    "phone": v.phone,
+                    "attempts": v.attempts,
+                    "created_at": v.created_at,
+                    "expires_at": v.expires_at,
+                    "consumed_at": v.consumed_at,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let quotations = records
+            .quotations
+            .iter()
+            .map(|quotation| {
+                let lines: Vec<_> = records
+                    .quotation_lines
+                    .iter()
+                    .filter(|line| line.quotation_id == quotation.quotation_id)
+                    .map(|line| {
+                        json!({
+                            "line_id": line.line_id,
+                            "description": line.description,
+                            "price": line.price.to_string(),
+                            "quantity": line.quantity,
+                            "decision": line.decision,
+                        })
+                    })
+                    .collect();
+                json!({
+                    "quotation_id": quotation.quotation_id,
+                    "order_id": quotation.order_id,
+                    "version": quotation.version,
+                    "status": quotation.status,
+                    "total": quotation.total.to_string(),
+                    "approved_total": quotation.approved_total.as_ref().map(|t| t.to_string()),
+                    "notes": quotation.notes,
+                    "valid_until": quotation.valid_until,
+                    "responded_at": quotation.responded_at,
+                    "created_at": quotation.created_at,
+                    "lines": lines,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(PersonalDataExport {
+            exported_at: Utc::now(),
+            profile,
+            line_account,
+            motorcycles: json!(records.motorcycles),
+            service_orders: json!(service_orders),
+            feedbacks: json!(records.feedbacks),
+            notifications: json!(records.notifications),
+            sessions: json!(sessions),
+            maintenance_reminders: json!(records.maintenance_reminders),
+            motorcycle_transfers: json!(records.motorcycle_transfers),
+            merged_guest_profiles: json!(merged_guest_profiles),
+            phone_verifications: json!(phone_verifications),
+            loyalty_points: json!(records.loyalty_entries),
+            quotations: json!(quotations),
+        })
+    }
+}
+
+/// Packs an export as a ZIP with one pretty-printed JSON file per section
+pub fn export_to_zip(export: &PersonalDataExport) -> Result<Vec<u8>, String> {
+    let Value::Object(sections) = serde_json::to_value(export).map_err(|e| e.to_string())? else {
+        return Err("Export is not an object".to_string());
+    };
+
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+
+    for (name, content) in sections {
+        zip.start_file(format!("{}.json", name), options)
+            .map_err(|e| e.to_string())?;
+        let body = serde_json::to_vec_pretty(&content).map_err(|e| e.to_string())?;
+        zip.write_all(&body).map_err(|e| e.to_string())?;
+    }
+
+    let cursor = zip.finish().map_err(|e| e.to_string())?;
+    Ok(cursor.into_inner())
+}
@@ -1,6 +1,8 @@
 use crate::domain::service::entity::OrderStatus;
 use crate::infrastructure::db::repositories::motorcycle::MotorcycleRepository;
+use crate::infrastructure::db::repositories::service_item::ServiceItemRepository;
 use crate::infrastructure::db::repositories::service_order::ServiceOrderRepository;
+use crate::infrastructure::db::repositories::stock::StockItemRepository;
 use crate::infrastructure::db::repositories::user::UserRepository;
 use serde::Serialize;
 use std::collections::HashMap;
@@ -8,11 +10,20 @@ use std::collections::HashMap;
 #[derive(Debug, Serialize)]
 pub struct DashboardStatsResult {
     pub total_revenue: f64,
+    /// Cost of the parts used on the paid orders
+    pub cost_of_goods_sold: f64,
+    pub gross_margin: f64,
+    /// Gross margin as a share of revenue; absent without revenue
+    pub gross_margin_percent: Option<f64>,
+    /// Stock on hand at its average cost, as of now
+    pub inventory_value: f64,
     pub total_orders: usize,
     pub total_users: usize,
     pub status_distribution: HashMap<String, usize>,
     pub brand_distribution: HashMap<String, usize>,
     pub daily_stats: Vec<DailyStat>,
+    /// Paid orders, newest first
+    pub order_margins: Vec<OrderMargin>,
 }
 
 #[derive(Debug, Serialize)]
@@ -20,6 +31,21 @@ pub struct DailyStat {
     pub date: String,
     pub order_count: usize,
     pub revenue: f64,
+    pub cost_of_goods_sold: f64,
+    pub gross_margin: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrderMargin {
+    pub order_id: i32,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revenue: f64,
+    pub cost_of_goods_sold: f64,
+    pub gross_margin: f64,
+}
+
+fn margin_percent(revenue: f64, margin: f64) -> Option<f64> {
+    (revenue != 0.0).then(|| (margin / revenue * 10000.0).round() / 100.0)
 }
 
 #[derive(Clone)]
@@ -27,6 +53,8 @@ pub struct GetDashboardStatsUseCase {
     order_repo: ServiceOrderRepository,
     user_repo: UserRepository,
     motorcycle_repo: MotorcycleRepository,
+    service_item_repo: ServiceItemRepository,
+    stock_repo: StockItemRepository,
 }
 
 impl GetDashboardStatsUseCase {
@@ -34,16 +62,26 @@ impl GetDashboardStatsUseCase {
         order_repo: ServiceOrderRepository,
         user_repo: UserRepository,
         motorcycle_repo: MotorcycleRepository,
+        service_item_repo: ServiceItemRepository,
+        stock_repo: StockItemRepository,
     ) -> Self {
         Self {
             order_repo,
             user_repo,
             motorcycle_repo,
+            service_item_repo,
+            stock_repo,
         }
     }
 
-    pub async fn execute(&self, days: Option<i64>) -> Result<DashboardStatsResult, String> {
-        let all_orders = self.order_repo.list_orders().await?;
+    /// Figures for the whole shop, or only the given branches. Users and
+    /// bike brands aren't tied to a branch, so they always cover the shop.
+    pub async fn execute(
+        &self,
+        days: Option<i64>,
+        branch_ids: Option<Vec<i32>>,
+    ) -> Result<DashboardStatsResult, String> {
+        let all_orders = self.order_repo.list_orders(branch_ids.clone()).await?;
 
         let cutoff_date = days.map(|d| chrono::Utc::now() - chrono::Duration::days(d));
         let orders: Vec<_> = all_orders
@@ -63,11 +101,15 @@ impl GetDashboardStatsUseCase {
 
         let users = self.user_repo.list_users().await?;
         let motorcycles = self.motorcycle_repo.find_all().await?;
+        let parts_cost = self.service_item_repo.parts_cost_by_order().await?;
+        let inventory_value = self.stock_repo.inventory_value(branch_ids).await?;
 
         let mut total_revenue = 0.0;
+        let mut cost_of_goods_sold = 0.0;
+        let mut order_margins = Vec::new();
         let mut status_distribution = HashMap::new();
         let mut brand_distribution = HashMap::new();
-        let mut daily_map: HashMap<String, (usize, f64)> = HashMap::new();
+        let mut daily_map: HashMap<String, (usize, f64, f64)> = HashMap::new();
 
         for order in &orders {
             let date_key = order
@@ -75,12 +117,27 @@ impl GetDashboardStatsUseCase {
                 .map(|dt| dt.format("%Y-%m-%d").to_string())
                 .unwrap_or_else(|| "Unknown".to_string());
 
-            let day_entry = daily_map.entry(date_key).or_insert((0, 0.0));
+            let day_entry = daily_map.entry(date_key).or_insert((0, 0.0, 0.0));
             day_entry.0 += 1;
 
             if order.status == OrderStatus::Paid {
+                let cost = order
+                    .id
+                    .and_then(|id| parts_cost.get(&id).copied())
+                    .unwrap_or(0.0);
                 total_revenue += order.total_price;
+                cost_of_goods_sold += cost;
                 day_entry.1 += order.total_price;
+                day_entry.2 += cost;
+                if let Some(order_id) = order.id {
+                    order_margins.push(OrderMargin {
+                        order_id,
+                        created_at: order.created_at,
+                        revenue: order.total_price,
+                        cost_of_goods_sold: cost,
+                        gross_margin: order.total_price - cost,
+                    });
+                }
             }
 
             let status_key = format!("{:?}", order.status);
@@ -93,23 +150,32 @@ impl GetDashboardStatsUseCase {
 
         let mut daily_stats: Vec<DailyStat> = daily_map
             .into_iter()
-            .map(|(date, (order_count, revenue))| DailyStat {
+            .map(|(date, (order_count, revenue, cost))| DailyStat {
                 date,
                 order_count,
                 revenue,
+                cost_of_goods_sold: cost,
+                gross_margin: revenue - cost,
             })
             .collect();
 
         // Sort by date ascending
         daily_stats.sort_by(|a, b| a.date.cmp(&b.date));
+        order_margins.sort_by_key(|m| std::cmp::Reverse(m.created_at));
 
+        let gross_margin = total_revenue - cost_of_goods_sold;
         Ok(DashboardStatsResult {
             total_revenue,
+            cost_of_goods_sold,
+            gross_margin,
+            gross_margin_percent: margin_percent(total_revenue, gross_margin),
+            inventory_value,
             total_orders: orders.len(),
             total_users: users.len(),
             status_distribution,
             brand_distribution,
             daily_stats,
+            order_margins,
         })
     }
 }
@@ -0,0 +1,128 @@
+use crate::domain::service::entity::OrderStatus;
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::models::{MotorcycleModel, ServiceOrderStatusEnum};
+use crate::infrastructure::db::repositories::motorcycle::MotorcycleRepository;
+use crate::infrastructure::db::repositories::repair_log::RepairLogRepository;
+use crate::infrastructure::db::repositories::service_order::ServiceOrderRepository;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct ServiceHistoryEvent {
+    pub at: DateTime<Utc>,
+    pub status: ServiceOrderStatusEnum,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServiceHistoryItem {
+    pub description: String,
+    pub quantity: i32,
+    pub price: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServiceHistoryEntry {
+    pub order_id: i32,
+    pub opened_at: Option<DateTime<Utc>>,
+    pub status: OrderStatus,
+    pub odometer_km: Option<i32>,
+    pub total_price: Option<f64>,
+    /// Set when the work was ordered by a previous owner; prices and
+    /// mechanic notes are withheld from the current owner
+    pub previous_owner: bool,
+    pub items: Vec<ServiceHistoryItem>,
+    pub events: Vec<ServiceHistoryEvent>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MotorcycleHistory {
+    pub motorcycle: MotorcycleModel,
+    pub entries: Vec<ServiceHistoryEntry>,
+}
+
+pub struct GetMotorcycleHistoryUseCase {
+    bike_repo: MotorcycleRepository,
+    order_repo: ServiceOrderRepository,
+    repair_log_repo: RepairLogRepository,
+}
+
+impl GetMotorcycleHistoryUseCase {
+    pub fn new(
+        bike_repo: MotorcycleRepository,
+        order_repo: ServiceOrderRepository,
+        repair_log_repo: RepairLogRepository,
+    ) -> Self {
+        Self {
+            bike_repo,
+            order_repo,
+            repair_log_repo,
+        }
+    }
+
+    /// Chronological service history of a bike: each order with its line items
+    /// and the status changes mechanics logged against it. Customers see what
+    /// was done under previous owners, but not what it cost or mechanic notes,
+    /// and not orders that were cancelled.
+    pub async fn execute(
+        &self,
+        bike_id: i32,
+        viewer_id: i32,
+        viewer_role: &Role,
+    ) -> Result<MotorcycleHistory, String> {
+        let motorcycle = self
+            .bike_repo
+            .find_by_id(bike_id)
+            .await?
+            .filter(|b| *viewer_role != Role::Customer || b.user_id == viewer_id)
+            .ok_or("Motorcycle not found".to_string())?;
+
+        let orders = self.order_repo.list_orders_for_bike(bike_id).await?;
+        let mut logs = self.repair_log_repo.get_logs_for_bike(bike_id).await?;
+
+        let entries = orders
+            .into_iter()
+            .filter_map(|order| {
+                let order_id = order.id?;
+                let previous_owner = order.customer_id != motorcycle.user_id;
+                let redacted = previous_owner && *viewer_role == Role::Customer;
+                if redacted && order.status == OrderStatus::Cancelled {
+                    return None;
+                }
+
+                let events = logs
+                    .extract_if(.., |l| l.order_id == order_id)
+                    .map(|l| ServiceHistoryEvent {
+                        at: l.updated_at,
+                        status: l.status,
+                        note: (!redacted).then_some(l.note),
+                    })
+                    .collect();
+                let items = order
+                    .items
+                    .into_iter()
+                    .map(|item| ServiceHistoryItem {
+                        description: item.description,
+                        quantity: item.quantity,
+                        price: (!redacted).then_some(item.price),
+                    })
+                    .collect();
+                Some(ServiceHistoryEntry {
+                    order_id,
+                    opened_at: order.created_at,
+                    status: order.status,
+                    odometer_km: order.odometer_km,
+                    total_price: (!redacted).then_some(order.total_price),
+                    previous_owner,
+                    items,
+                    events,
+                })
+            })
+            .collect();
+
+        Ok(MotorcycleHistory {
+            motorcycle,
+            entries,
+        })
+    }
+}
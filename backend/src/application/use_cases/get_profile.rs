@@ -1,6 +1,7 @@
 use crate::domain::user::entity::Role;
 use crate::infrastructure::db::repositories::user::UserRepository;
 use crate::infrastructure::db::repositories::user_line_account::UserLineAccountRepository;
+use crate::infrastructure::db::repositories::user_mfa::UserMfaRepository;
 use serde::Serialize;
 
 #[derive(Serialize)]
@@ -12,18 +13,25 @@ pub struct ProfileResult {
     pub role: Role,
     pub line_connected: bool,
     pub avatar_url: Option<String>,
+    pub mfa_enabled: bool,
 }
 
 pub struct GetProfileUseCase {
     user_repo: UserRepository,
     line_repo: UserLineAccountRepository,
+    mfa_repo: UserMfaRepository,
 }
 
 impl GetProfileUseCase {
-    pub fn new(user_repo: UserRepository, line_repo: UserLineAccountRepository) -> Self {
+    pub fn new(
+        user_repo: UserRepository,
+        line_repo: UserLineAccountRepository,
+        mfa_repo: UserMfaRepository,
+    ) -> Self {
         Self {
             user_repo,
             line_repo,
+            mfa_repo,
         }
     }
 
@@ -35,6 +43,7 @@ impl GetProfileUseCase {
             .ok_or("User not found".to_string())?;
 
         let line_account = self.line_repo.find_by_user_id(user_id).await?;
+        let mfa = self.mfa_repo.find_by_user_id(user_id).await?;
 
         Ok(ProfileResult {
             id: user.id.unwrap_or(user_id),
@@ -44,6 +53,7 @@ impl GetProfileUseCase {
             role: user.role,
             line_connected: line_account.is_some(),
             avatar_url: line_account.and_then(|a| a.picture_url),
+            mfa_enabled: mfa.is_some_and(|m| m.enabled),
         })
     }
 }
@@ -0,0 +1,46 @@
+use crate::application::use_cases::branch::BranchUseCase;
+use crate::domain::service::stock_entity::{StockItem, is_valid_barcode};
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::repositories::stock::StockItemRepository;
+
+/// Resolves a scanned EAN/UPC code to the stock item it's printed on
+pub struct GetStockItemByBarcodeUseCase {
+    stock_repo: StockItemRepository,
+    branch_use_case: BranchUseCase,
+}
+
+impl GetStockItemByBarcodeUseCase {
+    pub fn new(stock_repo: StockItemRepository, branch_use_case: BranchUseCase) -> Self {
+        Self {
+            stock_repo,
+            branch_use_case,
+        }
+    }
+
+    /// Quantities are the shop's totals, or what the given branch, or else
+    /// the branches the user works at, hold
+    pub async fn execute(
+        &self,
+        code: &str,
+        branch_id: Option<i32>,
+        user_id: i32,
+        role: &Role,
+    ) -> Result<Option<StockItem>, String> {
+        let code = code.trim();
+        if !is_valid_barcode(code) {
+            return Err("Not a valid EAN or UPC barcode".to_string());
+        }
+        let scope = self.branch_use_case.scope(user_id, role, branch_id).await?;
+        let Some(item) = self.stock_repo.find_by_barcode(code).await? else {
+            return Ok(None);
+        };
+        match scope {
+            Some(branch_ids) => Ok(self
+                .stock_repo
+                .at_branches(vec![item], &branch_ids)
+                .await?
+                .pop()),
+            None => Ok(Some(item)),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::infrastructure::db::repositories::audit_event::{
+    AuditEventFilter, AuditEventRepository,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct AuditEventQuery {
+    pub actor_id: Option<i32>,
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct AuditEventResponse {
+    pub id: i64,
+    pub actor_id: Option<i32>,
+    pub actor_username: Option<String>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub reason: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct ListAuditEventsUseCase {
+    audit_repo: AuditEventRepository,
+}
+
+impl ListAuditEventsUseCase {
+    pub fn new(audit_repo: AuditEventRepository) -> Self {
+        Self { audit_repo }
+    }
+
+    pub async fn execute(&self, query: AuditEventQuery) -> Result<Vec<AuditEventResponse>, String> {
+        let filter = AuditEventFilter {
+            actor_id: query.actor_id,
+            action: query.action,
+            target_type: query.target_type,
+            target_id: query.target_id,
+            from: query.from,
+            to: query.to,
+            limit: query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+            offset: query.offset.unwrap_or(0).max(0),
+        };
+
+        let events = self.audit_repo.list(filter).await?;
+
+        Ok(events
+            .into_iter()
+            .map(|(e, actor_username)| AuditEventResponse {
+                id: e.event_id,
+                actor_id: e.actor_id,
+                actor_username,
+                action: e.action,
+                target_type: e.target_type,
+                target_id: e.target_id,
+                before: e.before_state,
+                after: e.after_state,
+                reason: e.reason,
+                ip_address: e.ip_address,
+                created_at: e.created_at,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,26 @@
+use crate::infrastructure::db::models::{ErasureRequestModel, ErasureRequestStatusEnum};
+use crate::infrastructure::db::repositories::erasure_request::ErasureRequestRepository;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct ErasureRequestQuery {
+    pub status: Option<ErasureRequestStatusEnum>,
+}
+
+#[derive(Clone)]
+pub struct ListErasureRequestsUseCase {
+    erasure_repo: ErasureRequestRepository,
+}
+
+impl ListErasureRequestsUseCase {
+    pub fn new(erasure_repo: ErasureRequestRepository) -> Self {
+        Self { erasure_repo }
+    }
+
+    pub async fn execute(
+        &self,
+        query: ErasureRequestQuery,
+    ) -> Result<Vec<ErasureRequestModel>, String> {
+        self.erasure_repo.list(query.status).await
+    }
+}
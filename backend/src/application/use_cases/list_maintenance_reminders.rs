@@ -0,0 +1,52 @@
+use crate::infrastructure::db::repositories::maintenance::{
+    MaintenanceReminderView, MaintenanceRepository,
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+
+const DEFAULT_SNOOZE_DAYS: i64 = 7;
+const MAX_SNOOZE_DAYS: i64 = 90;
+
+#[derive(Debug, Deserialize)]
+pub struct SnoozeReminderCommand {
+    pub days: Option<i64>,
+}
+
+pub struct ListMaintenanceRemindersUseCase {
+    maintenance_repo: MaintenanceRepository,
+}
+
+impl ListMaintenanceRemindersUseCase {
+    pub fn new(maintenance_repo: MaintenanceRepository) -> Self {
+        Self { maintenance_repo }
+    }
+
+    pub async fn execute(&self, user_id: i32) -> Result<Vec<MaintenanceReminderView>, String> {
+        self.maintenance_repo.list_for_user(user_id).await
+    }
+
+    pub async fn snooze(
+        &self,
+        user_id: i32,
+        reminder_id: i32,
+        command: SnoozeReminderCommand,
+    ) -> Result<(), String> {
+        let days = command.days.unwrap_or(DEFAULT_SNOOZE_DAYS);
+        if !(1..=MAX_SNOOZE_DAYS).contains(&days) {
+            return Err(format!(
+                "Reminders can be snoozed for 1 to {} days",
+                MAX_SNOOZE_DAYS
+            ));
+        }
+
+        let until = Utc::now() + Duration::days(days);
+        if !self
+            .maintenance_repo
+            .snooze(reminder_id, user_id, until)
+            .await?
+        {
+            return Err("Reminder not found".to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+use crate::infrastructure::db::models::MotorcycleModel;
+use crate::infrastructure::db::repositories::motorcycle::MotorcycleRepository;
+
+pub struct ListMotorcyclesUseCase {
+    bike_repo: MotorcycleRepository,
+}
+
+impl ListMotorcyclesUseCase {
+    pub fn new(bike_repo: MotorcycleRepository) -> Self {
+        Self { bike_repo }
+    }
+
+    pub async fn execute(&self, user_id: i32) -> Result<Vec<MotorcycleModel>, String> {
+        self.bike_repo.find_by_user(user_id).await
+    }
+}
@@ -0,0 +1,16 @@
+use crate::infrastructure::db::models::ScheduledJobModel;
+use crate::infrastructure::db::repositories::scheduled_job::ScheduledJobRepository;
+
+pub struct ListScheduledJobsUseCase {
+    job_repo: ScheduledJobRepository,
+}
+
+impl ListScheduledJobsUseCase {
+    pub fn new(job_repo: ScheduledJobRepository) -> Self {
+        Self { job_repo }
+    }
+
+    pub async fn execute(&self) -> Result<Vec<ScheduledJobModel>, String> {
+        self.job_repo.list().await
+    }
+}
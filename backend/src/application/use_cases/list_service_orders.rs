@@ -7,6 +7,7 @@ pub struct ServiceOrderResponse {
     pub id: i32,
     pub bike_id: Option<i32>,
     pub customer_id: i32,
+    pub branch_id: i32,
     pub status: OrderStatus,
     pub total_price: f64,
 }
@@ -31,8 +32,12 @@ impl ListServiceOrdersUseCase {
         Ok(orders.into_iter().map(Self::map_to_response).collect())
     }
 
-    pub async fn execute_all(&self) -> Result<Vec<ServiceOrderResponse>, String> {
-        let orders = self.order_repo.list_orders().await?;
+    /// Every order, or only those at the given branches
+    pub async fn execute_all(
+        &self,
+        branch_ids: Option<Vec<i32>>,
+    ) -> Result<Vec<ServiceOrderResponse>, String> {
+        let orders = self.order_repo.list_orders(branch_ids).await?;
         Ok(orders.into_iter().map(Self::map_to_response).collect())
     }
 
@@ -41,6 +46,7 @@ impl ListServiceOrdersUseCase {
             id: order.id.unwrap_or(0),
             bike_id: order.bike_id,
             customer_id: order.customer_id,
+            branch_id: order.branch_id,
             status: order.status,
             total_price: order.total_price,
         }
@@ -10,7 +10,13 @@ impl ListStockItemsUseCase {
         Self { stock_repo }
     }
 
-    pub async fn execute(&self) -> Result<Vec<StockItem>, String> {
-        self.stock_repo.list_stock_items().await
+    /// Quantities are the shop's totals, or what the given branches hold
+    /// between them
+    pub async fn execute(&self, branch_ids: Option<Vec<i32>>) -> Result<Vec<StockItem>, String> {
+        let items = self.stock_repo.list_stock_items().await?;
+        match branch_ids {
+            Some(branch_ids) => self.stock_repo.at_branches(items, &branch_ids).await,
+            None => Ok(items),
+        }
     }
 }
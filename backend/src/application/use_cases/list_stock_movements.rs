@@ -0,0 +1,99 @@
+use crate::application::use_cases::branch::BranchUseCase;
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::models::{StockMovementModel, StockMovementReasonEnum};
+use crate::infrastructure::db::repositories::stock::StockItemRepository;
+use crate::infrastructure::db::repositories::stock_movement::StockMovementRepository;
+use bigdecimal::ToPrimitive;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct StockMovementQuery {
+    pub limit: Option<i64>,
+    pub branch_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StockMovement {
+    pub movement_id: i32,
+    pub stock_item_id: i32,
+    pub branch_id: i32,
+    pub delta: i32,
+    pub reason: StockMovementReasonEnum,
+    pub order_id: Option<i32>,
+    pub actor_id: Option<i32>,
+    pub note: Option<String>,
+    /// What the branch held afterwards
+    pub quantity_after: i32,
+    /// Cost each unit moved at; absent on movements from before costing
+    pub unit_cost: Option<f64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<StockMovementModel> for StockMovement {
+    fn from(movement: StockMovementModel) -> Self {
+        Self {
+            movement_id: movement.movement_id,
+            stock_item_id: movement.stock_item_id,
+            branch_id: movement.branch_id,
+            delta: movement.delta,
+            reason: movement.reason,
+            order_id: movement.order_id,
+            actor_id: movement.actor_id,
+            note: movement.note,
+            quantity_after: movement.quantity_after,
+            unit_cost: movement.unit_cost.and_then(|c| c.to_f64()),
+            created_at: movement.created_at,
+        }
+    }
+}
+
+pub struct ListStockMovementsUseCase {
+    movement_repo: StockMovementRepository,
+    stock_repo: StockItemRepository,
+    branch_use_case: BranchUseCase,
+}
+
+impl ListStockMovementsUseCase {
+    pub fn new(
+        movement_repo: StockMovementRepository,
+        stock_repo: StockItemRepository,
+        branch_use_case: BranchUseCase,
+    ) -> Self {
+        Self {
+            movement_repo,
+            stock_repo,
+            branch_use_case,
+        }
+    }
+
+    /// Newest movements first, at the branches the user may see
+    pub async fn execute(
+        &self,
+        stock_item_id: i32,
+        query: StockMovementQuery,
+        user_id: i32,
+        role: &Role,
+    ) -> Result<Vec<StockMovement>, String> {
+        let branch_ids = self
+            .branch_use_case
+            .scope(user_id, role, query.branch_id)
+            .await?;
+        self.stock_repo
+            .find_by_id(stock_item_id)
+            .await?
+            .ok_or("Stock item not found")?;
+
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        Ok(self
+            .movement_repo
+            .list_for_item(stock_item_id, branch_ids, limit)
+            .await?
+            .into_iter()
+            .map(StockMovement::from)
+            .collect())
+    }
+}
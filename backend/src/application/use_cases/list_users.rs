@@ -1,4 +1,4 @@
-use crate::domain::user::entity::Role;
+use crate::domain::user::entity::{Role, User};
 use crate::infrastructure::db::repositories::user::UserRepository;
 use serde::Serialize;
 
@@ -9,6 +9,22 @@ pub struct UserResponse {
     pub name: String,
     pub phone: String,
     pub role: Role,
+    pub is_active: bool,
+    pub is_guest: bool,
+}
+
+impl From<User> for UserResponse {
+    fn from(u: User) -> Self {
+        Self {
+            id: u.id.unwrap_or(0),
+            username: u.username,
+            name: u.name,
+            phone: u.phone,
+            role: u.role,
+            is_active: u.is_active,
+            is_guest: u.is_guest,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -24,15 +40,6 @@ impl ListUsersUseCase {
     pub async fn execute(&self) -> Result<Vec<UserResponse>, String> {
         let users = self.user_repo.list_users().await?;
 
-        Ok(users
-            .into_iter()
-            .map(|u| UserResponse {
-                id: u.id.unwrap_or(0),
-                username: u.username,
-                name: u.name,
-                phone: u.phone,
-                role: u.role,
-            })
-            .collect())
+        Ok(users.into_iter().map(UserResponse::from).collect())
     }
 }
@@ -1,14 +1,21 @@
 use crate::domain::user::entity::Role;
+use crate::domain::user::entity::User;
 use crate::infrastructure::db::repositories::refresh_token::RefreshTokenRepository;
 use crate::infrastructure::db::repositories::user::UserRepository;
 use crate::infrastructure::db::repositories::user_line_account::UserLineAccountRepository;
+use crate::infrastructure::db::repositories::user_mfa::UserMfaRepository;
 use crate::infrastructure::external::notification::line::LineNotificationGateway;
 use crate::infrastructure::security::jwt::service::JwtService;
-use crate::infrastructure::security::password::verify_password;
+use crate::infrastructure::security::mfa::{normalize_recovery_code, verify_totp_code};
+use crate::infrastructure::security::password::{hash_password, verify_password};
 use chrono::{Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+/// Wrong second-step codes allowed before the step locks
+const MAX_MFA_ATTEMPTS: i32 = 5;
+const MFA_LOCKOUT_MINUTES: i64 = 15;
+
 #[derive(Debug, Deserialize)]
 pub struct LoginCommand {
     pub username: String,
@@ -27,13 +34,56 @@ pub struct LoginResult {
     pub phone: String,
 }
 
+/// Returned instead of tokens when the password was right but a second factor
+/// is still needed. `enrollment_required` means the account must set up an
+/// authenticator first (mandatory MFA) via the /auth/mfa/enroll flow.
+#[derive(Debug, Serialize)]
+pub struct MfaChallenge {
+    pub mfa_required: bool,
+    pub enrollment_required: bool,
+    pub challenge_token: String,
+}
+
+/// Returned for accounts still on an admin-issued one-time password. The token
+/// can only be used with /auth/password/change.
+#[derive(Debug, Serialize)]
+pub struct PasswordChangeChallenge {
+    pub password_change_required: bool,
+    pub challenge_token: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Authenticated(LoginResult),
+    MfaChallenge(MfaChallenge),
+    PasswordChangeRequired(PasswordChangeChallenge),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompletePasswordChangeCommand {
+    pub challenge_token: String,
+    pub new_password: String,
+}
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyMfaCommand {
+    pub challenge_token: String,
+    // Either a 6-digit authenticator code or one of the recovery codes
+    pub code: String,
+}
+
 #[derive(Clone)]
 pub struct LoginUseCase {
     user_repository: UserRepository,
     line_repository: UserLineAccountRepository,
     refresh_token_repository: RefreshTokenRepository,
+    mfa_repository: UserMfaRepository,
     jwt_service: JwtService,
     line_gateway: Arc<LineNotificationGateway>,
+    mfa_required_for_admins: bool,
 }
 
 impl LoginUseCase {
@@ -41,19 +91,23 @@ impl LoginUseCase {
         user_repository: UserRepository,
         line_repository: UserLineAccountRepository,
         refresh_token_repository: RefreshTokenRepository,
+        mfa_repository: UserMfaRepository,
         jwt_service: JwtService,
         line_gateway: Arc<LineNotificationGateway>,
+        mfa_required_for_admins: bool,
     ) -> Self {
         Self {
             user_repository,
             line_repository,
             refresh_token_repository,
+            mfa_repository,
             jwt_service,
             line_gateway,
+            mfa_required_for_admins,
         }
     }
 
-    pub async fn execute(&self, command: LoginCommand) -> Result<LoginResult, String> {
+    pub async fn execute(&self, command: LoginCommand) -> Result<LoginResponse, String> {
         // 1. Find user
         let user = self
             .user_repository
@@ -61,13 +115,200 @@ impl LoginUseCase {
             .await?
             .ok_or("Invalid username or password".to_string())?;
 
-        // 2. Verify password
-        if !verify_password(&user.password_hash, &command.password)? {
+        // 2. Verify password (guests have none and can never sign in)
+        if user.is_guest
+            || !verify_password(&user.password_hash, &command.password).unwrap_or(false)
+        {
             return Err("Invalid username or password".to_string());
         }
 
-        // 3. Generate Tokens
+        self.continue_login(user).await
+    }
+
+    /// Replaces a one-time password, then carries on with the rest of the login
+    pub async fn complete_password_change(
+        &self,
+        command: CompletePasswordChangeCommand,
+    ) -> Result<LoginResponse, String> {
+        let claims = self
+            .jwt_service
+            .verify_password_change_token(&command.challenge_token)?
+            .claims;
+
+        if command.new_password.chars().count() < MIN_PASSWORD_LENGTH {
+            return Err(format!(
+                "Password must be at least {} characters",
+                MIN_PASSWORD_LENGTH
+            ));
+        }
+
+        let user = self
+            .user_repository
+            .find_by_id(claims.user_id)
+            .await?
+            .ok_or("User not found".to_string())?;
+        // The token is only good while the change is still pending, so it
+        // can't be replayed to reset the password again
+        if !user.is_active {
+            return Err("This account has been deactivated".to_string());
+        }
+        if !user.must_change_password {
+            return Err("Password has already been changed".to_string());
+        }
+        if verify_password(&user.password_hash, &command.new_password).unwrap_or(false) {
+            return Err("New password must differ from the one-time password".to_string());
+        }
+
+        let password_hash = hash_password(&command.new_password)?;
+        if !self
+            .user_repository
+            .replace_one_time_password(claims.user_id, &password_hash)
+            .await?
+        {
+            return Err("Password has already been changed".to_string());
+        }
+
+        let user = self
+            .user_repository
+            .find_by_id(claims.user_id)
+            .await?
+            .ok_or("User not found".to_string())?;
+        self.continue_login(user).await
+    }
+
+    /// Everything after the password check: account state, forced password
+    /// change, then the second factor
+    async fn continue_login(&self, user: User) -> Result<LoginResponse, String> {
         let user_id = user.id.ok_or("User has no ID")?;
+
+        if !user.is_active {
+            return Err("This account has been deactivated".to_string());
+        }
+
+        if user.must_change_password {
+            let challenge_token = self.jwt_service.generate_password_change_token(
+                user_id,
+                &user.username,
+                user.role.clone(),
+            )?;
+            return Ok(LoginResponse::PasswordChangeRequired(
+                PasswordChangeChallenge {
+                    password_change_required: true,
+                    challenge_token,
+                },
+            ));
+        }
+
+        // Second factor, if enrolled or mandatory for this role
+        let mfa_enabled = self
+            .mfa_repository
+            .find_by_user_id(user_id)
+            .await?
+            .is_some_and(|m| m.enabled);
+        let enrollment_required =
+            !mfa_enabled && self.mfa_required_for_admins && user.role == Role::Admin;
+
+        if mfa_enabled || enrollment_required {
+            let challenge_token =
+                self.jwt_service
+                    .generate_mfa_token(user_id, &user.username, user.role.clone())?;
+            return Ok(LoginResponse::MfaChallenge(MfaChallenge {
+                mfa_required: true,
+                enrollment_required,
+                challenge_token,
+            }));
+        }
+
+        Ok(LoginResponse::Authenticated(
+            self.issue_session(user_id, user).await?,
+        ))
+    }
+
+    /// Second half of an MFA login: trades the challenge token plus a code for a session
+    pub async fn verify_mfa(&self, command: VerifyMfaCommand) -> Result<LoginResult, String> {
+        let claims = self
+            .jwt_service
+            .verify_mfa_token(&command.challenge_token)?
+            .claims;
+
+        let mfa = self
+            .mfa_repository
+            .find_by_user_id(claims.user_id)
+            .await?
+            .filter(|m| m.enabled)
+            .ok_or("Two-factor authentication is not enabled".to_string())?;
+
+        // Counted up front, so neither the code nor the recovery code check
+        // runs once too many have failed
+        if !self
+            .mfa_repository
+            .record_attempt(
+                claims.user_id,
+                MAX_MFA_ATTEMPTS,
+                Duration::minutes(MFA_LOCKOUT_MINUTES),
+                Utc::now(),
+            )
+            .await?
+        {
+            return Err(format!(
+                "Too many wrong codes. Please try again in {} minutes",
+                MFA_LOCKOUT_MINUTES
+            ));
+        }
+
+        if let Some(step) = verify_totp_code(&mfa.totp_secret, &command.code)? {
+            if !self
+                .mfa_repository
+                .consume_step(claims.user_id, step)
+                .await?
+            {
+                return Err("Verification code has already been used".to_string());
+            }
+        } else if !self
+            .redeem_recovery_code(claims.user_id, &command.code)
+            .await?
+        {
+            return Err("Invalid verification code".to_string());
+        }
+        self.mfa_repository.clear_attempts(claims.user_id).await?;
+
+        self.start_session(claims.user_id).await
+    }
+
+    /// Issues tokens for a user whose second factor has already been checked
+    pub async fn start_session(&self, user_id: i32) -> Result<LoginResult, String> {
+        let user = self
+            .user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or("User not found".to_string())?;
+
+        if !user.is_active {
+            return Err("This account has been deactivated".to_string());
+        }
+
+        self.issue_session(user_id, user).await
+    }
+
+    async fn redeem_recovery_code(&self, user_id: i32, code: &str) -> Result<bool, String> {
+        let code = normalize_recovery_code(code);
+        for candidate in self
+            .mfa_repository
+            .find_unused_recovery_codes(user_id)
+            .await?
+        {
+            if verify_password(&candidate.code_hash, &code)? {
+                return self
+                    .mfa_repository
+                    .mark_recovery_code_used(candidate.code_id)
+                    .await;
+            }
+        }
+        Ok(false)
+    }
+
+    async fn issue_session(&self, user_id: i32, user: User) -> Result<LoginResult, String> {
+        // Generate Tokens
         let token = self
             .jwt_service
             .generate_token(user_id, &user.username, user.role.clone())?;
@@ -76,13 +317,13 @@ impl LoginUseCase {
             self.jwt_service
                 .generate_refresh_token(user_id, &user.username, user.role.clone())?;
 
-        // 4. Save Refresh Token (expires in 7 days by default)
+        // Save Refresh Token (expires in 7 days by default)
         let expires_at = Utc::now() + Duration::days(7);
         self.refresh_token_repository
             .create_token(user_id, refresh_token_value.clone(), expires_at)
             .await?;
 
-        // 5. Get LINE profile if available
+        // Get LINE profile if available
         let line_account = self
             .line_repository
             .find_by_user_id(user_id)
@@ -94,19 +335,18 @@ impl LoginUseCase {
             .and_then(|acc| acc.picture_url.clone());
 
         // Proactive sync: if we have a line_user_id but no avatar, try to fetch it
-        if let Some(acc) = line_account {
-            if acc.picture_url.is_none() {
-                if let Ok(profile) = self.line_gateway.get_profile(&acc.line_user_id).await {
-                    let dn = profile["displayName"].as_str().map(|s| s.to_string());
-                    let pu = profile["pictureUrl"].as_str().map(|s| s.to_string());
-                    // Update DB with latest profile
-                    let _ = self
-                        .line_repository
-                        .link_account(user_id, acc.line_user_id, dn, pu.clone())
-                        .await;
-                    avatar_url = pu;
-                }
-            }
+        if let Some(acc) = line_account
+            && acc.picture_url.is_none()
+            && let Ok(profile) = self.line_gateway.get_profile(&acc.line_user_id).await
+        {
+            let dn = profile["displayName"].as_str().map(|s| s.to_string());
+            let pu = profile["pictureUrl"].as_str().map(|s| s.to_string());
+            // Update DB with latest profile
+            let _ = self
+                .line_repository
+                .link_account(user_id, acc.line_user_id, dn, pu.clone())
+                .await;
+            avatar_url = pu;
         }
 
         Ok(LoginResult {
@@ -0,0 +1,154 @@
+use crate::domain::notification::gateway::{NotificationGateway, NotificationMessage};
+use crate::infrastructure::db::repositories::stock::{LowStockItem, StockItemRepository};
+use crate::infrastructure::db::repositories::user::UserRepository;
+use crate::infrastructure::db::repositories::user_line_account::UserLineAccountRepository;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Keeps admins aware of parts running out: an alert as soon as a stock change
+/// takes an item to its reorder point, and a daily digest of everything still low.
+/// Reorder points are shop-wide, so both go by the shop's total; a branch can
+/// run dry while the total is above the point, which the report's per-branch
+/// quantities show.
+#[derive(Clone)]
+pub struct LowStockUseCase {
+    stock_repo: StockItemRepository,
+    user_repo: UserRepository,
+    line_repo: UserLineAccountRepository,
+    notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
+    /// One dispatch at a time, so an alert isn't sent twice while it waits to
+    /// be marked
+    dispatching: Arc<Mutex<()>>,
+}
+
+impl LowStockUseCase {
+    pub fn new(
+        stock_repo: StockItemRepository,
+        user_repo: UserRepository,
+        line_repo: UserLineAccountRepository,
+        notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
+    ) -> Self {
+        Self {
+            stock_repo,
+            user_repo,
+            line_repo,
+            notification_gateway,
+            dispatching: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// The low items, with what the given branches, or all of them, hold
+    pub async fn report(&self, branch_ids: Option<Vec<i32>>) -> Result<Vec<LowStockItem>, String> {
+        self.stock_repo.find_low_stock(branch_ids).await
+    }
+
+    /// Sends the alerts queued by recent stock changes, marking each once it
+    /// has gone out. An alert nobody could be sent stays queued for the next
+    /// try. Returns how many went out.
+    pub async fn dispatch_alerts(&self) -> Result<usize, String> {
+        let _guard = self.dispatching.lock().await;
+
+        let mut sent = 0;
+        for alert in self.stock_repo.pending_alerts().await? {
+            let reorder = alert
+                .reorder_quantity
+                .map(|q| format!(" Reorder {} to restock.", q))
+                .unwrap_or_default();
+            self.notify_admins(
+                format!("⚠️ Low Stock | {}", alert.name),
+                format!(
+                    "{} is down to {} (reorder point {}).{}",
+                    alert.name, alert.quantity, alert.reorder_point, reorder
+                ),
+            )
+            .await?;
+            if self.stock_repo.mark_alert_sent(alert.alert_id).await? {
+                sent += 1;
+            }
+        }
+        Ok(sent)
+    }
+
+    /// One message listing every item at or below its reorder point. Returns
+    /// how many items it listed; nothing is sent when none are low.
+    pub async fn send_digest(&self) -> Result<usize, String> {
+        let items = self.report(None).await?;
+        if items.is_empty() {
+            return Ok(0);
+        }
+
+        let lines: Vec<String> = items
+            .iter()
+            .map(|item| {
+                let on_order = if item.on_order > 0 {
+                    format!(", {} on order", item.on_order)
+                } else {
+                    String::new()
+                };
+                format!(
+                    "• {}: {} left (reorder point {}{})",
+                    item.name, item.quantity, item.reorder_point, on_order
+                )
+            })
+            .collect();
+        self.notify_admins(
+            format!("📦 Low Stock Digest | {} items", items.len()),
+            lines.join("\n"),
+        )
+        .await?;
+        Ok(items.len())
+    }
+
+    /// Sends the queued alerts without holding up the request that took the
+    /// stock. Logs rather than fails: that stock change has already been saved.
+    pub fn dispatch_alerts_in_background(&self) {
+        let low_stock = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = low_stock.dispatch_alerts().await {
+                tracing::error!("Failed to send low-stock alerts: {}", e);
+            }
+        });
+    }
+
+    /// Fails only when no admin could be reached; a single failed send is
+    /// logged and the rest still go out
+    async fn notify_admins(&self, title: String, body: String) -> Result<(), String> {
+        let mut last_error = None;
+        let mut delivered = false;
+        for admin in self.user_repo.find_admins().await? {
+            let Some(admin_id) = admin.id else {
+                continue;
+            };
+            let recipient = self
+                .line_repo
+                .find_by_user_id(admin_id)
+                .await
+                .ok()
+                .flatten()
+                .map(|l| l.line_user_id)
+                .unwrap_or_default();
+            match self
+                .notification_gateway
+                .send_notification(NotificationMessage {
+                    user_id: admin_id,
+                    order_id: None,
+                    recipient,
+                    title: title.clone(),
+                    body: body.clone(),
+                    custom_payload: None,
+                })
+                .await
+            {
+                Ok(()) => delivered = true,
+                Err(e) => {
+                    tracing::error!("Failed to notify admin {}: {}", admin_id, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        match last_error {
+            Some(e) if !delivered => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use crate::domain::audit::entity::LOYALTY_ADJUSTED;
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::loyalty::LoyaltyTier;
+use crate::domain::loyalty::entity::{POINT_VALUE_BAHT, POINTS_VALID_MONTHS, TIER_WINDOW_MONTHS};
+use crate::domain::service::entity::ServiceOrder;
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::models::{LoyaltyEntryKindEnum, LoyaltyEntryModel, NewLoyaltyEntry};
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::loyalty::{LoyaltyRepository, NewRedemption};
+use crate::infrastructure::db::repositories::service_order::ServiceOrderRepository;
+use crate::infrastructure::db::repositories::user::UserRepository;
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use chrono::{DateTime, Duration, Months, Utc};
+use serde::{Deserialize, Serialize};
+
+const RECENT_ENTRIES: i64 = 50;
+const EXPIRING_SOON_DAYS: i64 = 30;
+
+#[derive(Debug, Deserialize)]
+pub struct RedeemPointsCommand {
+    pub points: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdjustPointsCommand {
+    /// Positive to credit, negative to take points away
+    pub points: i32,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoyaltyEntry {
+    pub entry_id: i32,
+    pub kind: LoyaltyEntryKindEnum,
+    pub points: i32,
+    pub remaining: i32,
+    pub spend: Option<f64>,
+    pub order_id: Option<i32>,
+    pub reason: Option<String>,
+    pub created_by: Option<i32>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub reversed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<LoyaltyEntryModel> for LoyaltyEntry {
+    fn from(model: LoyaltyEntryModel) -> Self {
+        Self {
+            entry_id: model.entry_id,
+            kind: model.kind,
+            points: model.points,
+            remaining: model.remaining,
+            spend: model.spend.and_then(|s| s.to_f64()),
+            order_id: model.order_id,
+            reason: model.reason,
+            created_by: model.created_by,
+            expires_at: model.expires_at,
+            reversed_at: model.reversed_at,
+            created_at: model.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExpiringPoints {
+    pub points: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoyaltySummary {
+    pub user_id: i32,
+    pub balance: i64,
+    /// What the balance is worth as a discount, in baht
+    pub balance_value: f64,
+    pub tier: LoyaltyTier,
+    pub perks: Vec<&'static str>,
+    /// Paid spend over the tier window
+    pub rolling_spend: f64,
+    pub next_tier: Option<LoyaltyTier>,
+    pub spend_to_next_tier: Option<f64>,
+    pub expiring_soon: Vec<ExpiringPoints>,
+    pub entries: Vec<LoyaltyEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RedemptionResult {
+    pub order_id: i32,
+    pub points: i32,
+    pub discount: f64,
+    pub balance: i64,
+}
+
+/// Points ledger: earning on paid orders, spending them as order discounts,
+/// tiers from rolling spend, expiry and manual adjustments. Other use cases
+/// hold a clone to award and refund points as orders move.
+#[derive(Clone)]
+pub struct LoyaltyUseCase {
+    loyalty_repo: LoyaltyRepository,
+    order_repo: ServiceOrderRepository,
+    user_repo: UserRepository,
+    audit_repo: AuditEventRepository,
+}
+
+impl LoyaltyUseCase {
+    pub fn new(
+        loyalty_repo: LoyaltyRepository,
+        order_repo: ServiceOrderRepository,
+        user_repo: UserRepository,
+        audit_repo: AuditEventRepository,
+    ) -> Self {
+        Self {
+            loyalty_repo,
+            order_repo,
+            user_repo,
+            audit_repo,
+        }
+    }
+
+    pub async fn summary(&self, user_id: i32) -> Result<LoyaltySummary, String> {
+        let now = Utc::now();
+        let balance = self.loyalty_repo.balance(user_id, now).await?;
+        let rolling_spend = self.rolling_spend(user_id, now).await?;
+        let tier = LoyaltyTier::for_spend(rolling_spend);
+        let next_tier = tier.next();
+
+        let expiring_soon = self
+            .loyalty_repo
+            .expiring_credits(user_id, now, now + Duration::days(EXPIRING_SOON_DAYS))
+            .await?
+            .into_iter()
+            .map(|credit| ExpiringPoints {
+                points: credit.remaining,
+                expires_at: credit.expires_at,
+            })
+            .collect();
+
+        Ok(LoyaltySummary {
+            user_id,
+            balance,
+            balance_value: balance as f64 * POINT_VALUE_BAHT,
+            tier,
+            perks: tier.perks(),
+            rolling_spend,
+            next_tier,
+            spend_to_next_tier: next_tier.map(|next| next.min_spend() - rolling_spend),
+            expiring_soon,
+            entries: self
+                .loyalty_repo
+                .list_entries(user_id, RECENT_ENTRIES)
+                .await?
+                .into_iter()
+                .map(LoyaltyEntry::from)
+                .collect(),
+        })
+    }
+
+    /// Staff view of a customer's points
+    pub async fn summary_for(&self, user_id: i32) -> Result<LoyaltySummary, String> {
+        self.user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or("User not found")?;
+        self.summary(user_id).await
+    }
+
+    /// Spends points on an open order. Customers redeem on their own orders;
+    /// staff can redeem at the counter on the customer's behalf.
+    pub async fn redeem(
+        &self,
+        order_id: i32,
+        command: RedeemPointsCommand,
+        user_id: i32,
+        role: Role,
+    ) -> Result<RedemptionResult, String> {
+        if command.points <= 0 {
+            return Err("Points must be greater than zero".to_string());
+        }
+
+        let order = self
+            .order_repo
+            .find_by_id(order_id)
+            .await?
+            .ok_or("Order not found")?;
+        if role == Role::Customer && order.customer_id != user_id {
+            return Err("Access denied: Not your order".to_string());
+        }
+
+        let discount = command.points as f64 * POINT_VALUE_BAHT;
+        self.loyalty_repo
+            .redeem(NewRedemption {
+                user_id: order.customer_id,
+                order_id,
+                points: command.points,
+                discount,
+                description: format!("Loyalty discount ({} points)", command.points),
+            })
+            .await?;
+
+        Ok(RedemptionResult {
+            order_id,
+            points: command.points,
+            discount,
+            balance: self
+                .loyalty_repo
+                .balance(order.customer_id, Utc::now())
+                .await?,
+        })
+    }
+
+    /// Manual credit or debit with a recorded reason
+    pub async fn adjust(
+        &self,
+        user_id: i32,
+        command: AdjustPointsCommand,
+        actor: &AuditActor,
+    ) -> Result<LoyaltyEntry, String> {
+        let reason = command.reason.trim();
+        if reason.is_empty() {
+            return Err("A reason is required".to_string());
+        }
+        if command.points == 0 {
+            return Err("Points must not be zero".to_string());
+        }
+        self.user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or("User not found")?;
+
+        let entry = self
+            .loyalty_repo
+            .adjust(NewLoyaltyEntry {
+                user_id,
+                kind: LoyaltyEntryKindEnum::Adjusted,
+                points: command.points,
+                remaining: command.points.max(0),
+                spend: None,
+                order_id: None,
+                service_item_id: None,
+                reason: Some(reason.to_string()),
+                created_by: Some(actor.user_id),
+                expires_at: points_expiry(Utc::now()),
+            })
+            .await?;
+
+        let event = AuditEvent::new(actor, LOYALTY_ADJUSTED, "user", user_id)
+            .with_snapshot(&entry)
+            .with_reason(reason);
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        Ok(entry.into())
+    }
+
+    /// Credits points for a paid order at the tier the customer held before
+    /// it. Safe to call more than once for the same order.
+    pub async fn award_for_order(&self, order: &ServiceOrder) -> Result<i32, String> {
+        let Some(order_id) = order.id else {
+            return Ok(0);
+        };
+        let now = Utc::now();
+        let tier = LoyaltyTier::for_spend(self.rolling_spend(order.customer_id, now).await?);
+        let points = tier.points_for(order.total_price);
+        if points == 0 {
+            return Ok(0);
+        }
+
+        let awarded = self
+            .loyalty_repo
+            .earn(NewLoyaltyEntry {
+                user_id: order.customer_id,
+                kind: LoyaltyEntryKindEnum::Earned,
+                points,
+                remaining: points,
+                spend: BigDecimal::from_f64(order.total_price),
+                order_id: Some(order_id),
+                service_item_id: None,
+                reason: None,
+                created_by: None,
+                expires_at: points_expiry(now),
+            })
+            .await?;
+
+        Ok(if awarded { points } else { 0 })
+    }
+
+    /// Gives back points spent on an order that won't be paid
+    pub async fn refund_order(&self, order_id: i32) -> Result<i32, String> {
+        self.loyalty_repo.refund_order(order_id).await
+    }
+
+    pub async fn expire_points(&self) -> Result<i64, String> {
+        self.loyalty_repo.expire_due(Utc::now()).await
+    }
+
+    async fn rolling_spend(&self, user_id: i32, now: DateTime<Utc>) -> Result<f64, String> {
+        let since = now
+            .checked_sub_months(Months::new(TIER_WINDOW_MONTHS))
+            .unwrap_or(now);
+        self.loyalty_repo.spend_since(user_id, since).await
+    }
+}
+
+fn points_expiry(now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    now.checked_add_months(Months::new(POINTS_VALID_MONTHS))
+}
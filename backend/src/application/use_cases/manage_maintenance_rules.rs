@@ -0,0 +1,106 @@
+use crate::domain::audit::entity::{MAINTENANCE_RULE_CREATED, MAINTENANCE_RULE_UPDATED};
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::infrastructure::db::models::{MaintenanceRuleChangeset, MaintenanceRuleModel};
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::maintenance::MaintenanceRepository;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct MaintenanceRuleCommand {
+    pub name: String,
+    pub match_keyword: String,
+    pub interval_months: Option<i32>,
+    pub interval_km: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+impl MaintenanceRuleCommand {
+    fn into_changeset(self) -> Result<MaintenanceRuleChangeset, String> {
+        let name = self.name.trim().to_string();
+        let match_keyword = self.match_keyword.trim().to_string();
+        if name.is_empty() || match_keyword.is_empty() {
+            return Err("Name and match keyword are required".to_string());
+        }
+        if self.interval_months.is_none() && self.interval_km.is_none() {
+            return Err("Set an interval in months, kilometres or both".to_string());
+        }
+        if self.interval_months.is_some_and(|m| m <= 0) || self.interval_km.is_some_and(|k| k <= 0)
+        {
+            return Err("Intervals must be positive".to_string());
+        }
+
+        Ok(MaintenanceRuleChangeset {
+            name,
+            match_keyword,
+            interval_months: self.interval_months,
+            interval_km: self.interval_km,
+            is_active: self.is_active.unwrap_or(true),
+        })
+    }
+}
+
+pub struct ManageMaintenanceRulesUseCase {
+    maintenance_repo: MaintenanceRepository,
+    audit_repo: AuditEventRepository,
+}
+
+impl ManageMaintenanceRulesUseCase {
+    pub fn new(maintenance_repo: MaintenanceRepository, audit_repo: AuditEventRepository) -> Self {
+        Self {
+            maintenance_repo,
+            audit_repo,
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<MaintenanceRuleModel>, String> {
+        self.maintenance_repo.list_rules().await
+    }
+
+    pub async fn create(
+        &self,
+        command: MaintenanceRuleCommand,
+        actor: &AuditActor,
+    ) -> Result<MaintenanceRuleModel, String> {
+        let rule = self
+            .maintenance_repo
+            .create_rule(command.into_changeset()?)
+            .await?;
+
+        let event = AuditEvent::new(
+            actor,
+            MAINTENANCE_RULE_CREATED,
+            "maintenance_rule",
+            rule.rule_id,
+        )
+        .with_change(&serde_json::json!({}), &serde_json::json!(rule));
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        Ok(rule)
+    }
+
+    pub async fn update(
+        &self,
+        rule_id: i32,
+        command: MaintenanceRuleCommand,
+        actor: &AuditActor,
+    ) -> Result<MaintenanceRuleModel, String> {
+        let changes = command.into_changeset()?;
+        let previous = self
+            .maintenance_repo
+            .find_rule(rule_id)
+            .await?
+            .ok_or("Maintenance rule not found")?;
+
+        let updated = self.maintenance_repo.update_rule(rule_id, changes).await?;
+
+        let event = AuditEvent::new(actor, MAINTENANCE_RULE_UPDATED, "maintenance_rule", rule_id)
+            .with_change(&previous, &updated);
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        Ok(updated)
+    }
+}
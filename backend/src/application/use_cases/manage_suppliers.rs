@@ -0,0 +1,118 @@
+use crate::application::input::optional_text;
+use crate::domain::audit::entity::{SUPPLIER_CREATED, SUPPLIER_UPDATED};
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::infrastructure::db::models::{SupplierChangeset, SupplierModel};
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::supplier::SupplierRepository;
+use serde::Deserialize;
+
+const DEFAULT_LEAD_TIME_DAYS: i32 = 7;
+
+#[derive(Debug, Deserialize)]
+pub struct SupplierCommand {
+    pub name: String,
+    pub contact_name: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub notes: Option<String>,
+    pub lead_time_days: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+impl SupplierCommand {
+    fn into_changeset(self) -> Result<SupplierChangeset, String> {
+        let name = self.name.trim().to_string();
+        if name.is_empty() {
+            return Err("Supplier name is required".to_string());
+        }
+        let lead_time_days = self.lead_time_days.unwrap_or(DEFAULT_LEAD_TIME_DAYS);
+        if lead_time_days < 0 {
+            return Err("Lead time cannot be negative".to_string());
+        }
+
+        Ok(SupplierChangeset {
+            name,
+            contact_name: optional_text(self.contact_name),
+            phone: optional_text(self.phone),
+            email: optional_text(self.email),
+            notes: optional_text(self.notes),
+            lead_time_days,
+            is_active: self.is_active.unwrap_or(true),
+        })
+    }
+}
+
+pub struct ManageSuppliersUseCase {
+    supplier_repo: SupplierRepository,
+    audit_repo: AuditEventRepository,
+}
+
+impl ManageSuppliersUseCase {
+    pub fn new(supplier_repo: SupplierRepository, audit_repo: AuditEventRepository) -> Self {
+        Self {
+            supplier_repo,
+            audit_repo,
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<SupplierModel>, String> {
+        self.supplier_repo.list().await
+    }
+
+    pub async fn create(
+        &self,
+        command: SupplierCommand,
+        actor: &AuditActor,
+    ) -> Result<SupplierModel, String> {
+        let changes = command.into_changeset()?;
+        if self
+            .supplier_repo
+            .find_by_name(&changes.name)
+            .await?
+            .is_some()
+        {
+            return Err("A supplier with this name already exists".to_string());
+        }
+        let supplier = self.supplier_repo.create(changes).await?;
+
+        let event = AuditEvent::new(actor, SUPPLIER_CREATED, "supplier", supplier.supplier_id)
+            .with_change(&serde_json::json!({}), &serde_json::json!(supplier));
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        Ok(supplier)
+    }
+
+    pub async fn update(
+        &self,
+        supplier_id: i32,
+        command: SupplierCommand,
+        actor: &AuditActor,
+    ) -> Result<SupplierModel, String> {
+        let changes = command.into_changeset()?;
+        let previous = self
+            .supplier_repo
+            .find_by_id(supplier_id)
+            .await?
+            .ok_or("Supplier not found")?;
+        if self
+            .supplier_repo
+            .find_by_name(&changes.name)
+            .await?
+            .is_some_and(|other| other.supplier_id != supplier_id)
+        {
+            return Err("A supplier with this name already exists".to_string());
+        }
+
+        let updated = self.supplier_repo.update(supplier_id, changes).await?;
+
+        let event = AuditEvent::new(actor, SUPPLIER_UPDATED, "supplier", supplier_id)
+            .with_change(&previous, &updated);
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        Ok(updated)
+    }
+}
@@ -1,31 +1,68 @@
 pub mod add_service_item;
 pub mod add_stock_item;
+pub mod branch;
+pub mod check_stock_consistency;
 pub mod checkout_cart;
+pub mod confirm_mfa;
 pub mod connect_line;
+pub mod create_guest_customer;
 pub mod create_service_order;
+pub mod create_staff;
 pub mod delete_feedback;
+pub mod delete_motorcycle;
 pub mod delete_service_order;
 pub mod delete_stock_item;
+pub mod disable_mfa;
 pub mod disconnect_line;
+pub mod enroll_mfa;
+pub mod expire_stale_orders;
+pub mod export_personal_data;
 pub mod get_dashboard_stats;
+pub mod get_motorcycle_history;
 pub mod get_profile;
 pub mod get_service_order_detail;
+pub mod get_stock_item_by_barcode;
+pub mod list_audit_events;
+pub mod list_erasure_requests;
 pub mod list_feedbacks;
+pub mod list_maintenance_reminders;
+pub mod list_motorcycles;
 pub mod list_notifications;
+pub mod list_scheduled_jobs;
 pub mod list_service_orders;
 pub mod list_stock_items;
+pub mod list_stock_movements;
 pub mod list_users;
 pub mod login;
 pub mod logout;
+pub mod low_stock;
+pub mod loyalty;
+pub mod manage_maintenance_rules;
+pub mod manage_suppliers;
 pub mod mark_notification_read;
+pub mod part_compatibility;
 pub mod process_payment;
 pub mod promote_user;
+pub mod purchase_order;
+pub mod quotation;
 pub mod refresh_token;
+pub mod register_motorcycle;
 pub mod register_user;
 pub mod remove_service_item;
+pub mod request_erasure;
+pub mod review_erasure_request;
+pub mod search;
+pub mod send_maintenance_reminders;
+pub mod set_user_active;
+pub mod stock_catalogue;
+pub mod stock_take;
+pub mod stock_transfer;
 pub mod submit_feedback;
+pub mod transfer_motorcycle;
+pub mod update_motorcycle;
 pub mod update_order_photos;
 pub mod update_order_status;
 pub mod update_profile;
 pub mod update_stock_item;
 pub mod use_stock_item;
+pub mod verify_phone;
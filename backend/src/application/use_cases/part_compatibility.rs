@@ -0,0 +1,250 @@
+use crate::domain::audit::entity::STOCK_ITEM_COMPATIBILITY_CHANGED;
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::service::entity::ServiceOrder;
+use crate::domain::service::{CompatibilityTag, Fit};
+use crate::infrastructure::db::models::MotorcycleModel;
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::motorcycle::MotorcycleRepository;
+use crate::infrastructure::db::repositories::part_compatibility::PartCompatibilityRepository;
+use crate::infrastructure::db::repositories::service_order::ServiceOrderRepository;
+use crate::infrastructure::db::repositories::stock::StockItemRepository;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+pub struct SetCompatibilityCommand {
+    /// Leave empty when the part isn't tied to any motorcycle
+    pub tags: Vec<CompatibilityTag>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompatibilityResponse {
+    pub stock_item_id: i32,
+    pub tags: Vec<CompatibilityTag>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SuggestedPart {
+    pub stock_item_id: i32,
+    pub name: String,
+    pub sku: Option<String>,
+    pub brand: Option<String>,
+    pub category: Option<String>,
+    pub price: f64,
+    /// Free to use at the order's branch
+    pub available_quantity: i32,
+    pub shelf_location: Option<String>,
+    pub fit: Fit,
+    /// The tag that matched, e.g. "Honda Wave 110i 2015-2020"
+    pub matched: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SuggestedPartsResponse {
+    pub order_id: i32,
+    pub bike_id: i32,
+    pub brand: String,
+    pub model: String,
+    pub year: Option<i32>,
+    /// Closest fit first, then parts in stock before those that aren't
+    pub parts: Vec<SuggestedPart>,
+}
+
+/// Which motorcycles each part fits, and using that to help fill an order
+#[derive(Clone)]
+pub struct PartCompatibilityUseCase {
+    compatibility_repo: PartCompatibilityRepository,
+    stock_repo: StockItemRepository,
+    order_repo: ServiceOrderRepository,
+    motorcycle_repo: MotorcycleRepository,
+    audit_repo: AuditEventRepository,
+}
+
+impl PartCompatibilityUseCase {
+    pub fn new(
+        compatibility_repo: PartCompatibilityRepository,
+        stock_repo: StockItemRepository,
+        order_repo: ServiceOrderRepository,
+        motorcycle_repo: MotorcycleRepository,
+        audit_repo: AuditEventRepository,
+    ) -> Self {
+        Self {
+            compatibility_repo,
+            stock_repo,
+            order_repo,
+            motorcycle_repo,
+            audit_repo,
+        }
+    }
+
+    pub async fn tags(&self, stock_item_id: i32) -> Result<CompatibilityResponse, String> {
+        self.stock_repo
+            .find_by_id(stock_item_id)
+            .await?
+            .ok_or("Stock item not found")?;
+
+        Ok(CompatibilityResponse {
+            stock_item_id,
+            tags: self.compatibility_repo.list_for_item(stock_item_id).await?,
+        })
+    }
+
+    pub async fn set_tags(
+        &self,
+        stock_item_id: i32,
+        command: SetCompatibilityCommand,
+        actor: &AuditActor,
+    ) -> Result<CompatibilityResponse, String> {
+        self.stock_repo
+            .find_by_id(stock_item_id)
+            .await?
+            .ok_or("Stock item not found")?;
+
+        let mut tags: Vec<CompatibilityTag> = Vec::with_capacity(command.tags.len());
+        for tag in command.tags {
+            let tag = tag.normalized()?;
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        let previous = self.compatibility_repo.list_for_item(stock_item_id).await?;
+        self.compatibility_repo
+            .replace(stock_item_id, &tags)
+            .await?;
+
+        let event = AuditEvent::new(
+            actor,
+            STOCK_ITEM_COMPATIBILITY_CHANGED,
+            "stock_item",
+            stock_item_id,
+        )
+        .with_change(
+            &serde_json::json!({ "tags": previous }),
+            &serde_json::json!({ "tags": tags }),
+        );
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        Ok(CompatibilityResponse {
+            stock_item_id,
+            tags,
+        })
+    }
+
+    async fn order_with_bike(
+        &self,
+        order_id: i32,
+    ) -> Result<(ServiceOrder, Option<MotorcycleModel>), String> {
+        let order = self
+            .order_repo
+            .find_by_id(order_id)
+            .await?
+            .ok_or_else(|| format!("Service order {} not found", order_id))?;
+        let bike = match order.bike_id {
+            Some(bike_id) => self.motorcycle_repo.find_by_id(bike_id).await?,
+            None => None,
+        };
+        Ok((order, bike))
+    }
+
+    /// Parts tagged as fitting the order's motorcycle, with what the order's
+    /// branch has free. Untagged parts aren't suggested.
+    pub async fn suggest(&self, order_id: i32) -> Result<SuggestedPartsResponse, String> {
+        let (order, bike) = self.order_with_bike(order_id).await?;
+        let bike = bike.ok_or("This order has no motorcycle to match parts against")?;
+
+        let mut best: HashMap<i32, (Fit, CompatibilityTag)> = HashMap::new();
+        for (stock_item_id, tag) in self.compatibility_repo.list_for_brand(&bike.brand).await? {
+            let Some(fit) = tag.fit(&bike.brand, &bike.model, bike.year) else {
+                continue;
+            };
+            if best
+                .get(&stock_item_id)
+                .is_none_or(|(known, _)| fit > *known)
+            {
+                best.insert(stock_item_id, (fit, tag));
+            }
+        }
+
+        let item_ids: Vec<i32> = best.keys().copied().collect();
+        let items = self.stock_repo.find_by_ids(&item_ids).await?;
+        let mut parts: Vec<SuggestedPart> = self
+            .stock_repo
+            .at_branch(items, order.branch_id)
+            .await?
+            .into_iter()
+            .filter_map(|item| {
+                let id = item.id?;
+                let (fit, tag) = best.remove(&id)?;
+                Some(SuggestedPart {
+                    stock_item_id: id,
+                    name: item.name,
+                    sku: item.sku,
+                    brand: item.brand,
+                    category: item.category,
+                    price: item.price,
+                    available_quantity: item.available_quantity,
+                    shelf_location: item.shelf_location,
+                    fit,
+                    matched: tag.describe(),
+                })
+            })
+            .collect();
+        parts.sort_by(|a, b| {
+            b.fit
+                .cmp(&a.fit)
+                .then_with(|| (b.available_quantity > 0).cmp(&(a.available_quantity > 0)))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        Ok(SuggestedPartsResponse {
+            order_id,
+            bike_id: bike.bike_id,
+            brand: bike.brand,
+            model: bike.model,
+            year: bike.year,
+            parts,
+        })
+    }
+
+    /// A warning when the part is tagged for other motorcycles than the
+    /// order's. Nothing to say about untagged parts or orders without a bike.
+    pub async fn incompatibility_warning(
+        &self,
+        order_id: i32,
+        stock_item_id: i32,
+    ) -> Result<Option<String>, String> {
+        let (_, bike) = self.order_with_bike(order_id).await?;
+        let Some(bike) = bike else {
+            return Ok(None);
+        };
+        let tags = self.compatibility_repo.list_for_item(stock_item_id).await?;
+        if tags.is_empty()
+            || tags
+                .iter()
+                .any(|tag| tag.fit(&bike.brand, &bike.model, bike.year).is_some())
+        {
+            return Ok(None);
+        }
+
+        let name = self
+            .stock_repo
+            .find_by_id(stock_item_id)
+            .await?
+            .map(|item| item.name)
+            .unwrap_or_else(|| format!("Stock item {}", stock_item_id));
+        let fits: Vec<String> = tags.iter().map(CompatibilityTag::describe).collect();
+        let bike_name = match bike.year {
+            Some(year) => format!("{} {} {}", bike.brand, bike.model, year),
+            None => format!("{} {}", bike.brand, bike.model),
+        };
+        Ok(Some(format!(
+            "{} is listed for {}, not this order's {}",
+            name,
+            fits.join(", "),
+            bike_name
+        )))
+    }
+}
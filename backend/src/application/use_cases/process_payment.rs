@@ -1,3 +1,4 @@
+use crate::application::use_cases::loyalty::LoyaltyUseCase;
 use crate::domain::notification::gateway::{NotificationGateway, NotificationMessage};
 use crate::domain::payment::gateway::PaymentGateway;
 use crate::domain::service::entity::OrderStatus;
@@ -30,6 +31,7 @@ pub struct ProcessPaymentUseCase {
     pub payment_gateway: Arc<dyn PaymentGateway + Send + Sync>,
     pub notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
     pub repair_log_repo: RepairLogRepository,
+    pub loyalty: LoyaltyUseCase,
 }
 
 impl ProcessPaymentUseCase {
@@ -40,6 +42,7 @@ impl ProcessPaymentUseCase {
         payment_gateway: Arc<dyn PaymentGateway + Send + Sync>,
         notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
         repair_log_repo: RepairLogRepository,
+        loyalty: LoyaltyUseCase,
     ) -> Self {
         Self {
             service_order_repo,
@@ -48,6 +51,7 @@ impl ProcessPaymentUseCase {
             payment_gateway,
             notification_gateway,
             repair_log_repo,
+            loyalty,
         }
     }
 
@@ -84,7 +88,9 @@ impl ProcessPaymentUseCase {
         if is_successful {
             // 3. Update order status
             order.status = OrderStatus::Paid;
-            self.service_order_repo.update_order(order.clone()).await?;
+            self.service_order_repo
+                .update_order(order.clone(), Some(order.customer_id))
+                .await?;
 
             // New: Log the status change
             let _ = self
@@ -97,6 +103,10 @@ impl ProcessPaymentUseCase {
                 )
                 .await;
 
+            if let Err(e) = self.loyalty.award_for_order(&order).await {
+                tracing::error!("Failed to award loyalty points: {}", e);
+            }
+
             // 4. Fire notifications in background
             let self_clone = self.clone();
             let order_clone = order.clone();
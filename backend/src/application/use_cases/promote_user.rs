@@ -1,6 +1,12 @@
+use crate::domain::audit::entity::USER_ROLE_CHANGED;
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::notification::gateway::{NotificationGateway, NotificationMessage};
 use crate::domain::user::entity::Role;
+use crate::infrastructure::db::repositories::branch::BranchRepository;
 use crate::infrastructure::db::repositories::user::UserRepository;
+use crate::infrastructure::db::repositories::user_line_account::UserLineAccountRepository;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
 pub struct PromoteUserCommand {
@@ -18,27 +24,91 @@ pub struct PromoteUserResult {
 #[derive(Clone)]
 pub struct PromoteUserUseCase {
     user_repository: UserRepository,
+    line_repository: UserLineAccountRepository,
+    branch_repository: BranchRepository,
+    notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
 }
 
 impl PromoteUserUseCase {
-    pub fn new(user_repository: UserRepository) -> Self {
-        Self { user_repository }
+    pub fn new(
+        user_repository: UserRepository,
+        line_repository: UserLineAccountRepository,
+        branch_repository: BranchRepository,
+        notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
+    ) -> Self {
+        Self {
+            user_repository,
+            line_repository,
+            branch_repository,
+            notification_gateway,
+        }
     }
 
-    pub async fn execute(&self, command: PromoteUserCommand) -> Result<PromoteUserResult, String> {
+    pub async fn execute(
+        &self,
+        command: PromoteUserCommand,
+        actor: &AuditActor,
+    ) -> Result<PromoteUserResult, String> {
         // 1. Find user by ID
-        let mut user = self
+        let user = self
             .user_repository
             .find_by_id(command.user_id)
             .await?
             .ok_or("User not found".to_string())?;
 
-        // 2. Update role
-        // Simple update: just change the role field.
-        user.role = command.target_role;
+        let previous_role = user.role.clone();
+        if previous_role == command.target_role {
+            return Err(format!("User already has the {:?} role", previous_role));
+        }
 
-        // 3. Save changes
-        let updated_user = self.user_repository.update_user(user).await?;
+        // 2. Guard: an admin can't demote themselves
+        if previous_role == Role::Admin && command.user_id == actor.user_id {
+            return Err("You cannot remove your own admin role".to_string());
+        }
+
+        // 3. Save the change together with its audit event; refused if it
+        // would leave the shop without an active admin
+        let event = AuditEvent::new(actor, USER_ROLE_CHANGED, "user", command.user_id).with_change(
+            &serde_json::json!({ "role": previous_role }),
+            &serde_json::json!({ "role": command.target_role }),
+        );
+        let updated_user = self
+            .user_repository
+            .change_role(command.user_id, command.target_role, event)
+            .await?;
+        // New staff start at the default branch
+        if updated_user.role != Role::Customer {
+            self.branch_repository
+                .assign_default_branch(command.user_id)
+                .await?;
+        }
+
+        // 4. Let the user know; their current session keeps the old role until it refreshes
+        let recipient = self
+            .line_repository
+            .find_by_user_id(command.user_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|l| l.line_user_id)
+            .unwrap_or_default();
+        if let Err(e) = self
+            .notification_gateway
+            .send_notification(NotificationMessage {
+                user_id: command.user_id,
+                order_id: None,
+                recipient,
+                title: "Account role updated".to_string(),
+                body: format!(
+                    "Your role has been changed from {:?} to {:?}.",
+                    previous_role, updated_user.role
+                ),
+                custom_payload: None,
+            })
+            .await
+        {
+            tracing::warn!("Failed to send role change notification: {}", e);
+        }
 
         Ok(PromoteUserResult {
             user_id: updated_user.id.unwrap_or(0),
@@ -0,0 +1,406 @@
+use crate::application::input::optional_text;
+use crate::application::use_cases::branch::BranchUseCase;
+use crate::domain::audit::entity::{GOODS_RECEIVED, PURCHASE_ORDER_CANCELLED};
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::models::{
+    PurchaseOrderLineModel, PurchaseOrderModel, PurchaseOrderStatusEnum,
+};
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::purchase_order::{
+    GoodsReceiptDetail, PurchaseOrderDetail, PurchaseOrderDraft, PurchaseOrderLineInput,
+    PurchaseOrderRepository, ReceiptLineInput, ReorderSuggestion,
+};
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_USAGE_DAYS: i32 = 30;
+const DEFAULT_COVER_DAYS: i32 = 14;
+const MAX_DAYS: i32 = 365;
+
+#[derive(Debug, Deserialize)]
+pub struct PurchaseOrderLineCommand {
+    pub stock_item_id: i32,
+    pub quantity: i32,
+    pub unit_cost: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PurchaseOrderCommand {
+    pub supplier_id: i32,
+    /// Where the goods go; leave out for your own branch, or to keep the
+    /// branch when editing
+    pub branch_id: Option<i32>,
+    pub expected_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub lines: Vec<PurchaseOrderLineCommand>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReceiveLineCommand {
+    pub line_id: i32,
+    pub quantity: i32,
+    /// Leave out to use the cost agreed on the PO
+    pub unit_cost: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReceiveGoodsCommand {
+    pub note: Option<String>,
+    pub lines: Vec<ReceiveLineCommand>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CancelPurchaseOrderCommand {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PurchaseOrderQuery {
+    pub status: Option<PurchaseOrderStatusEnum>,
+    pub branch_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReorderQuery {
+    /// How far back to look at usage
+    pub usage_days: Option<i32>,
+    /// How long the reorder should last once it arrives
+    pub cover_days: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PurchaseOrderLine {
+    pub line_id: i32,
+    pub stock_item_id: i32,
+    pub quantity_ordered: i32,
+    pub quantity_received: i32,
+    pub unit_cost: f64,
+}
+
+impl From<PurchaseOrderLineModel> for PurchaseOrderLine {
+    fn from(line: PurchaseOrderLineModel) -> Self {
+        Self {
+            line_id: line.line_id,
+            stock_item_id: line.stock_item_id,
+            quantity_ordered: line.quantity_ordered,
+            quantity_received: line.quantity_received,
+            unit_cost: line.unit_cost.to_f64().unwrap_or(0.0),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoodsReceiptLine {
+    pub receipt_line_id: i32,
+    pub po_line_id: i32,
+    pub stock_item_id: i32,
+    pub quantity: i32,
+    pub unit_cost: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoodsReceipt {
+    pub receipt_id: i32,
+    pub po_id: i32,
+    pub received_by: Option<i32>,
+    pub note: Option<String>,
+    pub received_at: DateTime<Utc>,
+    pub lines: Vec<GoodsReceiptLine>,
+}
+
+impl From<GoodsReceiptDetail> for GoodsReceipt {
+    fn from(detail: GoodsReceiptDetail) -> Self {
+        Self {
+            receipt_id: detail.receipt.receipt_id,
+            po_id: detail.receipt.po_id,
+            received_by: detail.receipt.received_by,
+            note: detail.receipt.note,
+            received_at: detail.receipt.received_at,
+            lines: detail
+                .lines
+                .into_iter()
+                .map(|line| GoodsReceiptLine {
+                    receipt_line_id: line.receipt_line_id,
+                    po_line_id: line.po_line_id,
+                    stock_item_id: line.stock_item_id,
+                    quantity: line.quantity,
+                    unit_cost: line.unit_cost.to_f64().unwrap_or(0.0),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PurchaseOrderResponse {
+    pub po_id: i32,
+    pub supplier_id: i32,
+    pub branch_id: i32,
+    pub status: PurchaseOrderStatusEnum,
+    pub expected_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub created_by: Option<i32>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Ordered quantities at the agreed costs
+    pub total_cost: f64,
+    pub lines: Vec<PurchaseOrderLine>,
+    pub receipts: Vec<GoodsReceipt>,
+}
+
+impl From<PurchaseOrderDetail> for PurchaseOrderResponse {
+    fn from(detail: PurchaseOrderDetail) -> Self {
+        let order = detail.order;
+        let total_cost: BigDecimal = detail
+            .lines
+            .iter()
+            .map(|line| &line.unit_cost * BigDecimal::from(line.quantity_ordered))
+            .sum();
+        Self {
+            po_id: order.po_id,
+            supplier_id: order.supplier_id,
+            branch_id: order.branch_id,
+            status: order.status,
+            expected_date: order.expected_date,
+            notes: order.notes,
+            created_by: order.created_by,
+            sent_at: order.sent_at,
+            closed_at: order.closed_at,
+            created_at: order.created_at,
+            updated_at: order.updated_at,
+            total_cost: total_cost.to_f64().unwrap_or(0.0),
+            lines: detail
+                .lines
+                .into_iter()
+                .map(PurchaseOrderLine::from)
+                .collect(),
+            receipts: detail
+                .receipts
+                .into_iter()
+                .map(GoodsReceipt::from)
+                .collect(),
+        }
+    }
+}
+
+fn to_cost(value: f64) -> Result<BigDecimal, String> {
+    if !value.is_finite() || value < 0.0 {
+        return Err("Unit cost must be zero or more".to_string());
+    }
+    BigDecimal::from_f64(value)
+        .map(|cost| cost.round(2))
+        .ok_or_else(|| "Invalid unit cost".to_string())
+}
+
+impl PurchaseOrderCommand {
+    fn into_draft(self, branch_id: i32) -> Result<PurchaseOrderDraft, String> {
+        let lines = self
+            .lines
+            .into_iter()
+            .map(|line| {
+                Ok(PurchaseOrderLineInput {
+                    stock_item_id: line.stock_item_id,
+                    quantity: line.quantity,
+                    unit_cost: to_cost(line.unit_cost)?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(PurchaseOrderDraft {
+            supplier_id: self.supplier_id,
+            branch_id,
+            expected_date: self.expected_date,
+            notes: optional_text(self.notes),
+            lines,
+        })
+    }
+}
+
+/// Buying stock from suppliers: draft, send, receive deliveries against it
+/// (which books the stock in), or cancel what's still outstanding
+pub struct PurchaseOrderUseCase {
+    purchase_order_repo: PurchaseOrderRepository,
+    audit_repo: AuditEventRepository,
+    branch_use_case: BranchUseCase,
+}
+
+impl PurchaseOrderUseCase {
+    pub fn new(
+        purchase_order_repo: PurchaseOrderRepository,
+        audit_repo: AuditEventRepository,
+        branch_use_case: BranchUseCase,
+    ) -> Self {
+        Self {
+            purchase_order_repo,
+            audit_repo,
+            branch_use_case,
+        }
+    }
+
+    /// POs for the branches the user may see
+    pub async fn list(
+        &self,
+        query: PurchaseOrderQuery,
+        user_id: i32,
+        role: &Role,
+    ) -> Result<Vec<PurchaseOrderModel>, String> {
+        let branch_ids = self
+            .branch_use_case
+            .scope(user_id, role, query.branch_id)
+            .await?;
+        self.purchase_order_repo
+            .list(query.status, branch_ids)
+            .await
+    }
+
+    pub async fn get(&self, po_id: i32) -> Result<PurchaseOrderResponse, String> {
+        self.purchase_order_repo
+            .find_detail(po_id)
+            .await?
+            .map(PurchaseOrderResponse::from)
+            .ok_or_else(|| "Purchase order not found".to_string())
+    }
+
+    pub async fn create(
+        &self,
+        command: PurchaseOrderCommand,
+        user_id: i32,
+        role: &Role,
+    ) -> Result<PurchaseOrderResponse, String> {
+        let branch_id = self
+            .branch_use_case
+            .resolve(user_id, role, command.branch_id)
+            .await?;
+        let (order, lines) = self
+            .purchase_order_repo
+            .create(command.into_draft(branch_id)?, user_id)
+            .await?;
+
+        Ok(PurchaseOrderDetail {
+            order,
+            lines,
+            receipts: Vec::new(),
+        }
+        .into())
+    }
+
+    pub async fn update(
+        &self,
+        po_id: i32,
+        command: PurchaseOrderCommand,
+        user_id: i32,
+        role: &Role,
+    ) -> Result<PurchaseOrderResponse, String> {
+        let branch_id = match command.branch_id {
+            Some(_) => {
+                self.branch_use_case
+                    .resolve(user_id, role, command.branch_id)
+                    .await?
+            }
+            None => self.get(po_id).await?.branch_id,
+        };
+        let (order, lines) = self
+            .purchase_order_repo
+            .update_draft(po_id, command.into_draft(branch_id)?, Utc::now())
+            .await?;
+
+        Ok(PurchaseOrderDetail {
+            order,
+            lines,
+            receipts: Vec::new(),
+        }
+        .into())
+    }
+
+    pub async fn send(&self, po_id: i32) -> Result<PurchaseOrderResponse, String> {
+        self.purchase_order_repo.send(po_id, Utc::now()).await?;
+        self.get(po_id).await
+    }
+
+    pub async fn cancel(
+        &self,
+        po_id: i32,
+        command: CancelPurchaseOrderCommand,
+        actor: &AuditActor,
+    ) -> Result<PurchaseOrderResponse, String> {
+        let previous = self.get(po_id).await?;
+        self.purchase_order_repo.cancel(po_id, Utc::now()).await?;
+        let cancelled = self.get(po_id).await?;
+
+        let mut event = AuditEvent::new(actor, PURCHASE_ORDER_CANCELLED, "purchase_order", po_id)
+            .with_snapshot(&previous);
+        if let Some(reason) = optional_text(command.reason) {
+            event = event.with_reason(&reason);
+        }
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        Ok(cancelled)
+    }
+
+    /// Books a delivery into stock and returns the updated PO
+    pub async fn receive(
+        &self,
+        po_id: i32,
+        command: ReceiveGoodsCommand,
+        actor: &AuditActor,
+    ) -> Result<PurchaseOrderResponse, String> {
+        let lines = command
+            .lines
+            .into_iter()
+            .map(|line| {
+                Ok(ReceiptLineInput {
+                    line_id: line.line_id,
+                    quantity: line.quantity,
+                    unit_cost: line.unit_cost.map(to_cost).transpose()?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let (_, receipt) = self
+            .purchase_order_repo
+            .receive(
+                po_id,
+                lines,
+                optional_text(command.note),
+                actor.user_id,
+                Utc::now(),
+            )
+            .await?;
+
+        let event = AuditEvent::new(actor, GOODS_RECEIVED, "purchase_order", po_id).with_change(
+            &serde_json::json!({}),
+            &serde_json::json!({
+                "receipt_id": receipt.receipt.receipt_id,
+                "lines": receipt.lines,
+            }),
+        );
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+        self.get(po_id).await
+    }
+
+    pub async fn suggest_reorder(
+        &self,
+        query: ReorderQuery,
+    ) -> Result<Vec<ReorderSuggestion>, String> {
+        let usage_days = query.usage_days.unwrap_or(DEFAULT_USAGE_DAYS);
+        let cover_days = query.cover_days.unwrap_or(DEFAULT_COVER_DAYS);
+        if !(1..=MAX_DAYS).contains(&usage_days) {
+            return Err(format!("usage_days must be between 1 and {}", MAX_DAYS));
+        }
+        if !(0..=MAX_DAYS).contains(&cover_days) {
+            return Err(format!("cover_days must be between 0 and {}", MAX_DAYS));
+        }
+
+        self.purchase_order_repo
+            .reorder_suggestions(usage_days, cover_days)
+            .await
+    }
+}
@@ -0,0 +1,308 @@
+use crate::application::input::optional_text;
+use crate::application::use_cases::low_stock::LowStockUseCase;
+use crate::application::use_cases::update_order_status::UpdateOrderStatusUseCase;
+use crate::domain::notification::gateway::{NotificationGateway, NotificationMessage};
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::models::{
+    QuotationLineDecisionEnum, QuotationLineModel, QuotationModel, QuotationStatusEnum,
+    ServiceOrderStatusEnum,
+};
+use crate::infrastructure::db::repositories::quotation::{IssueQuotation, QuotationRepository};
+use crate::infrastructure::db::repositories::repair_log::RepairLogRepository;
+use crate::infrastructure::db::repositories::service_order::ServiceOrderRepository;
+use crate::infrastructure::db::repositories::user_line_account::UserLineAccountRepository;
+use bigdecimal::ToPrimitive;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct IssueQuotationCommand {
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LineDecision {
+    pub line_id: i32,
+    pub approved: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RespondQuotationCommand {
+    pub decisions: Vec<LineDecision>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuotationLine {
+    pub line_id: i32,
+    pub service_item_id: Option<i32>,
+    pub description: String,
+    pub price: f64,
+    pub quantity: i32,
+    pub decision: QuotationLineDecisionEnum,
+    /// Already agreed or a discount, so not up for a decision
+    pub locked: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuotationResponse {
+    pub quotation_id: i32,
+    pub order_id: i32,
+    pub version: i32,
+    pub status: QuotationStatusEnum,
+    pub total: f64,
+    pub approved_total: Option<f64>,
+    pub notes: Option<String>,
+    pub valid_until: DateTime<Utc>,
+    pub issued_by: Option<i32>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub lines: Vec<QuotationLine>,
+}
+
+impl From<(QuotationModel, Vec<QuotationLineModel>)> for QuotationResponse {
+    fn from((quotation, lines): (QuotationModel, Vec<QuotationLineModel>)) -> Self {
+        Self {
+            quotation_id: quotation.quotation_id,
+            order_id: quotation.order_id,
+            version: quotation.version,
+            status: quotation.status,
+            total: quotation.total.to_f64().unwrap_or(0.0),
+            approved_total: quotation.approved_total.and_then(|t| t.to_f64()),
+            notes: quotation.notes,
+            valid_until: quotation.valid_until,
+            issued_by: quotation.issued_by,
+            responded_at: quotation.responded_at,
+            created_at: quotation.created_at,
+            lines: lines
+                .into_iter()
+                .map(|line| QuotationLine {
+                    line_id: line.line_id,
+                    service_item_id: line.service_item_id,
+                    description: line.description,
+                    price: line.price.to_f64().unwrap_or(0.0),
+                    quantity: line.quantity,
+                    decision: line.decision,
+                    locked: line.locked,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Versioned quotes the customer answers line by line. The approved lines
+/// are what gets repaired; extra work found later goes out as a new version.
+#[derive(Clone)]
+pub struct QuotationUseCase {
+    quotation_repo: QuotationRepository,
+    order_repo: ServiceOrderRepository,
+    repair_log_repo: RepairLogRepository,
+    status_updates: UpdateOrderStatusUseCase,
+    line_repo: UserLineAccountRepository,
+    notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
+    valid_days: i64,
+    low_stock: LowStockUseCase,
+}
+
+impl QuotationUseCase {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        quotation_repo: QuotationRepository,
+        order_repo: ServiceOrderRepository,
+        repair_log_repo: RepairLogRepository,
+        status_updates: UpdateOrderStatusUseCase,
+        line_repo: UserLineAccountRepository,
+        notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
+        valid_days: i64,
+        low_stock: LowStockUseCase,
+    ) -> Self {
+        Self {
+            quotation_repo,
+            order_repo,
+            repair_log_repo,
+            status_updates,
+            line_repo,
+            notification_gateway,
+            valid_days,
+            low_stock,
+        }
+    }
+
+    pub async fn issue(
+        &self,
+        order_id: i32,
+        command: IssueQuotationCommand,
+        user_id: i32,
+    ) -> Result<QuotationResponse, String> {
+        let (quotation, lines) = self
+            .quotation_repo
+            .issue(IssueQuotation {
+                order_id,
+                notes: optional_text(command.notes),
+                valid_until: Utc::now() + Duration::days(self.valid_days),
+                issued_by: user_id,
+            })
+            .await?;
+
+        if let Err(e) = self
+            .repair_log_repo
+            .add_log(
+                order_id,
+                user_id,
+                format!(
+                    "Quotation v{} issued for ฿{}",
+                    quotation.version, quotation.total
+                ),
+                ServiceOrderStatusEnum::OfferSent,
+            )
+            .await
+        {
+            tracing::error!("Failed to log quotation issue: {}", e);
+        }
+
+        if let Some(order) = self.order_repo.find_by_id(order_id).await? {
+            let title = if quotation.version > 1 {
+                format!("🧾 Revised Quote v{} | #SO-{}", quotation.version, order_id)
+            } else {
+                format!("🧾 Quote Ready | #SO-{}", order_id)
+            };
+            self.notify(
+                order.customer_id,
+                order_id,
+                title,
+                format!(
+                    "Your quote for order #SO-{} comes to ฿{}. Approve or decline each item before {}.",
+                    order_id,
+                    quotation.total,
+                    quotation.valid_until.format("%d %b %Y")
+                ),
+            )
+            .await;
+        }
+
+        Ok((quotation, lines).into())
+    }
+
+    /// All versions for an order, newest first
+    pub async fn list(
+        &self,
+        order_id: i32,
+        user_id: i32,
+        role: Role,
+    ) -> Result<Vec<QuotationResponse>, String> {
+        let order = self
+            .order_repo
+            .find_by_id(order_id)
+            .await?
+            .ok_or("Order not found")?;
+        if role == Role::Customer && order.customer_id != user_id {
+            return Err("Access denied: Not your order".to_string());
+        }
+
+        Ok(self
+            .quotation_repo
+            .list_for_order(order_id)
+            .await?
+            .into_iter()
+            .map(QuotationResponse::from)
+            .collect())
+    }
+
+    pub async fn respond(
+        &self,
+        quotation_id: i32,
+        command: RespondQuotationCommand,
+        user_id: i32,
+    ) -> Result<QuotationResponse, String> {
+        let mut decisions = HashMap::new();
+        for decision in command.decisions {
+            if decisions
+                .insert(decision.line_id, decision.approved)
+                .is_some()
+            {
+                return Err(format!(
+                    "Line {} was answered more than once",
+                    decision.line_id
+                ));
+            }
+        }
+
+        let (quotation, lines) = self
+            .quotation_repo
+            .respond(quotation_id, user_id, decisions, Utc::now())
+            .await?;
+        let order_id = quotation.order_id;
+        // Accepting starts the work, which takes the reserved parts
+        if quotation.status == QuotationStatusEnum::Accepted {
+            self.low_stock.dispatch_alerts_in_background();
+        }
+
+        let (note, status) = if quotation.status == QuotationStatusEnum::Accepted {
+            let approved = lines
+                .iter()
+                .filter(|l| l.decision == QuotationLineDecisionEnum::Approved)
+                .count();
+            (
+                format!(
+                    "Customer approved {} of {} lines on quotation v{} (฿{})",
+                    approved,
+                    lines.len(),
+                    quotation.version,
+                    quotation.approved_total.clone().unwrap_or_default()
+                ),
+                ServiceOrderStatusEnum::Repairing,
+            )
+        } else {
+            (
+                format!(
+                    "Customer declined quotation v{}; order cancelled",
+                    quotation.version
+                ),
+                ServiceOrderStatusEnum::Cancelled,
+            )
+        };
+        if let Err(e) = self
+            .repair_log_repo
+            .add_log(order_id, user_id, note, status)
+            .await
+        {
+            tracing::error!("Failed to log quotation response: {}", e);
+        }
+
+        // The answer moved the order on, so everyone hears about it the same
+        // way as any other status change
+        match self.order_repo.find_by_id(order_id).await {
+            Ok(Some(order)) => self.status_updates.notify_status_change(&order, user_id),
+            Ok(None) => {}
+            Err(e) => tracing::error!("Failed to load order for notification: {}", e),
+        }
+
+        Ok((quotation, lines).into())
+    }
+
+    async fn notify(&self, user_id: i32, order_id: i32, title: String, body: String) {
+        let recipient = self
+            .line_repo
+            .find_by_user_id(user_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|l| l.line_user_id)
+            .unwrap_or_default();
+        if let Err(e) = self
+            .notification_gateway
+            .send_notification(NotificationMessage {
+                user_id,
+                order_id: Some(order_id),
+                recipient,
+                title,
+                body,
+                custom_payload: None,
+            })
+            .await
+        {
+            tracing::error!("Failed to send quotation notification: {}", e);
+        }
+    }
+}
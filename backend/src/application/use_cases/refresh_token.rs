@@ -1,4 +1,5 @@
 use crate::infrastructure::db::repositories::refresh_token::RefreshTokenRepository;
+use crate::infrastructure::db::repositories::user::UserRepository;
 use crate::infrastructure::security::jwt::service::JwtService;
 use chrono::{Duration, Utc};
 use serde::{Deserialize, Serialize};
@@ -17,13 +18,19 @@ pub struct TokenPair {
 #[derive(Clone)]
 pub struct RefreshTokenUseCase {
     refresh_token_repository: RefreshTokenRepository,
+    user_repository: UserRepository,
     jwt_service: JwtService,
 }
 
 impl RefreshTokenUseCase {
-    pub fn new(refresh_token_repository: RefreshTokenRepository, jwt_service: JwtService) -> Self {
+    pub fn new(
+        refresh_token_repository: RefreshTokenRepository,
+        user_repository: UserRepository,
+        jwt_service: JwtService,
+    ) -> Self {
         Self {
             refresh_token_repository,
+            user_repository,
             jwt_service,
         }
     }
@@ -54,19 +61,31 @@ impl RefreshTokenUseCase {
             return Err("Refresh token expired".to_string());
         }
 
-        // 3. Rotate Token: Revoke current and issue new pair
+        // 3. Deactivated accounts can't extend their session. The role is also
+        //    re-read so a demotion takes effect on the next refresh.
+        let user = self
+            .user_repository
+            .find_by_id(claims.user_id)
+            .await?
+            .ok_or("User not found")?;
+        if !user.is_active {
+            self.refresh_token_repository
+                .revoke_all_for_user(claims.user_id)
+                .await?;
+            return Err("This account has been deactivated".to_string());
+        }
+
+        // 4. Rotate Token: Revoke current and issue new pair
         self.refresh_token_repository
             .revoke_token(&command.refresh_token)
             .await?;
 
         let new_access_token =
             self.jwt_service
-                .generate_token(claims.user_id, &claims.sub, claims.role.clone())?;
-        let new_refresh_token_value = self.jwt_service.generate_refresh_token(
-            claims.user_id,
-            &claims.sub,
-            claims.role.clone(),
-        )?;
+                .generate_token(claims.user_id, &user.username, user.role.clone())?;
+        let new_refresh_token_value =
+            self.jwt_service
+                .generate_refresh_token(claims.user_id, &user.username, user.role)?;
 
         let expires_at = Utc::now() + Duration::days(7);
         self.refresh_token_repository
@@ -0,0 +1,52 @@
+use crate::domain::motorcycle::MotorcycleDetails;
+use crate::infrastructure::db::models::{MotorcycleModel, NewMotorcycle};
+use crate::infrastructure::db::repositories::motorcycle::MotorcycleRepository;
+
+pub struct RegisterMotorcycleUseCase {
+    bike_repo: MotorcycleRepository,
+}
+
+impl RegisterMotorcycleUseCase {
+    pub fn new(bike_repo: MotorcycleRepository) -> Self {
+        Self { bike_repo }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: i32,
+        details: MotorcycleDetails,
+    ) -> Result<MotorcycleModel, String> {
+        let (details, plate) = details.normalise()?;
+
+        if self
+            .bike_repo
+            .find_by_license_and_user(&plate, user_id)
+            .await?
+            .is_some()
+        {
+            return Err("A motorcycle with this license plate is already registered".to_string());
+        }
+
+        if let Some(vin) = &details.vin
+            && self.bike_repo.find_by_vin(vin).await?.is_some()
+        {
+            return Err("A motorcycle with this VIN is already registered".to_string());
+        }
+
+        self.bike_repo
+            .create_motorcycle(NewMotorcycle {
+                brand: details.brand,
+                model: details.model,
+                license_plate: details.license_plate,
+                user_id,
+                year: details.year,
+                colour: details.colour,
+                engine_number: details.engine_number,
+                vin: details.vin,
+                mileage: details.mileage.unwrap_or(0),
+                plate_key: Some(plate.key()),
+                plate_province: plate.province().map(str::to_string),
+            })
+            .await
+    }
+}
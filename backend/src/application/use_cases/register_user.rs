@@ -1,6 +1,9 @@
+use crate::application::use_cases::verify_phone::VerifyPhoneUseCase;
 use crate::domain::user::entity::{Role, User};
+use crate::domain::value_objects::PhoneNumber;
 use crate::infrastructure::db::repositories::user::UserRepository;
 use crate::infrastructure::security::password::hash_password;
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
@@ -9,6 +12,10 @@ pub struct RegisterUserCommand {
     pub password: String,
     pub name: String,
     pub phone: String,
+    /// Code texted to the phone; when valid the phone is verified and any
+    /// walk-in guest records under it move to the new account
+    #[serde(default)]
+    pub phone_code: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -16,16 +23,21 @@ pub struct RegisterUserResult {
     pub user_id: i32,
     pub username: String,
     pub role: Role,
+    pub merged_guests: usize,
 }
 
 #[derive(Clone)]
 pub struct RegisterUserUseCase {
     user_repository: UserRepository,
+    verify_phone: VerifyPhoneUseCase,
 }
 
 impl RegisterUserUseCase {
-    pub fn new(user_repository: UserRepository) -> Self {
-        Self { user_repository }
+    pub fn new(user_repository: UserRepository, verify_phone: VerifyPhoneUseCase) -> Self {
+        Self {
+            user_repository,
+            verify_phone,
+        }
     }
 
     pub async fn execute(
@@ -33,28 +45,72 @@ impl RegisterUserUseCase {
         command: RegisterUserCommand,
     ) -> Result<RegisterUserResult, String> {
         // 1. Check if user already exists
-        if let Some(_) = self
+        if self
             .user_repository
             .find_by_username(&command.username)
             .await?
+            .is_some()
         {
             return Err("Username already exists".to_string());
         }
 
+        // Verify the phone up front so a bad code doesn't leave a half-set-up account
+        let verified_phone = match &command.phone_code {
+            Some(code) => {
+                let phone = PhoneNumber::new(command.phone.clone())?;
+                if self
+                    .user_repository
+                    .find_account_by_phone(&phone)
+                    .await?
+                    .is_some()
+                {
+                    return Err("Phone number is already registered".to_string());
+                }
+                self.verify_phone.check_code(&phone, code).await?;
+                Some(phone)
+            }
+            None => None,
+        };
+
         // 2. Hash password
         let password_hash = hash_password(&command.password)?;
 
         // 3. Create user entity (Customer role by default)
-        let new_user =
-            User::new_customer(command.username, password_hash, command.name, command.phone);
+        let mut new_user = User::new_customer(
+            command.username,
+            password_hash,
+            command.name,
+            verified_phone
+                .as_ref()
+                .map(|p| p.value().to_string())
+                .unwrap_or(command.phone),
+        );
+        if verified_phone.is_some() {
+            new_user.phone_verified_at = Some(Utc::now());
+        }
 
         // 4. Save to repository
         let created_user = self.user_repository.create_user(new_user).await?;
+        let user_id = created_user.id.unwrap_or(0); // Should define consistent behavior for ID
+
+        // 5. Claim walk-in records kept under the verified phone
+        let merged_guests = match &verified_phone {
+            Some(phone) => self
+                .verify_phone
+                .claim_guests(user_id, phone)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::error!("Failed to merge guest records into user {}: {}", user_id, e);
+                    0
+                }),
+            None => 0,
+        };
 
         Ok(RegisterUserResult {
-            user_id: created_user.id.unwrap_or(0), // Should define consistent behavior for ID
+            user_id,
             username: created_user.username,
             role: created_user.role,
+            merged_guests,
         })
     }
 }
@@ -10,7 +10,13 @@ impl RemoveServiceItemUseCase {
         Self { inventory_repo }
     }
 
-    pub async fn execute(&self, item_id: i32) -> Result<(), String> {
-        self.inventory_repo.remove_service_item(item_id).await
+    pub async fn order_id(&self, item_id: i32) -> Result<Option<i32>, String> {
+        self.inventory_repo.item_order_id(item_id).await
+    }
+
+    pub async fn execute(&self, item_id: i32, user_id: i32) -> Result<(), String> {
+        self.inventory_repo
+            .remove_service_item(item_id, user_id)
+            .await
     }
 }
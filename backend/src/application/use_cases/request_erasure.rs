@@ -0,0 +1,52 @@
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::models::ErasureRequestModel;
+use crate::infrastructure::db::repositories::erasure_request::ErasureRequestRepository;
+use crate::infrastructure::db::repositories::user::UserRepository;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct RequestErasureCommand {
+    pub reason: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct RequestErasureUseCase {
+    user_repo: UserRepository,
+    erasure_repo: ErasureRequestRepository,
+}
+
+impl RequestErasureUseCase {
+    pub fn new(user_repo: UserRepository, erasure_repo: ErasureRequestRepository) -> Self {
+        Self {
+            user_repo,
+            erasure_repo,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: i32,
+        command: RequestErasureCommand,
+    ) -> Result<ErasureRequestModel, String> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or("User not found".to_string())?;
+
+        if user.role != Role::Customer {
+            return Err("Staff accounts are removed by an administrator".to_string());
+        }
+
+        if self
+            .erasure_repo
+            .find_pending_for_user(user_id)
+            .await?
+            .is_some()
+        {
+            return Err("An erasure request is already awaiting review".to_string());
+        }
+
+        self.erasure_repo.create(user_id, command.reason).await
+    }
+}
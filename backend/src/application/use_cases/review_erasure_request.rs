@@ -0,0 +1,133 @@
+use crate::domain::audit::entity::{ERASURE_REQUEST_REJECTED, USER_ERASED};
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::service::entity::OrderStatus;
+use crate::infrastructure::db::models::{ErasureRequestModel, ErasureRequestStatusEnum};
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::erasure_request::ErasureRequestRepository;
+use crate::infrastructure::db::repositories::personal_data::PersonalDataRepository;
+use crate::infrastructure::db::repositories::service_order::ServiceOrderRepository;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewErasureCommand {
+    pub note: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct ReviewErasureRequestUseCase {
+    erasure_repo: ErasureRequestRepository,
+    personal_data_repo: PersonalDataRepository,
+    order_repo: ServiceOrderRepository,
+    audit_repo: AuditEventRepository,
+}
+
+impl ReviewErasureRequestUseCase {
+    pub fn new(
+        erasure_repo: ErasureRequestRepository,
+        personal_data_repo: PersonalDataRepository,
+        order_repo: ServiceOrderRepository,
+        audit_repo: AuditEventRepository,
+    ) -> Self {
+        Self {
+            erasure_repo,
+            personal_data_repo,
+            order_repo,
+            audit_repo,
+        }
+    }
+
+    pub async fn approve(
+        &self,
+        request_id: i32,
+        command: ReviewErasureCommand,
+        actor: &AuditActor,
+    ) -> Result<(), String> {
+        let request = self.find_pending(request_id).await?;
+
+        // An unsettled order still needs the customer's contact details
+        let orders = self
+            .order_repo
+            .list_orders_for_customer(request.user_id)
+            .await?;
+        if orders
+            .iter()
+            .any(|o| !matches!(o.status, OrderStatus::Paid | OrderStatus::Cancelled))
+        {
+            return Err("Customer still has unpaid or in-progress orders".to_string());
+        }
+
+        if !self
+            .personal_data_repo
+            .anonymise(
+                request_id,
+                request.user_id,
+                actor.user_id,
+                command.note.clone(),
+            )
+            .await?
+        {
+            return Err("Erasure request has already been reviewed".to_string());
+        }
+
+        let mut event = AuditEvent::new(actor, USER_ERASED, "user", request.user_id);
+        if let Some(note) = &command.note {
+            event = event.with_reason(note);
+        }
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        Ok(())
+    }
+
+    pub async fn reject(
+        &self,
+        request_id: i32,
+        command: ReviewErasureCommand,
+        actor: &AuditActor,
+    ) -> Result<(), String> {
+        self.find_pending(request_id).await?;
+
+        if !self
+            .erasure_repo
+            .mark_reviewed(
+                request_id,
+                ErasureRequestStatusEnum::Rejected,
+                actor.user_id,
+                command.note.clone(),
+            )
+            .await?
+        {
+            return Err("Erasure request has already been reviewed".to_string());
+        }
+
+        let mut event = AuditEvent::new(
+            actor,
+            ERASURE_REQUEST_REJECTED,
+            "erasure_request",
+            request_id,
+        );
+        if let Some(note) = &command.note {
+            event = event.with_reason(note);
+        }
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        Ok(())
+    }
+
+    async fn find_pending(&self, request_id: i32) -> Result<ErasureRequestModel, String> {
+        let request = self
+            .erasure_repo
+            .find_by_id(request_id)
+            .await?
+            .ok_or("Erasure request not found".to_string())?;
+
+        if request.status != ErasureRequestStatusEnum::Pending {
+            return Err("Erasure request has already been reviewed".to_string());
+        }
+
+        Ok(request)
+    }
+}
@@ -0,0 +1,81 @@
+use crate::domain::user::entity::Role;
+use crate::domain::value_objects::LicensePlate;
+use crate::infrastructure::db::repositories::search::{SearchHit, SearchRepository, SearchTerms};
+use serde::Deserialize;
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 50;
+const MIN_QUERY_LENGTH: usize = 2;
+/// Fewer digits than this would match half the phone book
+const MIN_PHONE_DIGITS: usize = 3;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    pub limit: Option<i64>,
+}
+
+#[derive(Clone)]
+pub struct SearchUseCase {
+    search_repo: SearchRepository,
+}
+
+impl SearchUseCase {
+    pub fn new(search_repo: SearchRepository) -> Self {
+        Self { search_repo }
+    }
+
+    /// Front-desk search across customers, their bikes and orders, best match first
+    pub async fn execute(&self, role: &Role, query: SearchQuery) -> Result<Vec<SearchHit>, String> {
+        if *role == Role::Customer {
+            return Err("Only staff can search".to_string());
+        }
+
+        let text = query.q.trim().to_string();
+        if text.chars().count() < MIN_QUERY_LENGTH {
+            return Err(format!(
+                "Search text must be at least {} characters",
+                MIN_QUERY_LENGTH
+            ));
+        }
+
+        let terms = parse_terms(text);
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        self.search_repo.search(&terms, limit).await
+    }
+}
+
+fn parse_terms(text: String) -> SearchTerms {
+    let digits: String = text.chars().filter(|c| c.is_ascii_digit()).collect();
+    // Only treat it as a phone number when it is mostly digits
+    let is_phone_like = text
+        .chars()
+        .all(|c| c.is_ascii_digit() || c.is_whitespace() || matches!(c, '-' | '+' | '(' | ')'));
+    let phone_digits = (is_phone_like && digits.len() >= MIN_PHONE_DIGITS).then(|| digits.clone());
+
+    let plate: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '-' | '.'))
+        .collect();
+    let plate_key = LicensePlate::new(text.clone()).ok().map(|p| p.key());
+
+    SearchTerms {
+        order_id: parse_order_id(&text),
+        text,
+        phone_digits,
+        plate,
+        plate_key,
+    }
+}
+
+/// Accepts "SO-123", "#SO-123", "so123" and a bare "123"
+fn parse_order_id(text: &str) -> Option<i32> {
+    let text = text.trim_start_matches('#').trim();
+    let text = match text.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("so") => {
+            text[2..].trim_start_matches(['-', ' '])
+        }
+        _ => text,
+    };
+    text.parse::<i32>().ok().filter(|id| *id > 0)
+}
@@ -0,0 +1,235 @@
+use crate::domain::notification::gateway::{NotificationGateway, NotificationMessage};
+use crate::infrastructure::db::models::{
+    MaintenanceReminderModel, MaintenanceReminderStatusEnum, MaintenanceRuleModel, MotorcycleModel,
+    NewMaintenanceReminder,
+};
+use crate::infrastructure::db::repositories::maintenance::{MaintenanceRepository, ServiceRecord};
+use crate::infrastructure::db::repositories::user_line_account::UserLineAccountRepository;
+use chrono::{DateTime, Duration, Months, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A reminder nobody reacted to is sent again after this long
+const REMINDER_REPEAT_DAYS: i64 = 30;
+
+pub struct SendMaintenanceRemindersUseCase {
+    maintenance_repo: MaintenanceRepository,
+    line_repo: UserLineAccountRepository,
+    notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
+    frontend_url: String,
+}
+
+impl SendMaintenanceRemindersUseCase {
+    pub fn new(
+        maintenance_repo: MaintenanceRepository,
+        line_repo: UserLineAccountRepository,
+        notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
+        frontend_url: String,
+    ) -> Self {
+        Self {
+            maintenance_repo,
+            line_repo,
+            notification_gateway,
+            frontend_url,
+        }
+    }
+
+    /// Evaluates every active rule against every bike and sends the reminders
+    /// that are due. Returns how many reminders went out.
+    pub async fn execute(&self) -> Result<usize, String> {
+        let rules: Vec<MaintenanceRuleModel> = self
+            .maintenance_repo
+            .list_rules()
+            .await?
+            .into_iter()
+            .filter(|r| r.is_active)
+            .collect();
+        if rules.is_empty() {
+            return Ok(0);
+        }
+
+        let bikes = self.maintenance_repo.find_reminder_candidates().await?;
+        let mut records: HashMap<i32, Vec<ServiceRecord>> = HashMap::new();
+        for record in self.maintenance_repo.find_service_records().await? {
+            records.entry(record.bike_id).or_default().push(record);
+        }
+        let open: HashMap<(i32, i32), MaintenanceReminderModel> = self
+            .maintenance_repo
+            .find_open_reminders()
+            .await?
+            .into_iter()
+            .map(|r| ((r.bike_id, r.rule_id), r))
+            .collect();
+
+        let now = Utc::now();
+        let mut sent = 0;
+        for bike in &bikes {
+            let bike_records = records.get(&bike.bike_id).map(Vec::as_slice).unwrap_or(&[]);
+            for rule in &rules {
+                let Some(reason) = due_reason(rule, bike, bike_records, now) else {
+                    continue;
+                };
+
+                let reminder_id = match open.get(&(bike.bike_id, rule.rule_id)) {
+                    Some(existing) if !should_resend(existing, now) => continue,
+                    Some(existing) => {
+                        self.maintenance_repo
+                            .mark_resent(existing.reminder_id)
+                            .await?;
+                        existing.reminder_id
+                    }
+                    None => {
+                        self.maintenance_repo
+                            .create_reminder(NewMaintenanceReminder {
+                                rule_id: rule.rule_id,
+                                bike_id: bike.bike_id,
+                                user_id: bike.user_id,
+                            })
+                            .await?
+                            .reminder_id
+                    }
+                };
+
+                self.notify(bike, rule, reminder_id, &reason).await;
+                sent += 1;
+            }
+        }
+
+        Ok(sent)
+    }
+
+    async fn notify(
+        &self,
+        bike: &MotorcycleModel,
+        rule: &MaintenanceRuleModel,
+        reminder_id: i32,
+        reason: &str,
+    ) {
+        let booking_link = format!(
+            "{}/book?bike_id={}&reminder_id={}",
+            self.frontend_url, bike.bike_id, reminder_id
+        );
+        let bike_label = format!("{} {} ({})", bike.brand, bike.model, bike.license_plate);
+
+        let recipient = self
+            .line_repo
+            .find_by_user_id(bike.user_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|l| l.line_user_id)
+            .unwrap_or_default();
+
+        let result = self
+            .notification_gateway
+            .send_notification(NotificationMessage {
+                user_id: bike.user_id,
+                order_id: None,
+                recipient,
+                title: format!("🛠️ Maintenance due | {}", rule.name),
+                body: format!("{}: {}. Book now: {}", bike_label, reason, booking_link),
+                custom_payload: Some(serde_json::json!({
+                    "type": "flex",
+                    "altText": format!("🛠️ {} is due for {}", bike_label, rule.name),
+                    "contents": {
+                        "type": "bubble",
+                        "header": {
+                            "type": "box",
+                            "layout": "vertical",
+                            "backgroundColor": "#004B7E",
+                            "contents": [
+                                { "type": "text", "text": "MAINTENANCE REMINDER", "color": "#FFD700", "size": "xs", "weight": "bold" },
+                                { "type": "text", "text": rule.name.clone(), "color": "#FFFFFF", "size": "xl", "weight": "bold", "wrap": true }
+                            ]
+                        },
+                        "body": {
+                            "type": "box",
+                            "layout": "vertical",
+                            "spacing": "md",
+                            "contents": [
+                                { "type": "text", "text": bike_label, "weight": "bold", "size": "md", "wrap": true },
+                                { "type": "text", "text": reason, "size": "sm", "color": "#666666", "wrap": true }
+                            ]
+                        },
+                        "footer": {
+                            "type": "box",
+                            "layout": "vertical",
+                            "contents": [
+                                {
+                                    "type": "button",
+                                    "style": "primary",
+                                    "color": "#004B7E",
+                                    "action": { "type": "uri", "label": "Book Service", "uri": booking_link }
+                                }
+                            ]
+                        }
+                    }
+                })),
+            })
+            .await;
+
+        if let Err(e) = result {
+            tracing::warn!("Failed to send maintenance reminder {}: {}", reminder_id, e);
+        }
+    }
+}
+
+fn should_resend(reminder: &MaintenanceReminderModel, now: DateTime<Utc>) -> bool {
+    match reminder.status {
+        MaintenanceReminderStatusEnum::Snoozed => reminder.snoozed_until.is_none_or(|t| t <= now),
+        MaintenanceReminderStatusEnum::Sent => {
+            reminder.sent_at + Duration::days(REMINDER_REPEAT_DAYS) <= now
+        }
+        MaintenanceReminderStatusEnum::Converted => false,
+    }
+}
+
+/// Why the rule is due for this bike, or None if it isn't. Records must be oldest first.
+fn due_reason(
+    rule: &MaintenanceRuleModel,
+    bike: &MotorcycleModel,
+    records: &[ServiceRecord],
+    now: DateTime<Utc>,
+) -> Option<String> {
+    let keyword = rule.match_keyword.to_lowercase();
+    let last_done = records
+        .iter()
+        .rev()
+        .find(|r| r.description.to_lowercase().contains(&keyword));
+
+    if let Some(interval_km) = rule.interval_km {
+        let since_km = match last_done {
+            Some(record) => record.odometer_km.map(|km| bike.mileage - km),
+            None => Some(bike.mileage),
+        };
+        if let Some(since_km) = since_km
+            && since_km >= interval_km
+        {
+            return Some(match last_done {
+                Some(_) => format!("{} km since the last {}", since_km, rule.name),
+                None => format!(
+                    "{} km on the clock with no {} on record",
+                    since_km, rule.name
+                ),
+            });
+        }
+    }
+
+    if let Some(interval_months) = rule.interval_months {
+        // Without a matching service, measure from the bike's first visit
+        let baseline = last_done.or(records.first())?.performed_at;
+        let due_at = baseline.checked_add_months(Months::new(interval_months as u32))?;
+        if due_at <= now {
+            return Some(match last_done {
+                Some(_) => format!("last {} was on {}", rule.name, baseline.format("%Y-%m-%d")),
+                None => format!(
+                    "no {} on record since {}",
+                    rule.name,
+                    baseline.format("%Y-%m-%d")
+                ),
+            });
+        }
+    }
+
+    None
+}
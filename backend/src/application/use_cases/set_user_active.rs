@@ -0,0 +1,73 @@
+use crate::domain::audit::entity::{USER_DEACTIVATED, USER_REACTIVATED};
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::infrastructure::db::repositories::refresh_token::RefreshTokenRepository;
+use crate::infrastructure::db::repositories::user::UserRepository;
+
+/// Deactivation keeps the account and its history but blocks login and
+/// token refresh. Access tokens already issued run out on their own (JWT_EXPIRATION).
+#[derive(Clone)]
+pub struct SetUserActiveUseCase {
+    user_repository: UserRepository,
+    refresh_token_repository: RefreshTokenRepository,
+}
+
+impl SetUserActiveUseCase {
+    pub fn new(
+        user_repository: UserRepository,
+        refresh_token_repository: RefreshTokenRepository,
+    ) -> Self {
+        Self {
+            user_repository,
+            refresh_token_repository,
+        }
+    }
+
+    pub async fn deactivate(&self, user_id: i32, actor: &AuditActor) -> Result<(), String> {
+        let user = self
+            .user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or("User not found".to_string())?;
+
+        if !user.is_active {
+            return Err("User is already deactivated".to_string());
+        }
+        if user_id == actor.user_id {
+            return Err("You cannot deactivate your own account".to_string());
+        }
+
+        // Refused inside the update if they're the last active admin
+        self.user_repository
+            .set_active(
+                user_id,
+                false,
+                AuditEvent::new(actor, USER_DEACTIVATED, "user", user_id),
+            )
+            .await?;
+        self.refresh_token_repository
+            .revoke_all_for_user(user_id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn reactivate(&self, user_id: i32, actor: &AuditActor) -> Result<(), String> {
+        let user = self
+            .user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or("User not found".to_string())?;
+
+        if user.is_active {
+            return Err("User is already active".to_string());
+        }
+
+        self.user_repository
+            .set_active(
+                user_id,
+                true,
+                AuditEvent::new(actor, USER_REACTIVATED, "user", user_id),
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,580 @@
+use crate::application::use_cases::branch::BranchUseCase;
+use crate::application::use_cases::low_stock::LowStockUseCase;
+use crate::domain::audit::entity::STOCK_CATALOGUE_IMPORTED;
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::service::stock_entity::{DEFAULT_UNIT, StockItem};
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::stock::{ImportedUpdate, StockItemRepository};
+use calamine::{Data, Reader, Xlsx};
+use rust_xlsxwriter::{Format, Workbook};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::str::FromStr;
+
+/// Columns of an export, in order. An import reads the same headers in any
+/// order; cost_price only seeds new items, quantity only seeds them unless the
+/// import asks to update quantities, and the reserved and available counts
+/// are ignored.
+const COLUMNS: [&str; 14] = [
+    "sku",
+    "barcode",
+    "name",
+    "category",
+    "brand",
+    "unit",
+    "shelf_location",
+    "price",
+    "cost_price",
+    "quantity",
+    "reserved_quantity",
+    "available_quantity",
+    "reorder_point",
+    "reorder_quantity",
+];
+
+const IMPORT_NOTE: &str = "Catalogue import";
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CatalogueFormat {
+    Csv,
+    Xlsx,
+}
+
+impl CatalogueFormat {
+    /// Picks the format from a file name's extension
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        let extension = name.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "xlsx" => Some(Self::Xlsx),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Xlsx => "xlsx",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CatalogueExportQuery {
+    pub format: Option<CatalogueFormat>,
+    /// Export this branch's quantities rather than the shop's totals, or
+    /// your own branches' if you aren't allowed every branch
+    pub branch_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CatalogueImportQuery {
+    /// Validate and preview without saving anything
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Overrides the format implied by the file name
+    pub format: Option<CatalogueFormat>,
+    /// The branch the quantities are for; leave out for your own branch
+    pub branch_id: Option<i32>,
+    /// Also set existing items' quantities at the branch to the file's
+    #[serde(default)]
+    pub update_quantities: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum ImportAction {
+    Create,
+    Update,
+    Unchanged,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CatalogueImportRow {
+    /// Row number in the file, counting the header as row 1
+    pub row: usize,
+    pub sku: Option<String>,
+    pub action: Option<ImportAction>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CatalogueImportReport {
+    pub dry_run: bool,
+    /// Whether the rows were saved; nothing is saved on a dry run or when
+    /// any row has an error
+    pub applied: bool,
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub errors: usize,
+    pub rows: Vec<CatalogueImportRow>,
+}
+
+enum Cell {
+    Text(String),
+    Number(f64),
+    Empty,
+}
+
+fn text_cell(value: &Option<String>) -> Cell {
+    value.clone().map(Cell::Text).unwrap_or(Cell::Empty)
+}
+
+fn whole_cell(value: Option<i32>) -> Cell {
+    value
+        .map(|v| Cell::Number(f64::from(v)))
+        .unwrap_or(Cell::Empty)
+}
+
+fn export_cells(item: &StockItem) -> Vec<Cell> {
+    vec![
+        text_cell(&item.sku),
+        text_cell(&item.barcode),
+        Cell::Text(item.name.clone()),
+        text_cell(&item.category),
+        text_cell(&item.brand),
+        Cell::Text(item.unit.clone()),
+        text_cell(&item.shelf_location),
+        Cell::Number(item.price),
+        Cell::Number(item.average_cost),
+        whole_cell(Some(item.quantity)),
+        whole_cell(Some(item.reserved_quantity)),
+        whole_cell(Some(item.available_quantity)),
+        whole_cell(item.reorder_point),
+        whole_cell(item.reorder_quantity),
+    ]
+}
+
+fn write_csv(items: &[StockItem]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(COLUMNS).map_err(|e| e.to_string())?;
+    for item in items {
+        let record: Vec<String> = export_cells(item)
+            .into_iter()
+            .map(|cell| match cell {
+                Cell::Text(text) => text,
+                Cell::Number(number) => number.to_string(),
+                Cell::Empty => String::new(),
+            })
+            .collect();
+        writer.write_record(&record).map_err(|e| e.to_string())?;
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+fn write_xlsx(items: &[StockItem]) -> Result<Vec<u8>, String> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Catalogue").map_err(|e| e.to_string())?;
+    let bold = Format::new().set_bold();
+    for (col, header) in COLUMNS.iter().enumerate() {
+        sheet
+            .write_string_with_format(0, col as u16, *header, &bold)
+            .map_err(|e| e.to_string())?;
+    }
+    for (index, item) in items.iter().enumerate() {
+        let row = index as u32 + 1;
+        for (col, cell) in export_cells(item).into_iter().enumerate() {
+            let col = col as u16;
+            match cell {
+                Cell::Text(text) => sheet.write_string(row, col, text),
+                Cell::Number(number) => sheet.write_number(row, col, number),
+                Cell::Empty => continue,
+            }
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    sheet.set_freeze_panes(1, 0).map_err(|e| e.to_string())?;
+    workbook.save_to_buffer().map_err(|e| e.to_string())
+}
+
+/// One data row of an import, keyed by lower-cased header
+struct SheetRow {
+    number: usize,
+    values: HashMap<String, String>,
+}
+
+fn to_rows(header: Vec<String>, records: Vec<Vec<String>>) -> Vec<SheetRow> {
+    let header: Vec<String> = header.iter().map(|h| h.trim().to_lowercase()).collect();
+    records
+        .into_iter()
+        .enumerate()
+        .filter(|(_, record)| record.iter().any(|v| !v.trim().is_empty()))
+        .map(|(index, record)| SheetRow {
+            number: index + 2,
+            values: header.iter().cloned().zip(record).collect(),
+        })
+        .collect()
+}
+
+fn read_csv(bytes: &[u8]) -> Result<Vec<SheetRow>, String> {
+    // Excel saves CSV with a byte order mark
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(bytes);
+    let header = reader
+        .headers()
+        .map_err(|e| format!("Could not read the CSV header: {}", e))?
+        .iter()
+        .map(str::to_string)
+        .collect();
+    let records = reader
+        .records()
+        .map(|record| {
+            record
+                .map(|r| r.iter().map(str::to_string).collect())
+                .map_err(|e| format!("Could not read the CSV: {}", e))
+        })
+        .collect::<Result<Vec<Vec<String>>, String>>()?;
+    Ok(to_rows(header, records))
+}
+
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Empty => String::new(),
+        Data::String(text) => text.clone(),
+        // Numeric SKUs and barcodes come back as floats
+        Data::Float(number) if number.fract() == 0.0 && number.abs() < 1e15 => {
+            format!("{}", *number as i64)
+        }
+        other => other.to_string(),
+    }
+}
+
+fn read_xlsx(bytes: &[u8]) -> Result<Vec<SheetRow>, String> {
+    let mut workbook =
+        Xlsx::new(Cursor::new(bytes)).map_err(|e| format!("Could not open the workbook: {}", e))?;
+    let range = workbook
+        .worksheet_range_at(0)
+        .ok_or("The workbook has no sheets")?
+        .map_err(|e| format!("Could not read the first sheet: {}", e))?;
+    let mut rows = range.rows();
+    let header = rows
+        .next()
+        .ok_or("The sheet is empty")?
+        .iter()
+        .map(cell_text)
+        .collect();
+    let records = rows
+        .map(|row| row.iter().map(cell_text).collect())
+        .collect();
+    Ok(to_rows(header, records))
+}
+
+impl SheetRow {
+    fn text(&self, column: &str) -> Option<String> {
+        self.values
+            .get(column)
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    }
+
+    fn number<T: FromStr>(&self, column: &str) -> Result<Option<T>, String> {
+        self.text(column)
+            .map(|v| {
+                v.parse::<T>()
+                    .map_err(|_| format!("{} must be a number, got \"{}\"", column, v))
+            })
+            .transpose()
+    }
+
+    /// The item this row describes: the existing one with the row's non-empty
+    /// cells applied, or a new one. An existing item's quantity is only taken
+    /// from the row when `set_quantity` is on.
+    fn to_item(
+        &self,
+        existing: Option<&StockItem>,
+        sku: Option<String>,
+        set_quantity: bool,
+    ) -> Result<StockItem, String> {
+        let price = self.number::<f64>("price")?;
+        let quantity = self.number::<i32>("quantity")?;
+        if price.is_some_and(|p| !p.is_finite() || p < 0.0) {
+            return Err("price must be zero or more".to_string());
+        }
+        if quantity.is_some_and(|q| q < 0) {
+            return Err("quantity cannot be negative".to_string());
+        }
+
+        let mut item = match existing {
+            Some(existing) => existing.clone(),
+            None if sku.is_none() => return Err("sku is required for a new item".to_string()),
+            None => StockItem {
+                id: None,
+                name: self.text("name").ok_or("name is required for a new item")?,
+                price: price.ok_or("price is required for a new item")?,
+                quantity: 0,
+                reserved_quantity: 0,
+                available_quantity: 0,
+                reorder_point: None,
+                reorder_quantity: None,
+                sku: None,
+                barcode: None,
+                category: None,
+                brand: None,
+                unit: DEFAULT_UNIT.to_string(),
+                shelf_location: None,
+                average_cost: self.number::<f64>("cost_price")?.unwrap_or(0.0),
+            },
+        };
+        if sku.is_some() {
+            item.sku = sku;
+        }
+        if let Some(name) = self.text("name") {
+            item.name = name;
+        }
+        if let Some(price) = price {
+            item.price = price;
+        }
+        if let Some(quantity) = quantity
+            && (existing.is_none() || set_quantity)
+        {
+            item.quantity = quantity;
+            item.available_quantity = quantity - item.reserved_quantity;
+        }
+        if let Some(point) = self.number::<i32>("reorder_point")? {
+            item.reorder_point = Some(point);
+        }
+        if let Some(reorder) = self.number::<i32>("reorder_quantity")? {
+            item.reorder_quantity = Some(reorder);
+        }
+        for (column, field) in [
+            ("barcode", &mut item.barcode),
+            ("category", &mut item.category),
+            ("brand", &mut item.brand),
+            ("shelf_location", &mut item.shelf_location),
+        ] {
+            if let Some(value) = self.text(column) {
+                *field = Some(value);
+            }
+        }
+        if let Some(unit) = self.text("unit") {
+            item.unit = unit;
+        }
+        item.normalized()
+    }
+}
+
+/// Bulk export of the parts catalogue, and import that adds new SKUs and
+/// updates existing ones from a spreadsheet
+pub struct StockCatalogueUseCase {
+    stock_repo: StockItemRepository,
+    audit_repo: AuditEventRepository,
+    low_stock: LowStockUseCase,
+    branch_use_case: BranchUseCase,
+}
+
+impl StockCatalogueUseCase {
+    pub fn new(
+        stock_repo: StockItemRepository,
+        audit_repo: AuditEventRepository,
+        low_stock: LowStockUseCase,
+        branch_use_case: BranchUseCase,
+    ) -> Self {
+        Self {
+            stock_repo,
+            audit_repo,
+            low_stock,
+            branch_use_case,
+        }
+    }
+
+    /// The whole catalogue, ordered by SKU with unnumbered items last, with
+    /// the shop's totals or what the given branches hold between them
+    pub async fn export(
+        &self,
+        format: CatalogueFormat,
+        branch_ids: Option<Vec<i32>>,
+    ) -> Result<Vec<u8>, String> {
+        let mut items = self.stock_repo.list_stock_items().await?;
+        if let Some(branch_ids) = branch_ids {
+            items = self.stock_repo.at_branches(items, &branch_ids).await?;
+        }
+        items.sort_by(|a, b| {
+            (a.sku.is_none(), &a.sku, &a.name).cmp(&(b.sku.is_none(), &b.sku, &b.name))
+        });
+        match format {
+            CatalogueFormat::Csv => write_csv(&items),
+            CatalogueFormat::Xlsx => write_xlsx(&items),
+        }
+    }
+
+    /// Matches rows to items and checks every row, reading quantities as the
+    /// branch's. A row finds its item by SKU, then by barcode, then for items
+    /// without a SKU by name. Saves them all in one go unless it's a dry run
+    /// or any row failed.
+    pub async fn import(
+        &self,
+        bytes: &[u8],
+        query: &CatalogueImportQuery,
+        format: CatalogueFormat,
+        actor: &AuditActor,
+        role: &Role,
+    ) -> Result<CatalogueImportReport, String> {
+        let dry_run = query.dry_run;
+        let branch_id = self
+            .branch_use_case
+            .resolve(actor.user_id, role, query.branch_id)
+            .await?;
+        let rows = match format {
+            CatalogueFormat::Csv => read_csv(bytes)?,
+            CatalogueFormat::Xlsx => read_xlsx(bytes)?,
+        };
+        if rows.is_empty() {
+            return Err("The file has no rows to import".to_string());
+        }
+        if !rows[0].values.contains_key("sku") {
+            return Err("The file needs a sku column".to_string());
+        }
+        let set_quantity = query.update_quantities;
+
+        let existing = self.stock_repo.list_stock_items().await?;
+        let existing = self.stock_repo.at_branch(existing, branch_id).await?;
+        let by_sku: HashMap<&str, &StockItem> = existing
+            .iter()
+            .filter_map(|item| item.sku.as_deref().map(|sku| (sku, item)))
+            .collect();
+        let barcode_owners: HashMap<&str, &StockItem> = existing
+            .iter()
+            .filter_map(|item| item.barcode.as_deref().map(|code| (code, item)))
+            .collect();
+        let mut unnumbered: HashMap<String, Vec<&StockItem>> = HashMap::new();
+        for item in existing.iter().filter(|item| item.sku.is_none()) {
+            unnumbered
+                .entry(item.name.to_lowercase())
+                .or_default()
+                .push(item);
+        }
+        // The one item without a SKU by this row's barcode or name
+        let unnumbered_match = |row: &SheetRow| -> Option<&StockItem> {
+            if let Some(owner) = row
+                .text("barcode")
+                .and_then(|code| barcode_owners.get(code.as_str()).copied())
+            {
+                return owner.sku.is_none().then_some(owner);
+            }
+            match unnumbered
+                .get(&row.text("name")?.to_lowercase())?
+                .as_slice()
+            {
+                [only] => Some(*only),
+                _ => None,
+            }
+        };
+
+        let mut report_rows = Vec::with_capacity(rows.len());
+        let mut creates = Vec::new();
+        let mut updates = Vec::new();
+        let mut unchanged = 0;
+        let mut seen_skus: HashMap<String, usize> = HashMap::new();
+        let mut seen_items: HashMap<i32, usize> = HashMap::new();
+        let mut seen_barcodes: HashSet<String> = HashSet::new();
+
+        for row in &rows {
+            let sku = row.text("sku").map(|s| s.to_uppercase());
+            let outcome = (|| {
+                if let Some(sku) = &sku
+                    && let Some(first) = seen_skus.insert(sku.clone(), row.number)
+                {
+                    return Err(format!("SKU {} already appears on row {}", sku, first));
+                }
+                let current = sku
+                    .as_deref()
+                    .and_then(|sku| by_sku.get(sku).copied())
+                    .or_else(|| unnumbered_match(row));
+                if let Some(id) = current.and_then(|item| item.id)
+                    && let Some(first) = seen_items.insert(id, row.number)
+                {
+                    return Err(format!("Row {} already updates this item", first));
+                }
+                let item = row.to_item(current, sku.clone(), set_quantity)?;
+                if let Some(barcode) = &item.barcode {
+                    if let Some(owner) = barcode_owners.get(barcode.as_str())
+                        && owner.id != item.id
+                    {
+                        return Err(format!(
+                            "Barcode {} is already used by {}",
+                            barcode, owner.name
+                        ));
+                    }
+                    if !seen_barcodes.insert(barcode.clone()) {
+                        return Err(format!("Barcode {} appears on more than one row", barcode));
+                    }
+                }
+                Ok((current, item))
+            })();
+
+            let (action, error) = match outcome {
+                Ok((None, item)) => {
+                    creates.push(item);
+                    (Some(ImportAction::Create), None)
+                }
+                Ok((Some(current), item)) if *current == item => {
+                    unchanged += 1;
+                    (Some(ImportAction::Unchanged), None)
+                }
+                Ok((Some(current), item)) => {
+                    let counted_from =
+                        (item.quantity != current.quantity).then_some(current.quantity);
+                    updates.push(ImportedUpdate { item, counted_from });
+                    (Some(ImportAction::Update), None)
+                }
+                Err(e) => (None, Some(e)),
+            };
+            report_rows.push(CatalogueImportRow {
+                row: row.number,
+                sku,
+                action,
+                error,
+            });
+        }
+
+        let errors = report_rows.iter().filter(|r| r.error.is_some()).count();
+        let mut report = CatalogueImportReport {
+            dry_run,
+            applied: false,
+            created: creates.len(),
+            updated: updates.len(),
+            unchanged,
+            errors,
+            rows: report_rows,
+        };
+        if dry_run || errors > 0 || creates.len() + updates.len() == 0 {
+            return Ok(report);
+        }
+
+        let summary = serde_json::json!({
+            "branch_id": branch_id,
+            "created": creates.iter().map(|item| &item.sku).collect::<Vec<_>>(),
+            "updated": updates.iter().map(|update| update.item.id).collect::<Vec<_>>(),
+            "quantities": set_quantity,
+        });
+        self.stock_repo
+            .import_items(creates, updates, actor.user_id, IMPORT_NOTE, branch_id)
+            .await?;
+        report.applied = true;
+
+        let event = AuditEvent::new(actor, STOCK_CATALOGUE_IMPORTED, "stock_catalogue", 0)
+            .with_snapshot(&summary);
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+        if set_quantity {
+            self.low_stock.dispatch_alerts_in_background();
+        }
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,411 @@
+use crate::application::input::optional_text;
+use crate::application::use_cases::branch::BranchUseCase;
+use crate::application::use_cases::low_stock::LowStockUseCase;
+use crate::domain::audit::entity::{STOCK_TAKE_CANCELLED, STOCK_TAKE_POSTED};
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::models::{StockTakeModel, StockTakeStatusEnum};
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::stock_take::{
+    CountInput, StockTakeDetail, StockTakeLineDetail, StockTakeRepository,
+};
+use bigdecimal::{BigDecimal, ToPrimitive, Zero};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct OpenStockTakeCommand {
+    /// Leave out to count the whole catalogue
+    pub category: Option<String>,
+    pub notes: Option<String>,
+    /// Leave out to count at your own branch
+    pub branch_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CountCommand {
+    pub stock_item_id: i32,
+    pub counted_quantity: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecordCountsCommand {
+    pub counts: Vec<CountCommand>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CancelStockTakeCommand {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StockTakeQuery {
+    pub status: Option<StockTakeStatusEnum>,
+    pub branch_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StockTakeLine {
+    pub line_id: i32,
+    pub stock_item_id: i32,
+    pub name: String,
+    pub sku: Option<String>,
+    pub shelf_location: Option<String>,
+    pub system_quantity: i32,
+    pub counted_quantity: Option<i32>,
+    /// Counted minus system quantity; empty until the item is counted
+    pub variance: Option<i32>,
+    /// The cost the variance was booked at once posted, the current average
+    /// cost before that
+    pub unit_cost: f64,
+    pub variance_value: Option<f64>,
+    pub counted_by: Option<i32>,
+    pub counted_at: Option<DateTime<Utc>>,
+}
+
+impl From<StockTakeLineDetail> for StockTakeLine {
+    fn from(detail: StockTakeLineDetail) -> Self {
+        let line = detail.line;
+        let unit_cost = line.unit_cost.unwrap_or(detail.average_cost);
+        let variance = line.counted_quantity.map(|c| c - line.system_quantity);
+        Self {
+            line_id: line.line_id,
+            stock_item_id: line.stock_item_id,
+            name: detail.name,
+            sku: detail.sku,
+            shelf_location: detail.shelf_location,
+            system_quantity: line.system_quantity,
+            counted_quantity: line.counted_quantity,
+            variance,
+            unit_cost: unit_cost.to_f64().unwrap_or(0.0),
+            variance_value: variance.map(|v| {
+                (&unit_cost * BigDecimal::from(v))
+                    .round(2)
+                    .to_f64()
+                    .unwrap_or(0.0)
+            }),
+            counted_by: line.counted_by,
+            counted_at: line.counted_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StockTakeResponse {
+    pub stock_take_id: i32,
+    pub branch_id: i32,
+    pub status: StockTakeStatusEnum,
+    pub category: Option<String>,
+    pub notes: Option<String>,
+    pub opened_by: Option<i32>,
+    pub posted_by: Option<i32>,
+    pub opened_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub counted: usize,
+    pub uncounted: usize,
+    pub lines: Vec<StockTakeLine>,
+}
+
+impl From<StockTakeDetail> for StockTakeResponse {
+    fn from(detail: StockTakeDetail) -> Self {
+        let session = detail.stock_take;
+        let lines: Vec<StockTakeLine> = detail.lines.into_iter().map(StockTakeLine::from).collect();
+        let counted = lines
+            .iter()
+            .filter(|l| l.counted_quantity.is_some())
+            .count();
+        Self {
+            stock_take_id: session.stock_take_id,
+            branch_id: session.branch_id,
+            status: session.status,
+            category: session.category,
+            notes: session.notes,
+            opened_by: session.opened_by,
+            posted_by: session.posted_by,
+            opened_at: session.opened_at,
+            closed_at: session.closed_at,
+            counted,
+            uncounted: lines.len() - counted,
+            lines,
+        }
+    }
+}
+
+/// A line of the count sheet as the people counting see it, without what the
+/// system expects or what the stock is worth, so the count stays blind
+#[derive(Debug, Serialize)]
+pub struct CountSheetLine {
+    pub line_id: i32,
+    pub stock_item_id: i32,
+    pub name: String,
+    pub sku: Option<String>,
+    pub shelf_location: Option<String>,
+    pub counted_quantity: Option<i32>,
+    pub counted_by: Option<i32>,
+    pub counted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CountSheet {
+    pub stock_take_id: i32,
+    pub branch_id: i32,
+    pub status: StockTakeStatusEnum,
+    pub category: Option<String>,
+    pub notes: Option<String>,
+    pub opened_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub counted: usize,
+    pub uncounted: usize,
+    pub lines: Vec<CountSheetLine>,
+}
+
+impl From<StockTakeResponse> for CountSheet {
+    fn from(response: StockTakeResponse) -> Self {
+        Self {
+            stock_take_id: response.stock_take_id,
+            branch_id: response.branch_id,
+            status: response.status,
+            category: response.category,
+            notes: response.notes,
+            opened_at: response.opened_at,
+            closed_at: response.closed_at,
+            counted: response.counted,
+            uncounted: response.uncounted,
+            lines: response
+                .lines
+                .into_iter()
+                .map(|line| CountSheetLine {
+                    line_id: line.line_id,
+                    stock_item_id: line.stock_item_id,
+                    name: line.name,
+                    sku: line.sku,
+                    shelf_location: line.shelf_location,
+                    counted_quantity: line.counted_quantity,
+                    counted_by: line.counted_by,
+                    counted_at: line.counted_at,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// What the count found: the items that were off and what that was worth.
+/// Final once the stock take is posted, since the lines keep the cost they
+/// were booked at.
+#[derive(Debug, Serialize)]
+pub struct VarianceReport {
+    pub stock_take_id: i32,
+    pub branch_id: i32,
+    pub status: StockTakeStatusEnum,
+    pub category: Option<String>,
+    pub opened_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub posted_by: Option<i32>,
+    pub counted: usize,
+    pub uncounted: usize,
+    pub surplus_value: f64,
+    pub shortage_value: f64,
+    pub net_value: f64,
+    pub lines: Vec<StockTakeLine>,
+}
+
+impl From<StockTakeDetail> for VarianceReport {
+    fn from(detail: StockTakeDetail) -> Self {
+        let mut surplus = BigDecimal::zero();
+        let mut shortage = BigDecimal::zero();
+        for item in &detail.lines {
+            let line = &item.line;
+            let Some(counted) = line.counted_quantity else {
+                continue;
+            };
+            let unit_cost = line.unit_cost.as_ref().unwrap_or(&item.average_cost);
+            let value = unit_cost * BigDecimal::from(counted - line.system_quantity);
+            if value > BigDecimal::zero() {
+                surplus += value;
+            } else {
+                shortage -= value;
+            }
+        }
+
+        let summary = StockTakeResponse::from(detail);
+        Self {
+            stock_take_id: summary.stock_take_id,
+            branch_id: summary.branch_id,
+            status: summary.status,
+            category: summary.category,
+            opened_at: summary.opened_at,
+            closed_at: summary.closed_at,
+            posted_by: summary.posted_by,
+            counted: summary.counted,
+            uncounted: summary.uncounted,
+            net_value: (&surplus - &shortage).round(2).to_f64().unwrap_or(0.0),
+            surplus_value: surplus.round(2).to_f64().unwrap_or(0.0),
+            shortage_value: shortage.round(2).to_f64().unwrap_or(0.0),
+            lines: summary
+                .lines
+                .into_iter()
+                .filter(|l| l.variance.is_some_and(|v| v != 0))
+                .collect(),
+        }
+    }
+}
+
+/// Counting a branch's shelves: open a session for everything or one
+/// category, record counts, review the variances and post them as
+/// stock-take movements
+pub struct StockTakeUseCase {
+    stock_take_repo: StockTakeRepository,
+    audit_repo: AuditEventRepository,
+    low_stock: LowStockUseCase,
+    branch_use_case: BranchUseCase,
+}
+
+impl StockTakeUseCase {
+    pub fn new(
+        stock_take_repo: StockTakeRepository,
+        audit_repo: AuditEventRepository,
+        low_stock: LowStockUseCase,
+        branch_use_case: BranchUseCase,
+    ) -> Self {
+        Self {
+            stock_take_repo,
+            audit_repo,
+            low_stock,
+            branch_use_case,
+        }
+    }
+
+    /// Stock takes at the branches the user may see
+    pub async fn list(
+        &self,
+        query: StockTakeQuery,
+        user_id: i32,
+        role: &Role,
+    ) -> Result<Vec<StockTakeModel>, String> {
+        let branch_ids = self
+            .branch_use_case
+            .scope(user_id, role, query.branch_id)
+            .await?;
+        self.stock_take_repo.list(query.status, branch_ids).await
+    }
+
+    /// The branch the stock take counts
+    pub async fn branch_of(&self, stock_take_id: i32) -> Result<i32, String> {
+        Ok(self.detail(stock_take_id).await?.stock_take.branch_id)
+    }
+
+    async fn detail(&self, stock_take_id: i32) -> Result<StockTakeDetail, String> {
+        self.stock_take_repo
+            .find_detail(stock_take_id)
+            .await?
+            .ok_or_else(|| "Stock take not found".to_string())
+    }
+
+    pub async fn get(&self, stock_take_id: i32) -> Result<StockTakeResponse, String> {
+        self.detail(stock_take_id)
+            .await
+            .map(StockTakeResponse::from)
+    }
+
+    pub async fn variance_report(&self, stock_take_id: i32) -> Result<VarianceReport, String> {
+        self.detail(stock_take_id).await.map(VarianceReport::from)
+    }
+
+    pub async fn open(
+        &self,
+        command: OpenStockTakeCommand,
+        user_id: i32,
+        role: &Role,
+    ) -> Result<StockTakeResponse, String> {
+        let branch_id = self
+            .branch_use_case
+            .resolve(user_id, role, command.branch_id)
+            .await?;
+        let session = self
+            .stock_take_repo
+            .open(
+                optional_text(command.category),
+                optional_text(command.notes),
+                user_id,
+                branch_id,
+            )
+            .await?;
+        self.get(session.stock_take_id).await
+    }
+
+    pub async fn record_counts(
+        &self,
+        stock_take_id: i32,
+        command: RecordCountsCommand,
+        user_id: i32,
+    ) -> Result<StockTakeResponse, String> {
+        let counts = command
+            .counts
+            .into_iter()
+            .map(|count| CountInput {
+                stock_item_id: count.stock_item_id,
+                counted_quantity: count.counted_quantity,
+            })
+            .collect();
+
+        self.stock_take_repo
+            .record_counts(stock_take_id, counts, user_id, Utc::now())
+            .await
+            .map(StockTakeResponse::from)
+    }
+
+    /// Books the variances into stock and returns the final report
+    pub async fn post(
+        &self,
+        stock_take_id: i32,
+        actor: &AuditActor,
+    ) -> Result<VarianceReport, String> {
+        let report = VarianceReport::from(
+            self.stock_take_repo
+                .post(stock_take_id, actor.user_id, Utc::now())
+                .await?,
+        );
+
+        let summary = serde_json::json!({
+            "branch_id": report.branch_id,
+            "category": report.category,
+            "counted": report.counted,
+            "uncounted": report.uncounted,
+            "adjusted": report.lines.len(),
+            "surplus_value": report.surplus_value,
+            "shortage_value": report.shortage_value,
+            "net_value": report.net_value,
+        });
+        let event = AuditEvent::new(actor, STOCK_TAKE_POSTED, "stock_take", stock_take_id)
+            .with_snapshot(&summary);
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+        if !report.lines.is_empty() {
+            self.low_stock.dispatch_alerts_in_background();
+        }
+
+        Ok(report)
+    }
+
+    pub async fn cancel(
+        &self,
+        stock_take_id: i32,
+        command: CancelStockTakeCommand,
+        actor: &AuditActor,
+    ) -> Result<StockTakeResponse, String> {
+        self.stock_take_repo
+            .cancel(stock_take_id, Utc::now())
+            .await?;
+
+        let mut event = AuditEvent::new(actor, STOCK_TAKE_CANCELLED, "stock_take", stock_take_id);
+        if let Some(reason) = optional_text(command.reason) {
+            event = event.with_reason(&reason);
+        }
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        self.get(stock_take_id).await
+    }
+}
@@ -0,0 +1,174 @@
+use crate::application::input::optional_text;
+use crate::application::use_cases::branch::BranchUseCase;
+use crate::domain::audit::entity::STOCK_TRANSFERRED;
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::models::NewStockTransfer;
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::stock_transfer::{
+    StockTransferDetail, StockTransferRepository, TransferLineInput,
+};
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct TransferLineCommand {
+    pub stock_item_id: i32,
+    pub quantity: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StockTransferCommand {
+    pub from_branch_id: i32,
+    pub to_branch_id: i32,
+    pub note: Option<String>,
+    pub lines: Vec<TransferLineCommand>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StockTransferQuery {
+    /// Only transfers leaving or reaching this branch
+    pub branch_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StockTransferLine {
+    pub line_id: i32,
+    pub stock_item_id: i32,
+    pub quantity: i32,
+    pub unit_cost: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StockTransferResponse {
+    pub transfer_id: i32,
+    pub from_branch_id: i32,
+    pub to_branch_id: i32,
+    pub note: Option<String>,
+    pub created_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    /// What the moved stock is worth at the cost it moved at
+    pub total_value: f64,
+    pub lines: Vec<StockTransferLine>,
+}
+
+impl From<StockTransferDetail> for StockTransferResponse {
+    fn from(detail: StockTransferDetail) -> Self {
+        let transfer = detail.transfer;
+        let total_value: BigDecimal = detail
+            .lines
+            .iter()
+            .map(|line| &line.unit_cost * BigDecimal::from(line.quantity))
+            .sum();
+        Self {
+            transfer_id: transfer.transfer_id,
+            from_branch_id: transfer.from_branch_id,
+            to_branch_id: transfer.to_branch_id,
+            note: transfer.note,
+            created_by: transfer.created_by,
+            created_at: transfer.created_at,
+            total_value: total_value.round(2).to_f64().unwrap_or(0.0),
+            lines: detail
+                .lines
+                .into_iter()
+                .map(|line| StockTransferLine {
+                    line_id: line.line_id,
+                    stock_item_id: line.stock_item_id,
+                    quantity: line.quantity,
+                    unit_cost: line.unit_cost.to_f64().unwrap_or(0.0),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Sending stock from one branch to another
+pub struct StockTransferUseCase {
+    transfer_repo: StockTransferRepository,
+    audit_repo: AuditEventRepository,
+    branch_use_case: BranchUseCase,
+}
+
+impl StockTransferUseCase {
+    pub fn new(
+        transfer_repo: StockTransferRepository,
+        audit_repo: AuditEventRepository,
+        branch_use_case: BranchUseCase,
+    ) -> Self {
+        Self {
+            transfer_repo,
+            audit_repo,
+            branch_use_case,
+        }
+    }
+
+    /// Transfers touching the branches the user may see
+    pub async fn list(
+        &self,
+        query: StockTransferQuery,
+        user_id: i32,
+        role: &Role,
+    ) -> Result<Vec<StockTransferResponse>, String> {
+        let branch_ids = self
+            .branch_use_case
+            .scope(user_id, role, query.branch_id)
+            .await?;
+        Ok(self
+            .transfer_repo
+            .list(branch_ids)
+            .await?
+            .into_iter()
+            .map(StockTransferResponse::from)
+            .collect())
+    }
+
+    /// Only someone who can act on the sending branch can send its stock;
+    /// the receiving branch just has to be open
+    pub async fn create(
+        &self,
+        command: StockTransferCommand,
+        actor: &AuditActor,
+        role: &Role,
+    ) -> Result<StockTransferResponse, String> {
+        self.branch_use_case
+            .resolve(actor.user_id, role, Some(command.from_branch_id))
+            .await?;
+        self.branch_use_case.find_open(command.to_branch_id).await?;
+
+        let lines = command
+            .lines
+            .into_iter()
+            .map(|line| TransferLineInput {
+                stock_item_id: line.stock_item_id,
+                quantity: line.quantity,
+            })
+            .collect();
+        let transfer = StockTransferResponse::from(
+            self.transfer_repo
+                .create(
+                    NewStockTransfer {
+                        from_branch_id: command.from_branch_id,
+                        to_branch_id: command.to_branch_id,
+                        note: optional_text(command.note),
+                        created_by: Some(actor.user_id),
+                    },
+                    lines,
+                )
+                .await?,
+        );
+
+        let event = AuditEvent::new(
+            actor,
+            STOCK_TRANSFERRED,
+            "stock_transfer",
+            transfer.transfer_id,
+        )
+        .with_snapshot(&transfer);
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+
+        Ok(transfer)
+    }
+}
@@ -0,0 +1,227 @@
+use crate::domain::audit::entity::MOTORCYCLE_TRANSFER_INITIATED;
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::notification::gateway::{NotificationGateway, NotificationMessage};
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::models::{
+    MotorcycleTransferModel, MotorcycleTransferStatusEnum, NewMotorcycleTransfer,
+};
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::motorcycle::MotorcycleRepository;
+use crate::infrastructure::db::repositories::motorcycle_transfer::{
+    MotorcycleTransferRepository, MotorcycleTransferView,
+};
+use crate::infrastructure::db::repositories::user::UserRepository;
+use crate::infrastructure::db::repositories::user_line_account::UserLineAccountRepository;
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct InitiateTransferCommand {
+    /// Username or phone number of the new owner
+    pub new_owner: String,
+}
+
+pub struct TransferMotorcycleUseCase {
+    bike_repo: MotorcycleRepository,
+    transfer_repo: MotorcycleTransferRepository,
+    user_repo: UserRepository,
+    line_repo: UserLineAccountRepository,
+    notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
+    audit_repo: AuditEventRepository,
+}
+
+impl TransferMotorcycleUseCase {
+    pub fn new(
+        bike_repo: MotorcycleRepository,
+        transfer_repo: MotorcycleTransferRepository,
+        user_repo: UserRepository,
+        line_repo: UserLineAccountRepository,
+        notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
+        audit_repo: AuditEventRepository,
+    ) -> Self {
+        Self {
+            bike_repo,
+            transfer_repo,
+            user_repo,
+            line_repo,
+            notification_gateway,
+            audit_repo,
+        }
+    }
+
+    pub async fn list(&self, user_id: i32) -> Result<Vec<MotorcycleTransferView>, String> {
+        self.transfer_repo.list_for_user(user_id).await
+    }
+
+    /// Offers a bike to another customer. Only the owner or an admin may do this.
+    pub async fn initiate(
+        &self,
+        actor: &AuditActor,
+        role: &Role,
+        bike_id: i32,
+        command: InitiateTransferCommand,
+    ) -> Result<MotorcycleTransferModel, String> {
+        let bike = self
+            .bike_repo
+            .find_by_id(bike_id)
+            .await?
+            .filter(|b| *role == Role::Admin || b.user_id == actor.user_id)
+            .ok_or("Motorcycle not found".to_string())?;
+
+        if self.bike_repo.has_open_orders(bike_id).await? {
+            return Err("Motorcycles with open service orders can't be transferred".to_string());
+        }
+        if self
+            .transfer_repo
+            .find_pending_for_bike(bike_id)
+            .await?
+            .is_some()
+        {
+            return Err("A transfer is already pending for this motorcycle".to_string());
+        }
+
+        let new_owner_ref = command.new_owner.trim();
+        let new_owner = match self.user_repo.find_by_username(new_owner_ref).await? {
+            Some(user) => Some(user),
+            None => self.user_repo.find_by_phone(new_owner_ref).await?,
+        }
+        .filter(|u| u.is_active && !u.is_guest && u.role == Role::Customer)
+        .ok_or("No customer found with that username or phone".to_string())?;
+        let new_owner_id = new_owner.id.ok_or("User ID missing")?;
+        if new_owner_id == bike.user_id {
+            return Err("This customer already owns the motorcycle".to_string());
+        }
+
+        let transfer = self
+            .transfer_repo
+            .create(NewMotorcycleTransfer {
+                bike_id,
+                from_user_id: bike.user_id,
+                to_user_id: new_owner_id,
+                initiated_by: actor.user_id,
+            })
+            .await?;
+
+        if *role == Role::Admin {
+            let event =
+                AuditEvent::new(actor, MOTORCYCLE_TRANSFER_INITIATED, "motorcycle", bike_id)
+                    .with_change(
+                        &serde_json::json!({ "user_id": bike.user_id }),
+                        &serde_json::json!({ "user_id": new_owner_id }),
+                    );
+            if let Err(e) = self.audit_repo.record(event).await {
+                tracing::error!("Failed to record audit event: {}", e);
+            }
+        }
+
+        self.notify(
+            new_owner_id,
+            "🏍️ Motorcycle transfer".to_string(),
+            format!(
+                "{} {} ({}) is being transferred to you. Accept it in My Garage to add it to your account.",
+                bike.brand, bike.model, bike.license_plate
+            ),
+        )
+        .await;
+
+        Ok(transfer)
+    }
+
+    /// The new owner takes the bike; its service history moves with it
+    pub async fn accept(&self, user_id: i32, transfer_id: i32) -> Result<(), String> {
+        let transfer = self.find_pending(transfer_id).await?;
+        if transfer.to_user_id != user_id {
+            return Err("Transfer not found".to_string());
+        }
+
+        self.transfer_repo.accept(&transfer).await?;
+
+        self.notify(
+            transfer.from_user_id,
+            "🏍️ Transfer completed".to_string(),
+            "The new owner accepted your motorcycle transfer.".to_string(),
+        )
+        .await;
+        Ok(())
+    }
+
+    pub async fn decline(&self, user_id: i32, transfer_id: i32) -> Result<(), String> {
+        let transfer = self.find_pending(transfer_id).await?;
+        if transfer.to_user_id != user_id {
+            return Err("Transfer not found".to_string());
+        }
+
+        self.close(&transfer, MotorcycleTransferStatusEnum::Declined)
+            .await?;
+        self.notify(
+            transfer.from_user_id,
+            "🏍️ Transfer declined".to_string(),
+            "The recipient declined your motorcycle transfer.".to_string(),
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Withdrawn by the current owner, whoever started it, or an admin
+    pub async fn cancel(&self, user_id: i32, role: &Role, transfer_id: i32) -> Result<(), String> {
+        let transfer = self.find_pending(transfer_id).await?;
+        if *role != Role::Admin
+            && transfer.from_user_id != user_id
+            && transfer.initiated_by != user_id
+        {
+            return Err("Transfer not found".to_string());
+        }
+
+        self.close(&transfer, MotorcycleTransferStatusEnum::Cancelled)
+            .await
+    }
+
+    async fn find_pending(&self, transfer_id: i32) -> Result<MotorcycleTransferModel, String> {
+        self.transfer_repo
+            .find_by_id(transfer_id)
+            .await?
+            .filter(|t| t.status == MotorcycleTransferStatusEnum::Pending)
+            .ok_or("Transfer not found".to_string())
+    }
+
+    async fn close(
+        &self,
+        transfer: &MotorcycleTransferModel,
+        status: MotorcycleTransferStatusEnum,
+    ) -> Result<(), String> {
+        if !self
+            .transfer_repo
+            .close(transfer.transfer_id, status)
+            .await?
+        {
+            return Err("This transfer is no longer valid".to_string());
+        }
+        Ok(())
+    }
+
+    async fn notify(&self, user_id: i32, title: String, body: String) {
+        let recipient = self
+            .line_repo
+            .find_by_user_id(user_id)
+            .await
+            .ok()
+            .flatten()
+            .map(|l| l.line_user_id)
+            .unwrap_or_default();
+
+        if let Err(e) = self
+            .notification_gateway
+            .send_notification(NotificationMessage {
+                user_id,
+                order_id: None,
+                recipient,
+                title,
+                body,
+                custom_payload: None,
+            })
+            .await
+        {
+            tracing::warn!("Failed to send transfer notification: {}", e);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::domain::motorcycle::MotorcycleDetails;
+use crate::infrastructure::db::models::{MotorcycleChangeset, MotorcycleModel};
+use crate::infrastructure::db::repositories::motorcycle::MotorcycleRepository;
+
+pub struct UpdateMotorcycleUseCase {
+    bike_repo: MotorcycleRepository,
+}
+
+impl UpdateMotorcycleUseCase {
+    pub fn new(bike_repo: MotorcycleRepository) -> Self {
+        Self { bike_repo }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: i32,
+        bike_id: i32,
+        details: MotorcycleDetails,
+    ) -> Result<MotorcycleModel, String> {
+        let (details, plate) = details.normalise()?;
+
+        let bike = self
+            .bike_repo
+            .find_by_id(bike_id)
+            .await?
+            .filter(|b| b.user_id == user_id && b.archived_at.is_none())
+            .ok_or("Motorcycle not found".to_string())?;
+
+        // Odometers only go up; a lower reading is a typo or a swapped bike
+        let mileage = details.mileage.unwrap_or(bike.mileage);
+        if mileage < bike.mileage {
+            return Err(format!(
+                "Mileage cannot go below the recorded {} km",
+                bike.mileage
+            ));
+        }
+
+        if let Some(other) = self
+            .bike_repo
+            .find_by_license_and_user(&plate, user_id)
+            .await?
+            && other.bike_id != bike_id
+        {
+            return Err("A motorcycle with this license plate is already registered".to_string());
+        }
+
+        if let Some(vin) = &details.vin
+            && let Some(other) = self.bike_repo.find_by_vin(vin).await?
+            && other.bike_id != bike_id
+        {
+            return Err("A motorcycle with this VIN is already registered".to_string());
+        }
+
+        self.bike_repo
+            .update_motorcycle(
+                bike_id,
+                MotorcycleChangeset {
+                    brand: details.brand,
+                    model: details.model,
+                    license_plate: details.license_plate,
+                    year: details.year,
+                    colour: details.colour,
+                    engine_number: details.engine_number,
+                    vin: details.vin,
+                    mileage,
+                    plate_key: Some(plate.key()),
+                    plate_province: plate.province().map(str::to_string),
+                },
+            )
+            .await
+    }
+}
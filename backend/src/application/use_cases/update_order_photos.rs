@@ -18,7 +18,11 @@ impl UpdateOrderPhotosUseCase {
         Self { order_repo }
     }
 
-    pub async fn execute(&self, command: UpdateOrderPhotosCommand) -> Result<(), String> {
+    pub async fn execute(
+        &self,
+        command: UpdateOrderPhotosCommand,
+        user_id: i32,
+    ) -> Result<(), String> {
         let mut order = self
             .order_repo
             .find_by_id(command.order_id)
@@ -32,7 +36,7 @@ impl UpdateOrderPhotosUseCase {
             order.after_picture_url = Some(url);
         }
 
-        self.order_repo.update_order(order).await?;
+        self.order_repo.update_order(order, Some(user_id)).await?;
         Ok(())
     }
 }
@@ -1,6 +1,10 @@
+use crate::application::use_cases::low_stock::LowStockUseCase;
+use crate::application::use_cases::loyalty::LoyaltyUseCase;
 use crate::domain::notification::gateway::{NotificationGateway, NotificationMessage};
 use crate::domain::service::entity::{OrderStatus, ServiceOrder};
 use crate::domain::user::entity::Role;
+use crate::infrastructure::db::models::QuotationStatusEnum;
+use crate::infrastructure::db::repositories::quotation::QuotationRepository;
 use crate::infrastructure::db::repositories::repair_log::RepairLogRepository;
 use crate::infrastructure::db::repositories::service_order::ServiceOrderRepository;
 use crate::infrastructure::db::repositories::user::UserRepository;
@@ -22,15 +26,22 @@ pub struct UpdateOrderStatusUseCase {
     user_repo: UserRepository,
     notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
     repair_log_repo: RepairLogRepository,
+    loyalty: LoyaltyUseCase,
+    quotation_repo: QuotationRepository,
+    low_stock: LowStockUseCase,
 }
 
 impl UpdateOrderStatusUseCase {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         order_repo: ServiceOrderRepository,
         line_repo: UserLineAccountRepository,
         user_repo: UserRepository,
         notification_gateway: Arc<dyn NotificationGateway + Send + Sync>,
         repair_log_repo: RepairLogRepository,
+        loyalty: LoyaltyUseCase,
+        quotation_repo: QuotationRepository,
+        low_stock: LowStockUseCase,
     ) -> Self {
         Self {
             order_repo,
@@ -38,6 +49,9 @@ impl UpdateOrderStatusUseCase {
             user_repo,
             notification_gateway,
             repair_log_repo,
+            loyalty,
+            quotation_repo,
+            low_stock,
         }
     }
 
@@ -67,6 +81,16 @@ impl UpdateOrderStatusUseCase {
                                 "You can only confirm an order that has a price offer".into()
                             );
                         }
+                        if self
+                            .quotation_repo
+                            .find_pending(command.order_id)
+                            .await?
+                            .is_some()
+                        {
+                            return Err(
+                                "Please approve or decline the items on the quotation".into()
+                            );
+                        }
                     }
                     OrderStatus::Cancelled => {
                         if order.status == OrderStatus::Repairing
@@ -140,7 +164,8 @@ impl UpdateOrderStatusUseCase {
             order.total_price = price;
         }
 
-        let updated_order = self.order_repo.update_order(order).await?;
+        let updated_order = self.order_repo.update_order(order, Some(user_id)).await?;
+        let order_id = updated_order.id.ok_or("Order has no ID")?;
 
         // 4. Log the repair trail
         let log_note = format!(
@@ -150,269 +175,278 @@ impl UpdateOrderStatusUseCase {
         let _ = self
             .repair_log_repo
             .add_log(
-                updated_order.id.unwrap(),
+                order_id,
                 user_id,
                 log_note,
                 updated_order.status.clone().into(),
             )
             .await;
 
-        // Only send notification if status has changed
-        if old_status != updated_order.status {
-            let status_text = match updated_order.status {
-                OrderStatus::Booked => "📋 Review Pending",
-                OrderStatus::ReviewPending => "🔍 Inspection in Progress",
-                OrderStatus::OfferSent => "💰 Quote Sent - Awaiting Confirmation",
-                OrderStatus::Repairing => "🔧 Repairing",
-                OrderStatus::Completed => "✅ Repair Completed - Ready for Pickup",
-                OrderStatus::Cancelled => "❌ Cancelled",
-                OrderStatus::Paid => "💳 Paid",
+        // 5. A quote still waiting on the customer no longer applies
+        if old_status == OrderStatus::OfferSent && updated_order.status != OrderStatus::OfferSent {
+            let closed = if updated_order.status == OrderStatus::Cancelled {
+                QuotationStatusEnum::Declined
+            } else {
+                QuotationStatusEnum::Superseded
             };
+            if let Err(e) = self.quotation_repo.close_pending(order_id, closed).await {
+                tracing::error!("Failed to close pending quotation: {}", e);
+            }
+        }
 
-            let status_color = match updated_order.status {
-                OrderStatus::ReviewPending => "#8b5cf6", // Violet
-                OrderStatus::OfferSent => "#6366f1",     // Indigo
-                OrderStatus::Repairing => "#f59e0b",     // Amber
-                OrderStatus::Completed => "#10b981",     // Emerald
-                OrderStatus::Paid => "#3b82f6",          // Blue
-                OrderStatus::Cancelled => "#ef4444",     // Red
-                _ => "#6b7280",                          // Gray
+        // 6. Points: earned once paid; on cancellation spent points come back
+        // and earned ones are taken back
+        if old_status != updated_order.status {
+            let loyalty_result = match updated_order.status {
+                OrderStatus::Paid => self.loyalty.award_for_order(&updated_order).await,
+                OrderStatus::Cancelled => self.loyalty.refund_order(order_id).await,
+                _ => Ok(0),
             };
+            if let Err(e) = loyalty_result {
+                tracing::error!("Failed to update loyalty points: {}", e);
+            }
+            // Starting work takes the order's reserved parts out of stock
+            let was_quoting = matches!(
+                old_status,
+                OrderStatus::Booked | OrderStatus::ReviewPending | OrderStatus::OfferSent
+            );
+            let work_started = matches!(
+                updated_order.status,
+                OrderStatus::Repairing | OrderStatus::Completed | OrderStatus::Paid
+            );
+            if was_quoting && work_started {
+                self.low_stock.dispatch_alerts_in_background();
+            }
+        }
 
-            let self_clone = self.clone();
-            let updated_order_clone = updated_order.clone();
-            let status_text_str = status_text.to_string();
-            let flex_payload =
-                self.create_flex_message(&updated_order, "ORDER UPDATE", status_text, status_color);
-
-            // Fire and forget notifications in background
-            tokio::spawn(async move {
-                // 1. Notify Customer
-                let customer_line_id = self_clone
-                    .line_repo
-                    .find_by_user_id(updated_order_clone.customer_id)
-                    .await
-                    .ok()
-                    .flatten()
-                    .map(|l| l.line_user_id)
-                    .unwrap_or_default();
-
-                let (customer_title, customer_body, _customer_alt) = match updated_order_clone
-                    .status
-                {
-                    OrderStatus::Completed => (
-                        format!(
-                            "✅ Repair Completed! | #SO-{}",
-                            updated_order_clone.id.unwrap()
-                        ),
-                        format!(
-                            "Your vehicle for order #SO-{} is ready for pickup! 🛵",
-                            updated_order_clone.id.unwrap()
-                        ),
-                        format!(
-                            "✅ Repair Completed! Order #SO-{}\nYour vehicle is ready for pickup!",
-                            updated_order_clone.id.unwrap()
-                        ),
+        // Only send notification if status has changed
+        if old_status != updated_order.status {
+            self.notify_status_change(&updated_order, user_id);
+        }
+
+        Ok(updated_order)
+    }
+
+    /// Tells the customer, admins and mechanics (other than `user_id`, who
+    /// made the change) that the order has moved to its current status
+    pub fn notify_status_change(&self, order: &ServiceOrder, user_id: i32) {
+        let Some(order_id) = order.id else {
+            return;
+        };
+        let status_text = match order.status {
+            OrderStatus::Booked => "📋 Review Pending",
+            OrderStatus::ReviewPending => "🔍 Inspection in Progress",
+            OrderStatus::OfferSent => "💰 Quote Sent - Awaiting Confirmation",
+            OrderStatus::Repairing => "🔧 Repairing",
+            OrderStatus::Completed => "✅ Repair Completed - Ready for Pickup",
+            OrderStatus::Cancelled => "❌ Cancelled",
+            OrderStatus::Paid => "💳 Paid",
+        };
+
+        let status_color = match order.status {
+            OrderStatus::ReviewPending => "#8b5cf6", // Violet
+            OrderStatus::OfferSent => "#6366f1",     // Indigo
+            OrderStatus::Repairing => "#f59e0b",     // Amber
+            OrderStatus::Completed => "#10b981",     // Emerald
+            OrderStatus::Paid => "#3b82f6",          // Blue
+            OrderStatus::Cancelled => "#ef4444",     // Red
+            _ => "#6b7280",                          // Gray
+        };
+
+        let self_clone = self.clone();
+        let updated_order_clone = order.clone();
+        let status_text_str = status_text.to_string();
+        let flex_payload =
+            self.create_flex_message(order, "ORDER UPDATE", status_text, status_color);
+
+        // Fire and forget notifications in background
+        tokio::spawn(async move {
+            // 1. Notify Customer
+            let customer_line_id = self_clone
+                .line_repo
+                .find_by_user_id(updated_order_clone.customer_id)
+                .await
+                .ok()
+                .flatten()
+                .map(|l| l.line_user_id)
+                .unwrap_or_default();
+
+            let (customer_title, customer_body, _customer_alt) = match updated_order_clone.status {
+                OrderStatus::Completed => (
+                    format!("✅ Repair Completed! | #SO-{}", order_id),
+                    format!(
+                        "Your vehicle for order #SO-{} is ready for pickup! 🛵",
+                        order_id
                     ),
-                    OrderStatus::Repairing => (
-                        format!(
-                            "🔧 Repair Started | #SO-{}",
-                            updated_order_clone.id.unwrap()
-                        ),
-                        format!(
-                            "Repair has started for order #SO-{}. We will notify you once it's finished.",
-                            updated_order_clone.id.unwrap()
-                        ),
-                        format!(
-                            "🔧 Order #SO-{} is now being repaired.\nOur mechanics have started. We'll notify you when done.",
-                            updated_order_clone.id.unwrap()
-                        ),
+                    format!(
+                        "✅ Repair Completed! Order #SO-{}\nYour vehicle is ready for pickup!",
+                        order_id
                     ),
-                    OrderStatus::OfferSent => (
-                        format!("💰 Repair Quote | #SO-{}", updated_order_clone.id.unwrap()),
-                        format!(
-                            "Quote for order #SO-{} is available: ฿{}.\nPlease confirm to start the repair.",
-                            updated_order_clone.id.unwrap(),
-                            updated_order_clone.total_price
-                        ),
-                        format!(
-                            "💰 Quote Sent! Order #SO-{}\nPrice: ฿{}\nPlease check and confirm.",
-                            updated_order_clone.id.unwrap(),
-                            updated_order_clone.total_price
-                        ),
+                ),
+                OrderStatus::Repairing => (
+                    format!("🔧 Repair Started | #SO-{}", order_id),
+                    format!(
+                        "Repair has started for order #SO-{}. We will notify you once it's finished.",
+                        order_id
                     ),
-                    OrderStatus::ReviewPending => (
-                        format!(
-                            "🔍 Inspection in Progress | #SO-{}",
-                            updated_order_clone.id.unwrap()
-                        ),
-                        format!(
-                            "Order #SO-{} is currently being inspected by our mechanic.\nWe will provide a quote shortly.",
-                            updated_order_clone.id.unwrap()
-                        ),
-                        format!(
-                            "🔍 Inspection started for #SO-{}\nWait for our quote. We'll notify you soon.",
-                            updated_order_clone.id.unwrap()
-                        ),
+                    format!(
+                        "🔧 Order #SO-{} is now being repaired.\nOur mechanics have started. We'll notify you when done.",
+                        order_id
                     ),
-                    OrderStatus::Cancelled => (
-                        format!(
-                            "❌ Order Cancelled | #SO-{}",
-                            updated_order_clone.id.unwrap()
-                        ),
-                        format!(
-                            "Order #SO-{} has been cancelled.\nPlease contact us if you have any questions.",
-                            updated_order_clone.id.unwrap()
-                        ),
-                        format!(
-                            "❌ Order #SO-{} cancelled.\nContact us if you have issues.",
-                            updated_order_clone.id.unwrap()
-                        ),
+                ),
+                OrderStatus::OfferSent => (
+                    format!("💰 Repair Quote | #SO-{}", order_id),
+                    format!(
+                        "Quote for order #SO-{} is available: ฿{}.\nPlease confirm to start the repair.",
+                        order_id, updated_order_clone.total_price
                     ),
-                    _ => (
-                        format!("📋 Status Update | #SO-{}", updated_order_clone.id.unwrap()),
-                        format!(
-                            "Order #SO-{} status updated to: {}",
-                            updated_order_clone.id.unwrap(),
-                            status_text_str
-                        ),
-                        format!(
-                            "Order #SO-{} status changed: {}",
-                            updated_order_clone.id.unwrap(),
-                            status_text_str
-                        ),
+                    format!(
+                        "💰 Quote Sent! Order #SO-{}\nPrice: ฿{}\nPlease check and confirm.",
+                        order_id, updated_order_clone.total_price
                     ),
-                };
-
-                let _ = self_clone
-                    .notification_gateway
-                    .send_notification(NotificationMessage {
-                        user_id: updated_order_clone.customer_id,
-                        order_id: updated_order_clone.id,
-                        recipient: customer_line_id,
-                        title: customer_title,
-                        body: customer_body,
-                        custom_payload: Some(flex_payload.clone()),
-                    })
-                    .await;
-
-                // 2. Notify Admins
-                if let Ok(admins) = self_clone.user_repo.find_admins().await {
-                    for admin in admins {
-                        if let Some(admin_id) = admin.id {
-                            // Don't notify the person who made the change if they are an admin
-                            if admin_id == user_id {
-                                continue;
-                            }
+                ),
+                OrderStatus::ReviewPending => (
+                    format!("🔍 Inspection in Progress | #SO-{}", order_id),
+                    format!(
+                        "Order #SO-{} is currently being inspected by our mechanic.\nWe will provide a quote shortly.",
+                        order_id
+                    ),
+                    format!(
+                        "🔍 Inspection started for #SO-{}\nWait for our quote. We'll notify you soon.",
+                        order_id
+                    ),
+                ),
+                OrderStatus::Cancelled => (
+                    format!("❌ Order Cancelled | #SO-{}", order_id),
+                    format!(
+                        "Order #SO-{} has been cancelled.\nPlease contact us if you have any questions.",
+                        order_id
+                    ),
+                    format!(
+                        "❌ Order #SO-{} cancelled.\nContact us if you have issues.",
+                        order_id
+                    ),
+                ),
+                _ => (
+                    format!("📋 Status Update | #SO-{}", order_id),
+                    format!(
+                        "Order #SO-{} status updated to: {}",
+                        order_id, status_text_str
+                    ),
+                    format!("Order #SO-{} status changed: {}", order_id, status_text_str),
+                ),
+            };
 
-                            let admin_line_id = self_clone
-                                .line_repo
-                                .find_by_user_id(admin_id)
-                                .await
-                                .ok()
-                                .flatten()
-                                .map(|l| l.line_user_id)
-                                .unwrap_or_default();
-
-                            let admin_body = match updated_order_clone.status {
-                                OrderStatus::ReviewPending => format!(
-                                    "⚠️ Order #SO-{} pending inspection.\nPlease review and send a quote.",
-                                    updated_order_clone.id.unwrap()
-                                ),
-                                OrderStatus::Completed => format!(
-                                    "✅ Order #SO-{} completed successfully.\nTotal Price: ฿{}",
-                                    updated_order_clone.id.unwrap(),
-                                    updated_order_clone.total_price
-                                ),
-                                OrderStatus::Cancelled => {
-                                    format!(
-                                        "❌ Order #SO-{} has been cancelled.",
-                                        updated_order_clone.id.unwrap()
-                                    )
-                                }
-                                _ => format!(
-                                    "📋 Order #SO-{} Status Update: {}\nPrice: ฿{}",
-                                    updated_order_clone.id.unwrap(),
-                                    status_text_str,
-                                    updated_order_clone.total_price
-                                ),
-                            };
-
-                            let _ = self_clone
-                                .notification_gateway
-                                .send_notification(NotificationMessage {
-                                    user_id: admin_id,
-                                    order_id: updated_order_clone.id,
-                                    recipient: admin_line_id,
-                                    title: format!(
-                                        "🔔 Order Update | #SO-{}",
-                                        updated_order_clone.id.unwrap()
-                                    ),
-                                    body: admin_body,
-                                    custom_payload: Some(flex_payload.clone()),
-                                })
-                                .await;
+            let _ = self_clone
+                .notification_gateway
+                .send_notification(NotificationMessage {
+                    user_id: updated_order_clone.customer_id,
+                    order_id: updated_order_clone.id,
+                    recipient: customer_line_id,
+                    title: customer_title,
+                    body: customer_body,
+                    custom_payload: Some(flex_payload.clone()),
+                })
+                .await;
+
+            // 2. Notify Admins
+            if let Ok(admins) = self_clone.user_repo.find_admins().await {
+                for admin in admins {
+                    if let Some(admin_id) = admin.id {
+                        // Don't notify the person who made the change if they are an admin
+                        if admin_id == user_id {
+                            continue;
                         }
+
+                        let admin_line_id = self_clone
+                            .line_repo
+                            .find_by_user_id(admin_id)
+                            .await
+                            .ok()
+                            .flatten()
+                            .map(|l| l.line_user_id)
+                            .unwrap_or_default();
+
+                        let admin_body = match updated_order_clone.status {
+                            OrderStatus::ReviewPending => format!(
+                                "⚠️ Order #SO-{} pending inspection.\nPlease review and send a quote.",
+                                order_id
+                            ),
+                            OrderStatus::Completed => format!(
+                                "✅ Order #SO-{} completed successfully.\nTotal Price: ฿{}",
+                                order_id, updated_order_clone.total_price
+                            ),
+                            OrderStatus::Cancelled => {
+                                format!("❌ Order #SO-{} has been cancelled.", order_id)
+                            }
+                            _ => format!(
+                                "📋 Order #SO-{} Status Update: {}\nPrice: ฿{}",
+                                order_id, status_text_str, updated_order_clone.total_price
+                            ),
+                        };
+
+                        let _ = self_clone
+                            .notification_gateway
+                            .send_notification(NotificationMessage {
+                                user_id: admin_id,
+                                order_id: updated_order_clone.id,
+                                recipient: admin_line_id,
+                                title: format!("🔔 Order Update | #SO-{}", order_id),
+                                body: admin_body,
+                                custom_payload: Some(flex_payload.clone()),
+                            })
+                            .await;
                     }
                 }
+            }
+
+            // 3. Notify Mechanics
+            if let Ok(mechanics) = self_clone.user_repo.find_mechanics().await {
+                for mechanic in mechanics {
+                    if let Some(mech_id) = mechanic.id {
+                        // Don't notify the person who made the change
+                        if mech_id == user_id {
+                            continue;
+                        }
+
+                        let mech_line_id = self_clone
+                            .line_repo
+                            .find_by_user_id(mech_id)
+                            .await
+                            .ok()
+                            .flatten()
+                            .map(|l| l.line_user_id)
+                            .unwrap_or_default();
 
-                // 3. Notify Mechanics
-                if let Ok(mechanics) = self_clone.user_repo.find_mechanics().await {
-                    for mechanic in mechanics {
-                        if let Some(mech_id) = mechanic.id {
-                            // Don't notify the person who made the change
-                            if mech_id == user_id {
-                                continue;
+                        let mech_body = match updated_order_clone.status {
+                            OrderStatus::Repairing => format!(
+                                "🔧 Order #SO-{} customer confirmed!\nYou can start the repair now.",
+                                order_id
+                            ),
+                            OrderStatus::Cancelled => {
+                                format!("❌ Order #SO-{} has been cancelled.", order_id)
                             }
+                            _ => format!(
+                                "📋 Order #SO-{} Status Update: {}",
+                                order_id, status_text_str
+                            ),
+                        };
 
-                            let mech_line_id = self_clone
-                                .line_repo
-                                .find_by_user_id(mech_id)
-                                .await
-                                .ok()
-                                .flatten()
-                                .map(|l| l.line_user_id)
-                                .unwrap_or_default();
-
-                            let mech_body = match updated_order_clone.status {
-                                OrderStatus::Repairing => format!(
-                                    "🔧 Order #SO-{} customer confirmed!\nYou can start the repair now.",
-                                    updated_order_clone.id.unwrap()
-                                ),
-                                OrderStatus::Cancelled => {
-                                    format!(
-                                        "❌ Order #SO-{} has been cancelled.",
-                                        updated_order_clone.id.unwrap()
-                                    )
-                                }
-                                _ => format!(
-                                    "📋 Order #SO-{} Status Update: {}",
-                                    updated_order_clone.id.unwrap(),
-                                    status_text_str
-                                ),
-                            };
-
-                            let _ = self_clone
-                                .notification_gateway
-                                .send_notification(NotificationMessage {
-                                    user_id: mech_id,
-                                    order_id: updated_order_clone.id,
-                                    recipient: mech_line_id,
-                                    title: format!(
-                                        "🔧 Repair Update | #SO-{}",
-                                        updated_order_clone.id.unwrap()
-                                    ),
-                                    body: mech_body,
-                                    custom_payload: Some(flex_payload.clone()),
-                                })
-                                .await;
-                        }
+                        let _ = self_clone
+                            .notification_gateway
+                            .send_notification(NotificationMessage {
+                                user_id: mech_id,
+                                order_id: updated_order_clone.id,
+                                recipient: mech_line_id,
+                                title: format!("🔧 Repair Update | #SO-{}", order_id),
+                                body: mech_body,
+                                custom_payload: Some(flex_payload.clone()),
+                            })
+                            .await;
                     }
                 }
-            });
-        }
-
-        Ok(updated_order)
+            }
+        });
     }
     fn create_flex_message(
         &self,
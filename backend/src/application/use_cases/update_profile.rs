@@ -23,10 +23,15 @@ impl UpdateProfileUseCase {
             .await?
             .ok_or("User not found".to_string())?;
 
+        let phone_changed = user.phone != command.phone;
         user.name = command.name;
         user.phone = command.phone;
 
         self.user_repo.update_user(user).await?;
+        // A new number has to be verified again
+        if phone_changed {
+            self.user_repo.set_phone_verified_at(user_id, None).await?;
+        }
 
         Ok(())
     }
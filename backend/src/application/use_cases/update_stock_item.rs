@@ -1,31 +1,147 @@
-use crate::domain::service::stock_entity::StockItem;
+use crate::application::input::patch;
+use crate::application::use_cases::add_stock_item::ensure_unique_identifiers;
+use crate::application::use_cases::branch::BranchUseCase;
+use crate::application::use_cases::low_stock::LowStockUseCase;
+use crate::domain::audit::entity::STOCK_ITEM_UPDATED;
+use crate::domain::audit::{AuditActor, AuditEvent};
+use crate::domain::service::stock_entity::{DEFAULT_UNIT, StockItem};
+use crate::domain::user::entity::Role;
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
 use crate::infrastructure::db::repositories::stock::StockItemRepository;
 use serde::Deserialize;
 
+/// The optional details keep their saved value when left out and are cleared
+/// by sending null
 #[derive(Deserialize)]
 pub struct UpdateStockItemCommand {
     pub id: i32,
     pub name: String,
     pub price: f64,
+    /// What the branch holds
     pub quantity: i32,
+    #[serde(default, deserialize_with = "patch")]
+    pub reorder_point: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "patch")]
+    pub reorder_quantity: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "patch")]
+    pub sku: Option<Option<String>>,
+    #[serde(default, deserialize_with = "patch")]
+    pub barcode: Option<Option<String>>,
+    #[serde(default, deserialize_with = "patch")]
+    pub category: Option<Option<String>>,
+    #[serde(default, deserialize_with = "patch")]
+    pub brand: Option<Option<String>>,
+    /// Null puts it back to the default unit
+    #[serde(default, deserialize_with = "patch")]
+    pub unit: Option<Option<String>>,
+    #[serde(default, deserialize_with = "patch")]
+    pub shelf_location: Option<Option<String>>,
+    /// Why the quantity changed, kept on the stock movement
+    pub note: Option<String>,
+    /// The branch whose quantity this is; leave out for your own branch
+    pub branch_id: Option<i32>,
 }
 
 pub struct UpdateStockItemUseCase {
     stock_repo: StockItemRepository,
+    audit_repo: AuditEventRepository,
+    low_stock: LowStockUseCase,
+    branch_use_case: BranchUseCase,
 }
 
 impl UpdateStockItemUseCase {
-    pub fn new(stock_repo: StockItemRepository) -> Self {
-        Self { stock_repo }
+    pub fn new(
+        stock_repo: StockItemRepository,
+        audit_repo: AuditEventRepository,
+        low_stock: LowStockUseCase,
+        branch_use_case: BranchUseCase,
+    ) -> Self {
+        Self {
+            stock_repo,
+            audit_repo,
+            low_stock,
+            branch_use_case,
+        }
     }
 
-    pub async fn execute(&self, command: UpdateStockItemCommand) -> Result<StockItem, String> {
+    /// Returns the item with the branch's quantities
+    pub async fn execute(
+        &self,
+        command: UpdateStockItemCommand,
+        actor: &AuditActor,
+        role: &Role,
+    ) -> Result<StockItem, String> {
+        let branch_id = self
+            .branch_use_case
+            .resolve(actor.user_id, role, command.branch_id)
+            .await?;
+        let previous = self
+            .stock_repo
+            .find_by_id(command.id)
+            .await?
+            .ok_or("Stock item not found")?;
+        let previous = self
+            .stock_repo
+            .at_branch(vec![previous], branch_id)
+            .await?
+            .remove(0);
+
         let item = StockItem {
             id: Some(command.id),
             name: command.name,
             price: command.price,
             quantity: command.quantity,
+            reserved_quantity: previous.reserved_quantity,
+            available_quantity: command.quantity - previous.reserved_quantity,
+            reorder_point: command.reorder_point.unwrap_or(previous.reorder_point),
+            reorder_quantity: command
+                .reorder_quantity
+                .unwrap_or(previous.reorder_quantity),
+            sku: command.sku.unwrap_or_else(|| previous.sku.clone()),
+            barcode: command.barcode.unwrap_or_else(|| previous.barcode.clone()),
+            category: command
+                .category
+                .unwrap_or_else(|| previous.category.clone()),
+            brand: command.brand.unwrap_or_else(|| previous.brand.clone()),
+            unit: match command.unit {
+                None => previous.unit.clone(),
+                Some(unit) => unit.unwrap_or_else(|| DEFAULT_UNIT.to_string()),
+            },
+            shelf_location: command
+                .shelf_location
+                .unwrap_or_else(|| previous.shelf_location.clone()),
+            average_cost: previous.average_cost,
+        }
+        .normalized()?;
+        ensure_unique_identifiers(&self.stock_repo, &item).await?;
+        let updated = match self
+            .stock_repo
+            .update_stock_item(item.clone(), actor.user_id, command.note, branch_id)
+            .await
+        {
+            Ok(updated) => updated,
+            Err(e) => {
+                // Another save may have taken the SKU or barcode since the
+                // check above; say so the same way
+                ensure_unique_identifiers(&self.stock_repo, &item).await?;
+                return Err(e);
+            }
         };
-        self.stock_repo.update_stock_item(item).await
+        let updated = self
+            .stock_repo
+            .at_branch(vec![updated], branch_id)
+            .await?
+            .remove(0);
+
+        let event = AuditEvent::new(actor, STOCK_ITEM_UPDATED, "stock_item", command.id)
+            .with_change(&previous, &updated);
+        if let Err(e) = self.audit_repo.record(event).await {
+            tracing::error!("Failed to record audit event: {}", e);
+        }
+        if updated.quantity < previous.quantity {
+            self.low_stock.dispatch_alerts_in_background();
+        }
+
+        Ok(updated)
     }
 }
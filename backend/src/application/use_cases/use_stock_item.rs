@@ -1,5 +1,7 @@
+use crate::application::use_cases::low_stock::LowStockUseCase;
+use crate::application::use_cases::part_compatibility::PartCompatibilityUseCase;
 use crate::infrastructure::db::repositories::inventory::InventoryRepository;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize)]
 pub struct UseStockItemCommand {
@@ -8,18 +10,64 @@ pub struct UseStockItemCommand {
     pub quantity: i32,
 }
 
+#[derive(Serialize)]
+pub struct UseStockItemResult {
+    /// Set when the part is tagged for other motorcycles than the order's.
+    /// The part is added anyway.
+    pub warning: Option<String>,
+}
+
 pub struct UseStockItemUseCase {
     inventory_repo: InventoryRepository,
+    low_stock: LowStockUseCase,
+    compatibility: PartCompatibilityUseCase,
 }
 
 impl UseStockItemUseCase {
-    pub fn new(inventory_repo: InventoryRepository) -> Self {
-        Self { inventory_repo }
+    pub fn new(
+        inventory_repo: InventoryRepository,
+        low_stock: LowStockUseCase,
+        compatibility: PartCompatibilityUseCase,
+    ) -> Self {
+        Self {
+            inventory_repo,
+            low_stock,
+            compatibility,
+        }
     }
 
-    pub async fn execute(&self, command: UseStockItemCommand) -> Result<(), String> {
-        self.inventory_repo
-            .use_stock_item(command.order_id, command.stock_item_id, command.quantity)
+    pub async fn execute(
+        &self,
+        command: UseStockItemCommand,
+        user_id: i32,
+    ) -> Result<UseStockItemResult, String> {
+        if command.quantity <= 0 {
+            return Err("Quantity must be positive".to_string());
+        }
+        let taken = self
+            .inventory_repo
+            .use_stock_item(
+                command.order_id,
+                command.stock_item_id,
+                command.quantity,
+                user_id,
+            )
+            .await?;
+        if taken {
+            self.low_stock.dispatch_alerts_in_background();
+        }
+
+        let warning = match self
+            .compatibility
+            .incompatibility_warning(command.order_id, command.stock_item_id)
             .await
+        {
+            Ok(warning) => warning,
+            Err(e) => {
+                tracing::error!("Failed to check part compatibility: {}", e);
+                None
+            }
+        };
+        Ok(UseStockItemResult { warning })
     }
 }
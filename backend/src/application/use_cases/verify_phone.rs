@@ -0,0 +1,205 @@
+use crate::domain::audit::AuditEvent;
+use crate::domain::audit::entity::GUEST_MERGED;
+use crate::domain::notification::gateway::SmsGateway;
+use crate::domain::user::entity::Role;
+use crate::domain::value_objects::PhoneNumber;
+use crate::infrastructure::db::models::NewPhoneVerification;
+use crate::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use crate::infrastructure::db::repositories::guest_customer::GuestCustomerRepository;
+use crate::infrastructure::db::repositories::phone_verification::PhoneVerificationRepository;
+use crate::infrastructure::db::repositories::user::UserRepository;
+use crate::infrastructure::security::password::{
+    generate_phone_code, hash_password, verify_password,
+};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const CODE_TTL_MINUTES: i64 = 10;
+const RESEND_COOLDOWN_SECS: i64 = 60;
+const MAX_CODES_PER_HOUR: usize = 5;
+/// Anyone can ask for a code, so texts are also capped per address and
+/// across the shop to keep the SMS bill bounded
+const MAX_CODES_PER_IP_PER_HOUR: i64 = 10;
+const MAX_CODES_OVERALL_PER_HOUR: i64 = 200;
+const MAX_ATTEMPTS: i32 = 5;
+
+const TOO_MANY_CODES: &str = "Too many verification codes requested. Try again later";
+
+#[derive(Debug, Deserialize)]
+pub struct RequestPhoneCodeCommand {
+    pub phone: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmPhoneCommand {
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PhoneVerifiedResult {
+    pub phone: String,
+    /// Walk-in guest records that were merged into the account
+    pub merged_guests: usize,
+}
+
+/// Proves phone ownership with a texted code. A verified phone is what lets
+/// a customer claim the walk-in guest records kept under that number.
+#[derive(Clone)]
+pub struct VerifyPhoneUseCase {
+    verification_repo: PhoneVerificationRepository,
+    user_repo: UserRepository,
+    guest_repo: GuestCustomerRepository,
+    sms_gateway: Arc<dyn SmsGateway + Send + Sync>,
+    audit_repo: AuditEventRepository,
+}
+
+impl VerifyPhoneUseCase {
+    pub fn new(
+        verification_repo: PhoneVerificationRepository,
+        user_repo: UserRepository,
+        guest_repo: GuestCustomerRepository,
+        sms_gateway: Arc<dyn SmsGateway + Send + Sync>,
+        audit_repo: AuditEventRepository,
+    ) -> Self {
+        Self {
+            verification_repo,
+            user_repo,
+            guest_repo,
+            sms_gateway,
+            audit_repo,
+        }
+    }
+
+    pub async fn request_code(
+        &self,
+        command: RequestPhoneCodeCommand,
+        requested_ip: Option<String>,
+    ) -> Result<(), String> {
+        let phone = PhoneNumber::new(command.phone)?;
+        let now = Utc::now();
+        let hour_ago = now - Duration::hours(1);
+
+        if self.verification_repo.count_since(None, hour_ago).await? >= MAX_CODES_OVERALL_PER_HOUR {
+            return Err(TOO_MANY_CODES.to_string());
+        }
+        if let Some(ip) = requested_ip.as_deref()
+            && self
+                .verification_repo
+                .count_since(Some(ip), hour_ago)
+                .await?
+                >= MAX_CODES_PER_IP_PER_HOUR
+        {
+            return Err(TOO_MANY_CODES.to_string());
+        }
+
+        let recent = self
+            .verification_repo
+            .find_recent(phone.value(), hour_ago)
+            .await?;
+        if recent.len() >= MAX_CODES_PER_HOUR {
+            return Err("Too many codes requested for this phone. Try again later".to_string());
+        }
+        if let Some(last) = recent.first()
+            && last.created_at > now - Duration::seconds(RESEND_COOLDOWN_SECS)
+        {
+            return Err("Please wait a minute before requesting another code".to_string());
+        }
+
+        let code = generate_phone_code();
+        self.verification_repo
+            .create(NewPhoneVerification {
+                phone: phone.value().to_string(),
+                code_hash: hash_password(&code)?,
+                expires_at: now + Duration::minutes(CODE_TTL_MINUTES),
+                requested_ip,
+            })
+            .await?;
+
+        self.sms_gateway
+            .send_sms(
+                phone.value(),
+                &format!(
+                    "Your verification code is {}. It expires in {} minutes.",
+                    code, CODE_TTL_MINUTES
+                ),
+            )
+            .await
+    }
+
+    /// Verifies the signed-in user's current phone and claims any guest
+    /// records kept under it
+    pub async fn confirm(
+        &self,
+        user_id: i32,
+        command: ConfirmPhoneCommand,
+    ) -> Result<PhoneVerifiedResult, String> {
+        let user = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .ok_or("User not found")?;
+        let phone = PhoneNumber::new(user.phone.clone())?;
+
+        self.check_code(&phone, &command.code).await?;
+        self.user_repo
+            .set_phone_verified_at(user_id, Some(Utc::now()))
+            .await?;
+
+        let merged_guests = if user.role == Role::Customer {
+            self.claim_guests(user_id, &phone).await?
+        } else {
+            0
+        };
+
+        Ok(PhoneVerifiedResult {
+            phone: phone.value().to_string(),
+            merged_guests,
+        })
+    }
+
+    /// Uses up the latest code sent to the phone if it matches
+    pub async fn check_code(&self, phone: &PhoneNumber, code: &str) -> Result<(), String> {
+        let verification = self
+            .verification_repo
+            .find_active(phone.value(), Utc::now())
+            .await?
+            .ok_or("No valid code for this phone. Please request a new one")?;
+        if verification.attempts >= MAX_ATTEMPTS {
+            return Err("Too many wrong attempts. Please request a new code".to_string());
+        }
+
+        self.verification_repo
+            .record_attempt(verification.verification_id)
+            .await?;
+        if !verify_password(&verification.code_hash, code.trim())? {
+            return Err("Incorrect verification code".to_string());
+        }
+        if !self
+            .verification_repo
+            .consume(verification.verification_id)
+            .await?
+        {
+            return Err("This code has already been used".to_string());
+        }
+        Ok(())
+    }
+
+    /// Merges unclaimed guests with this phone into the account. Callers must
+    /// have verified that the account holds the phone.
+    pub async fn claim_guests(&self, user_id: i32, phone: &PhoneNumber) -> Result<usize, String> {
+        let merged = self.guest_repo.merge_into(user_id, phone.value()).await?;
+
+        for guest_id in &merged {
+            let event = AuditEvent::system(GUEST_MERGED, "user", *guest_id).with_change(
+                &serde_json::json!({ "merged_into_user_id": null }),
+                &serde_json::json!({ "merged_into_user_id": user_id }),
+            );
+            if let Err(e) = self.audit_repo.record(event).await {
+                tracing::error!("Failed to record audit event: {}", e);
+            }
+        }
+
+        Ok(merged.len())
+    }
+}
@@ -0,0 +1,118 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// Action names stored in audit_events.action
+pub const USER_ROLE_CHANGED: &str = "user.role_changed";
+pub const USER_CREATED: &str = "user.created";
+pub const USER_DEACTIVATED: &str = "user.deactivated";
+pub const USER_REACTIVATED: &str = "user.reactivated";
+pub const SERVICE_ORDER_DELETED: &str = "service_order.deleted";
+pub const FEEDBACK_DELETED: &str = "feedback.deleted";
+pub const STOCK_ITEM_UPDATED: &str = "stock_item.updated";
+pub const USER_ERASED: &str = "user.erased";
+pub const ERASURE_REQUEST_REJECTED: &str = "erasure_request.rejected";
+pub const MAINTENANCE_RULE_CREATED: &str = "maintenance_rule.created";
+pub const MAINTENANCE_RULE_UPDATED: &str = "maintenance_rule.updated";
+pub const MOTORCYCLE_TRANSFER_INITIATED: &str = "motorcycle.transfer_initiated";
+pub const SERVICE_ORDER_EXPIRED: &str = "service_order.expired";
+pub const GUEST_CREATED: &str = "user.guest_created";
+pub const GUEST_MERGED: &str = "user.guest_merged";
+pub const LOYALTY_ADJUSTED: &str = "loyalty.adjusted";
+pub const SUPPLIER_CREATED: &str = "supplier.created";
+pub const SUPPLIER_UPDATED: &str = "supplier.updated";
+pub const PURCHASE_ORDER_CANCELLED: &str = "purchase_order.cancelled";
+pub const GOODS_RECEIVED: &str = "purchase_order.goods_received";
+pub const STOCK_CATALOGUE_IMPORTED: &str = "stock_catalogue.imported";
+pub const STOCK_TAKE_POSTED: &str = "stock_take.posted";
+pub const STOCK_TAKE_CANCELLED: &str = "stock_take.cancelled";
+pub const BRANCH_CREATED: &str = "branch.created";
+pub const BRANCH_UPDATED: &str = "branch.updated";
+pub const USER_BRANCHES_CHANGED: &str = "user.branches_changed";
+pub const STOCK_TRANSFERRED: &str = "stock.transferred";
+pub const STOCK_ITEM_COMPATIBILITY_CHANGED: &str = "stock_item.compatibility_changed";
+
+/// Who performed an administrative action, and from where
+#[derive(Debug, Clone)]
+pub struct AuditActor {
+    pub user_id: i32,
+    pub ip_address: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditEvent {
+    pub actor_id: Option<i32>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub reason: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl AuditEvent {
+    pub fn new(actor: &AuditActor, action: &str, target_type: &str, target_id: i32) -> Self {
+        Self {
+            actor_id: Some(actor.user_id),
+            action: action.to_string(),
+            target_type: target_type.to_string(),
+            target_id: target_id.to_string(),
+            before: None,
+            after: None,
+            reason: None,
+            ip_address: actor.ip_address.clone(),
+        }
+    }
+
+    /// An action taken by a background job rather than a person
+    pub fn system(action: &str, target_type: &str, target_id: i32) -> Self {
+        Self {
+            actor_id: None,
+            action: action.to_string(),
+            target_type: target_type.to_string(),
+            target_id: target_id.to_string(),
+            before: None,
+            after: None,
+            reason: None,
+            ip_address: None,
+        }
+    }
+
+    /// Records only the top-level fields that differ between the two snapshots
+    pub fn with_change<T: Serialize>(mut self, before: &T, after: &T) -> Self {
+        let before = serde_json::to_value(before).unwrap_or(Value::Null);
+        let after = serde_json::to_value(after).unwrap_or(Value::Null);
+
+        match (before, after) {
+            (Value::Object(before), Value::Object(after)) => {
+                let mut before_diff = Map::new();
+                let mut after_diff = Map::new();
+                for (key, new_value) in after {
+                    let old_value = before.get(&key).cloned().unwrap_or(Value::Null);
+                    if old_value != new_value {
+                        before_diff.insert(key.clone(), old_value);
+                        after_diff.insert(key, new_value);
+                    }
+                }
+                self.before = Some(Value::Object(before_diff));
+                self.after = Some(Value::Object(after_diff));
+            }
+            (before, after) => {
+                self.before = Some(before);
+                self.after = Some(after);
+            }
+        }
+        self
+    }
+
+    /// Full snapshot of something that no longer exists after the action
+    pub fn with_snapshot<T: Serialize>(mut self, before: &T) -> Self {
+        self.before = serde_json::to_value(before).ok();
+        self
+    }
+
+    pub fn with_reason(mut self, reason: &str) -> Self {
+        self.reason = Some(reason.to_string());
+        self
+    }
+}
@@ -0,0 +1,73 @@
+use serde::Serialize;
+
+/// Baht of paid order total per point earned, before the tier multiplier
+pub const BAHT_PER_POINT_EARNED: f64 = 25.0;
+/// Discount in baht that one point buys
+pub const POINT_VALUE_BAHT: f64 = 1.0;
+/// Points lapse this long after they were credited
+pub const POINTS_VALID_MONTHS: u32 = 12;
+/// Window of paid spend that decides the tier
+pub const TIER_WINDOW_MONTHS: u32 = 12;
+
+/// Membership level, decided by what the customer paid over the last
+/// TIER_WINDOW_MONTHS
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LoyaltyTier {
+    Member,
+    Silver,
+    Gold,
+}
+
+impl LoyaltyTier {
+    pub fn for_spend(spend: f64) -> Self {
+        [Self::Gold, Self::Silver]
+            .into_iter()
+            .find(|tier| spend >= tier.min_spend())
+            .unwrap_or(Self::Member)
+    }
+
+    /// Rolling spend in baht needed to reach this tier
+    pub fn min_spend(&self) -> f64 {
+        match self {
+            Self::Member => 0.0,
+            Self::Silver => 10_000.0,
+            Self::Gold => 30_000.0,
+        }
+    }
+
+    pub fn next(&self) -> Option<Self> {
+        match self {
+            Self::Member => Some(Self::Silver),
+            Self::Silver => Some(Self::Gold),
+            Self::Gold => None,
+        }
+    }
+
+    pub fn earn_multiplier(&self) -> f64 {
+        match self {
+            Self::Member => 1.0,
+            Self::Silver => 1.25,
+            Self::Gold => 1.5,
+        }
+    }
+
+    pub fn perks(&self) -> Vec<&'static str> {
+        match self {
+            Self::Member => vec!["1 point for every ฿25 spent"],
+            Self::Silver => vec!["1.25 points for every ฿25 spent", "Priority booking slots"],
+            Self::Gold => vec![
+                "1.5 points for every ฿25 spent",
+                "Priority booking slots",
+                "Free safety inspection with every service",
+            ],
+        }
+    }
+
+    /// Points earned for a paid order of this amount
+    pub fn points_for(&self, amount: f64) -> i32 {
+        if amount <= 0.0 {
+            return 0;
+        }
+        ((amount / BAHT_PER_POINT_EARNED) * self.earn_multiplier()).floor() as i32
+    }
+}
@@ -1,3 +1,6 @@
+pub mod audit;
+pub mod loyalty;
+pub mod motorcycle;
 pub mod notification;
 pub mod payment;
 pub mod service;
@@ -0,0 +1,102 @@
+use crate::domain::value_objects::LicensePlate;
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+
+/// Oldest model year we accept; anything earlier is almost certainly a typo
+const MIN_MODEL_YEAR: i32 = 1900;
+const VIN_LENGTH: usize = 17;
+const MAX_ENGINE_NUMBER_LENGTH: usize = 50;
+const MAX_COLOUR_LENGTH: usize = 50;
+
+/// Customer-editable description of a bike in their garage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MotorcycleDetails {
+    pub brand: String,
+    pub model: String,
+    pub license_plate: String,
+    pub year: Option<i32>,
+    pub colour: Option<String>,
+    pub engine_number: Option<String>,
+    pub vin: Option<String>,
+    pub mileage: Option<i32>,
+}
+
+impl MotorcycleDetails {
+    /// Trims and upper-cases identifiers and rejects values that can't be right.
+    /// The license plate comes back in canonical form alongside its parsed parts.
+    pub fn normalise(self) -> Result<(Self, LicensePlate), String> {
+        let brand = self.brand.trim().to_string();
+        let model = self.model.trim().to_string();
+        if brand.is_empty() || model.is_empty() {
+            return Err("Brand and model are required".to_string());
+        }
+
+        let plate = LicensePlate::new(self.license_plate)?;
+
+        if let Some(year) = self.year {
+            let max_year = chrono::Utc::now().year() + 1;
+            if !(MIN_MODEL_YEAR..=max_year).contains(&year) {
+                return Err(format!(
+                    "Year must be between {} and {}",
+                    MIN_MODEL_YEAR, max_year
+                ));
+            }
+        }
+
+        let colour = non_empty(self.colour);
+        if colour
+            .as_ref()
+            .is_some_and(|c| c.chars().count() > MAX_COLOUR_LENGTH)
+        {
+            return Err("Colour is too long".to_string());
+        }
+
+        let engine_number = non_empty(self.engine_number).map(|e| e.to_uppercase());
+        if let Some(engine_number) = &engine_number
+            && (engine_number.len() > MAX_ENGINE_NUMBER_LENGTH
+                || !engine_number
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-'))
+        {
+            return Err("Engine number may only contain letters, digits and dashes".to_string());
+        }
+
+        let vin = non_empty(self.vin).map(|v| v.to_uppercase());
+        if let Some(vin) = &vin {
+            // I, O and Q are never used in a VIN to avoid confusion with 1 and 0
+            let valid = vin.len() == VIN_LENGTH
+                && vin
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() && !matches!(c, 'I' | 'O' | 'Q'));
+            if !valid {
+                return Err(
+                    "VIN must be 17 characters (letters except I, O, Q and digits)".to_string(),
+                );
+            }
+        }
+
+        if self.mileage.is_some_and(|m| m < 0) {
+            return Err("Mileage cannot be negative".to_string());
+        }
+
+        Ok((
+            Self {
+                brand,
+                model,
+                license_plate: plate.value(),
+                year: self.year,
+                colour,
+                engine_number,
+                vin,
+                mileage: self.mileage,
+            },
+            plate,
+        ))
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
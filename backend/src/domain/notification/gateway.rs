@@ -15,3 +15,9 @@ pub struct NotificationMessage {
 pub trait NotificationGateway {
     async fn send_notification(&self, message: NotificationMessage) -> Result<(), String>;
 }
+
+/// Text messages to a phone number, used where we need to prove someone holds it
+#[async_trait]
+pub trait SmsGateway {
+    async fn send_sms(&self, phone: &str, message: &str) -> Result<(), String>;
+}
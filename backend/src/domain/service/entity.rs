@@ -26,26 +26,32 @@ pub struct ServiceOrder {
     pub id: Option<i32>,
     pub bike_id: Option<i32>,
     pub customer_id: i32,
+    // The branch doing the work
+    pub branch_id: i32,
     pub status: OrderStatus,
     pub total_price: f64,
     pub items: Vec<ServiceItem>,
     pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     pub before_picture_url: Option<String>,
     pub after_picture_url: Option<String>,
+    // Odometer reading (km) taken at intake
+    pub odometer_km: Option<i32>,
 }
 
 impl ServiceOrder {
-    pub fn new_booking(bike_id: Option<i32>, customer_id: i32) -> Self {
+    pub fn new_booking(bike_id: Option<i32>, customer_id: i32, branch_id: i32) -> Self {
         Self {
             id: None,
             bike_id,
             customer_id,
+            branch_id,
             status: OrderStatus::Booked,
             total_price: 0.0,
             items: Vec::new(),
             created_at: Some(chrono::Utc::now()),
             before_picture_url: None,
             after_picture_url: None,
+            odometer_km: None,
         }
     }
 
@@ -3,4 +3,4 @@ pub mod stock_entity;
 
 pub use entity::OrderStatus;
 pub use entity::ServiceOrder;
-pub use stock_entity::StockItem;
+pub use stock_entity::{CompatibilityTag, Fit, StockItem};
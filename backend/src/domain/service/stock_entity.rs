@@ -1,9 +1,225 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub const DEFAULT_UNIT: &str = "pcs";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StockItem {
+    pub id: Option<i32>,
+    pub name: String,
+    pub price: f64,
+    /// On the shelf
+    pub quantity: i32,
+    /// Held for orders that are still being quoted
+    pub reserved_quantity: i32,
+    /// What can still be promised: on hand less reserved
+    pub available_quantity: i32,
+    /// Admins are alerted when the quantity drops to this level
+    pub reorder_point: Option<i32>,
+    /// How many to buy when it does
+    pub reorder_quantity: Option<i32>,
+    pub sku: Option<String>,
+    /// EAN-8, UPC-A, EAN-13 or GTIN-14
+    pub barcode: Option<String>,
+    pub category: Option<String>,
+    pub brand: Option<String>,
+    /// Unit of measure the quantity is counted in
+    pub unit: String,
+    pub shelf_location: Option<String>,
+    /// Weighted-average cost of the units on hand
+    pub average_cost: f64,
+}
+
+/// A stock item as anyone but an admin sees it: everything except what it
+/// cost the shop
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PublicStockItem {
     pub id: Option<i32>,
     pub name: String,
     pub price: f64,
     pub quantity: i32,
+    pub reserved_quantity: i32,
+    pub available_quantity: i32,
+    pub reorder_point: Option<i32>,
+    pub reorder_quantity: Option<i32>,
+    pub sku: Option<String>,
+    pub barcode: Option<String>,
+    pub category: Option<String>,
+    pub brand: Option<String>,
+    pub unit: String,
+    pub shelf_location: Option<String>,
+}
+
+impl From<StockItem> for PublicStockItem {
+    fn from(item: StockItem) -> Self {
+        Self {
+            id: item.id,
+            name: item.name,
+            price: item.price,
+            quantity: item.quantity,
+            reserved_quantity: item.reserved_quantity,
+            available_quantity: item.available_quantity,
+            reorder_point: item.reorder_point,
+            reorder_quantity: item.reorder_quantity,
+            sku: item.sku,
+            barcode: item.barcode,
+            category: item.category,
+            brand: item.brand,
+            unit: item.unit,
+            shelf_location: item.shelf_location,
+        }
+    }
+}
+
+/// Checks the length and GS1 check digit of an EAN/UPC barcode
+pub fn is_valid_barcode(code: &str) -> bool {
+    if !matches!(code.len(), 8 | 12 | 13 | 14) || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    let digits: Vec<u32> = code.bytes().map(|b| u32::from(b - b'0')).collect();
+    let (body, check) = digits.split_at(digits.len() - 1);
+    // Weights alternate 3, 1, 3, ... starting next to the check digit
+    let sum: u32 = body
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { d * 3 } else { *d })
+        .sum();
+    (10 - sum % 10) % 10 == check[0]
+}
+
+fn clean(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+impl StockItem {
+    /// Trims the descriptive fields, upper-cases the SKU and checks the
+    /// identifiers and reorder levels
+    pub fn normalized(mut self) -> Result<Self, String> {
+        self.name = self.name.trim().to_string();
+        if self.name.is_empty() {
+            return Err("Name is required".to_string());
+        }
+        self.sku = clean(self.sku).map(|s| s.to_uppercase());
+        self.barcode = clean(self.barcode);
+        self.category = clean(self.category);
+        self.brand = clean(self.brand);
+        self.shelf_location = clean(self.shelf_location);
+        self.unit = clean(Some(self.unit)).unwrap_or_else(|| DEFAULT_UNIT.to_string());
+
+        for (label, value, max) in [
+            ("SKU", &self.sku, 64),
+            ("Category", &self.category, 100),
+            ("Brand", &self.brand, 100),
+            ("Shelf location", &self.shelf_location, 64),
+        ] {
+            if value.as_ref().is_some_and(|v| v.chars().count() > max) {
+                return Err(format!("{} can be at most {} characters", label, max));
+            }
+        }
+        if let Some(barcode) = &self.barcode
+            && !is_valid_barcode(barcode)
+        {
+            return Err("Barcode must be a valid EAN-8, UPC-A, EAN-13 or GTIN-14".to_string());
+        }
+        if self.unit.chars().count() > 16 {
+            return Err("Unit can be at most 16 characters".to_string());
+        }
+        if !self.average_cost.is_finite() || self.average_cost < 0.0 {
+            return Err("Cost price must be zero or more".to_string());
+        }
+        if self.reorder_point.is_some_and(|p| p < 0) {
+            return Err("Reorder point cannot be negative".to_string());
+        }
+        if self.reorder_quantity.is_some_and(|q| q <= 0) {
+            return Err("Reorder quantity must be positive".to_string());
+        }
+        Ok(self)
+    }
+}
+
+/// A motorcycle a part fits: a brand, optionally narrowed to one model and
+/// a range of model years
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompatibilityTag {
+    pub brand: String,
+    /// Leave out for every model of the brand
+    pub model: Option<String>,
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+}
+
+/// How closely a part's tag matches a motorcycle, loosest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Fit {
+    Brand,
+    Model,
+    ModelYear,
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+impl CompatibilityTag {
+    pub fn normalized(mut self) -> Result<Self, String> {
+        self.brand = self.brand.trim().to_string();
+        if self.brand.is_empty() {
+            return Err("Each compatibility tag needs a brand".to_string());
+        }
+        self.model = clean(self.model);
+        if self.brand.chars().count() > 255
+            || self.model.as_ref().is_some_and(|m| m.chars().count() > 255)
+        {
+            return Err("Brand and model can be at most 255 characters".to_string());
+        }
+        if let (Some(from), Some(to)) = (self.year_from, self.year_to)
+            && from > to
+        {
+            return Err(format!("Year range {}-{} runs backwards", from, to));
+        }
+        Ok(self)
+    }
+
+    /// How well the tag fits the motorcycle, or `None` if it doesn't. A bike
+    /// with no year on record can't be ruled out by the year range, but it
+    /// doesn't count as a year match either.
+    pub fn fit(&self, brand: &str, model: &str, year: Option<i32>) -> Option<Fit> {
+        if !same_name(&self.brand, brand) {
+            return None;
+        }
+        if self.model.as_ref().is_some_and(|m| !same_name(m, model)) {
+            return None;
+        }
+        let has_years = self.year_from.is_some() || self.year_to.is_some();
+        if let Some(year) = year
+            && (self.year_from.is_some_and(|from| year < from)
+                || self.year_to.is_some_and(|to| year > to))
+        {
+            return None;
+        }
+
+        Some(match (&self.model, has_years && year.is_some()) {
+            (None, _) => Fit::Brand,
+            (Some(_), false) => Fit::Model,
+            (Some(_), true) => Fit::ModelYear,
+        })
+    }
+
+    /// "Honda Wave 110i 2015-2020", "Yamaha (all models)", ...
+    pub fn describe(&self) -> String {
+        let mut text = match &self.model {
+            Some(model) => format!("{} {}", self.brand, model),
+            None => format!("{} (all models)", self.brand),
+        };
+        match (self.year_from, self.year_to) {
+            (Some(from), Some(to)) if from == to => text.push_str(&format!(" {}", from)),
+            (Some(from), Some(to)) => text.push_str(&format!(" {}-{}", from, to)),
+            (Some(from), None) => text.push_str(&format!(" {} on", from)),
+            (None, Some(to)) => text.push_str(&format!(" up to {}", to)),
+            (None, None) => {}
+        }
+        text
+    }
 }
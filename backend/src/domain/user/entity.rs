@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -15,6 +16,12 @@ pub struct User {
     pub name: String,
     pub phone: String,
     pub role: Role,
+    pub is_active: bool,
+    // True for admin-created staff until they replace their one-time password
+    pub must_change_password: bool,
+    // Walk-in customer recorded by staff; has no usable login
+    pub is_guest: bool,
+    pub phone_verified_at: Option<DateTime<Utc>>,
 }
 
 impl User {
@@ -31,6 +38,49 @@ impl User {
             name,
             phone,
             role: Role::Customer,
+            is_active: true,
+            must_change_password: false,
+            is_guest: false,
+            phone_verified_at: None,
+        }
+    }
+
+    /// Staff account created by an admin with a one-time password
+    pub fn new_staff(
+        username: String,
+        password_hash: String,
+        name: String,
+        phone: String,
+        role: Role,
+    ) -> Self {
+        Self {
+            id: None,
+            username,
+            password_hash,
+            name,
+            phone,
+            role,
+            is_active: true,
+            must_change_password: true,
+            is_guest: false,
+            phone_verified_at: None,
+        }
+    }
+
+    /// Walk-in customer with only a name and phone. The username is a
+    /// placeholder and the password hash can never verify.
+    pub fn new_guest(username: String, name: String, phone: String) -> Self {
+        Self {
+            id: None,
+            username,
+            password_hash: String::new(),
+            name,
+            phone,
+            role: Role::Customer,
+            is_active: true,
+            must_change_password: false,
+            is_guest: true,
+            phone_verified_at: None,
         }
     }
 
@@ -16,16 +16,227 @@ impl Price {
     }
 }
 
+/// The 77 provinces plus Betong, which issues its own plates
+const THAI_PROVINCES: [&str; 78] = [
+    "กรุงเทพมหานคร",
+    "กระบี่",
+    "กาญจนบุรี",
+    "กาฬสินธุ์",
+    "กำแพงเพชร",
+    "ขอนแก่น",
+    "จันทบุรี",
+    "ฉะเชิงเทรา",
+    "ชลบุรี",
+    "ชัยนาท",
+    "ชัยภูมิ",
+    "ชุมพร",
+    "เชียงราย",
+    "เชียงใหม่",
+    "ตรัง",
+    "ตราด",
+    "ตาก",
+    "นครนายก",
+    "นครปฐม",
+    "นครพนม",
+    "นครราชสีมา",
+    "นครศรีธรรมราช",
+    "นครสวรรค์",
+    "นนทบุรี",
+    "นราธิวาส",
+    "น่าน",
+    "บึงกาฬ",
+    "บุรีรัมย์",
+    "ปทุมธานี",
+    "ประจวบคีรีขันธ์",
+    "ปราจีนบุรี",
+    "ปัตตานี",
+    "พระนครศรีอยุธยา",
+    "พะเยา",
+    "พังงา",
+    "พัทลุง",
+    "พิจิตร",
+    "พิษณุโลก",
+    "เพชรบุรี",
+    "เพชรบูรณ์",
+    "แพร่",
+    "ภูเก็ต",
+    "มหาสารคาม",
+    "มุกดาหาร",
+    "แม่ฮ่องสอน",
+    "ยโสธร",
+    "ยะลา",
+    "ร้อยเอ็ด",
+    "ระนอง",
+    "ระยอง",
+    "ราชบุรี",
+    "ลพบุรี",
+    "ลำปาง",
+    "ลำพูน",
+    "เลย",
+    "ศรีสะเกษ",
+    "สกลนคร",
+    "สงขลา",
+    "สตูล",
+    "สมุทรปราการ",
+    "สมุทรสงคราม",
+    "สมุทรสาคร",
+    "สระแก้ว",
+    "สระบุรี",
+    "สิงห์บุรี",
+    "สุโขทัย",
+    "สุพรรณบุรี",
+    "สุราษฎร์ธานี",
+    "สุรินทร์",
+    "หนองคาย",
+    "หนองบัวลำภู",
+    "อ่างทอง",
+    "อำนาจเจริญ",
+    "อุดรธานี",
+    "อุตรดิตถ์",
+    "อุทัยธานี",
+    "อุบลราชธานี",
+    "เบตง",
+];
+
+/// Short names people commonly type instead of the full province
+const THAI_PROVINCE_ALIASES: [(&str, &str); 5] = [
+    ("กทม", "กรุงเทพมหานคร"),
+    ("กรุงเทพ", "กรุงเทพมหานคร"),
+    ("กรุงเทพฯ", "กรุงเทพมหานคร"),
+    ("อยุธยา", "พระนครศรีอยุธยา"),
+    ("โคราช", "นครราชสีมา"),
+];
+
+/// A Thai plate such as "1กข 1234 กรุงเทพมหานคร": an optional leading digit and
+/// up to three consonants, a number of up to four digits, and the province.
+/// Spaces, dashes, dots and Thai digits are accepted on input.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-pub struct LicensePlate(String);
+pub struct LicensePlate {
+    prefix: String,
+    number: String,
+    province: Option<String>,
+}
 
 impl LicensePlate {
-    pub fn new(plate: String) -> Result<Self, &'static str> {
-        if plate.is_empty() {
-            return Err("License plate cannot be empty");
+    pub fn new(plate: String) -> Result<Self, String> {
+        let compact: Vec<char> = plate
+            .chars()
+            .filter(|c| !c.is_whitespace() && !matches!(c, '-' | '.'))
+            .map(|c| match c {
+                // Thai digits ๐-๙
+                '\u{0E50}'..='\u{0E59}' => char::from_digit(c as u32 - 0x0E50, 10).unwrap_or(c),
+                _ => c,
+            })
+            .collect();
+        if compact.is_empty() {
+            return Err("License plate cannot be empty".to_string());
+        }
+
+        // Newer series start with a digit, e.g. the 1 in 1กข
+        let mut pos = usize::from(compact[0].is_ascii_digit());
+        let letters_start = pos;
+        while pos < compact.len() && is_thai_consonant(compact[pos]) {
+            pos += 1;
+        }
+        let letter_count = pos - letters_start;
+        if letter_count == 0 || letter_count > 3 {
+            return Err(
+                "License plate must start with an optional digit and 1-3 Thai letters, e.g. 1กข 1234"
+                    .to_string(),
+            );
+        }
+        let prefix: String = compact[..pos].iter().collect();
+
+        let number_start = pos;
+        while pos < compact.len() && compact[pos].is_ascii_digit() {
+            pos += 1;
+        }
+        let number: String = compact[number_start..pos].iter().collect();
+        if number.is_empty() || number.len() > 4 || number.starts_with('0') {
+            return Err("License plate number must be 1-4 digits, e.g. 1กข 1234".to_string());
+        }
+
+        let rest: String = compact[pos..].iter().collect();
+        let province = if rest.is_empty() {
+            None
+        } else {
+            Some(canonical_province(&rest).ok_or(format!("Unknown province '{}'", rest))?)
+        };
+
+        Ok(Self {
+            prefix,
+            number,
+            province,
+        })
+    }
+
+    /// Canonical storage form: "1กข 1234" or "1กข 1234 กรุงเทพมหานคร"
+    pub fn value(&self) -> String {
+        match &self.province {
+            Some(province) => format!("{} {} {}", self.prefix, self.number, province),
+            None => format!("{} {}", self.prefix, self.number),
         }
-        // Add more validation rules as needed (e.g., regex for format)
-        Ok(Self(plate))
+    }
+
+    /// Formatting-free form of prefix and number, used to match plates
+    pub fn key(&self) -> String {
+        format!("{}{}", self.prefix, self.number)
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn number(&self) -> &str {
+        &self.number
+    }
+
+    pub fn province(&self) -> Option<&str> {
+        self.province.as_deref()
+    }
+}
+
+fn is_thai_consonant(c: char) -> bool {
+    ('\u{0E01}'..='\u{0E2E}').contains(&c)
+}
+
+fn canonical_province(name: &str) -> Option<String> {
+    THAI_PROVINCES
+        .iter()
+        .find(|p| **p == name)
+        .or_else(|| {
+            THAI_PROVINCE_ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == name)
+                .map(|(_, p)| p)
+        })
+        .map(|p| p.to_string())
+}
+
+/// A Thai phone number in domestic form, e.g. "0812345678". Accepts spaces,
+/// dashes, brackets and the +66 country code on input.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PhoneNumber(String);
+
+impl PhoneNumber {
+    pub fn new(phone: String) -> Result<Self, String> {
+        if phone.chars().any(|c| {
+            !c.is_ascii_digit() && !c.is_whitespace() && !matches!(c, '-' | '+' | '(' | ')')
+        }) {
+            return Err("Phone number may only contain digits".to_string());
+        }
+
+        let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+        let domestic = match digits.strip_prefix("66") {
+            Some(rest) if !rest.starts_with('0') && digits.len() >= 10 => format!("0{}", rest),
+            _ => digits,
+        };
+
+        // Mobiles have 10 digits, landlines 9
+        if !domestic.starts_with('0') || !(9..=10).contains(&domestic.len()) {
+            return Err("Phone number must be a Thai number such as 081-234-5678".to_string());
+        }
+        Ok(Self(domestic))
     }
 
     pub fn value(&self) -> &str {
@@ -45,3 +256,108 @@ impl PasswordHash {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn license_plates_are_parsed_into_canonical_form() {
+        let cases = [
+            ("1กข 1234", "1กข 1234", None),
+            ("กข 12", "กข 12", None),
+            (
+                "1กข 1234 กรุงเทพมหานคร",
+                "1กข 1234 กรุงเทพมหานคร",
+                Some("กรุงเทพมหานคร"),
+            ),
+            ("กขค 9 เชียงใหม่", "กขค 9 เชียงใหม่", Some("เชียงใหม่")),
+            ("1กข-1234", "1กข 1234", None),
+            (" 1 กข  1234 ", "1กข 1234", None),
+            ("1กข.1234", "1กข 1234", None),
+            ("๑กข ๑๒๓๔", "1กข 1234", None),
+            (
+                "1กข 1234 กทม",
+                "1กข 1234 กรุงเทพมหานคร",
+                Some("กรุงเทพมหานคร"),
+            ),
+            ("กข-12-โคราช", "กข 12 นครราชสีมา", Some("นครราชสีมา")),
+        ];
+        for (input, value, province) in cases {
+            let plate = LicensePlate::new(input.to_string())
+                .unwrap_or_else(|e| panic!("{:?} was rejected: {}", input, e));
+            assert_eq!(plate.value(), value, "{:?}", input);
+            assert_eq!(plate.province(), province, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn license_plate_formatting_does_not_change_the_key() {
+        let keys: Vec<String> = ["1กข 1234", "1กข-1234", "๑กข๑๒๓๔", "1กข 1234 ภูเก็ต"]
+            .iter()
+            .map(|p| LicensePlate::new(p.to_string()).unwrap().key())
+            .collect();
+        assert!(keys.iter().all(|k| k == "1กข1234"), "{:?}", keys);
+    }
+
+    #[test]
+    fn malformed_license_plates_are_rejected() {
+        let cases = [
+            "",
+            " - ",
+            "1234",
+            "AB 1234",
+            "กขคง 12",
+            "กข",
+            "กข 12345",
+            "กข 0123",
+            "กข 12 Narnia",
+            "กข 12 เมืองไม่มี",
+        ];
+        for input in cases {
+            assert!(
+                LicensePlate::new(input.to_string()).is_err(),
+                "{:?} was accepted",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn phone_numbers_are_stored_in_domestic_form() {
+        let cases = [
+            ("0812345678", "0812345678"),
+            ("081-234-5678", "0812345678"),
+            ("081 234 5678", "0812345678"),
+            ("+66 81 234 5678", "0812345678"),
+            ("66812345678", "0812345678"),
+            ("(02) 123-4567", "021234567"),
+            ("+66 2 123 4567", "021234567"),
+        ];
+        for (input, value) in cases {
+            let phone = PhoneNumber::new(input.to_string())
+                .unwrap_or_else(|e| panic!("{:?} was rejected: {}", input, e));
+            assert_eq!(phone.value(), value, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn malformed_phone_numbers_are_rejected() {
+        let cases = [
+            "",
+            "081-234-567x",
+            "tel 0812345678",
+            "812345678",
+            "08123456",
+            "08123456789",
+            "+1 415 555 0100",
+        ];
+        for input in cases {
+            assert!(
+                PhoneNumber::new(input.to_string()).is_err(),
+                "{:?} was accepted",
+                input
+            );
+        }
+    }
+}
@@ -29,3 +29,31 @@ pub fn establish_connection() -> DbPool {
         .build(manager)
         .expect("Failed to create pool.")
 }
+
+/// Keeps everything a test writes inside one transaction that is never committed
+#[cfg(test)]
+#[derive(Debug)]
+struct TestTransaction;
+
+#[cfg(test)]
+impl CustomizeConnection<PgConnection, diesel::r2d2::Error> for TestTransaction {
+    fn on_acquire(&self, conn: &mut PgConnection) -> Result<(), diesel::r2d2::Error> {
+        conn.begin_test_transaction()
+            .map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
+/// A single-connection pool on the migrated database in TEST_DATABASE_URL,
+/// rolled back when the pool is dropped. Empty when the variable isn't set,
+/// so the database tests skip themselves.
+#[cfg(test)]
+pub fn test_pool() -> Option<DbPool> {
+    let database_url = env::var("TEST_DATABASE_URL").ok()?;
+    let manager = ConnectionManager::<PgConnection>::new(database_url);
+    let pool = diesel::r2d2::Pool::builder()
+        .max_size(1)
+        .connection_customizer(Box::new(TestTransaction))
+        .build(manager)
+        .expect("Failed to create test pool.");
+    Some(pool)
+}
@@ -0,0 +1,28 @@
+CREATE TABLE audit_events (
+    event_id BIGSERIAL PRIMARY KEY,
+    actor_id INTEGER REFERENCES users(user_id),
+    action VARCHAR(64) NOT NULL,
+    target_type VARCHAR(64) NOT NULL,
+    target_id VARCHAR(64) NOT NULL,
+    -- Only the fields that changed; NULL for creations/deletions respectively
+    before_state JSONB,
+    after_state JSONB,
+    reason TEXT,
+    ip_address VARCHAR(64),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_events_actor_id ON audit_events(actor_id);
+CREATE INDEX idx_audit_events_target ON audit_events(target_type, target_id);
+CREATE INDEX idx_audit_events_created_at ON audit_events(created_at);
+
+-- Append-only: history must not be rewritten, even by the application itself
+CREATE FUNCTION audit_events_immutable() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_events_no_update_delete
+    BEFORE UPDATE OR DELETE ON audit_events
+    FOR EACH ROW EXECUTE FUNCTION audit_events_immutable();
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS erasure_requests;
+DROP TYPE IF EXISTS erasure_request_status;
@@ -0,0 +1,16 @@
+CREATE TYPE erasure_request_status AS ENUM ('pending', 'approved', 'rejected');
+
+-- PDPA "right to be forgotten" requests; nothing is erased until an admin approves
+CREATE TABLE erasure_requests (
+    request_id SERIAL PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(user_id),
+    status erasure_request_status NOT NULL DEFAULT 'pending',
+    reason TEXT,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    reviewed_by INTEGER REFERENCES users(user_id),
+    reviewed_at TIMESTAMPTZ,
+    review_note TEXT
+);
+
+CREATE UNIQUE INDEX idx_erasure_requests_one_pending
+    ON erasure_requests(user_id) WHERE status = 'pending';
@@ -0,0 +1,6 @@
+DELETE FROM notifications WHERE order_id IS NULL;
+ALTER TABLE notifications ALTER COLUMN order_id SET NOT NULL;
+
+ALTER TABLE users DROP COLUMN must_change_password;
+ALTER TABLE users DROP COLUMN deactivated_at;
+ALTER TABLE users DROP COLUMN is_active;
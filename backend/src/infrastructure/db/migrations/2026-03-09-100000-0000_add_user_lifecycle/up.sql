@@ -0,0 +1,8 @@
+-- Deactivated users keep their history but can no longer log in or refresh
+ALTER TABLE users ADD COLUMN is_active BOOLEAN NOT NULL DEFAULT TRUE;
+ALTER TABLE users ADD COLUMN deactivated_at TIMESTAMPTZ;
+-- Set for admin-created staff until they replace their one-time password
+ALTER TABLE users ADD COLUMN must_change_password BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Account notifications (role changes, etc.) aren't tied to an order
+ALTER TABLE notifications ALTER COLUMN order_id DROP NOT NULL;
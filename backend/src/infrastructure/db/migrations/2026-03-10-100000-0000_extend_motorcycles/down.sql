@@ -0,0 +1,9 @@
+DROP INDEX motorcycles_user_id_idx;
+DROP INDEX motorcycles_vin_key;
+
+ALTER TABLE motorcycles DROP COLUMN archived_at;
+ALTER TABLE motorcycles DROP COLUMN mileage;
+ALTER TABLE motorcycles DROP COLUMN vin;
+ALTER TABLE motorcycles DROP COLUMN engine_number;
+ALTER TABLE motorcycles DROP COLUMN colour;
+ALTER TABLE motorcycles DROP COLUMN year;
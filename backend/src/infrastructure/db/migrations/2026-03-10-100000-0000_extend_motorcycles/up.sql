@@ -0,0 +1,10 @@
+ALTER TABLE motorcycles ADD COLUMN year INTEGER;
+ALTER TABLE motorcycles ADD COLUMN colour VARCHAR(50);
+ALTER TABLE motorcycles ADD COLUMN engine_number VARCHAR(50);
+ALTER TABLE motorcycles ADD COLUMN vin VARCHAR(17);
+ALTER TABLE motorcycles ADD COLUMN mileage INTEGER NOT NULL DEFAULT 0 CHECK (mileage >= 0);
+-- Bikes with past orders are archived instead of deleted so the orders keep their bike
+ALTER TABLE motorcycles ADD COLUMN archived_at TIMESTAMPTZ;
+
+CREATE UNIQUE INDEX motorcycles_vin_key ON motorcycles (vin) WHERE vin IS NOT NULL;
+CREATE INDEX motorcycles_user_id_idx ON motorcycles (user_id);
@@ -0,0 +1,4 @@
+DROP INDEX repair_logs_order_id_idx;
+DROP INDEX service_orders_bike_id_idx;
+
+ALTER TABLE service_orders DROP COLUMN odometer_km;
@@ -0,0 +1,5 @@
+-- Odometer reading taken when the bike is checked in
+ALTER TABLE service_orders ADD COLUMN odometer_km INTEGER CHECK (odometer_km >= 0);
+
+CREATE INDEX service_orders_bike_id_idx ON service_orders (bike_id);
+CREATE INDEX repair_logs_order_id_idx ON repair_logs (order_id);
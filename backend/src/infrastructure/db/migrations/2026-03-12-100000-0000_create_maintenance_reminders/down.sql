@@ -0,0 +1,3 @@
+DROP TABLE maintenance_reminders;
+DROP TYPE maintenance_reminder_status;
+DROP TABLE maintenance_rules;
@@ -0,0 +1,31 @@
+-- A rule applies to every bike; a line item whose description contains
+-- match_keyword (case-insensitive) counts as the service being done
+CREATE TABLE maintenance_rules (
+    rule_id SERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    match_keyword VARCHAR(100) NOT NULL,
+    interval_months INTEGER CHECK (interval_months > 0),
+    interval_km INTEGER CHECK (interval_km > 0),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (interval_months IS NOT NULL OR interval_km IS NOT NULL)
+);
+
+CREATE TYPE maintenance_reminder_status AS ENUM ('sent', 'snoozed', 'converted');
+
+CREATE TABLE maintenance_reminders (
+    reminder_id SERIAL PRIMARY KEY,
+    rule_id INTEGER NOT NULL REFERENCES maintenance_rules(rule_id),
+    bike_id INTEGER NOT NULL REFERENCES motorcycles(bike_id),
+    user_id INTEGER NOT NULL REFERENCES users(user_id),
+    status maintenance_reminder_status NOT NULL DEFAULT 'sent',
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    snoozed_until TIMESTAMPTZ,
+    converted_order_id INTEGER REFERENCES service_orders(order_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- At most one reminder per bike and rule is waiting on the customer
+CREATE UNIQUE INDEX idx_maintenance_reminders_one_open
+    ON maintenance_reminders(bike_id, rule_id) WHERE status <> 'converted';
+CREATE INDEX idx_maintenance_reminders_user ON maintenance_reminders(user_id);
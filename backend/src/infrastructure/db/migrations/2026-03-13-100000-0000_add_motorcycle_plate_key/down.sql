@@ -0,0 +1,4 @@
+DROP INDEX motorcycles_plate_key_idx;
+
+ALTER TABLE motorcycles DROP COLUMN plate_province;
+ALTER TABLE motorcycles DROP COLUMN plate_key;
@@ -0,0 +1,12 @@
+-- Prefix + number without spacing ("1กข1234") so differently typed plates match
+ALTER TABLE motorcycles ADD COLUMN plate_key VARCHAR(16);
+ALTER TABLE motorcycles ADD COLUMN plate_province VARCHAR(50);
+
+-- Best effort for existing rows; plates that don't look Thai keep a NULL key
+UPDATE motorcycles
+SET plate_key = substring(
+    regexp_replace(license_plate, '[[:space:].-]', '', 'g')
+    FROM '^([1-9]?[ก-ฮ]{1,3}[1-9][0-9]{0,3})'
+);
+
+CREATE INDEX motorcycles_plate_key_idx ON motorcycles (plate_key);
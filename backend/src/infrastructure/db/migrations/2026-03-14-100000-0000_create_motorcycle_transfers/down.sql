@@ -0,0 +1,2 @@
+DROP TABLE motorcycle_transfers;
+DROP TYPE motorcycle_transfer_status;
@@ -0,0 +1,18 @@
+CREATE TYPE motorcycle_transfer_status AS ENUM ('pending', 'accepted', 'declined', 'cancelled');
+
+-- Ownership moves only once the new owner accepts; the bike keeps its orders
+CREATE TABLE motorcycle_transfers (
+    transfer_id SERIAL PRIMARY KEY,
+    bike_id INTEGER NOT NULL REFERENCES motorcycles(bike_id),
+    from_user_id INTEGER NOT NULL REFERENCES users(user_id),
+    to_user_id INTEGER NOT NULL REFERENCES users(user_id),
+    initiated_by INTEGER NOT NULL REFERENCES users(user_id),
+    status motorcycle_transfer_status NOT NULL DEFAULT 'pending',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    responded_at TIMESTAMPTZ,
+    CHECK (from_user_id <> to_user_id)
+);
+
+CREATE UNIQUE INDEX idx_motorcycle_transfers_one_pending
+    ON motorcycle_transfers(bike_id) WHERE status = 'pending';
+CREATE INDEX idx_motorcycle_transfers_to_user ON motorcycle_transfers(to_user_id);
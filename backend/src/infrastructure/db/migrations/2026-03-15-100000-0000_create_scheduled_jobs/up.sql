@@ -0,0 +1,12 @@
+-- Run state of in-process background jobs, shared by every replica
+CREATE TABLE scheduled_jobs (
+    job_name VARCHAR(64) PRIMARY KEY,
+    schedule VARCHAR(100) NOT NULL,
+    next_run_at TIMESTAMPTZ NOT NULL,
+    last_started_at TIMESTAMPTZ,
+    last_finished_at TIMESTAMPTZ,
+    last_status VARCHAR(16),
+    last_message TEXT,
+    run_count INTEGER NOT NULL DEFAULT 0,
+    failure_count INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS idx_motorcycles_model_trgm;
+DROP INDEX IF EXISTS idx_motorcycles_brand_trgm;
+DROP INDEX IF EXISTS idx_motorcycles_plate_key_trgm;
+DROP INDEX IF EXISTS idx_motorcycles_license_plate_trgm;
+DROP INDEX IF EXISTS idx_users_phone_digits_trgm;
+DROP INDEX IF EXISTS idx_users_username_trgm;
+DROP INDEX IF EXISTS idx_users_name_trgm;
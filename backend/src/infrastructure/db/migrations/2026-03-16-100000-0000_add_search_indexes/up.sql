@@ -0,0 +1,12 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_users_name_trgm ON users USING gin (name gin_trgm_ops);
+CREATE INDEX idx_users_username_trgm ON users USING gin (username gin_trgm_ops);
+-- Phones are stored as typed; search compares digits only
+CREATE INDEX idx_users_phone_digits_trgm ON users
+    USING gin ((regexp_replace(phone, '\D', '', 'g')) gin_trgm_ops);
+
+CREATE INDEX idx_motorcycles_license_plate_trgm ON motorcycles USING gin (license_plate gin_trgm_ops);
+CREATE INDEX idx_motorcycles_plate_key_trgm ON motorcycles USING gin (plate_key gin_trgm_ops);
+CREATE INDEX idx_motorcycles_brand_trgm ON motorcycles USING gin (brand gin_trgm_ops);
+CREATE INDEX idx_motorcycles_model_trgm ON motorcycles USING gin (model gin_trgm_ops);
@@ -0,0 +1,8 @@
+DROP TABLE IF EXISTS phone_verifications;
+DROP INDEX IF EXISTS idx_users_open_guest_phone;
+DROP INDEX IF EXISTS users_phone_key;
+ALTER TABLE users
+    ADD CONSTRAINT users_phone_key UNIQUE (phone),
+    DROP COLUMN IF EXISTS merged_into_user_id,
+    DROP COLUMN IF EXISTS phone_verified_at,
+    DROP COLUMN IF EXISTS is_guest;
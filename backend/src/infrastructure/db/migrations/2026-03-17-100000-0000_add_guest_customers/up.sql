@@ -0,0 +1,26 @@
+-- Walk-in customers without an app account. Guests can't log in; once someone
+-- verifies the same phone, their records move over and the guest row is kept,
+-- deactivated, pointing at the account it was merged into.
+ALTER TABLE users
+    ADD COLUMN is_guest BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN phone_verified_at TIMESTAMPTZ,
+    ADD COLUMN merged_into_user_id INT REFERENCES users(user_id);
+
+-- A guest shares its phone with the account that later claims it, so phones
+-- are only unique among real accounts, and among guests not yet claimed
+ALTER TABLE users DROP CONSTRAINT users_phone_key;
+CREATE UNIQUE INDEX users_phone_key ON users (phone) WHERE NOT is_guest;
+CREATE UNIQUE INDEX idx_users_open_guest_phone ON users (phone)
+    WHERE is_guest AND merged_into_user_id IS NULL;
+
+CREATE TABLE phone_verifications (
+    verification_id SERIAL PRIMARY KEY,
+    phone VARCHAR(20) NOT NULL,
+    code_hash VARCHAR(255) NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ NOT NULL,
+    consumed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_phone_verifications_phone ON phone_verifications (phone, created_at DESC);
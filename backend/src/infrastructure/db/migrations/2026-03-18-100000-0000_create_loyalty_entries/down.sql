@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS loyalty_entries;
+DROP TYPE IF EXISTS loyalty_entry_kind;
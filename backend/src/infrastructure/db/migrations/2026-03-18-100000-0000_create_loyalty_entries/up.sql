@@ -0,0 +1,30 @@
+CREATE TYPE loyalty_entry_kind AS ENUM ('earned', 'redeemed', 'adjusted', 'expired', 'refunded');
+
+-- Points ledger. Credits (earned, positive adjustments, refunds) carry how much
+-- of them is still unspent in `remaining` and lapse at `expires_at`; debits
+-- use up the oldest credits first. The balance is the sum of `remaining` over
+-- credits that haven't lapsed; `points` records what each entry moved.
+CREATE TABLE loyalty_entries (
+    entry_id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL REFERENCES users(user_id),
+    kind loyalty_entry_kind NOT NULL,
+    points INT NOT NULL,
+    remaining INT NOT NULL DEFAULT 0,
+    -- Paid order total that earned the points; drives the rolling-spend tier
+    spend NUMERIC(12, 2),
+    order_id INT REFERENCES service_orders(order_id) ON DELETE SET NULL,
+    service_item_id INT REFERENCES service_items(item_id) ON DELETE SET NULL,
+    reason TEXT,
+    created_by INT REFERENCES users(user_id),
+    expires_at TIMESTAMPTZ,
+    reversed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (points <> 0),
+    CHECK (remaining >= 0 AND remaining <= GREATEST(points, 0))
+);
+
+CREATE UNIQUE INDEX idx_loyalty_entries_one_earn_per_order ON loyalty_entries (order_id)
+    WHERE kind = 'earned';
+CREATE INDEX idx_loyalty_entries_user ON loyalty_entries (user_id, created_at DESC);
+CREATE INDEX idx_loyalty_entries_open_credits ON loyalty_entries (expires_at)
+    WHERE remaining > 0;
@@ -0,0 +1,4 @@
+DROP TABLE quotation_lines;
+DROP TABLE quotations;
+DROP TYPE quotation_line_decision;
+DROP TYPE quotation_status;
@@ -0,0 +1,38 @@
+CREATE TYPE quotation_status AS ENUM ('pending', 'accepted', 'declined', 'superseded', 'expired');
+CREATE TYPE quotation_line_decision AS ENUM ('pending', 'approved', 'declined');
+
+-- A priced offer for an order, snapshotted from its items when issued. Finding
+-- more work issues a new version; earlier versions are kept as they were.
+CREATE TABLE quotations (
+    quotation_id SERIAL PRIMARY KEY,
+    order_id INT NOT NULL REFERENCES service_orders(order_id) ON DELETE CASCADE,
+    version INT NOT NULL,
+    status quotation_status NOT NULL DEFAULT 'pending',
+    total NUMERIC(12, 2) NOT NULL,
+    approved_total NUMERIC(12, 2),
+    notes TEXT,
+    valid_until TIMESTAMPTZ NOT NULL,
+    issued_by INT REFERENCES users(user_id),
+    responded_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (order_id, version)
+);
+
+-- Only one version of a quote can be waiting on the customer
+CREATE UNIQUE INDEX idx_quotations_one_pending_per_order ON quotations (order_id)
+    WHERE status = 'pending';
+
+CREATE TABLE quotation_lines (
+    line_id SERIAL PRIMARY KEY,
+    quotation_id INT NOT NULL REFERENCES quotations(quotation_id) ON DELETE CASCADE,
+    -- The order line this was copied from; cleared once a declined line is removed
+    service_item_id INT REFERENCES service_items(item_id) ON DELETE SET NULL,
+    description VARCHAR(255) NOT NULL,
+    price NUMERIC(12, 2) NOT NULL,
+    quantity INT NOT NULL,
+    decision quotation_line_decision NOT NULL DEFAULT 'pending',
+    -- Approved on an earlier version or a discount; the customer can't decline it
+    locked BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX idx_quotation_lines_quotation ON quotation_lines (quotation_id);
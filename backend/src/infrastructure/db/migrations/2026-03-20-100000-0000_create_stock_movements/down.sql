@@ -0,0 +1,3 @@
+DROP TABLE stock_movements;
+DROP FUNCTION stock_movements_append_only();
+DROP TYPE stock_movement_reason;
@@ -0,0 +1,54 @@
+CREATE TYPE stock_movement_reason AS ENUM ('opening', 'order_usage', 'order_return', 'adjustment');
+
+-- Append-only record of every change to stock_items.quantity, written in the
+-- same transaction as the change. Summing delta per item gives its quantity.
+CREATE TABLE stock_movements (
+    movement_id SERIAL PRIMARY KEY,
+    stock_item_id INT NOT NULL REFERENCES stock_items(item_id) ON DELETE CASCADE,
+    delta INT NOT NULL,
+    reason stock_movement_reason NOT NULL,
+    order_id INT REFERENCES service_orders(order_id) ON DELETE SET NULL,
+    actor_id INT REFERENCES users(user_id),
+    note TEXT,
+    quantity_after INT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (delta <> 0)
+);
+
+CREATE INDEX idx_stock_movements_item ON stock_movements (stock_item_id, movement_id DESC);
+CREATE INDEX idx_stock_movements_order ON stock_movements (order_id) WHERE order_id IS NOT NULL;
+
+-- Rows can't be edited or removed directly. Clearing order_id when an order
+-- is deleted, and removing the rows of a deleted stock item, still work
+-- because those come from foreign key actions (trigger depth > 1).
+CREATE FUNCTION stock_movements_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        IF pg_trigger_depth() < 2 THEN
+            RAISE EXCEPTION 'stock_movements is append-only';
+        END IF;
+        RETURN OLD;
+    END IF;
+    IF NEW.stock_item_id <> OLD.stock_item_id
+        OR NEW.delta <> OLD.delta
+        OR NEW.reason <> OLD.reason
+        OR NEW.quantity_after <> OLD.quantity_after
+        OR NEW.created_at <> OLD.created_at
+        OR NEW.actor_id IS DISTINCT FROM OLD.actor_id
+        OR NEW.note IS DISTINCT FROM OLD.note
+        OR (NEW.order_id IS NOT NULL AND NEW.order_id IS DISTINCT FROM OLD.order_id) THEN
+        RAISE EXCEPTION 'stock_movements is append-only';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_stock_movements_append_only
+    BEFORE UPDATE OR DELETE ON stock_movements
+    FOR EACH ROW EXECUTE FUNCTION stock_movements_append_only();
+
+-- Open the ledger with what is on hand today
+INSERT INTO stock_movements (stock_item_id, delta, reason, note, quantity_after)
+SELECT item_id, quantity, 'opening', 'Balance when the ledger was introduced', quantity
+FROM stock_items
+WHERE quantity <> 0;
@@ -0,0 +1,16 @@
+DROP TABLE goods_receipt_lines;
+DROP TABLE goods_receipts;
+DROP TABLE purchase_order_lines;
+DROP TABLE purchase_orders;
+DROP TABLE suppliers;
+DROP TYPE purchase_order_status;
+
+-- Enum values can't be dropped, so rebuild the type; receipts become adjustments
+ALTER TABLE stock_movements DISABLE TRIGGER trg_stock_movements_append_only;
+ALTER TABLE stock_movements ALTER COLUMN reason TYPE TEXT;
+DROP TYPE stock_movement_reason;
+CREATE TYPE stock_movement_reason AS ENUM ('opening', 'order_usage', 'order_return', 'adjustment');
+UPDATE stock_movements SET reason = 'adjustment' WHERE reason = 'received';
+ALTER TABLE stock_movements
+    ALTER COLUMN reason TYPE stock_movement_reason USING reason::stock_movement_reason;
+ALTER TABLE stock_movements ENABLE TRIGGER trg_stock_movements_append_only;
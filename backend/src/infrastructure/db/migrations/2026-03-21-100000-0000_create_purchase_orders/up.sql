@@ -0,0 +1,67 @@
+CREATE TYPE purchase_order_status AS ENUM ('draft', 'sent', 'partially_received', 'received', 'cancelled');
+
+-- Only added here; nothing in this migration uses the new value
+ALTER TYPE stock_movement_reason ADD VALUE 'received';
+
+CREATE TABLE suppliers (
+    supplier_id SERIAL PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    contact_name VARCHAR(255),
+    phone VARCHAR(32),
+    email VARCHAR(255),
+    notes TEXT,
+    lead_time_days INT NOT NULL DEFAULT 7 CHECK (lead_time_days >= 0),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_suppliers_name ON suppliers (LOWER(name));
+
+CREATE TABLE purchase_orders (
+    po_id SERIAL PRIMARY KEY,
+    supplier_id INT NOT NULL REFERENCES suppliers(supplier_id),
+    status purchase_order_status NOT NULL DEFAULT 'draft',
+    expected_date DATE,
+    notes TEXT,
+    created_by INT REFERENCES users(user_id),
+    sent_at TIMESTAMPTZ,
+    closed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_purchase_orders_status ON purchase_orders (status, expected_date);
+
+CREATE TABLE purchase_order_lines (
+    line_id SERIAL PRIMARY KEY,
+    po_id INT NOT NULL REFERENCES purchase_orders(po_id) ON DELETE CASCADE,
+    stock_item_id INT NOT NULL REFERENCES stock_items(item_id),
+    quantity_ordered INT NOT NULL CHECK (quantity_ordered > 0),
+    quantity_received INT NOT NULL DEFAULT 0,
+    unit_cost NUMERIC(12, 2) NOT NULL CHECK (unit_cost >= 0),
+    UNIQUE (po_id, stock_item_id),
+    CHECK (quantity_received BETWEEN 0 AND quantity_ordered)
+);
+
+CREATE INDEX idx_purchase_order_lines_item ON purchase_order_lines (stock_item_id);
+
+-- One delivery against a PO. The unit cost is what was actually paid and may
+-- differ from the price agreed on the PO line.
+CREATE TABLE goods_receipts (
+    receipt_id SERIAL PRIMARY KEY,
+    po_id INT NOT NULL REFERENCES purchase_orders(po_id) ON DELETE CASCADE,
+    received_by INT REFERENCES users(user_id),
+    note TEXT,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE goods_receipt_lines (
+    receipt_line_id SERIAL PRIMARY KEY,
+    receipt_id INT NOT NULL REFERENCES goods_receipts(receipt_id) ON DELETE CASCADE,
+    po_line_id INT NOT NULL REFERENCES purchase_order_lines(line_id) ON DELETE CASCADE,
+    stock_item_id INT NOT NULL REFERENCES stock_items(item_id),
+    quantity INT NOT NULL CHECK (quantity > 0),
+    unit_cost NUMERIC(12, 2) NOT NULL CHECK (unit_cost >= 0)
+);
+
+CREATE INDEX idx_goods_receipt_lines_item ON goods_receipt_lines (stock_item_id, receipt_line_id DESC);
@@ -0,0 +1,2 @@
+DROP TABLE stock_alerts;
+ALTER TABLE stock_items DROP COLUMN reorder_quantity, DROP COLUMN reorder_point;
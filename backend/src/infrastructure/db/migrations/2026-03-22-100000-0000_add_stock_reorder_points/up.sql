@@ -0,0 +1,17 @@
+-- No reorder point means the item is never reported as low
+ALTER TABLE stock_items
+    ADD COLUMN reorder_point INT CHECK (reorder_point >= 0),
+    ADD COLUMN reorder_quantity INT CHECK (reorder_quantity > 0);
+
+-- Written in the same transaction as a stock change that takes an item down to
+-- its reorder point; admins are notified from here once the change commits
+CREATE TABLE stock_alerts (
+    alert_id SERIAL PRIMARY KEY,
+    stock_item_id INT NOT NULL REFERENCES stock_items(item_id) ON DELETE CASCADE,
+    quantity INT NOT NULL,
+    reorder_point INT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    notified_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_stock_alerts_pending ON stock_alerts (alert_id) WHERE notified_at IS NULL;
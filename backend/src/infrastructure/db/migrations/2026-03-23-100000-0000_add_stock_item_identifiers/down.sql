@@ -0,0 +1,7 @@
+ALTER TABLE stock_items
+    DROP COLUMN shelf_location,
+    DROP COLUMN unit,
+    DROP COLUMN brand,
+    DROP COLUMN category,
+    DROP COLUMN barcode,
+    DROP COLUMN sku;
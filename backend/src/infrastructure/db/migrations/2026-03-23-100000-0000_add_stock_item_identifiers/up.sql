@@ -0,0 +1,12 @@
+ALTER TABLE stock_items
+    ADD COLUMN sku VARCHAR(64),
+    ADD COLUMN barcode VARCHAR(14),
+    ADD COLUMN category VARCHAR(100),
+    ADD COLUMN brand VARCHAR(100),
+    ADD COLUMN unit VARCHAR(16) NOT NULL DEFAULT 'pcs',
+    ADD COLUMN shelf_location VARCHAR(64);
+
+-- SKUs are stored upper-cased, so this is unique regardless of case
+CREATE UNIQUE INDEX idx_stock_items_sku ON stock_items (sku);
+CREATE UNIQUE INDEX idx_stock_items_barcode ON stock_items (barcode);
+CREATE INDEX idx_stock_items_category ON stock_items (category);
@@ -0,0 +1,25 @@
+CREATE OR REPLACE FUNCTION stock_movements_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        IF pg_trigger_depth() < 2 THEN
+            RAISE EXCEPTION 'stock_movements is append-only';
+        END IF;
+        RETURN OLD;
+    END IF;
+    IF NEW.stock_item_id <> OLD.stock_item_id
+        OR NEW.delta <> OLD.delta
+        OR NEW.reason <> OLD.reason
+        OR NEW.quantity_after <> OLD.quantity_after
+        OR NEW.created_at <> OLD.created_at
+        OR NEW.actor_id IS DISTINCT FROM OLD.actor_id
+        OR NEW.note IS DISTINCT FROM OLD.note
+        OR (NEW.order_id IS NOT NULL AND NEW.order_id IS DISTINCT FROM OLD.order_id) THEN
+        RAISE EXCEPTION 'stock_movements is append-only';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE service_items DROP COLUMN unit_cost;
+ALTER TABLE stock_movements DROP COLUMN unit_cost;
+ALTER TABLE stock_items DROP COLUMN average_cost;
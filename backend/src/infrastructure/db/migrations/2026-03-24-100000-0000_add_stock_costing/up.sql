@@ -0,0 +1,48 @@
+-- Weighted-average cost of the units on hand, moved by every stock increase
+ALTER TABLE stock_items
+    ADD COLUMN average_cost NUMERIC(12,4) NOT NULL DEFAULT 0 CHECK (average_cost >= 0);
+
+-- What each unit of a movement was valued at: the incoming cost for
+-- increases, the average cost at the time for decreases
+ALTER TABLE stock_movements ADD COLUMN unit_cost NUMERIC(12,4);
+
+-- Cost of the part when it was used on the order
+ALTER TABLE service_items ADD COLUMN unit_cost NUMERIC(12,4);
+
+-- Best estimate for what is already on hand: the average of everything received
+UPDATE stock_items s
+SET average_cost = r.average_cost
+FROM (
+    SELECT stock_item_id, ROUND(SUM(quantity * unit_cost) / SUM(quantity), 4) AS average_cost
+    FROM goods_receipt_lines
+    GROUP BY stock_item_id
+) r
+WHERE r.stock_item_id = s.item_id;
+
+UPDATE service_items i
+SET unit_cost = s.average_cost
+FROM stock_items s
+WHERE s.item_id = i.stock_item_id;
+
+CREATE OR REPLACE FUNCTION stock_movements_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        IF pg_trigger_depth() < 2 THEN
+            RAISE EXCEPTION 'stock_movements is append-only';
+        END IF;
+        RETURN OLD;
+    END IF;
+    IF NEW.stock_item_id <> OLD.stock_item_id
+        OR NEW.delta <> OLD.delta
+        OR NEW.reason <> OLD.reason
+        OR NEW.quantity_after <> OLD.quantity_after
+        OR NEW.created_at <> OLD.created_at
+        OR NEW.actor_id IS DISTINCT FROM OLD.actor_id
+        OR NEW.note IS DISTINCT FROM OLD.note
+        OR NEW.unit_cost IS DISTINCT FROM OLD.unit_cost
+        OR (NEW.order_id IS NOT NULL AND NEW.order_id IS DISTINCT FROM OLD.order_id) THEN
+        RAISE EXCEPTION 'stock_movements is append-only';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,3 @@
+ALTER TABLE stock_items DROP COLUMN reserved_quantity;
+ALTER TABLE service_items DROP COLUMN stock_status;
+DROP TYPE service_item_stock_status;
@@ -0,0 +1,17 @@
+CREATE TYPE service_item_stock_status AS ENUM ('reserved', 'consumed', 'released');
+
+-- Parts added to an order before the repair starts only hold stock back;
+-- they come off the shelf when the repair starts and go back to being
+-- available if the order is cancelled
+ALTER TABLE service_items ADD COLUMN stock_status service_item_stock_status;
+UPDATE service_items SET stock_status = 'consumed' WHERE stock_item_id IS NOT NULL;
+ALTER TABLE service_items
+    ADD CONSTRAINT service_items_stock_status_check
+    CHECK ((stock_item_id IS NULL) = (stock_status IS NULL));
+
+CREATE INDEX idx_service_items_reserved ON service_items (order_id)
+    WHERE stock_status = 'reserved';
+
+-- Sum of the reserved lines; what can still be promised is quantity minus this
+ALTER TABLE stock_items
+    ADD COLUMN reserved_quantity INT NOT NULL DEFAULT 0 CHECK (reserved_quantity >= 0);
@@ -0,0 +1,14 @@
+DROP TABLE stock_take_lines;
+DROP TABLE stock_takes;
+DROP TYPE stock_take_status;
+
+-- Enum values can't be dropped, so rebuild the type; stock-take corrections
+-- become adjustments
+ALTER TABLE stock_movements DISABLE TRIGGER trg_stock_movements_append_only;
+ALTER TABLE stock_movements ALTER COLUMN reason TYPE TEXT;
+DROP TYPE stock_movement_reason;
+CREATE TYPE stock_movement_reason AS ENUM ('opening', 'order_usage', 'order_return', 'adjustment', 'received');
+UPDATE stock_movements SET reason = 'adjustment' WHERE reason = 'stock_take';
+ALTER TABLE stock_movements
+    ALTER COLUMN reason TYPE stock_movement_reason USING reason::stock_movement_reason;
+ALTER TABLE stock_movements ENABLE TRIGGER trg_stock_movements_append_only;
@@ -0,0 +1,36 @@
+CREATE TYPE stock_take_status AS ENUM ('open', 'posted', 'cancelled');
+
+-- Only added here; nothing in this migration uses the new value
+ALTER TYPE stock_movement_reason ADD VALUE 'stock_take';
+
+-- A count of the shelves, either everything or one category
+CREATE TABLE stock_takes (
+    stock_take_id SERIAL PRIMARY KEY,
+    status stock_take_status NOT NULL DEFAULT 'open',
+    category VARCHAR(100),
+    notes TEXT,
+    opened_by INT REFERENCES users(user_id),
+    posted_by INT REFERENCES users(user_id),
+    opened_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    closed_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_stock_takes_status ON stock_takes (status, stock_take_id DESC);
+
+-- One line per item in scope. system_quantity is what the system held when the
+-- line was last counted (or when the session opened), so usage while the count
+-- runs doesn't show up as variance. unit_cost is the average cost the variance
+-- was booked at, kept for the variance value report.
+CREATE TABLE stock_take_lines (
+    line_id SERIAL PRIMARY KEY,
+    stock_take_id INT NOT NULL REFERENCES stock_takes(stock_take_id) ON DELETE CASCADE,
+    stock_item_id INT NOT NULL REFERENCES stock_items(item_id) ON DELETE CASCADE,
+    system_quantity INT NOT NULL,
+    counted_quantity INT CHECK (counted_quantity >= 0),
+    counted_by INT REFERENCES users(user_id),
+    counted_at TIMESTAMPTZ,
+    unit_cost NUMERIC(12, 4),
+    UNIQUE (stock_take_id, stock_item_id)
+);
+
+CREATE INDEX idx_stock_take_lines_item ON stock_take_lines (stock_item_id);
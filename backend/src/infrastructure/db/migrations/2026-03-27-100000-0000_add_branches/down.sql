@@ -0,0 +1,29 @@
+CREATE OR REPLACE FUNCTION stock_movements_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        IF pg_trigger_depth() < 2 THEN
+            RAISE EXCEPTION 'stock_movements is append-only';
+        END IF;
+        RETURN OLD;
+    END IF;
+    IF NEW.stock_item_id <> OLD.stock_item_id
+        OR NEW.delta <> OLD.delta
+        OR NEW.reason <> OLD.reason
+        OR NEW.quantity_after <> OLD.quantity_after
+        OR NEW.created_at <> OLD.created_at
+        OR NEW.actor_id IS DISTINCT FROM OLD.actor_id
+        OR NEW.note IS DISTINCT FROM OLD.note
+        OR NEW.unit_cost IS DISTINCT FROM OLD.unit_cost
+        OR (NEW.order_id IS NOT NULL AND NEW.order_id IS DISTINCT FROM OLD.order_id) THEN
+        RAISE EXCEPTION 'stock_movements is append-only';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE stock_movements DROP COLUMN branch_id;
+DROP TABLE stock_levels;
+ALTER TABLE stock_takes DROP COLUMN branch_id;
+ALTER TABLE purchase_orders DROP COLUMN branch_id;
+ALTER TABLE service_orders DROP COLUMN branch_id;
+DROP TABLE branches;
@@ -0,0 +1,74 @@
+CREATE TABLE branches (
+    branch_id SERIAL PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    address TEXT,
+    phone VARCHAR(32),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_branches_name ON branches (LOWER(name));
+
+-- Everything that exists today happened at the one shop
+INSERT INTO branches (name) VALUES ('Main branch');
+
+ALTER TABLE service_orders ADD COLUMN branch_id INT REFERENCES branches(branch_id);
+UPDATE service_orders SET branch_id = 1;
+ALTER TABLE service_orders ALTER COLUMN branch_id SET NOT NULL;
+CREATE INDEX idx_service_orders_branch ON service_orders (branch_id, status);
+
+ALTER TABLE purchase_orders ADD COLUMN branch_id INT REFERENCES branches(branch_id);
+UPDATE purchase_orders SET branch_id = 1;
+ALTER TABLE purchase_orders ALTER COLUMN branch_id SET NOT NULL;
+
+ALTER TABLE stock_takes ADD COLUMN branch_id INT REFERENCES branches(branch_id);
+UPDATE stock_takes SET branch_id = 1;
+ALTER TABLE stock_takes ALTER COLUMN branch_id SET NOT NULL;
+
+-- What each branch holds of an item. stock_items.quantity and
+-- reserved_quantity stay as the totals over all branches and are written in
+-- the same transaction as these rows.
+CREATE TABLE stock_levels (
+    stock_item_id INT NOT NULL REFERENCES stock_items(item_id) ON DELETE CASCADE,
+    branch_id INT NOT NULL REFERENCES branches(branch_id),
+    quantity INT NOT NULL DEFAULT 0,
+    reserved_quantity INT NOT NULL DEFAULT 0 CHECK (reserved_quantity >= 0),
+    PRIMARY KEY (stock_item_id, branch_id)
+);
+
+CREATE INDEX idx_stock_levels_branch ON stock_levels (branch_id);
+
+INSERT INTO stock_levels (stock_item_id, branch_id, quantity, reserved_quantity)
+SELECT item_id, 1, quantity, reserved_quantity FROM stock_items;
+
+-- Summing delta per item and branch gives that branch's quantity
+ALTER TABLE stock_movements ADD COLUMN branch_id INT REFERENCES branches(branch_id);
+ALTER TABLE stock_movements DISABLE TRIGGER trg_stock_movements_append_only;
+UPDATE stock_movements SET branch_id = 1;
+ALTER TABLE stock_movements ENABLE TRIGGER trg_stock_movements_append_only;
+ALTER TABLE stock_movements ALTER COLUMN branch_id SET NOT NULL;
+CREATE INDEX idx_stock_movements_branch ON stock_movements (branch_id, stock_item_id);
+
+CREATE OR REPLACE FUNCTION stock_movements_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        IF pg_trigger_depth() < 2 THEN
+            RAISE EXCEPTION 'stock_movements is append-only';
+        END IF;
+        RETURN OLD;
+    END IF;
+    IF NEW.stock_item_id <> OLD.stock_item_id
+        OR NEW.branch_id <> OLD.branch_id
+        OR NEW.delta <> OLD.delta
+        OR NEW.reason <> OLD.reason
+        OR NEW.quantity_after <> OLD.quantity_after
+        OR NEW.created_at <> OLD.created_at
+        OR NEW.actor_id IS DISTINCT FROM OLD.actor_id
+        OR NEW.note IS DISTINCT FROM OLD.note
+        OR NEW.unit_cost IS DISTINCT FROM OLD.unit_cost
+        OR (NEW.order_id IS NOT NULL AND NEW.order_id IS DISTINCT FROM OLD.order_id) THEN
+        RAISE EXCEPTION 'stock_movements is append-only';
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
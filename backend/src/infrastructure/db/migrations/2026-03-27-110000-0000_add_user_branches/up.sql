@@ -0,0 +1,12 @@
+-- The branches a staff member works at. A mechanic belongs to their
+-- branches; an admin with no rows here can act on every branch.
+CREATE TABLE user_branches (
+    user_id INT NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    branch_id INT NOT NULL REFERENCES branches(branch_id) ON DELETE CASCADE,
+    PRIMARY KEY (user_id, branch_id)
+);
+
+CREATE INDEX idx_user_branches_branch ON user_branches (branch_id);
+
+INSERT INTO user_branches (user_id, branch_id)
+SELECT user_id, 1 FROM users WHERE role = 'mechanic';
@@ -0,0 +1,14 @@
+DROP TABLE stock_transfer_lines;
+DROP TABLE stock_transfers;
+
+-- Enum values can't be dropped, so rebuild the type. Each transfer leg
+-- becomes an adjustment at its branch, which keeps every branch's ledger
+-- summing to its level.
+ALTER TABLE stock_movements DISABLE TRIGGER trg_stock_movements_append_only;
+ALTER TABLE stock_movements ALTER COLUMN reason TYPE TEXT;
+DROP TYPE stock_movement_reason;
+CREATE TYPE stock_movement_reason AS ENUM ('opening', 'order_usage', 'order_return', 'adjustment', 'received', 'stock_take');
+UPDATE stock_movements SET reason = 'adjustment' WHERE reason = 'transfer';
+ALTER TABLE stock_movements
+    ALTER COLUMN reason TYPE stock_movement_reason USING reason::stock_movement_reason;
+ALTER TABLE stock_movements ENABLE TRIGGER trg_stock_movements_append_only;
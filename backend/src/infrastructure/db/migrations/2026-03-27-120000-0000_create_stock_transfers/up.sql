@@ -0,0 +1,28 @@
+-- Only added here; nothing in this migration uses the new value
+ALTER TYPE stock_movement_reason ADD VALUE 'transfer';
+
+-- Stock moved from one branch to another. Both sides are booked at once, at
+-- the item's average cost, as transfer movements.
+CREATE TABLE stock_transfers (
+    transfer_id SERIAL PRIMARY KEY,
+    from_branch_id INT NOT NULL REFERENCES branches(branch_id),
+    to_branch_id INT NOT NULL REFERENCES branches(branch_id),
+    note TEXT,
+    created_by INT REFERENCES users(user_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (from_branch_id <> to_branch_id)
+);
+
+CREATE INDEX idx_stock_transfers_from ON stock_transfers (from_branch_id, transfer_id DESC);
+CREATE INDEX idx_stock_transfers_to ON stock_transfers (to_branch_id, transfer_id DESC);
+
+CREATE TABLE stock_transfer_lines (
+    line_id SERIAL PRIMARY KEY,
+    transfer_id INT NOT NULL REFERENCES stock_transfers(transfer_id) ON DELETE CASCADE,
+    stock_item_id INT NOT NULL REFERENCES stock_items(item_id),
+    quantity INT NOT NULL CHECK (quantity > 0),
+    unit_cost NUMERIC(12, 4) NOT NULL,
+    UNIQUE (transfer_id, stock_item_id)
+);
+
+CREATE INDEX idx_stock_transfer_lines_item ON stock_transfer_lines (stock_item_id);
@@ -0,0 +1,15 @@
+-- The motorcycles a part fits. A missing model fits every model of the
+-- brand; a missing year bound leaves that end of the range open. Parts with
+-- no rows aren't tied to any motorcycle.
+CREATE TABLE stock_item_compatibilities (
+    compatibility_id SERIAL PRIMARY KEY,
+    stock_item_id INT NOT NULL REFERENCES stock_items(item_id) ON DELETE CASCADE,
+    brand VARCHAR(255) NOT NULL,
+    model VARCHAR(255),
+    year_from INT,
+    year_to INT,
+    CHECK (year_from IS NULL OR year_to IS NULL OR year_from <= year_to)
+);
+
+CREATE INDEX idx_stock_item_compatibilities_item ON stock_item_compatibilities (stock_item_id);
+CREATE INDEX idx_stock_item_compatibilities_brand ON stock_item_compatibilities (LOWER(brand));
@@ -0,0 +1 @@
+-- Backfilled keys and provinces can't be told apart from typed ones, so they stay
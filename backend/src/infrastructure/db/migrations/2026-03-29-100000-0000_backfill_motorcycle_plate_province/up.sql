@@ -0,0 +1,116 @@
+-- The plate_key backfill left plate_province empty and skipped plates typed
+-- with Thai digits. Redo it the way LicensePlate parses: drop spacing, turn
+-- Thai digits into Arabic ones, take the prefix and number as the key, and
+-- read whatever follows as the province when it names one.
+CREATE TEMPORARY TABLE plate_province_names (name TEXT PRIMARY KEY, province TEXT NOT NULL);
+INSERT INTO plate_province_names (name, province) VALUES
+    ('กรุงเทพมหานคร', 'กรุงเทพมหานคร'),
+    ('กระบี่', 'กระบี่'),
+    ('กาญจนบุรี', 'กาญจนบุรี'),
+    ('กาฬสินธุ์', 'กาฬสินธุ์'),
+    ('กำแพงเพชร', 'กำแพงเพชร'),
+    ('ขอนแก่น', 'ขอนแก่น'),
+    ('จันทบุรี', 'จันทบุรี'),
+    ('ฉะเชิงเทรา', 'ฉะเชิงเทรา'),
+    ('ชลบุรี', 'ชลบุรี'),
+    ('ชัยนาท', 'ชัยนาท'),
+    ('ชัยภูมิ', 'ชัยภูมิ'),
+    ('ชุมพร', 'ชุมพร'),
+    ('เชียงราย', 'เชียงราย'),
+    ('เชียงใหม่', 'เชียงใหม่'),
+    ('ตรัง', 'ตรัง'),
+    ('ตราด', 'ตราด'),
+    ('ตาก', 'ตาก'),
+    ('นครนายก', 'นครนายก'),
+    ('นครปฐม', 'นครปฐม'),
+    ('นครพนม', 'นครพนม'),
+    ('นครราชสีมา', 'นครราชสีมา'),
+    ('นครศรีธรรมราช', 'นครศรีธรรมราช'),
+    ('นครสวรรค์', 'นครสวรรค์'),
+    ('นนทบุรี', 'นนทบุรี'),
+    ('นราธิวาส', 'นราธิวาส'),
+    ('น่าน', 'น่าน'),
+    ('บึงกาฬ', 'บึงกาฬ'),
+    ('บุรีรัมย์', 'บุรีรัมย์'),
+    ('ปทุมธานี', 'ปทุมธานี'),
+    ('ประจวบคีรีขันธ์', 'ประจวบคีรีขันธ์'),
+    ('ปราจีนบุรี', 'ปราจีนบุรี'),
+    ('ปัตตานี', 'ปัตตานี'),
+    ('พระนครศรีอยุธยา', 'พระนครศรีอยุธยา'),
+    ('พะเยา', 'พะเยา'),
+    ('พังงา', 'พังงา'),
+    ('พัทลุง', 'พัทลุง'),
+    ('พิจิตร', 'พิจิตร'),
+    ('พิษณุโลก', 'พิษณุโลก'),
+    ('เพชรบุรี', 'เพชรบุรี'),
+    ('เพชรบูรณ์', 'เพชรบูรณ์'),
+    ('แพร่', 'แพร่'),
+    ('ภูเก็ต', 'ภูเก็ต'),
+    ('มหาสารคาม', 'มหาสารคาม'),
+    ('มุกดาหาร', 'มุกดาหาร'),
+    ('แม่ฮ่องสอน', 'แม่ฮ่องสอน'),
+    ('ยโสธร', 'ยโสธร'),
+    ('ยะลา', 'ยะลา'),
+    ('ร้อยเอ็ด', 'ร้อยเอ็ด'),
+    ('ระนอง', 'ระนอง'),
+    ('ระยอง', 'ระยอง'),
+    ('ราชบุรี', 'ราชบุรี'),
+    ('ลพบุรี', 'ลพบุรี'),
+    ('ลำปาง', 'ลำปาง'),
+    ('ลำพูน', 'ลำพูน'),
+    ('เลย', 'เลย'),
+    ('ศรีสะเกษ', 'ศรีสะเกษ'),
+    ('สกลนคร', 'สกลนคร'),
+    ('สงขลา', 'สงขลา'),
+    ('สตูล', 'สตูล'),
+    ('สมุทรปราการ', 'สมุทรปราการ'),
+    ('สมุทรสงคราม', 'สมุทรสงคราม'),
+    ('สมุทรสาคร', 'สมุทรสาคร'),
+    ('สระแก้ว', 'สระแก้ว'),
+    ('สระบุรี', 'สระบุรี'),
+    ('สิงห์บุรี', 'สิงห์บุรี'),
+    ('สุโขทัย', 'สุโขทัย'),
+    ('สุพรรณบุรี', 'สุพรรณบุรี'),
+    ('สุราษฎร์ธานี', 'สุราษฎร์ธานี'),
+    ('สุรินทร์', 'สุรินทร์'),
+    ('หนองคาย', 'หนองคาย'),
+    ('หนองบัวลำภู', 'หนองบัวลำภู'),
+    ('อ่างทอง', 'อ่างทอง'),
+    ('อำนาจเจริญ', 'อำนาจเจริญ'),
+    ('อุดรธานี', 'อุดรธานี'),
+    ('อุตรดิตถ์', 'อุตรดิตถ์'),
+    ('อุทัยธานี', 'อุทัยธานี'),
+    ('อุบลราชธานี', 'อุบลราชธานี'),
+    ('เบตง', 'เบตง'),
+    ('กทม', 'กรุงเทพมหานคร'),
+    ('กรุงเทพ', 'กรุงเทพมหานคร'),
+    ('กรุงเทพฯ', 'กรุงเทพมหานคร'),
+    ('อยุธยา', 'พระนครศรีอยุธยา'),
+    ('โคราช', 'นครราชสีมา');
+
+WITH parsed AS (
+    SELECT
+        bike_id,
+        substring(compact FROM '^([1-9]?[ก-ฮ]{1,3}[1-9][0-9]{0,3})') AS plate_key,
+        regexp_replace(compact, '^[1-9]?[ก-ฮ]{1,3}[1-9][0-9]{0,3}', '') AS rest
+    FROM (
+        SELECT
+            bike_id,
+            translate(
+                regexp_replace(license_plate, '[[:space:].-]', '', 'g'),
+                '๐๑๒๓๔๕๖๗๘๙',
+                '0123456789'
+            ) AS compact
+        FROM motorcycles
+    ) AS plates
+)
+UPDATE motorcycles m
+SET plate_key = COALESCE(m.plate_key, parsed.plate_key),
+    plate_province = COALESCE(m.plate_province, names.province)
+FROM parsed
+LEFT JOIN plate_province_names names ON names.name = parsed.rest
+WHERE m.bike_id = parsed.bike_id
+  AND parsed.plate_key IS NOT NULL
+  AND (m.plate_key IS NULL OR m.plate_province IS NULL);
+
+DROP TABLE plate_province_names;
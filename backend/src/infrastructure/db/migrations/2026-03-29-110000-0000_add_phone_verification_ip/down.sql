@@ -0,0 +1,4 @@
+DROP INDEX idx_phone_verifications_created;
+DROP INDEX idx_phone_verifications_ip;
+
+ALTER TABLE phone_verifications DROP COLUMN requested_ip;
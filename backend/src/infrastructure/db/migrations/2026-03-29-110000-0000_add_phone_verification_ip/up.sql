@@ -0,0 +1,6 @@
+-- Who asked for each code, so requests can be capped per address as well as
+-- per phone and overall
+ALTER TABLE phone_verifications ADD COLUMN requested_ip VARCHAR(45);
+
+CREATE INDEX idx_phone_verifications_ip ON phone_verifications (requested_ip, created_at DESC);
+CREATE INDEX idx_phone_verifications_created ON phone_verifications (created_at DESC);
@@ -0,0 +1,18 @@
+-- Staff with this set act on every branch. Everyone else only acts on the
+-- branches listed in user_branches, and on none if they have no rows there.
+ALTER TABLE users ADD COLUMN all_branches BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Admins tied to no branch could act on all of them; keep it that way
+UPDATE users SET all_branches = TRUE
+WHERE role = 'admin'
+  AND NOT EXISTS (SELECT 1 FROM user_branches b WHERE b.user_id = users.user_id);
+
+-- Mechanics tied to no branch work at the default one, the oldest still open
+INSERT INTO user_branches (user_id, branch_id)
+SELECT u.user_id, d.branch_id
+FROM users u
+CROSS JOIN (
+    SELECT branch_id FROM branches WHERE is_active ORDER BY branch_id LIMIT 1
+) d
+WHERE u.role = 'mechanic'
+  AND NOT EXISTS (SELECT 1 FROM user_branches b WHERE b.user_id = u.user_id);
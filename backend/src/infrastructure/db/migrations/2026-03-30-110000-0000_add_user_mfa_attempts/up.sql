@@ -0,0 +1,4 @@
+-- Codes tried at the second login step since the last one that worked, and
+-- when a run of wrong codes stops locking the step
+ALTER TABLE user_mfa ADD COLUMN code_attempts INT NOT NULL DEFAULT 0;
+ALTER TABLE user_mfa ADD COLUMN locked_until TIMESTAMPTZ;
@@ -0,0 +1,4 @@
+ALTER TABLE maintenance_reminders
+    DROP CONSTRAINT maintenance_reminders_converted_order_id_fkey,
+    ADD CONSTRAINT maintenance_reminders_converted_order_id_fkey
+        FOREIGN KEY (converted_order_id) REFERENCES service_orders(order_id);
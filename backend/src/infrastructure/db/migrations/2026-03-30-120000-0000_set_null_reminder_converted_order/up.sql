@@ -0,0 +1,6 @@
+-- Deleting an order leaves the reminder it came from in place, just no
+-- longer linked to an order
+ALTER TABLE maintenance_reminders
+    DROP CONSTRAINT maintenance_reminders_converted_order_id_fkey,
+    ADD CONSTRAINT maintenance_reminders_converted_order_id_fkey
+        FOREIGN KEY (converted_order_id) REFERENCES service_orders(order_id) ON DELETE SET NULL;
@@ -0,0 +1,11 @@
+ALTER TABLE stock_movements
+    DROP CONSTRAINT stock_movements_stock_item_id_fkey,
+    ADD CONSTRAINT stock_movements_stock_item_id_fkey
+        FOREIGN KEY (stock_item_id) REFERENCES stock_items(item_id) ON DELETE CASCADE;
+
+DROP INDEX idx_stock_items_sku;
+DROP INDEX idx_stock_items_barcode;
+CREATE UNIQUE INDEX idx_stock_items_sku ON stock_items (sku);
+CREATE UNIQUE INDEX idx_stock_items_barcode ON stock_items (barcode);
+
+ALTER TABLE stock_items DROP COLUMN archived_at;
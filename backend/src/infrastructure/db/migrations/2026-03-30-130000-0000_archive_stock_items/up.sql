@@ -0,0 +1,15 @@
+-- Items that are no longer stocked are archived rather than deleted, so their
+-- movement history stays. Archived items give up their SKU and barcode for
+-- reuse.
+ALTER TABLE stock_items ADD COLUMN archived_at TIMESTAMPTZ;
+
+DROP INDEX idx_stock_items_sku;
+DROP INDEX idx_stock_items_barcode;
+CREATE UNIQUE INDEX idx_stock_items_sku ON stock_items (sku) WHERE archived_at IS NULL;
+CREATE UNIQUE INDEX idx_stock_items_barcode ON stock_items (barcode) WHERE archived_at IS NULL;
+
+-- The ledger must outlive the item
+ALTER TABLE stock_movements
+    DROP CONSTRAINT stock_movements_stock_item_id_fkey,
+    ADD CONSTRAINT stock_movements_stock_item_id_fkey
+        FOREIGN KEY (stock_item_id) REFERENCES stock_items(item_id) ON DELETE RESTRICT;
@@ -1,4 +1,6 @@
 pub mod connection;
+pub mod functions;
 pub mod models;
 pub mod repositories;
 pub mod schema;
+pub mod transaction;
@@ -21,6 +21,12 @@ pub struct UserModel {
     pub phone: String,
     pub role: UserRoleEnum,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    pub is_active: bool,
+    pub deactivated_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub must_change_password: bool,
+    pub is_guest: bool,
+    pub phone_verified_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub merged_into_user_id: Option<i32>,
 }
 
 #[derive(Insertable)]
@@ -31,6 +37,9 @@ pub struct NewUser<'a> {
     pub name: &'a str,
     pub phone: &'a str,
     pub role: UserRoleEnum,
+    pub must_change_password: bool,
+    pub is_guest: bool,
+    pub phone_verified_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(DbEnum, Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -82,6 +91,8 @@ pub struct ServiceOrderModel {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub before_picture_url: Option<String>,
     pub after_picture_url: Option<String>,
+    pub odometer_km: Option<i32>,
+    pub branch_id: i32,
 }
 
 #[derive(Insertable)]
@@ -94,6 +105,8 @@ pub struct NewServiceOrder {
     pub created_by: i32,
     pub before_picture_url: Option<String>,
     pub after_picture_url: Option<String>,
+    pub odometer_km: Option<i32>,
+    pub branch_id: i32,
 }
 #[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
 #[diesel(table_name = crate::infrastructure::db::schema::motorcycles)]
@@ -104,6 +117,46 @@ pub struct MotorcycleModel {
     pub model: String,
     pub license_plate: String,
     pub user_id: i32,
+    pub year: Option<i32>,
+    pub colour: Option<String>,
+    pub engine_number: Option<String>,
+    pub vin: Option<String>,
+    pub mileage: i32,
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub plate_key: Option<String>,
+    pub plate_province: Option<String>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::motorcycles)]
+pub struct NewMotorcycle {
+    pub brand: String,
+    pub model: String,
+    pub license_plate: String,
+    pub user_id: i32,
+    pub year: Option<i32>,
+    pub colour: Option<String>,
+    pub engine_number: Option<String>,
+    pub vin: Option<String>,
+    pub mileage: i32,
+    pub plate_key: Option<String>,
+    pub plate_province: Option<String>,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = crate::infrastructure::db::schema::motorcycles)]
+#[diesel(treat_none_as_null = true)]
+pub struct MotorcycleChangeset {
+    pub brand: String,
+    pub model: String,
+    pub license_plate: String,
+    pub year: Option<i32>,
+    pub colour: Option<String>,
+    pub engine_number: Option<String>,
+    pub vin: Option<String>,
+    pub mileage: i32,
+    pub plate_key: Option<String>,
+    pub plate_province: Option<String>,
 }
 
 #[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
@@ -137,6 +190,8 @@ pub struct ServiceItemModel {
     pub price: bigdecimal::BigDecimal,
     pub stock_item_id: Option<i32>,
     pub quantity: i32,
+    pub unit_cost: Option<bigdecimal::BigDecimal>,
+    pub stock_status: Option<ServiceItemStockStatusEnum>,
 }
 
 #[derive(Insertable)]
@@ -147,6 +202,20 @@ pub struct NewServiceItem {
     pub price: bigdecimal::BigDecimal,
     pub stock_item_id: Option<i32>,
     pub quantity: i32,
+    pub unit_cost: Option<bigdecimal::BigDecimal>,
+    pub stock_status: Option<ServiceItemStockStatusEnum>,
+}
+
+/// Where the stock behind a part line stands
+#[derive(DbEnum, Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[ExistingTypePath = "crate::infrastructure::db::schema::sql_types::ServiceItemStockStatus"]
+pub enum ServiceItemStockStatusEnum {
+    /// Held for the order, still on the shelf
+    Reserved,
+    /// Taken out of stock for the repair
+    Consumed,
+    /// Given back when the order was cancelled
+    Released,
 }
 
 #[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
@@ -157,6 +226,16 @@ pub struct StockItemModel {
     pub name: String,
     pub price: bigdecimal::BigDecimal,
     pub quantity: i32,
+    pub reorder_point: Option<i32>,
+    pub reorder_quantity: Option<i32>,
+    pub sku: Option<String>,
+    pub barcode: Option<String>,
+    pub category: Option<String>,
+    pub brand: Option<String>,
+    pub unit: String,
+    pub shelf_location: Option<String>,
+    pub average_cost: bigdecimal::BigDecimal,
+    pub reserved_quantity: i32,
 }
 
 #[derive(Insertable)]
@@ -165,6 +244,15 @@ pub struct NewStockItem<'a> {
     pub name: &'a str,
     pub price: bigdecimal::BigDecimal,
     pub quantity: i32,
+    pub reorder_point: Option<i32>,
+    pub reorder_quantity: Option<i32>,
+    pub sku: Option<&'a str>,
+    pub barcode: Option<&'a str>,
+    pub category: Option<&'a str>,
+    pub brand: Option<&'a str>,
+    pub unit: &'a str,
+    pub shelf_location: Option<&'a str>,
+    pub average_cost: bigdecimal::BigDecimal,
 }
 
 #[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
@@ -209,7 +297,7 @@ pub enum NotificationStatusEnum {
 pub struct NotificationModel {
     pub notification_id: i32,
     pub user_id: i32,
-    pub order_id: i32,
+    pub order_id: Option<i32>,
     pub channel: NotificationChannelEnum,
     pub message: String,
     pub sent_at: chrono::DateTime<chrono::Utc>,
@@ -220,7 +308,7 @@ pub struct NotificationModel {
 #[diesel(table_name = crate::infrastructure::db::schema::notifications)]
 pub struct NewNotification {
     pub user_id: i32,
-    pub order_id: i32,
+    pub order_id: Option<i32>,
     pub channel: NotificationChannelEnum,
     pub message: String,
     pub status: NotificationStatusEnum,
@@ -247,3 +335,665 @@ pub struct NewFeedback {
     pub phone: String,
     pub message: String,
 }
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::infrastructure::db::schema::user_mfa)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct UserMfaModel {
+    pub user_id: i32,
+    pub totp_secret: String,
+    pub enabled: bool,
+    pub last_used_step: Option<i64>,
+    pub confirmed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub code_attempts: i32,
+    pub locked_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::user_mfa)]
+pub struct NewUserMfa {
+    pub user_id: i32,
+    pub totp_secret: String,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::infrastructure::db::schema::mfa_recovery_codes)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct MfaRecoveryCodeModel {
+    pub code_id: i32,
+    pub user_id: i32,
+    pub code_hash: String,
+    pub used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::mfa_recovery_codes)]
+pub struct NewMfaRecoveryCode {
+    pub user_id: i32,
+    pub code_hash: String,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::infrastructure::db::schema::audit_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AuditEventModel {
+    pub event_id: i64,
+    pub actor_id: Option<i32>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: String,
+    pub before_state: Option<serde_json::Value>,
+    pub after_state: Option<serde_json::Value>,
+    pub reason: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::audit_events)]
+pub struct NewAuditEvent {
+    pub actor_id: Option<i32>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: String,
+    pub before_state: Option<serde_json::Value>,
+    pub after_state: Option<serde_json::Value>,
+    pub reason: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+#[derive(DbEnum, Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[ExistingTypePath = "crate::infrastructure::db::schema::sql_types::ErasureRequestStatus"]
+pub enum ErasureRequestStatusEnum {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::erasure_requests)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ErasureRequestModel {
+    pub request_id: i32,
+    pub user_id: i32,
+    pub status: ErasureRequestStatusEnum,
+    pub reason: Option<String>,
+    pub requested_at: chrono::DateTime<chrono::Utc>,
+    pub reviewed_by: Option<i32>,
+    pub reviewed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub review_note: Option<String>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::erasure_requests)]
+pub struct NewErasureRequest {
+    pub user_id: i32,
+    pub reason: Option<String>,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::maintenance_rules)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct MaintenanceRuleModel {
+    pub rule_id: i32,
+    pub name: String,
+    pub match_keyword: String,
+    pub interval_months: Option<i32>,
+    pub interval_km: Option<i32>,
+    pub is_active: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = crate::infrastructure::db::schema::maintenance_rules)]
+#[diesel(treat_none_as_null = true)]
+pub struct MaintenanceRuleChangeset {
+    pub name: String,
+    pub match_keyword: String,
+    pub interval_months: Option<i32>,
+    pub interval_km: Option<i32>,
+    pub is_active: bool,
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[ExistingTypePath = "crate::infrastructure::db::schema::sql_types::MaintenanceReminderStatus"]
+pub enum MaintenanceReminderStatusEnum {
+    Sent,
+    Snoozed,
+    Converted,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::maintenance_reminders)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct MaintenanceReminderModel {
+    pub reminder_id: i32,
+    pub rule_id: i32,
+    pub bike_id: i32,
+    pub user_id: i32,
+    pub status: MaintenanceReminderStatusEnum,
+    pub sent_at: chrono::DateTime<chrono::Utc>,
+    pub snoozed_until: Option<chrono::DateTime<chrono::Utc>>,
+    pub converted_order_id: Option<i32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::maintenance_reminders)]
+pub struct NewMaintenanceReminder {
+    pub rule_id: i32,
+    pub bike_id: i32,
+    pub user_id: i32,
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[ExistingTypePath = "crate::infrastructure::db::schema::sql_types::MotorcycleTransferStatus"]
+pub enum MotorcycleTransferStatusEnum {
+    Pending,
+    Accepted,
+    Declined,
+    Cancelled,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::motorcycle_transfers)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct MotorcycleTransferModel {
+    pub transfer_id: i32,
+    pub bike_id: i32,
+    pub from_user_id: i32,
+    pub to_user_id: i32,
+    pub initiated_by: i32,
+    pub status: MotorcycleTransferStatusEnum,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub responded_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::motorcycle_transfers)]
+pub struct NewMotorcycleTransfer {
+    pub bike_id: i32,
+    pub from_user_id: i32,
+    pub to_user_id: i32,
+    pub initiated_by: i32,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::scheduled_jobs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ScheduledJobModel {
+    pub job_name: String,
+    pub schedule: String,
+    pub next_run_at: chrono::DateTime<chrono::Utc>,
+    pub last_started_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_finished_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_status: Option<String>,
+    pub last_message: Option<String>,
+    pub run_count: i32,
+    pub failure_count: i32,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::infrastructure::db::schema::phone_verifications)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PhoneVerificationModel {
+    pub verification_id: i32,
+    pub phone: String,
+    pub code_hash: String,
+    pub attempts: i32,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub consumed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub requested_ip: Option<String>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::phone_verifications)]
+pub struct NewPhoneVerification {
+    pub phone: String,
+    pub code_hash: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub requested_ip: Option<String>,
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[ExistingTypePath = "crate::infrastructure::db::schema::sql_types::LoyaltyEntryKind"]
+pub enum LoyaltyEntryKindEnum {
+    Earned,
+    Redeemed,
+    Adjusted,
+    Expired,
+    Refunded,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::loyalty_entries)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct LoyaltyEntryModel {
+    pub entry_id: i32,
+    pub user_id: i32,
+    pub kind: LoyaltyEntryKindEnum,
+    pub points: i32,
+    pub remaining: i32,
+    pub spend: Option<bigdecimal::BigDecimal>,
+    pub order_id: Option<i32>,
+    pub service_item_id: Option<i32>,
+    pub reason: Option<String>,
+    pub created_by: Option<i32>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub reversed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::loyalty_entries)]
+pub struct NewLoyaltyEntry {
+    pub user_id: i32,
+    pub kind: LoyaltyEntryKindEnum,
+    pub points: i32,
+    pub remaining: i32,
+    pub spend: Option<bigdecimal::BigDecimal>,
+    pub order_id: Option<i32>,
+    pub service_item_id: Option<i32>,
+    pub reason: Option<String>,
+    pub created_by: Option<i32>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[ExistingTypePath = "crate::infrastructure::db::schema::sql_types::QuotationStatus"]
+pub enum QuotationStatusEnum {
+    Pending,
+    Accepted,
+    Declined,
+    Superseded,
+    Expired,
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[ExistingTypePath = "crate::infrastructure::db::schema::sql_types::QuotationLineDecision"]
+pub enum QuotationLineDecisionEnum {
+    Pending,
+    Approved,
+    Declined,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::quotations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct QuotationModel {
+    pub quotation_id: i32,
+    pub order_id: i32,
+    pub version: i32,
+    pub status: QuotationStatusEnum,
+    pub total: bigdecimal::BigDecimal,
+    pub approved_total: Option<bigdecimal::BigDecimal>,
+    pub notes: Option<String>,
+    pub valid_until: chrono::DateTime<chrono::Utc>,
+    pub issued_by: Option<i32>,
+    pub responded_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::quotations)]
+pub struct NewQuotation {
+    pub order_id: i32,
+    pub version: i32,
+    pub total: bigdecimal::BigDecimal,
+    pub notes: Option<String>,
+    pub valid_until: chrono::DateTime<chrono::Utc>,
+    pub issued_by: Option<i32>,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::quotation_lines)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct QuotationLineModel {
+    pub line_id: i32,
+    pub quotation_id: i32,
+    pub service_item_id: Option<i32>,
+    pub description: String,
+    pub price: bigdecimal::BigDecimal,
+    pub quantity: i32,
+    pub decision: QuotationLineDecisionEnum,
+    pub locked: bool,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::quotation_lines)]
+pub struct NewQuotationLine {
+    pub quotation_id: i32,
+    pub service_item_id: Option<i32>,
+    pub description: String,
+    pub price: bigdecimal::BigDecimal,
+    pub quantity: i32,
+    pub decision: QuotationLineDecisionEnum,
+    pub locked: bool,
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[ExistingTypePath = "crate::infrastructure::db::schema::sql_types::StockMovementReason"]
+pub enum StockMovementReasonEnum {
+    Opening,
+    OrderUsage,
+    OrderReturn,
+    Adjustment,
+    Received,
+    StockTake,
+    Transfer,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::stock_movements)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StockMovementModel {
+    pub movement_id: i32,
+    pub stock_item_id: i32,
+    pub delta: i32,
+    pub reason: StockMovementReasonEnum,
+    pub order_id: Option<i32>,
+    pub actor_id: Option<i32>,
+    pub note: Option<String>,
+    pub quantity_after: i32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub unit_cost: Option<bigdecimal::BigDecimal>,
+    pub branch_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::stock_movements)]
+pub struct NewStockMovement {
+    pub stock_item_id: i32,
+    pub delta: i32,
+    pub reason: StockMovementReasonEnum,
+    pub order_id: Option<i32>,
+    pub actor_id: Option<i32>,
+    pub note: Option<String>,
+    pub quantity_after: i32,
+    pub unit_cost: Option<bigdecimal::BigDecimal>,
+    pub branch_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::stock_alerts)]
+pub struct NewStockAlert {
+    pub stock_item_id: i32,
+    pub quantity: i32,
+    pub reorder_point: i32,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::suppliers)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct SupplierModel {
+    pub supplier_id: i32,
+    pub name: String,
+    pub contact_name: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub notes: Option<String>,
+    pub lead_time_days: i32,
+    pub is_active: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = crate::infrastructure::db::schema::suppliers)]
+#[diesel(treat_none_as_null = true)]
+pub struct SupplierChangeset {
+    pub name: String,
+    pub contact_name: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub notes: Option<String>,
+    pub lead_time_days: i32,
+    pub is_active: bool,
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[ExistingTypePath = "crate::infrastructure::db::schema::sql_types::PurchaseOrderStatus"]
+pub enum PurchaseOrderStatusEnum {
+    Draft,
+    Sent,
+    PartiallyReceived,
+    Received,
+    Cancelled,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::purchase_orders)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PurchaseOrderModel {
+    pub po_id: i32,
+    pub supplier_id: i32,
+    pub status: PurchaseOrderStatusEnum,
+    pub expected_date: Option<chrono::NaiveDate>,
+    pub notes: Option<String>,
+    pub created_by: Option<i32>,
+    pub sent_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub closed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub branch_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::purchase_orders)]
+pub struct NewPurchaseOrder {
+    pub supplier_id: i32,
+    pub expected_date: Option<chrono::NaiveDate>,
+    pub notes: Option<String>,
+    pub created_by: Option<i32>,
+    pub branch_id: i32,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::purchase_order_lines)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PurchaseOrderLineModel {
+    pub line_id: i32,
+    pub po_id: i32,
+    pub stock_item_id: i32,
+    pub quantity_ordered: i32,
+    pub quantity_received: i32,
+    pub unit_cost: bigdecimal::BigDecimal,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::purchase_order_lines)]
+pub struct NewPurchaseOrderLine {
+    pub po_id: i32,
+    pub stock_item_id: i32,
+    pub quantity_ordered: i32,
+    pub unit_cost: bigdecimal::BigDecimal,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::goods_receipts)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct GoodsReceiptModel {
+    pub receipt_id: i32,
+    pub po_id: i32,
+    pub received_by: Option<i32>,
+    pub note: Option<String>,
+    pub received_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::goods_receipts)]
+pub struct NewGoodsReceipt {
+    pub po_id: i32,
+    pub received_by: Option<i32>,
+    pub note: Option<String>,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::goods_receipt_lines)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct GoodsReceiptLineModel {
+    pub receipt_line_id: i32,
+    pub receipt_id: i32,
+    pub po_line_id: i32,
+    pub stock_item_id: i32,
+    pub quantity: i32,
+    pub unit_cost: bigdecimal::BigDecimal,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::goods_receipt_lines)]
+pub struct NewGoodsReceiptLine {
+    pub receipt_id: i32,
+    pub po_line_id: i32,
+    pub stock_item_id: i32,
+    pub quantity: i32,
+    pub unit_cost: bigdecimal::BigDecimal,
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[ExistingTypePath = "crate::infrastructure::db::schema::sql_types::StockTakeStatus"]
+pub enum StockTakeStatusEnum {
+    Open,
+    Posted,
+    Cancelled,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::stock_takes)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StockTakeModel {
+    pub stock_take_id: i32,
+    pub status: StockTakeStatusEnum,
+    pub category: Option<String>,
+    pub notes: Option<String>,
+    pub opened_by: Option<i32>,
+    pub posted_by: Option<i32>,
+    pub opened_at: chrono::DateTime<chrono::Utc>,
+    pub closed_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub branch_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::stock_takes)]
+pub struct NewStockTake {
+    pub category: Option<String>,
+    pub notes: Option<String>,
+    pub opened_by: Option<i32>,
+    pub branch_id: i32,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::stock_take_lines)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StockTakeLineModel {
+    pub line_id: i32,
+    pub stock_take_id: i32,
+    pub stock_item_id: i32,
+    pub system_quantity: i32,
+    pub counted_quantity: Option<i32>,
+    pub counted_by: Option<i32>,
+    pub counted_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub unit_cost: Option<bigdecimal::BigDecimal>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::stock_take_lines)]
+pub struct NewStockTakeLine {
+    pub stock_take_id: i32,
+    pub stock_item_id: i32,
+    pub system_quantity: i32,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::branches)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct BranchModel {
+    pub branch_id: i32,
+    pub name: String,
+    pub address: Option<String>,
+    pub phone: Option<String>,
+    pub is_active: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = crate::infrastructure::db::schema::branches)]
+#[diesel(treat_none_as_null = true)]
+pub struct BranchChangeset {
+    pub name: String,
+    pub address: Option<String>,
+    pub phone: Option<String>,
+    pub is_active: bool,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::user_branches)]
+pub struct NewUserBranch {
+    pub user_id: i32,
+    pub branch_id: i32,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::stock_transfers)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StockTransferModel {
+    pub transfer_id: i32,
+    pub from_branch_id: i32,
+    pub to_branch_id: i32,
+    pub note: Option<String>,
+    pub created_by: Option<i32>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::stock_transfers)]
+pub struct NewStockTransfer {
+    pub from_branch_id: i32,
+    pub to_branch_id: i32,
+    pub note: Option<String>,
+    pub created_by: Option<i32>,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone, Serialize)]
+#[diesel(table_name = crate::infrastructure::db::schema::stock_transfer_lines)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StockTransferLineModel {
+    pub line_id: i32,
+    pub transfer_id: i32,
+    pub stock_item_id: i32,
+    pub quantity: i32,
+    pub unit_cost: bigdecimal::BigDecimal,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::stock_transfer_lines)]
+pub struct NewStockTransferLine {
+    pub transfer_id: i32,
+    pub stock_item_id: i32,
+    pub quantity: i32,
+    pub unit_cost: bigdecimal::BigDecimal,
+}
+
+#[derive(Queryable, Selectable, Debug, Clone)]
+#[diesel(table_name = crate::infrastructure::db::schema::stock_item_compatibilities)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StockItemCompatibilityModel {
+    pub compatibility_id: i32,
+    pub stock_item_id: i32,
+    pub brand: String,
+    pub model: Option<String>,
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::infrastructure::db::schema::stock_item_compatibilities)]
+pub struct NewStockItemCompatibility {
+    pub stock_item_id: i32,
+    pub brand: String,
+    pub model: Option<String>,
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+}
@@ -0,0 +1,95 @@
+use crate::domain::audit::AuditEvent;
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::models::{AuditEventModel, NewAuditEvent};
+use crate::infrastructure::db::schema::{audit_events, users};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+#[derive(Debug, Default)]
+pub struct AuditEventFilter {
+    pub actor_id: Option<i32>,
+    pub action: Option<String>,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Writes the event on the caller's connection, so it commits or rolls back
+/// together with the action it describes
+pub fn insert_event(conn: &mut PgConnection, event: AuditEvent) -> QueryResult<()> {
+    let new_event = NewAuditEvent {
+        actor_id: event.actor_id,
+        action: event.action,
+        target_type: event.target_type,
+        target_id: event.target_id,
+        before_state: event.before,
+        after_state: event.after,
+        reason: event.reason,
+        ip_address: event.ip_address,
+    };
+
+    diesel::insert_into(audit_events::table)
+        .values(&new_event)
+        .execute(conn)?;
+
+    Ok(())
+}
+
+#[derive(Clone)]
+pub struct AuditEventRepository {
+    pool: DbPool,
+}
+
+impl AuditEventRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(&self, event: AuditEvent) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        insert_event(&mut conn, event).map_err(|e| e.to_string())
+    }
+
+    /// Newest first, with the actor's username when the actor still exists
+    pub async fn list(
+        &self,
+        filter: AuditEventFilter,
+    ) -> Result<Vec<(AuditEventModel, Option<String>)>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let mut query = audit_events::table
+            .left_join(users::table)
+            .select((AuditEventModel::as_select(), users::username.nullable()))
+            .into_boxed();
+
+        if let Some(actor_id) = filter.actor_id {
+            query = query.filter(audit_events::actor_id.eq(actor_id));
+        }
+        if let Some(action) = filter.action {
+            query = query.filter(audit_events::action.eq(action));
+        }
+        if let Some(target_type) = filter.target_type {
+            query = query.filter(audit_events::target_type.eq(target_type));
+        }
+        if let Some(target_id) = filter.target_id {
+            query = query.filter(audit_events::target_id.eq(target_id));
+        }
+        if let Some(from) = filter.from {
+            query = query.filter(audit_events::created_at.ge(from));
+        }
+        if let Some(to) = filter.to {
+            query = query.filter(audit_events::created_at.lt(to));
+        }
+
+        query
+            .order(audit_events::event_id.desc())
+            .limit(filter.limit)
+            .offset(filter.offset)
+            .load::<(AuditEventModel, Option<String>)>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+}
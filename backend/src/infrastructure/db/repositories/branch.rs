@@ -0,0 +1,193 @@
+use crate::domain::audit::AuditEvent;
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::functions::lower;
+use crate::infrastructure::db::models::{BranchChangeset, BranchModel, NewUserBranch};
+use crate::infrastructure::db::repositories::audit_event::insert_event;
+use crate::infrastructure::db::schema::{branches, user_branches, users};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+#[derive(Clone)]
+pub struct BranchRepository {
+    pool: DbPool,
+}
+
+impl BranchRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<BranchModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        branches::table
+            .order(branches::branch_id.asc())
+            .select(BranchModel::as_select())
+            .load::<BranchModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn find_by_id(&self, branch_id: i32) -> Result<Option<BranchModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        branches::table
+            .find(branch_id)
+            .select(BranchModel::as_select())
+            .first::<BranchModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Names are unique regardless of case
+    pub async fn find_by_name(&self, name: &str) -> Result<Option<BranchModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        branches::table
+            .filter(lower(branches::name).eq(name.to_lowercase()))
+            .select(BranchModel::as_select())
+            .first::<BranchModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    /// The oldest branch still open, which is where anything that doesn't
+    /// name a branch happens
+    pub async fn default_branch_id(&self) -> Result<i32, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        default_branch_id(&mut conn)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "No branch is open".to_string())
+    }
+
+    /// Creates the branch and writes the audit event built from it in the
+    /// same transaction
+    pub async fn create(
+        &self,
+        branch: BranchChangeset,
+        audit: impl FnOnce(&BranchModel) -> AuditEvent,
+    ) -> Result<BranchModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let created = diesel::insert_into(branches::table)
+                .values(&branch)
+                .returning(BranchModel::as_returning())
+                .get_result(conn)?;
+            insert_event(conn, audit(&created))?;
+            Ok(created)
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    pub async fn update(
+        &self,
+        branch_id: i32,
+        branch: BranchChangeset,
+        audit: impl FnOnce(&BranchModel) -> AuditEvent,
+    ) -> Result<BranchModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let updated = diesel::update(branches::table.find(branch_id))
+                .set(&branch)
+                .returning(BranchModel::as_returning())
+                .get_result(conn)?;
+            insert_event(conn, audit(&updated))?;
+            Ok(updated)
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    /// Whether the user acts on every branch regardless of their own
+    pub async fn has_all_branches(&self, user_id: i32) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        users::table
+            .find(user_id)
+            .select(users::all_branches)
+            .first::<bool>(&mut conn)
+            .optional()
+            .map(|all| all.unwrap_or(false))
+            .map_err(|e| e.to_string())
+    }
+
+    /// The branches a user is tied to
+    pub async fn user_branch_ids(&self, user_id: i32) -> Result<Vec<i32>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        user_branches::table
+            .filter(user_branches::user_id.eq(user_id))
+            .order(user_branches::branch_id.asc())
+            .select(user_branches::branch_id)
+            .load::<i32>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Replaces the user's branches with the given ones
+    pub async fn set_user_branches(
+        &self,
+        user_id: i32,
+        branch_ids: &[i32],
+        all_branches: bool,
+        event: AuditEvent,
+    ) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::update(users::table.find(user_id))
+                .set(users::all_branches.eq(all_branches))
+                .execute(conn)?;
+            diesel::delete(user_branches::table.filter(user_branches::user_id.eq(user_id)))
+                .execute(conn)?;
+            let rows: Vec<NewUserBranch> = branch_ids
+                .iter()
+                .map(|&branch_id| NewUserBranch { user_id, branch_id })
+                .collect();
+            diesel::insert_into(user_branches::table)
+                .values(&rows)
+                .execute(conn)?;
+            insert_event(conn, event)
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    /// Ties a staff member who works nowhere yet to the default branch, so
+    /// new and promoted staff can start work there. Returns the branch, if
+    /// one was assigned.
+    pub async fn assign_default_branch(&self, user_id: i32) -> Result<Option<i32>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let all_branches = users::table
+                .find(user_id)
+                .for_update()
+                .select(users::all_branches)
+                .first::<bool>(conn)?;
+            let has_branch = diesel::select(diesel::dsl::exists(
+                user_branches::table.filter(user_branches::user_id.eq(user_id)),
+            ))
+            .get_result::<bool>(conn)?;
+            if all_branches || has_branch {
+                return Ok(None);
+            }
+            let Some(branch_id) = default_branch_id(conn)? else {
+                return Ok(None);
+            };
+            diesel::insert_into(user_branches::table)
+                .values(&NewUserBranch { user_id, branch_id })
+                .execute(conn)?;
+            Ok(Some(branch_id))
+        })
+        .map_err(|e| e.to_string())
+    }
+}
+
+fn default_branch_id(conn: &mut PgConnection) -> QueryResult<Option<i32>> {
+    branches::table
+        .filter(branches::is_active.eq(true))
+        .order(branches::branch_id.asc())
+        .select(branches::branch_id)
+        .first::<i32>(conn)
+        .optional()
+}
@@ -0,0 +1,104 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::models::{
+    ErasureRequestModel, ErasureRequestStatusEnum, NewErasureRequest,
+};
+use crate::infrastructure::db::schema::erasure_requests;
+use chrono::Utc;
+use diesel::prelude::*;
+
+#[derive(Clone)]
+pub struct ErasureRequestRepository {
+    pool: DbPool,
+}
+
+impl ErasureRequestRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: i32,
+        reason: Option<String>,
+    ) -> Result<ErasureRequestModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::insert_into(erasure_requests::table)
+            .values(&NewErasureRequest { user_id, reason })
+            .returning(ErasureRequestModel::as_returning())
+            .get_result(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn find_by_id(&self, request_id: i32) -> Result<Option<ErasureRequestModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        erasure_requests::table
+            .find(request_id)
+            .select(ErasureRequestModel::as_select())
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn find_pending_for_user(
+        &self,
+        user_id: i32,
+    ) -> Result<Option<ErasureRequestModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        erasure_requests::table
+            .filter(erasure_requests::user_id.eq(user_id))
+            .filter(erasure_requests::status.eq(ErasureRequestStatusEnum::Pending))
+            .select(ErasureRequestModel::as_select())
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn list(
+        &self,
+        status: Option<ErasureRequestStatusEnum>,
+    ) -> Result<Vec<ErasureRequestModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let mut query = erasure_requests::table
+            .select(ErasureRequestModel::as_select())
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(erasure_requests::status.eq(status));
+        }
+
+        query
+            .order(erasure_requests::requested_at.desc())
+            .load(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Closes a pending request. Returns false if it was already reviewed.
+    pub async fn mark_reviewed(
+        &self,
+        request_id: i32,
+        status: ErasureRequestStatusEnum,
+        reviewed_by: i32,
+        review_note: Option<String>,
+    ) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let updated = diesel::update(
+            erasure_requests::table
+                .find(request_id)
+                .filter(erasure_requests::status.eq(ErasureRequestStatusEnum::Pending)),
+        )
+        .set((
+            erasure_requests::status.eq(status),
+            erasure_requests::reviewed_by.eq(Some(reviewed_by)),
+            erasure_requests::reviewed_at.eq(Some(Utc::now())),
+            erasure_requests::review_note.eq(review_note),
+        ))
+        .execute(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+        Ok(updated > 0)
+    }
+}
@@ -39,6 +39,17 @@ impl FeedbackRepository {
         Ok(results)
     }
 
+    pub async fn find_by_id(&self, id: i32) -> Result<Option<FeedbackModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        feedbacks::table
+            .find(id)
+            .select(FeedbackModel::as_select())
+            .first::<FeedbackModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
     pub async fn delete_feedback(&self, id: i32) -> Result<(), String> {
         use crate::infrastructure::db::schema::feedbacks::dsl::*;
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
@@ -0,0 +1,138 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::schema::{
+    feedbacks, loyalty_entries, maintenance_reminders, motorcycle_transfers, motorcycles,
+    notifications, service_orders, users,
+};
+use chrono::Utc;
+use diesel::prelude::*;
+
+#[derive(Clone)]
+pub struct GuestCustomerRepository {
+    pool: DbPool,
+}
+
+impl GuestCustomerRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Moves everything recorded against unclaimed guests with this phone onto
+    /// the account, then retires the guests. A guest bike that the account
+    /// already has in its garage (same plate or VIN) is folded into that bike.
+    /// Returns the ids of the guests that were merged.
+    pub async fn merge_into(&self, user_id: i32, phone: &str) -> Result<Vec<i32>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let guest_ids = users::table
+                .filter(users::is_guest.eq(true))
+                .filter(users::merged_into_user_id.is_null())
+                .filter(users::phone.eq(phone))
+                .filter(users::user_id.ne(user_id))
+                .select(users::user_id)
+                .for_update()
+                .load::<i32>(conn)?;
+            if guest_ids.is_empty() {
+                return Ok(guest_ids);
+            }
+
+            let own_bikes = motorcycles::table
+                .filter(motorcycles::user_id.eq(user_id))
+                .filter(motorcycles::archived_at.is_null())
+                .select((
+                    motorcycles::bike_id,
+                    motorcycles::plate_key,
+                    motorcycles::vin,
+                ))
+                .load::<(i32, Option<String>, Option<String>)>(conn)?;
+            let guest_bikes = motorcycles::table
+                .filter(motorcycles::user_id.eq_any(&guest_ids))
+                .select((
+                    motorcycles::bike_id,
+                    motorcycles::plate_key,
+                    motorcycles::vin,
+                ))
+                .load::<(i32, Option<String>, Option<String>)>(conn)?;
+
+            for (guest_bike, plate_key, vin) in guest_bikes {
+                let same_bike = own_bikes.iter().find(|(_, own_key, own_vin)| {
+                    (plate_key.is_some() && *own_key == plate_key)
+                        || (vin.is_some() && *own_vin == vin)
+                });
+                match same_bike {
+                    Some((own_bike, _, _)) => {
+                        diesel::update(
+                            service_orders::table.filter(service_orders::bike_id.eq(guest_bike)),
+                        )
+                        .set(service_orders::bike_id.eq(Some(*own_bike)))
+                        .execute(conn)?;
+                        diesel::delete(
+                            maintenance_reminders::table
+                                .filter(maintenance_reminders::bike_id.eq(guest_bike)),
+                        )
+                        .execute(conn)?;
+                        diesel::delete(
+                            motorcycle_transfers::table
+                                .filter(motorcycle_transfers::bike_id.eq(guest_bike)),
+                        )
+                        .execute(conn)?;
+                        diesel::delete(motorcycles::table.find(guest_bike)).execute(conn)?;
+                    }
+                    None => {
+                        diesel::update(motorcycles::table.find(guest_bike))
+                            .set(motorcycles::user_id.eq(user_id))
+                            .execute(conn)?;
+                    }
+                }
+            }
+
+            diesel::update(
+                service_orders::table.filter(service_orders::customer_id.eq_any(&guest_ids)),
+            )
+            .set(service_orders::customer_id.eq(user_id))
+            .execute(conn)?;
+            diesel::update(
+                maintenance_reminders::table
+                    .filter(maintenance_reminders::user_id.eq_any(&guest_ids)),
+            )
+            .set(maintenance_reminders::user_id.eq(user_id))
+            .execute(conn)?;
+            diesel::update(
+                loyalty_entries::table.filter(loyalty_entries::user_id.eq_any(&guest_ids)),
+            )
+            .set(loyalty_entries::user_id.eq(user_id))
+            .execute(conn)?;
+            diesel::update(notifications::table.filter(notifications::user_id.eq_any(&guest_ids)))
+                .set(notifications::user_id.eq(user_id))
+                .execute(conn)?;
+            diesel::update(
+                feedbacks::table.filter(feedbacks::user_id.assume_not_null().eq_any(&guest_ids)),
+            )
+            .set(feedbacks::user_id.eq(Some(user_id)))
+            .execute(conn)?;
+            diesel::update(
+                motorcycle_transfers::table
+                    .filter(motorcycle_transfers::from_user_id.eq_any(&guest_ids)),
+            )
+            .set(motorcycle_transfers::from_user_id.eq(user_id))
+            .execute(conn)?;
+            diesel::update(
+                motorcycle_transfers::table
+                    .filter(motorcycle_transfers::to_user_id.eq_any(&guest_ids)),
+            )
+            .set(motorcycle_transfers::to_user_id.eq(user_id))
+            .execute(conn)?;
+
+            diesel::update(users::table.filter(users::user_id.eq_any(&guest_ids)))
+                .set((
+                    users::is_active.eq(false),
+                    users::deactivated_at.eq(Some(Utc::now())),
+                    users::merged_into_user_id.eq(Some(user_id)),
+                ))
+                .execute(conn)?;
+
+            Ok(guest_ids)
+        })
+        .map_err(|e| e.to_string())
+    }
+}
@@ -1,9 +1,26 @@
 use crate::infrastructure::db::connection::DbPool;
-use crate::infrastructure::db::models::{NewServiceItem, ServiceOrderModel, StockItemModel};
+use crate::infrastructure::db::models::{
+    NewServiceItem, ServiceItemModel, ServiceItemStockStatusEnum, ServiceOrderModel,
+    ServiceOrderStatusEnum, StockItemModel, StockMovementReasonEnum,
+};
+use crate::infrastructure::db::repositories::loyalty::{RedemptionScope, refund_redemptions};
+use crate::infrastructure::db::repositories::stock_movement::{
+    StockChange, adjust_reserved, apply_stock_change, lock_stock_level,
+};
 use crate::infrastructure::db::schema::{service_items, service_orders, stock_items};
+use crate::infrastructure::db::transaction::{TransactionError, rejected};
 use bigdecimal::{BigDecimal, FromPrimitive};
+use diesel::pg::PgConnection;
 use diesel::prelude::*;
 
+/// Orders in these statuses have not started work, so parts added to them are
+/// only reserved
+const QUOTING_STATUSES: [ServiceOrderStatusEnum; 3] = [
+    ServiceOrderStatusEnum::Booked,
+    ServiceOrderStatusEnum::ReviewPending,
+    ServiceOrderStatusEnum::OfferSent,
+];
+
 #[derive(Clone)]
 pub struct InventoryRepository {
     pool: DbPool,
@@ -14,29 +31,76 @@ impl InventoryRepository {
         Self { pool }
     }
 
+    /// Adds the part to the order from its branch's shelves. While the order
+    /// is still being quoted the stock is only reserved; once work has started
+    /// it is taken straight away. Returns whether it was taken.
     pub async fn use_stock_item(
         &self,
         order_id: i32,
         stock_item_id: i32,
         quantity: i32,
-    ) -> Result<(), String> {
+        actor_id: i32,
+    ) -> Result<bool, String> {
+        if quantity <= 0 {
+            return Err("Quantity must be positive".to_string());
+        }
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
 
-        conn.transaction::<_, diesel::result::Error, _>(|conn| {
-            // 1. Get stock item and check availability (FOR UPDATE to lock the row)
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            let order = service_orders::table
+                .find(order_id)
+                .for_update()
+                .select(ServiceOrderModel::as_select())
+                .first::<ServiceOrderModel>(conn)?;
+            if is_closed(&order.status) {
+                return Err(TransactionError::Rejected(
+                    "Parts can't be added to a cancelled or paid order".to_string(),
+                ));
+            }
+
+            // 1. Get stock item and check what the branch still has free to
+            // promise (FOR UPDATE to lock the rows)
             let stock_item = stock_items::table
                 .find(stock_item_id)
+                .filter(stock_items::archived_at.is_null())
                 .for_update()
-                .first::<StockItemModel>(conn)?;
+                .select(StockItemModel::as_select())
+                .first::<StockItemModel>(conn)
+                .optional()?
+                .ok_or_else(|| rejected("Stock item not found"))?;
+            let (on_hand, reserved) = lock_stock_level(conn, stock_item_id, order.branch_id)?;
 
-            if stock_item.quantity < quantity {
-                return Err(diesel::result::Error::RollbackTransaction);
+            if on_hand - reserved < quantity {
+                return Err(TransactionError::Rejected(
+                    "Not enough items in stock at this branch".to_string(),
+                ));
             }
 
-            // 2. Decrease stock quantity
-            diesel::update(stock_items::table.find(stock_item_id))
-                .set(stock_items::quantity.eq(stock_items::quantity - quantity))
-                .execute(conn)?;
+            // 2. Hold the stock, or take it out while noting what the parts cost
+            let (stock_status, unit_cost) = if QUOTING_STATUSES.contains(&order.status) {
+                adjust_reserved(conn, stock_item_id, order.branch_id, quantity)?;
+                (ServiceItemStockStatusEnum::Reserved, None)
+            } else {
+                let applied = apply_stock_change(
+                    conn,
+                    StockChange {
+                        stock_item_id,
+                        branch_id: order.branch_id,
+                        delta: -quantity,
+                        reason: StockMovementReasonEnum::OrderUsage,
+                        order_id: Some(order_id),
+                        actor_id: Some(actor_id),
+                        note: None,
+                        unit_cost: None,
+                    },
+                )?;
+                (
+                    ServiceItemStockStatusEnum::Consumed,
+                    Some(applied.unit_cost),
+                )
+            };
+
+            let taken = stock_status == ServiceItemStockStatusEnum::Consumed;
 
             // 3. Calculate price
             let item_price_f64 = stock_item.price.to_string().parse::<f64>().unwrap_or(0.0);
@@ -51,6 +115,8 @@ impl InventoryRepository {
                 price: total_item_price_bd.clone(),
                 stock_item_id: Some(stock_item_id),
                 quantity,
+                unit_cost,
+                stock_status: Some(stock_status),
             };
 
             diesel::insert_into(service_items::table)
@@ -58,56 +124,479 @@ impl InventoryRepository {
                 .execute(conn)?;
 
             // 5. Update order total price
-            let order = service_orders::table
-                .find(order_id)
-                .first::<ServiceOrderModel>(conn)?;
-
             diesel::update(service_orders::table.find(order_id))
                 .set(service_orders::total_price.eq(order.total_price + total_item_price_bd))
                 .execute(conn)?;
 
-            Ok(())
+            Ok(taken)
         })
-        .map_err(|e| match e {
-            diesel::result::Error::RollbackTransaction => "Not enough items in stock".to_string(),
-            _ => e.to_string(),
-        })?;
+        .map_err(String::from)
+    }
+
+    /// The order a line belongs to, if the line exists
+    pub async fn item_order_id(&self, item_id: i32) -> Result<Option<i32>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
 
-        Ok(())
+        service_items::table
+            .find(item_id)
+            .select(service_items::order_id)
+            .first::<i32>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
     }
 
-    pub async fn remove_service_item(&self, item_id: i32) -> Result<(), String> {
+    pub async fn remove_service_item(&self, item_id: i32, actor_id: i32) -> Result<(), String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
 
-        conn.transaction::<_, diesel::result::Error, _>(|conn| {
-            // 1. Get the service item
-            let item = service_items::table
-                .find(item_id)
-                .first::<crate::infrastructure::db::models::ServiceItemModel>(conn)?;
-
-            // 2. If it has a stock_item_id, refund the stock
-            if let Some(stock_id) = item.stock_item_id {
-                diesel::update(stock_items::table.find(stock_id))
-                    .set(stock_items::quantity.eq(stock_items::quantity + item.quantity))
-                    .execute(conn)?;
-            }
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            remove_item(conn, item_id, Some(actor_id))
+        })
+        .map_err(String::from)
+    }
+}
 
-            // 3. Update order total price
-            let order = service_orders::table
-                .find(item.order_id)
-                .first::<ServiceOrderModel>(conn)?;
+/// Paid and cancelled orders are closed: their lines and the stock behind
+/// them no longer change
+fn is_closed(status: &ServiceOrderStatusEnum) -> bool {
+    matches!(
+        status,
+        ServiceOrderStatusEnum::Cancelled | ServiceOrderStatusEnum::Paid
+    )
+}
 
-            diesel::update(service_orders::table.find(item.order_id))
-                .set(service_orders::total_price.eq(order.total_price - item.price))
-                .execute(conn)?;
+/// Frees what a part line holds at its order's branch: a reservation is
+/// dropped, stock that was taken goes back on the shelf at the cost it left at
+fn release_item(
+    conn: &mut PgConnection,
+    item: &ServiceItemModel,
+    actor_id: Option<i32>,
+) -> QueryResult<()> {
+    let Some(stock_id) = item.stock_item_id else {
+        return Ok(());
+    };
+    let branch_id = service_orders::table
+        .find(item.order_id)
+        .select(service_orders::branch_id)
+        .first::<i32>(conn)?;
+    match item.stock_status {
+        Some(ServiceItemStockStatusEnum::Reserved) => {
+            adjust_reserved(conn, stock_id, branch_id, -item.quantity)?;
+        }
+        Some(ServiceItemStockStatusEnum::Consumed) => {
+            apply_stock_change(
+                conn,
+                StockChange {
+                    stock_item_id: stock_id,
+                    branch_id,
+                    delta: item.quantity,
+                    reason: StockMovementReasonEnum::OrderReturn,
+                    order_id: Some(item.order_id),
+                    actor_id,
+                    note: None,
+                    unit_cost: item.unit_cost.clone(),
+                },
+            )?;
+        }
+        Some(ServiceItemStockStatusEnum::Released) | None => {}
+    }
+    Ok(())
+}
 
-            // 4. Delete the service item
-            diesel::delete(service_items::table.find(item_id)).execute(conn)?;
+/// Takes a line off its order: frees its stock, lowers the order total
+/// and returns any points that paid for it. Runs inside the caller's transaction.
+pub fn remove_item(
+    conn: &mut PgConnection,
+    item_id: i32,
+    actor_id: Option<i32>,
+) -> Result<(), TransactionError> {
+    // 1. Get the service item, and lock its order so its status can't change
+    // underneath us
+    let item = service_items::table
+        .find(item_id)
+        .select(ServiceItemModel::as_select())
+        .first::<ServiceItemModel>(conn)?;
+    let order = service_orders::table
+        .find(item.order_id)
+        .for_update()
+        .select(ServiceOrderModel::as_select())
+        .first::<ServiceOrderModel>(conn)?;
+    if is_closed(&order.status) {
+        return Err(rejected(
+            "Parts can't be removed from a cancelled or paid order",
+        ));
+    }
+
+    // 2. If it has a stock_item_id, release the reservation or refund the stock
+    release_item(conn, &item, actor_id)?;
+
+    // 3. Update order total price
+
+    diesel::update(service_orders::table.find(item.order_id))
+        .set(service_orders::total_price.eq(order.total_price - item.price))
+        .execute(conn)?;
+
+    // 4. Give back any points that paid for this line
+    refund_redemptions(conn, RedemptionScope::ServiceItem(item_id))?;
 
+    // 5. Delete the service item
+    diesel::delete(service_items::table.find(item_id)).execute(conn)?;
+
+    Ok(())
+}
+
+/// Brings the order's parts in line with its new status: starting work takes
+/// the reserved parts out of stock, cancelling frees everything the order
+/// holds. Safe to call again for the same status. Runs inside the caller's
+/// transaction.
+pub fn settle_order_stock(
+    conn: &mut PgConnection,
+    order_id: i32,
+    status: &ServiceOrderStatusEnum,
+    actor_id: Option<i32>,
+) -> Result<(), TransactionError> {
+    match status {
+        ServiceOrderStatusEnum::Repairing
+        | ServiceOrderStatusEnum::Completed
+        | ServiceOrderStatusEnum::Paid => consume_reserved(conn, order_id, actor_id),
+        ServiceOrderStatusEnum::Cancelled => {
+            release_order_stock(conn, order_id, actor_id)?;
             Ok(())
-        })
-        .map_err(|e| e.to_string())?;
+        }
+        _ => Ok(()),
+    }
+}
+
+fn consume_reserved(
+    conn: &mut PgConnection,
+    order_id: i32,
+    actor_id: Option<i32>,
+) -> Result<(), TransactionError> {
+    let reserved = service_items::table
+        .filter(service_items::order_id.eq(order_id))
+        .filter(service_items::stock_status.eq(ServiceItemStockStatusEnum::Reserved))
+        .order(service_items::item_id.asc())
+        .for_update()
+        .select(ServiceItemModel::as_select())
+        .load::<ServiceItemModel>(conn)?;
+    let branch_id = service_orders::table
+        .find(order_id)
+        .select(service_orders::branch_id)
+        .first::<i32>(conn)?;
+
+    for item in reserved {
+        let Some(stock_id) = item.stock_item_id else {
+            continue;
+        };
+        let name = stock_items::table
+            .find(stock_id)
+            .for_update()
+            .select(stock_items::name)
+            .first::<String>(conn)?;
+        let (on_hand, _) = lock_stock_level(conn, stock_id, branch_id)?;
+        if on_hand < item.quantity {
+            return Err(rejected(format!(
+                "Only {} of {} left in stock, {} needed to start the repair",
+                on_hand, name, item.quantity
+            )));
+        }
+
+        adjust_reserved(conn, stock_id, branch_id, -item.quantity)?;
+        let applied = apply_stock_change(
+            conn,
+            StockChange {
+                stock_item_id: stock_id,
+                branch_id,
+                delta: -item.quantity,
+                reason: StockMovementReasonEnum::OrderUsage,
+                order_id: Some(order_id),
+                actor_id,
+                note: None,
+                unit_cost: None,
+            },
+        )?;
+        diesel::update(service_items::table.find(item.item_id))
+            .set((
+                service_items::stock_status.eq(ServiceItemStockStatusEnum::Consumed),
+                service_items::unit_cost.eq(Some(applied.unit_cost)),
+            ))
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+/// Drops the order's reservations and puts back any stock it took, keeping the
+/// lines on the order as a record. Once the repair is finished the parts it
+/// used are on the bike, so they only come back by taking each line off the
+/// order; until then the order is refused. Returns how many lines were
+/// released.
+pub fn release_order_stock(
+    conn: &mut PgConnection,
+    order_id: i32,
+    actor_id: Option<i32>,
+) -> Result<usize, TransactionError> {
+    let status = service_orders::table
+        .find(order_id)
+        .select(service_orders::status)
+        .first::<ServiceOrderStatusEnum>(conn)?;
+    let held = service_items::table
+        .filter(service_items::order_id.eq(order_id))
+        .filter(service_items::stock_status.eq_any([
+            ServiceItemStockStatusEnum::Reserved,
+            ServiceItemStockStatusEnum::Consumed,
+        ]))
+        .order(service_items::item_id.asc())
+        .for_update()
+        .select(ServiceItemModel::as_select())
+        .load::<ServiceItemModel>(conn)?;
+
+    let repaired = matches!(
+        status,
+        ServiceOrderStatusEnum::Completed | ServiceOrderStatusEnum::Paid
+    );
+    if repaired
+        && held
+            .iter()
+            .any(|item| item.stock_status == Some(ServiceItemStockStatusEnum::Consumed))
+    {
+        return Err(rejected(format!(
+            "The parts on order #SO-{} have already been fitted. Remove them from the order first to return them to stock.",
+            order_id
+        )));
+    }
+
+    for item in &held {
+        release_item(conn, item, actor_id)?;
+        diesel::update(service_items::table.find(item.item_id))
+            .set(service_items::stock_status.eq(ServiceItemStockStatusEnum::Released))
+            .execute(conn)?;
+    }
+    Ok(held.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::db::connection::test_pool;
+    use crate::infrastructure::db::models::UserRoleEnum;
+    use crate::infrastructure::db::repositories::test_support::{
+        assert_ledger_balanced, branch, decimal, item_state, level, order, part_line, stock_item,
+        user,
+    };
+    use crate::infrastructure::db::schema::stock_movements;
+
+    /// An item with `on_hand` at a branch, and a quoted order there holding
+    /// `reserved` of it
+    fn reserved_order(
+        conn: &mut PgConnection,
+        on_hand: i32,
+        reserved: i32,
+    ) -> (i32, i32, i32, i32) {
+        let shop = branch(conn);
+        let customer = user(conn, UserRoleEnum::Customer);
+        let item = stock_item(conn, "inventory-test", "0");
+        apply_stock_change(
+            conn,
+            StockChange {
+                stock_item_id: item,
+                branch_id: shop,
+                delta: on_hand,
+                reason: StockMovementReasonEnum::Opening,
+                order_id: None,
+                actor_id: None,
+                note: None,
+                unit_cost: Some(decimal("50")),
+            },
+        )
+        .unwrap();
+        let order_id = order(conn, customer, shop, ServiceOrderStatusEnum::OfferSent);
+        let line = part_line(
+            conn,
+            order_id,
+            item,
+            reserved,
+            ServiceItemStockStatusEnum::Reserved,
+            None,
+        );
+        adjust_reserved(conn, item, shop, reserved).unwrap();
+        (shop, item, order_id, line)
+    }
+
+    fn line_state(
+        conn: &mut PgConnection,
+        line: i32,
+    ) -> (Option<ServiceItemStockStatusEnum>, Option<BigDecimal>) {
+        service_items::table
+            .find(line)
+            .select((service_items::stock_status, service_items::unit_cost))
+            .first(conn)
+            .unwrap()
+    }
+
+    #[test]
+    fn starting_work_consumes_the_reservation() {
+        let Some(pool) = test_pool() else {
+            return;
+        };
+        let mut conn = pool.get().unwrap();
+        let conn = &mut *conn;
+        let (shop, item, order_id, line) = reserved_order(conn, 10, 3);
+
+        assert!(
+            settle_order_stock(conn, order_id, &ServiceOrderStatusEnum::Repairing, None).is_ok()
+        );
+        // Settling again for the same status changes nothing
+        assert!(
+            settle_order_stock(conn, order_id, &ServiceOrderStatusEnum::Completed, None).is_ok()
+        );
+
+        assert_eq!(level(conn, item, shop), (7, 0));
+        assert_eq!(item_state(conn, item).1, 0);
+        let (status, unit_cost) = line_state(conn, line);
+        assert_eq!(status, Some(ServiceItemStockStatusEnum::Consumed));
+        assert_eq!(unit_cost, Some(decimal("50")));
+        assert_ledger_balanced(conn, item);
+    }
+
+    #[test]
+    fn parts_stay_on_a_paid_order() {
+        let Some(pool) = test_pool() else {
+            return;
+        };
+        let mut conn = pool.get().unwrap();
+        let conn = &mut *conn;
+        let (shop, item, order_id, line) = reserved_order(conn, 10, 3);
+        assert!(
+            settle_order_stock(conn, order_id, &ServiceOrderStatusEnum::Repairing, None).is_ok()
+        );
+        diesel::update(service_orders::table.find(order_id))
+            .set(service_orders::status.eq(ServiceOrderStatusEnum::Paid))
+            .execute(conn)
+            .unwrap();
+
+        let refused =
+            conn.transaction::<_, TransactionError, _>(|conn| remove_item(conn, line, None));
+        assert!(matches!(refused, Err(TransactionError::Rejected(_))));
+
+        assert_eq!(level(conn, item, shop), (7, 0));
+        assert_eq!(
+            line_state(conn, line).0,
+            Some(ServiceItemStockStatusEnum::Consumed)
+        );
+        assert_ledger_balanced(conn, item);
+    }
+
+    #[test]
+    fn starting_work_without_the_stock_is_refused() {
+        let Some(pool) = test_pool() else {
+            return;
+        };
+        let mut conn = pool.get().unwrap();
+        let conn = &mut *conn;
+        let (shop, item, order_id, line) = reserved_order(conn, 2, 3);
+
+        let refused = conn.transaction::<_, TransactionError, _>(|conn| {
+            settle_order_stock(conn, order_id, &ServiceOrderStatusEnum::Repairing, None)
+        });
+        assert!(matches!(refused, Err(TransactionError::Rejected(_))));
+
+        assert_eq!(level(conn, item, shop), (2, 3));
+        assert_eq!(
+            line_state(conn, line).0,
+            Some(ServiceItemStockStatusEnum::Reserved)
+        );
+        assert_ledger_balanced(conn, item);
+    }
+
+    #[test]
+    fn cancelling_releases_reservations_and_returns_taken_stock() {
+        let Some(pool) = test_pool() else {
+            return;
+        };
+        let mut conn = pool.get().unwrap();
+        let conn = &mut *conn;
+        let (shop, item, order_id, reserved_line) = reserved_order(conn, 10, 3);
+        let taken_line = part_line(
+            conn,
+            order_id,
+            item,
+            2,
+            ServiceItemStockStatusEnum::Consumed,
+            Some("40"),
+        );
+        apply_stock_change(
+            conn,
+            StockChange {
+                stock_item_id: item,
+                branch_id: shop,
+                delta: -2,
+                reason: StockMovementReasonEnum::OrderUsage,
+                order_id: Some(order_id),
+                actor_id: None,
+                note: None,
+                unit_cost: None,
+            },
+        )
+        .unwrap();
+
+        let admin = user(conn, UserRoleEnum::Admin);
+        assert!(
+            settle_order_stock(
+                conn,
+                order_id,
+                &ServiceOrderStatusEnum::Cancelled,
+                Some(admin)
+            )
+            .is_ok()
+        );
+
+        assert_eq!(level(conn, item, shop), (10, 0));
+        assert_eq!(item_state(conn, item).1, 0);
+        for line in [reserved_line, taken_line] {
+            assert_eq!(
+                line_state(conn, line).0,
+                Some(ServiceItemStockStatusEnum::Released)
+            );
+        }
+        // Taken stock comes back at what it left at
+        let (returned_at, returned_by): (Option<BigDecimal>, Option<i32>) = stock_movements::table
+            .filter(stock_movements::stock_item_id.eq(item))
+            .filter(stock_movements::reason.eq(StockMovementReasonEnum::OrderReturn))
+            .select((stock_movements::unit_cost, stock_movements::actor_id))
+            .first(conn)
+            .unwrap();
+        assert_eq!(returned_at, Some(decimal("40")));
+        assert_eq!(returned_by, Some(admin));
+        // Nothing left to release a second time
+        assert!(matches!(release_order_stock(conn, order_id, None), Ok(0)));
+        assert_ledger_balanced(conn, item);
+    }
+
+    #[test]
+    fn fitted_parts_are_not_returned_once_the_repair_is_done() {
+        let Some(pool) = test_pool() else {
+            return;
+        };
+        let mut conn = pool.get().unwrap();
+        let conn = &mut *conn;
+        let (shop, item, order_id, line) = reserved_order(conn, 10, 3);
+        assert!(
+            settle_order_stock(conn, order_id, &ServiceOrderStatusEnum::Repairing, None).is_ok()
+        );
+        diesel::update(service_orders::table.find(order_id))
+            .set(service_orders::status.eq(ServiceOrderStatusEnum::Completed))
+            .execute(conn)
+            .unwrap();
+
+        let refused = conn.transaction::<_, TransactionError, _>(|conn| {
+            release_order_stock(conn, order_id, None)
+        });
+        assert!(matches!(refused, Err(TransactionError::Rejected(_))));
 
-        Ok(())
+        assert_eq!(level(conn, item, shop), (7, 0));
+        assert_eq!(
+            line_state(conn, line).0,
+            Some(ServiceItemStockStatusEnum::Consumed)
+        );
+        assert_ledger_balanced(conn, item);
     }
 }
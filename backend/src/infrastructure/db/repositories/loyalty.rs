@@ -0,0 +1,422 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::models::{
+    LoyaltyEntryKindEnum, LoyaltyEntryModel, NewLoyaltyEntry, NewServiceItem, ServiceOrderModel,
+    ServiceOrderStatusEnum,
+};
+use crate::infrastructure::db::schema::{loyalty_entries, service_items, service_orders};
+use crate::infrastructure::db::transaction::{TransactionError, rejected};
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use chrono::{DateTime, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+
+/// Points spent on an order, turned into a discount line
+pub struct NewRedemption {
+    pub user_id: i32,
+    pub order_id: i32,
+    pub points: i32,
+    pub discount: f64,
+    pub description: String,
+}
+
+#[derive(Clone)]
+pub struct LoyaltyRepository {
+    pool: DbPool,
+}
+
+impl LoyaltyRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Unspent points that haven't lapsed yet
+    pub async fn balance(&self, user_id: i32, now: DateTime<Utc>) -> Result<i64, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        loyalty_entries::table
+            .filter(loyalty_entries::user_id.eq(user_id))
+            .filter(loyalty_entries::remaining.gt(0))
+            .filter(loyalty_entries::expires_at.gt(now))
+            .select(diesel::dsl::sum(loyalty_entries::remaining))
+            .first::<Option<i64>>(&mut conn)
+            .map(|sum| sum.unwrap_or(0))
+            .map_err(|e| e.to_string())
+    }
+
+    /// Paid order totals that earned points since the given time, leaving out
+    /// orders whose points were taken back
+    pub async fn spend_since(&self, user_id: i32, since: DateTime<Utc>) -> Result<f64, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let spend = loyalty_entries::table
+            .filter(loyalty_entries::user_id.eq(user_id))
+            .filter(loyalty_entries::kind.eq(LoyaltyEntryKindEnum::Earned))
+            .filter(loyalty_entries::reversed_at.is_null())
+            .filter(loyalty_entries::created_at.ge(since))
+            .select(diesel::dsl::sum(loyalty_entries::spend))
+            .first::<Option<BigDecimal>>(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(spend.and_then(|s| s.to_f64()).unwrap_or(0.0))
+    }
+
+    pub async fn list_entries(
+        &self,
+        user_id: i32,
+        limit: i64,
+    ) -> Result<Vec<LoyaltyEntryModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        loyalty_entries::table
+            .filter(loyalty_entries::user_id.eq(user_id))
+            .order(loyalty_entries::entry_id.desc())
+            .limit(limit)
+            .select(LoyaltyEntryModel::as_select())
+            .load::<LoyaltyEntryModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Credits with points left that lapse within the window, soonest first
+    pub async fn expiring_credits(
+        &self,
+        user_id: i32,
+        now: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<LoyaltyEntryModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        loyalty_entries::table
+            .filter(loyalty_entries::user_id.eq(user_id))
+            .filter(loyalty_entries::remaining.gt(0))
+            .filter(loyalty_entries::expires_at.gt(now))
+            .filter(loyalty_entries::expires_at.le(until))
+            .order(loyalty_entries::expires_at.asc())
+            .select(LoyaltyEntryModel::as_select())
+            .load::<LoyaltyEntryModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Records points earned by a paid order. Returns false if the order
+    /// already earned, so repeated payment callbacks can't double-award.
+    pub async fn earn(&self, entry: NewLoyaltyEntry) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let inserted = diesel::insert_into(loyalty_entries::table)
+            .values(&entry)
+            .on_conflict_do_nothing()
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(inserted > 0)
+    }
+
+    /// Adds a credit, or takes points away starting with the oldest credits
+    pub async fn adjust(&self, entry: NewLoyaltyEntry) -> Result<LoyaltyEntryModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            let mut entry = entry;
+            if entry.points < 0 {
+                entry.expires_at = consume(conn, entry.user_id, -entry.points)?;
+                entry.remaining = 0;
+            }
+            Ok(diesel::insert_into(loyalty_entries::table)
+                .values(&entry)
+                .returning(LoyaltyEntryModel::as_returning())
+                .get_result::<LoyaltyEntryModel>(conn)?)
+        })
+        .map_err(String::from)
+    }
+
+    /// Spends points on an open order: adds the discount line, lowers the
+    /// order total and debits the ledger in one go
+    pub async fn redeem(&self, redemption: NewRedemption) -> Result<LoyaltyEntryModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            let order = service_orders::table
+                .find(redemption.order_id)
+                .for_update()
+                .select(ServiceOrderModel::as_select())
+                .first::<ServiceOrderModel>(conn)
+                .optional()?
+                .ok_or(rejected("Order not found"))?;
+            if order.customer_id != redemption.user_id {
+                return Err(rejected(
+                    "Points can only be used on the customer's own order",
+                ));
+            }
+            if matches!(
+                order.status,
+                ServiceOrderStatusEnum::Paid | ServiceOrderStatusEnum::Cancelled
+            ) {
+                return Err(rejected(
+                    "Points can't be used on a paid or cancelled order",
+                ));
+            }
+
+            let already_redeemed = loyalty_entries::table
+                .filter(loyalty_entries::order_id.eq(redemption.order_id))
+                .filter(loyalty_entries::kind.eq(LoyaltyEntryKindEnum::Redeemed))
+                .filter(loyalty_entries::reversed_at.is_null())
+                .count()
+                .get_result::<i64>(conn)?;
+            if already_redeemed > 0 {
+                return Err(rejected("Points have already been used on this order"));
+            }
+
+            let discount =
+                BigDecimal::from_f64(redemption.discount).ok_or(rejected("Invalid discount"))?;
+            if discount > order.total_price {
+                return Err(rejected("The discount can't be more than the order total"));
+            }
+
+            let expires_at = consume(conn, redemption.user_id, redemption.points)?;
+
+            let item_id = diesel::insert_into(service_items::table)
+                .values(&NewServiceItem {
+                    order_id: redemption.order_id,
+                    description: redemption.description,
+                    price: -discount.clone(),
+                    stock_item_id: None,
+                    quantity: 1,
+                    unit_cost: None,
+                    stock_status: None,
+                })
+                .returning(service_items::item_id)
+                .get_result::<i32>(conn)?;
+            diesel::update(service_orders::table.find(redemption.order_id))
+                .set(service_orders::total_price.eq(order.total_price - discount))
+                .execute(conn)?;
+
+            Ok(diesel::insert_into(loyalty_entries::table)
+                .values(&NewLoyaltyEntry {
+                    user_id: redemption.user_id,
+                    kind: LoyaltyEntryKindEnum::Redeemed,
+                    points: -redemption.points,
+                    remaining: 0,
+                    spend: None,
+                    order_id: Some(redemption.order_id),
+                    service_item_id: Some(item_id),
+                    reason: None,
+                    created_by: None,
+                    expires_at,
+                })
+                .returning(LoyaltyEntryModel::as_returning())
+                .get_result::<LoyaltyEntryModel>(conn)?)
+        })
+        .map_err(String::from)
+    }
+
+    /// Gives back points spent on a cancelled order and takes back what it
+    /// earned, since an admin can cancel an order after it was paid.
+    /// Returns how many points were returned.
+    pub async fn refund_order(&self, order_id: i32) -> Result<i32, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let refunded = refund_redemptions(conn, RedemptionScope::Order(order_id))?;
+            claw_back_earned(
+                conn,
+                order_id,
+                &format!("Order #SO-{} was cancelled", order_id),
+            )?;
+            Ok(refunded)
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    /// Writes off every credit past its expiry. Returns the points expired.
+    pub async fn expire_due(&self, now: DateTime<Utc>) -> Result<i64, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let due = loyalty_entries::table
+                .filter(loyalty_entries::remaining.gt(0))
+                .filter(loyalty_entries::expires_at.le(now))
+                .select(LoyaltyEntryModel::as_select())
+                .for_update()
+                .load::<LoyaltyEntryModel>(conn)?;
+
+            let mut expired = 0i64;
+            for credit in due {
+                diesel::update(loyalty_entries::table.find(credit.entry_id))
+                    .set(loyalty_entries::remaining.eq(0))
+                    .execute(conn)?;
+                diesel::insert_into(loyalty_entries::table)
+                    .values(&NewLoyaltyEntry {
+                        user_id: credit.user_id,
+                        kind: LoyaltyEntryKindEnum::Expired,
+                        points: -credit.remaining,
+                        remaining: 0,
+                        spend: None,
+                        order_id: None,
+                        service_item_id: None,
+                        reason: None,
+                        created_by: None,
+                        expires_at: credit.expires_at,
+                    })
+                    .execute(conn)?;
+                expired += i64::from(credit.remaining);
+            }
+            Ok(expired)
+        })
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// Takes points from the user's oldest live credits, all or nothing.
+/// Returns the latest expiry among the credits used, which a refund of these
+/// points inherits.
+fn consume(
+    conn: &mut PgConnection,
+    user_id: i32,
+    points: i32,
+) -> Result<Option<DateTime<Utc>>, TransactionError> {
+    let (taken, latest_expiry) = take_credits(conn, user_id, points)?;
+    if taken < points {
+        // Rolls back whatever was taken along with the transaction
+        return Err(rejected("Not enough points"));
+    }
+    Ok(latest_expiry)
+}
+
+/// Takes up to `points` from the user's oldest live credits. Returns how many
+/// were taken and the latest expiry among the credits used.
+fn take_credits(
+    conn: &mut PgConnection,
+    user_id: i32,
+    points: i32,
+) -> QueryResult<(i32, Option<DateTime<Utc>>)> {
+    let credits = loyalty_entries::table
+        .filter(loyalty_entries::user_id.eq(user_id))
+        .filter(loyalty_entries::remaining.gt(0))
+        .filter(loyalty_entries::expires_at.gt(Utc::now()))
+        .order((
+            loyalty_entries::expires_at.asc(),
+            loyalty_entries::entry_id.asc(),
+        ))
+        .select((
+            loyalty_entries::entry_id,
+            loyalty_entries::remaining,
+            loyalty_entries::expires_at,
+        ))
+        .for_update()
+        .load::<(i32, i32, Option<DateTime<Utc>>)>(conn)?;
+
+    let mut left = points;
+    let mut latest_expiry = None;
+    for (entry_id, remaining, expires_at) in credits {
+        if left == 0 {
+            break;
+        }
+        let used = remaining.min(left);
+        diesel::update(loyalty_entries::table.find(entry_id))
+            .set(loyalty_entries::remaining.eq(remaining - used))
+            .execute(conn)?;
+        left -= used;
+        latest_expiry = expires_at;
+    }
+    Ok((points - left, latest_expiry))
+}
+
+/// Takes back what an order earned once it no longer counts as paid: the
+/// unspent part of its credit, then as much of the spent part as the
+/// customer's other live credits cover. A credit that has lapsed is left
+/// alone. Returns how many points were taken back.
+pub fn claw_back_earned(conn: &mut PgConnection, order_id: i32, reason: &str) -> QueryResult<i32> {
+    let now = Utc::now();
+    let Some(earned) = loyalty_entries::table
+        .filter(loyalty_entries::order_id.eq(order_id))
+        .filter(loyalty_entries::kind.eq(LoyaltyEntryKindEnum::Earned))
+        .filter(loyalty_entries::reversed_at.is_null())
+        .select(LoyaltyEntryModel::as_select())
+        .for_update()
+        .first::<LoyaltyEntryModel>(conn)
+        .optional()?
+    else {
+        return Ok(0);
+    };
+
+    diesel::update(loyalty_entries::table.find(earned.entry_id))
+        .set((
+            loyalty_entries::remaining.eq(0),
+            loyalty_entries::reversed_at.eq(Some(now)),
+        ))
+        .execute(conn)?;
+    if earned
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= now)
+    {
+        return Ok(0);
+    }
+
+    let (from_others, _) = take_credits(conn, earned.user_id, earned.points - earned.remaining)?;
+    let clawed_back = earned.remaining + from_others;
+    if clawed_back > 0 {
+        diesel::insert_into(loyalty_entries::table)
+            .values(&NewLoyaltyEntry {
+                user_id: earned.user_id,
+                kind: LoyaltyEntryKindEnum::Adjusted,
+                points: -clawed_back,
+                remaining: 0,
+                spend: None,
+                order_id: Some(order_id),
+                service_item_id: None,
+                reason: Some(reason.to_string()),
+                created_by: None,
+                expires_at: earned.expires_at,
+            })
+            .execute(conn)?;
+    }
+    Ok(clawed_back)
+}
+
+/// Which redemptions to give back
+pub enum RedemptionScope {
+    Order(i32),
+    ServiceItem(i32),
+}
+
+/// Reverses live redemptions in scope, crediting the points back with the
+/// expiry they had. Used when an order or its discount line goes away.
+pub fn refund_redemptions(conn: &mut PgConnection, scope: RedemptionScope) -> QueryResult<i32> {
+    let live = loyalty_entries::table
+        .filter(loyalty_entries::kind.eq(LoyaltyEntryKindEnum::Redeemed))
+        .filter(loyalty_entries::reversed_at.is_null());
+    let redemptions = match scope {
+        RedemptionScope::Order(order_id) => live
+            .filter(loyalty_entries::order_id.eq(order_id))
+            .select(LoyaltyEntryModel::as_select())
+            .for_update()
+            .load::<LoyaltyEntryModel>(conn)?,
+        RedemptionScope::ServiceItem(item_id) => live
+            .filter(loyalty_entries::service_item_id.eq(item_id))
+            .select(LoyaltyEntryModel::as_select())
+            .for_update()
+            .load::<LoyaltyEntryModel>(conn)?,
+    };
+
+    let mut refunded = 0;
+    for redemption in redemptions {
+        diesel::update(loyalty_entries::table.find(redemption.entry_id))
+            .set(loyalty_entries::reversed_at.eq(Some(Utc::now())))
+            .execute(conn)?;
+        diesel::insert_into(loyalty_entries::table)
+            .values(&NewLoyaltyEntry {
+                user_id: redemption.user_id,
+                kind: LoyaltyEntryKindEnum::Refunded,
+                points: -redemption.points,
+                remaining: -redemption.points,
+                spend: None,
+                order_id: redemption.order_id,
+                service_item_id: None,
+                reason: None,
+                created_by: None,
+                expires_at: redemption.expires_at,
+            })
+            .execute(conn)?;
+        refunded -= redemption.points;
+    }
+    Ok(refunded)
+}
@@ -0,0 +1,268 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::models::{
+    MaintenanceReminderModel, MaintenanceReminderStatusEnum, MaintenanceRuleChangeset,
+    MaintenanceRuleModel, MotorcycleModel, NewMaintenanceReminder, ServiceOrderStatusEnum,
+};
+use crate::infrastructure::db::repositories::motorcycle::CLOSED_ORDER_STATUSES;
+use crate::infrastructure::db::schema::{
+    maintenance_reminders, maintenance_rules, motorcycles, service_items, service_orders, users,
+};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::Serialize;
+
+/// One line item of a finished order, used to work out when a service was last done
+#[derive(Debug, Clone)]
+pub struct ServiceRecord {
+    pub bike_id: i32,
+    pub performed_at: DateTime<Utc>,
+    pub odometer_km: Option<i32>,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MaintenanceReminderView {
+    pub reminder_id: i32,
+    pub rule_name: String,
+    pub bike_id: i32,
+    pub brand: String,
+    pub model: String,
+    pub license_plate: String,
+    pub status: MaintenanceReminderStatusEnum,
+    pub sent_at: DateTime<Utc>,
+    pub snoozed_until: Option<DateTime<Utc>>,
+    pub converted_order_id: Option<i32>,
+}
+
+#[derive(Clone)]
+pub struct MaintenanceRepository {
+    pool: DbPool,
+}
+
+impl MaintenanceRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_rules(&self) -> Result<Vec<MaintenanceRuleModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        maintenance_rules::table
+            .order(maintenance_rules::rule_id.asc())
+            .load::<MaintenanceRuleModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn find_rule(&self, rule_id: i32) -> Result<Option<MaintenanceRuleModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        maintenance_rules::table
+            .find(rule_id)
+            .first::<MaintenanceRuleModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn create_rule(
+        &self,
+        rule: MaintenanceRuleChangeset,
+    ) -> Result<MaintenanceRuleModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::insert_into(maintenance_rules::table)
+            .values(&rule)
+            .get_result(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn update_rule(
+        &self,
+        rule_id: i32,
+        rule: MaintenanceRuleChangeset,
+    ) -> Result<MaintenanceRuleModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::update(maintenance_rules::table.find(rule_id))
+            .set(&rule)
+            .get_result(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Bikes that could be due for a reminder: still in an active customer's
+    /// garage and not currently in the workshop
+    pub async fn find_reminder_candidates(&self) -> Result<Vec<MotorcycleModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let in_workshop = service_orders::table
+            .filter(service_orders::bike_id.is_not_null())
+            .filter(service_orders::status.ne_all(CLOSED_ORDER_STATUSES))
+            .select(service_orders::bike_id.assume_not_null());
+
+        motorcycles::table
+            .inner_join(users::table)
+            .filter(users::is_active.eq(true))
+            .filter(motorcycles::archived_at.is_null())
+            .filter(motorcycles::bike_id.ne_all(in_workshop))
+            .select(MotorcycleModel::as_select())
+            .load::<MotorcycleModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Line items of completed or paid orders, oldest first
+    pub async fn find_service_records(&self) -> Result<Vec<ServiceRecord>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let rows = service_items::table
+            .inner_join(service_orders::table)
+            .filter(service_orders::status.eq_any([
+                ServiceOrderStatusEnum::Completed,
+                ServiceOrderStatusEnum::Paid,
+            ]))
+            .filter(service_orders::bike_id.is_not_null())
+            .order(service_orders::created_at.asc())
+            .select((
+                service_orders::bike_id.assume_not_null(),
+                service_orders::created_at,
+                service_orders::odometer_km,
+                service_items::description,
+            ))
+            .load::<(i32, DateTime<Utc>, Option<i32>, String)>(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(bike_id, performed_at, odometer_km, description)| ServiceRecord {
+                    bike_id,
+                    performed_at,
+                    odometer_km,
+                    description,
+                },
+            )
+            .collect())
+    }
+
+    /// Reminders still waiting on the customer (sent or snoozed)
+    pub async fn find_open_reminders(&self) -> Result<Vec<MaintenanceReminderModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        maintenance_reminders::table
+            .filter(maintenance_reminders::status.ne(MaintenanceReminderStatusEnum::Converted))
+            .load::<MaintenanceReminderModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn create_reminder(
+        &self,
+        reminder: NewMaintenanceReminder,
+    ) -> Result<MaintenanceReminderModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::insert_into(maintenance_reminders::table)
+            .values(&reminder)
+            .get_result(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Puts an open reminder back to "sent" after a snooze runs out or it went unanswered
+    pub async fn mark_resent(&self, reminder_id: i32) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::update(maintenance_reminders::table.find(reminder_id))
+            .set((
+                maintenance_reminders::status.eq(MaintenanceReminderStatusEnum::Sent),
+                maintenance_reminders::sent_at.eq(Utc::now()),
+                maintenance_reminders::snoozed_until.eq(None::<DateTime<Utc>>),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub async fn list_for_user(
+        &self,
+        user_id: i32,
+    ) -> Result<Vec<MaintenanceReminderView>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let rows = maintenance_reminders::table
+            .inner_join(maintenance_rules::table)
+            .inner_join(motorcycles::table)
+            .filter(maintenance_reminders::user_id.eq(user_id))
+            .order(maintenance_reminders::sent_at.desc())
+            .select((
+                MaintenanceReminderModel::as_select(),
+                maintenance_rules::name,
+                motorcycles::brand,
+                motorcycles::model,
+                motorcycles::license_plate,
+            ))
+            .load::<(MaintenanceReminderModel, String, String, String, String)>(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(reminder, rule_name, brand, model, license_plate)| MaintenanceReminderView {
+                    reminder_id: reminder.reminder_id,
+                    rule_name,
+                    bike_id: reminder.bike_id,
+                    brand,
+                    model,
+                    license_plate,
+                    status: reminder.status,
+                    sent_at: reminder.sent_at,
+                    snoozed_until: reminder.snoozed_until,
+                    converted_order_id: reminder.converted_order_id,
+                },
+            )
+            .collect())
+    }
+
+    /// Snoozes one of the user's open reminders. Returns false if there is no such reminder.
+    pub async fn snooze(
+        &self,
+        reminder_id: i32,
+        user_id: i32,
+        until: DateTime<Utc>,
+    ) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let updated = diesel::update(
+            maintenance_reminders::table
+                .find(reminder_id)
+                .filter(maintenance_reminders::user_id.eq(user_id))
+                .filter(maintenance_reminders::status.ne(MaintenanceReminderStatusEnum::Converted)),
+        )
+        .set((
+            maintenance_reminders::status.eq(MaintenanceReminderStatusEnum::Snoozed),
+            maintenance_reminders::snoozed_until.eq(Some(until)),
+        ))
+        .execute(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+        Ok(updated > 0)
+    }
+
+    /// Booking a bike closes every reminder that was open for it
+    pub async fn convert_open_for_bike(
+        &self,
+        bike_id: i32,
+        order_id: i32,
+    ) -> Result<usize, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::update(
+            maintenance_reminders::table
+                .filter(maintenance_reminders::bike_id.eq(bike_id))
+                .filter(maintenance_reminders::status.ne(MaintenanceReminderStatusEnum::Converted)),
+        )
+        .set((
+            maintenance_reminders::status.eq(MaintenanceReminderStatusEnum::Converted),
+            maintenance_reminders::converted_order_id.eq(Some(order_id)),
+        ))
+        .execute(&mut conn)
+        .map_err(|e| e.to_string())
+    }
+}
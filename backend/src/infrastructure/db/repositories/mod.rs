@@ -1,11 +1,32 @@
+pub mod audit_event;
+pub mod branch;
+pub mod erasure_request;
 pub mod feedback;
+pub mod guest_customer;
 pub mod inventory;
+pub mod loyalty;
+pub mod maintenance;
 pub mod motorcycle;
+pub mod motorcycle_transfer;
 pub mod notification;
+pub mod part_compatibility;
+pub mod personal_data;
+pub mod phone_verification;
+pub mod purchase_order;
+pub mod quotation;
 pub mod refresh_token;
 pub mod repair_log;
+pub mod scheduled_job;
+pub mod search;
 pub mod service_item;
 pub mod service_order;
 pub mod stock;
+pub mod stock_movement;
+pub mod stock_take;
+pub mod stock_transfer;
+pub mod supplier;
+#[cfg(test)]
+pub mod test_support;
 pub mod user;
 pub mod user_line_account;
+pub mod user_mfa;
@@ -1,8 +1,27 @@
+use crate::domain::value_objects::LicensePlate;
 use crate::infrastructure::db::connection::DbPool;
-use crate::infrastructure::db::models::MotorcycleModel;
-use crate::infrastructure::db::schema::motorcycles;
+use crate::infrastructure::db::models::{
+    MotorcycleChangeset, MotorcycleModel, MotorcycleTransferStatusEnum, NewMotorcycle,
+    ServiceOrderStatusEnum,
+};
+use crate::infrastructure::db::schema::{
+    maintenance_reminders, motorcycle_transfers, motorcycles, service_orders,
+};
 use diesel::prelude::*;
 
+/// Orders in these states no longer need the bike in the workshop
+pub const CLOSED_ORDER_STATUSES: [ServiceOrderStatusEnum; 2] = [
+    ServiceOrderStatusEnum::Paid,
+    ServiceOrderStatusEnum::Cancelled,
+];
+
+/// What happened to a bike removed from a customer's garage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MotorcycleRemoval {
+    Deleted,
+    Archived,
+}
+
 #[derive(Clone)]
 pub struct MotorcycleRepository {
     pool: DbPool,
@@ -15,44 +34,34 @@ impl MotorcycleRepository {
 
     pub async fn create_motorcycle(
         &self,
-        brand: String,
-        model: String,
-        license_plate: String,
-        user_id: i32,
+        new_bike: NewMotorcycle,
     ) -> Result<MotorcycleModel, String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
 
-        // Simplified insert without a dedicated "New" struct if possible,
-        // but Diesel usually requires one or a tuple.
-        // Let's use a tuple for simplicity or just define a local struct.
-
-        #[derive(Insertable)]
-        #[diesel(table_name = motorcycles)]
-        struct NewBike {
-            brand: String,
-            model: String,
-            license_plate: String,
-            user_id: i32,
-        }
-
-        let new_bike = NewBike {
-            brand,
-            model,
-            license_plate,
-            user_id,
-        };
-
         diesel::insert_into(motorcycles::table)
             .values(&new_bike)
             .get_result(&mut conn)
             .map_err(|e| e.to_string())
     }
 
+    pub async fn find_by_id(&self, bike_id: i32) -> Result<Option<MotorcycleModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        motorcycles::table
+            .find(bike_id)
+            .first::<MotorcycleModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Bikes currently in the user's garage (archived bikes are hidden)
     pub async fn find_by_user(&self, user_id: i32) -> Result<Vec<MotorcycleModel>, String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
 
         motorcycles::table
             .filter(motorcycles::user_id.eq(user_id))
+            .filter(motorcycles::archived_at.is_null())
+            .order(motorcycles::bike_id.asc())
             .load::<MotorcycleModel>(&mut conn)
             .map_err(|e| e.to_string())
     }
@@ -65,18 +74,156 @@ impl MotorcycleRepository {
             .map_err(|e| e.to_string())
     }
 
+    /// Matches on prefix and number regardless of how the plate was typed.
+    /// The province only has to agree when both sides know it.
     pub async fn find_by_license_and_user(
         &self,
-        license: &str,
+        plate: &LicensePlate,
         user_id_val: i32,
     ) -> Result<Option<MotorcycleModel>, String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
 
-        motorcycles::table
-            .filter(motorcycles::license_plate.eq(license))
+        // Plates the backfill couldn't key are matched as typed
+        let typed = vec![
+            plate.value(),
+            format!("{} {}", plate.prefix(), plate.number()),
+            plate.key(),
+        ];
+        let mut query = motorcycles::table
+            .filter(
+                motorcycles::plate_key
+                    .eq(plate.key())
+                    .or(motorcycles::plate_key
+                        .is_null()
+                        .and(motorcycles::license_plate.eq_any(typed))),
+            )
             .filter(motorcycles::user_id.eq(user_id_val))
+            .filter(motorcycles::archived_at.is_null())
+            .into_boxed();
+        if let Some(province) = plate.province() {
+            query = query.filter(
+                motorcycles::plate_province
+                    .is_null()
+                    .or(motorcycles::plate_province.eq(province)),
+            );
+        }
+
+        query
+            .order(motorcycles::bike_id.asc())
+            .first::<MotorcycleModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn find_by_vin(&self, vin: &str) -> Result<Option<MotorcycleModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        motorcycles::table
+            .filter(motorcycles::vin.eq(vin))
             .first::<MotorcycleModel>(&mut conn)
             .optional()
             .map_err(|e| e.to_string())
     }
+
+    pub async fn update_motorcycle(
+        &self,
+        bike_id: i32,
+        changes: MotorcycleChangeset,
+    ) -> Result<MotorcycleModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::update(motorcycles::table.find(bike_id))
+            .set(&changes)
+            .get_result(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Raises the bike's mileage to a new odometer reading; lower readings are ignored
+    pub async fn record_mileage(&self, bike_id: i32, odometer_km: i32) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::update(
+            motorcycles::table
+                .find(bike_id)
+                .filter(motorcycles::mileage.lt(odometer_km)),
+        )
+        .set(motorcycles::mileage.eq(odometer_km))
+        .execute(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    pub async fn has_open_orders(&self, bike_id: i32) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::select(diesel::dsl::exists(
+            service_orders::table
+                .filter(service_orders::bike_id.eq(bike_id))
+                .filter(service_orders::status.ne_all(CLOSED_ORDER_STATUSES)),
+        ))
+        .get_result(&mut conn)
+        .map_err(|e| e.to_string())
+    }
+
+    /// Removes a bike from its owner's garage. Bikes with open orders are
+    /// refused; bikes with only closed orders are archived so that those
+    /// orders keep pointing at a real bike.
+    pub async fn remove_motorcycle(&self, bike_id: i32) -> Result<MotorcycleRemoval, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            motorcycles::table
+                .find(bike_id)
+                .select(motorcycles::bike_id)
+                .for_update()
+                .first::<i32>(conn)?;
+
+            let statuses = service_orders::table
+                .filter(service_orders::bike_id.eq(bike_id))
+                .select(service_orders::status)
+                .load::<ServiceOrderStatusEnum>(conn)?;
+
+            if statuses.iter().any(|s| !CLOSED_ORDER_STATUSES.contains(s)) {
+                return Err(diesel::result::Error::RollbackTransaction);
+            }
+
+            // A pending hand-over makes no sense once the bike is gone
+            diesel::update(
+                motorcycle_transfers::table
+                    .filter(motorcycle_transfers::bike_id.eq(bike_id))
+                    .filter(motorcycle_transfers::status.eq(MotorcycleTransferStatusEnum::Pending)),
+            )
+            .set((
+                motorcycle_transfers::status.eq(MotorcycleTransferStatusEnum::Cancelled),
+                motorcycle_transfers::responded_at.eq(Some(chrono::Utc::now())),
+            ))
+            .execute(conn)?;
+
+            if statuses.is_empty() {
+                diesel::delete(
+                    maintenance_reminders::table.filter(maintenance_reminders::bike_id.eq(bike_id)),
+                )
+                .execute(conn)?;
+                diesel::delete(
+                    motorcycle_transfers::table.filter(motorcycle_transfers::bike_id.eq(bike_id)),
+                )
+                .execute(conn)?;
+                diesel::delete(motorcycles::table.find(bike_id)).execute(conn)?;
+                Ok(MotorcycleRemoval::Deleted)
+            } else {
+                diesel::update(motorcycles::table.find(bike_id))
+                    .set(motorcycles::archived_at.eq(Some(chrono::Utc::now())))
+                    .execute(conn)?;
+                Ok(MotorcycleRemoval::Archived)
+            }
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::RollbackTransaction => {
+                "This motorcycle has open service orders and cannot be removed".to_string()
+            }
+            diesel::result::Error::NotFound => "Motorcycle not found".to_string(),
+            other => other.to_string(),
+        })
+    }
 }
@@ -0,0 +1,178 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::models::{
+    MaintenanceReminderStatusEnum, MotorcycleTransferModel, MotorcycleTransferStatusEnum,
+    NewMotorcycleTransfer,
+};
+use crate::infrastructure::db::schema::{maintenance_reminders, motorcycle_transfers, motorcycles};
+use chrono::Utc;
+use diesel::prelude::*;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct MotorcycleTransferView {
+    #[serde(flatten)]
+    pub transfer: MotorcycleTransferModel,
+    pub brand: String,
+    pub model: String,
+    pub license_plate: String,
+}
+
+#[derive(Clone)]
+pub struct MotorcycleTransferRepository {
+    pool: DbPool,
+}
+
+impl MotorcycleTransferRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        transfer: NewMotorcycleTransfer,
+    ) -> Result<MotorcycleTransferModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::insert_into(motorcycle_transfers::table)
+            .values(&transfer)
+            .get_result(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn find_by_id(
+        &self,
+        transfer_id: i32,
+    ) -> Result<Option<MotorcycleTransferModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        motorcycle_transfers::table
+            .find(transfer_id)
+            .first::<MotorcycleTransferModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn find_pending_for_bike(
+        &self,
+        bike_id: i32,
+    ) -> Result<Option<MotorcycleTransferModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        motorcycle_transfers::table
+            .filter(motorcycle_transfers::bike_id.eq(bike_id))
+            .filter(motorcycle_transfers::status.eq(MotorcycleTransferStatusEnum::Pending))
+            .first::<MotorcycleTransferModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Transfers the user is sending or receiving, newest first
+    pub async fn list_for_user(&self, user_id: i32) -> Result<Vec<MotorcycleTransferView>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let rows = motorcycle_transfers::table
+            .inner_join(motorcycles::table)
+            .filter(
+                motorcycle_transfers::from_user_id
+                    .eq(user_id)
+                    .or(motorcycle_transfers::to_user_id.eq(user_id)),
+            )
+            .order(motorcycle_transfers::created_at.desc())
+            .select((
+                MotorcycleTransferModel::as_select(),
+                motorcycles::brand,
+                motorcycles::model,
+                motorcycles::license_plate,
+            ))
+            .load::<(MotorcycleTransferModel, String, String, String)>(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(transfer, brand, model, license_plate)| MotorcycleTransferView {
+                    transfer,
+                    brand,
+                    model,
+                    license_plate,
+                },
+            )
+            .collect())
+    }
+
+    /// Closes a pending transfer without moving the bike. Returns false if it
+    /// was no longer pending.
+    pub async fn close(
+        &self,
+        transfer_id: i32,
+        status: MotorcycleTransferStatusEnum,
+    ) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let updated = diesel::update(
+            motorcycle_transfers::table
+                .find(transfer_id)
+                .filter(motorcycle_transfers::status.eq(MotorcycleTransferStatusEnum::Pending)),
+        )
+        .set((
+            motorcycle_transfers::status.eq(status),
+            motorcycle_transfers::responded_at.eq(Some(Utc::now())),
+        ))
+        .execute(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+        Ok(updated > 0)
+    }
+
+    /// Moves the bike to its new owner. Fails if the transfer is no longer
+    /// pending or the bike changed hands in the meantime. Reminders addressed
+    /// to the previous owner are dropped.
+    pub async fn accept(&self, transfer: &MotorcycleTransferModel) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let closed = diesel::update(
+                motorcycle_transfers::table
+                    .find(transfer.transfer_id)
+                    .filter(motorcycle_transfers::status.eq(MotorcycleTransferStatusEnum::Pending)),
+            )
+            .set((
+                motorcycle_transfers::status.eq(MotorcycleTransferStatusEnum::Accepted),
+                motorcycle_transfers::responded_at.eq(Some(Utc::now())),
+            ))
+            .execute(conn)?;
+
+            let moved = diesel::update(
+                motorcycles::table
+                    .find(transfer.bike_id)
+                    .filter(motorcycles::user_id.eq(transfer.from_user_id)),
+            )
+            .set((
+                motorcycles::user_id.eq(transfer.to_user_id),
+                motorcycles::archived_at.eq(None::<chrono::DateTime<Utc>>),
+            ))
+            .execute(conn)?;
+
+            if closed == 0 || moved == 0 {
+                return Err(diesel::result::Error::RollbackTransaction);
+            }
+
+            diesel::delete(
+                maintenance_reminders::table
+                    .filter(maintenance_reminders::bike_id.eq(transfer.bike_id))
+                    .filter(
+                        maintenance_reminders::status.ne(MaintenanceReminderStatusEnum::Converted),
+                    ),
+            )
+            .execute(conn)?;
+
+            Ok(())
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::RollbackTransaction => {
+                "This transfer is no longer valid".to_string()
+            }
+            other => other.to_string(),
+        })
+    }
+}
@@ -0,0 +1,90 @@
+use crate::domain::service::CompatibilityTag;
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::functions::lower;
+use crate::infrastructure::db::models::{NewStockItemCompatibility, StockItemCompatibilityModel};
+use crate::infrastructure::db::schema::stock_item_compatibilities;
+use diesel::prelude::*;
+
+fn to_tag(model: StockItemCompatibilityModel) -> CompatibilityTag {
+    CompatibilityTag {
+        brand: model.brand,
+        model: model.model,
+        year_from: model.year_from,
+        year_to: model.year_to,
+    }
+}
+
+#[derive(Clone)]
+pub struct PartCompatibilityRepository {
+    pool: DbPool,
+}
+
+impl PartCompatibilityRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_for_item(&self, stock_item_id: i32) -> Result<Vec<CompatibilityTag>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        Ok(stock_item_compatibilities::table
+            .filter(stock_item_compatibilities::stock_item_id.eq(stock_item_id))
+            .order(stock_item_compatibilities::compatibility_id.asc())
+            .select(StockItemCompatibilityModel::as_select())
+            .load::<StockItemCompatibilityModel>(&mut conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(to_tag)
+            .collect())
+    }
+
+    /// Every tag naming the brand, with the part it belongs to
+    pub async fn list_for_brand(
+        &self,
+        brand: &str,
+    ) -> Result<Vec<(i32, CompatibilityTag)>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        Ok(stock_item_compatibilities::table
+            .filter(lower(stock_item_compatibilities::brand).eq(brand.trim().to_lowercase()))
+            .order(stock_item_compatibilities::compatibility_id.asc())
+            .select(StockItemCompatibilityModel::as_select())
+            .load::<StockItemCompatibilityModel>(&mut conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|model| (model.stock_item_id, to_tag(model)))
+            .collect())
+    }
+
+    /// Replaces the part's tags with the given ones
+    pub async fn replace(
+        &self,
+        stock_item_id: i32,
+        tags: &[CompatibilityTag],
+    ) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::delete(
+                stock_item_compatibilities::table
+                    .filter(stock_item_compatibilities::stock_item_id.eq(stock_item_id)),
+            )
+            .execute(conn)?;
+            let rows: Vec<NewStockItemCompatibility> = tags
+                .iter()
+                .map(|tag| NewStockItemCompatibility {
+                    stock_item_id,
+                    brand: tag.brand.clone(),
+                    model: tag.model.clone(),
+                    year_from: tag.year_from,
+                    year_to: tag.year_to,
+                })
+                .collect();
+            diesel::insert_into(stock_item_compatibilities::table)
+                .values(&rows)
+                .execute(conn)?;
+            Ok(())
+        })
+        .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,305 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::models::{
+    ErasureRequestStatusEnum, FeedbackModel, LoyaltyEntryModel, MaintenanceReminderModel,
+    MotorcycleModel, MotorcycleTransferModel, NotificationModel, PhoneVerificationModel,
+    QuotationLineModel, QuotationModel, RefreshTokenModel, ServiceItemModel, ServiceOrderModel,
+    UserLineAccountModel, UserModel,
+};
+use crate::infrastructure::db::schema::{
+    erasure_requests, feedbacks, loyalty_entries, maintenance_reminders, mfa_recovery_codes,
+    motorcycle_transfers, motorcycles, notifications, phone_verifications, quotation_lines,
+    quotations, refresh_tokens, service_items, service_orders, user_line_accounts, user_mfa, users,
+};
+use chrono::Utc;
+use diesel::prelude::*;
+
+/// Everything stored about one person, across all tables
+pub struct PersonalDataRecords {
+    pub user: UserModel,
+    pub line_account: Option<UserLineAccountModel>,
+    pub motorcycles: Vec<MotorcycleModel>,
+    pub service_orders: Vec<ServiceOrderModel>,
+    pub service_items: Vec<ServiceItemModel>,
+    pub feedbacks: Vec<FeedbackModel>,
+    pub notifications: Vec<NotificationModel>,
+    pub refresh_tokens: Vec<RefreshTokenModel>,
+    pub maintenance_reminders: Vec<MaintenanceReminderModel>,
+    pub motorcycle_transfers: Vec<MotorcycleTransferModel>,
+    /// Guest profiles that were merged into this account
+    pub merged_guests: Vec<UserModel>,
+    pub phone_verifications: Vec<PhoneVerificationModel>,
+    pub loyalty_entries: Vec<LoyaltyEntryModel>,
+    pub quotations: Vec<QuotationModel>,
+    pub quotation_lines: Vec<QuotationLineModel>,
+}
+
+// Placeholder values written over personal fields on erasure
+pub const ERASED_NAME: &str = "Erased User";
+pub const ERASED_LICENSE_PLATE: &str = "ERASED";
+
+#[derive(Clone)]
+pub struct PersonalDataRepository {
+    pool: DbPool,
+}
+
+impl PersonalDataRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn collect(&self, user_id: i32) -> Result<Option<PersonalDataRecords>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let Some(user) = users::table
+            .find(user_id)
+            .select(UserModel::as_select())
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())?
+        else {
+            return Ok(None);
+        };
+
+        let line_account = user_line_accounts::table
+            .filter(user_line_accounts::user_id.eq(user_id))
+            .select(UserLineAccountModel::as_select())
+            .first(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())?;
+
+        let motorcycles = motorcycles::table
+            .filter(motorcycles::user_id.eq(user_id))
+            .select(MotorcycleModel::as_select())
+            .load(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let service_orders = service_orders::table
+            .filter(service_orders::customer_id.eq(user_id))
+            .select(ServiceOrderModel::as_select())
+            .order(service_orders::created_at.asc())
+            .load(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let order_ids: Vec<i32> = service_orders.iter().map(|o| o.order_id).collect();
+        let service_items = service_items::table
+            .filter(service_items::order_id.eq_any(&order_ids))
+            .select(ServiceItemModel::as_select())
+            .load(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let feedbacks = feedbacks::table
+            .filter(feedbacks::user_id.eq(user_id))
+            .select(FeedbackModel::as_select())
+            .load(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let notifications = notifications::table
+            .filter(notifications::user_id.eq(user_id))
+            .select(NotificationModel::as_select())
+            .order(notifications::sent_at.asc())
+            .load(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let refresh_tokens = refresh_tokens::table
+            .filter(refresh_tokens::user_id.eq(user_id))
+            .select(RefreshTokenModel::as_select())
+            .load(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let maintenance_reminders = maintenance_reminders::table
+            .filter(maintenance_reminders::user_id.eq(user_id))
+            .select(MaintenanceReminderModel::as_select())
+            .order(maintenance_reminders::sent_at.asc())
+            .load(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let motorcycle_transfers = motorcycle_transfers::table
+            .filter(
+                motorcycle_transfers::from_user_id
+                    .eq(user_id)
+                    .or(motorcycle_transfers::to_user_id.eq(user_id)),
+            )
+            .select(MotorcycleTransferModel::as_select())
+            .order(motorcycle_transfers::created_at.asc())
+            .load(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let merged_guests = users::table
+            .filter(users::merged_into_user_id.eq(user_id))
+            .select(UserModel::as_select())
+            .load(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let phones: Vec<&str> = std::iter::once(user.phone.as_str())
+            .chain(merged_guests.iter().map(|guest| guest.phone.as_str()))
+            .collect();
+        let phone_verifications = phone_verifications::table
+            .filter(phone_verifications::phone.eq_any(&phones))
+            .select(PhoneVerificationModel::as_select())
+            .order(phone_verifications::created_at.asc())
+            .load(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let loyalty_entries = loyalty_entries::table
+            .filter(loyalty_entries::user_id.eq(user_id))
+            .select(LoyaltyEntryModel::as_select())
+            .order(loyalty_entries::created_at.asc())
+            .load(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let quotations = quotations::table
+            .filter(quotations::order_id.eq_any(&order_ids))
+            .select(QuotationModel::as_select())
+            .order((quotations::order_id.asc(), quotations::version.asc()))
+            .load(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let quotation_ids: Vec<i32> = quotations.iter().map(|q| q.quotation_id).collect();
+        let quotation_lines = quotation_lines::table
+            .filter(quotation_lines::quotation_id.eq_any(&quotation_ids))
+            .select(QuotationLineModel::as_select())
+            .load(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(Some(PersonalDataRecords {
+            user,
+            line_account,
+            motorcycles,
+            service_orders,
+            service_items,
+            feedbacks,
+            notifications,
+            refresh_tokens,
+            maintenance_reminders,
+            motorcycle_transfers,
+            merged_guests,
+            phone_verifications,
+            loyalty_entries,
+            quotations,
+            quotation_lines,
+        }))
+    }
+
+    /// Approves a pending erasure request and irreversibly strips personal
+    /// data from its user, all in one transaction. Orders, items and payments
+    /// stay (with their amounts) because they are needed for tax records; only
+    /// the link back to a real person is removed. Returns false, changing
+    /// nothing, if the request was already reviewed.
+    pub async fn anonymise(
+        &self,
+        request_id: i32,
+        user_id: i32,
+        reviewed_by: i32,
+        review_note: Option<String>,
+    ) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let approved = diesel::update(
+                erasure_requests::table
+                    .find(request_id)
+                    .filter(erasure_requests::status.eq(ErasureRequestStatusEnum::Pending)),
+            )
+            .set((
+                erasure_requests::status.eq(ErasureRequestStatusEnum::Approved),
+                erasure_requests::reviewed_by.eq(Some(reviewed_by)),
+                erasure_requests::reviewed_at.eq(Some(Utc::now())),
+                erasure_requests::review_note.eq(review_note),
+            ))
+            .execute(conn)?;
+            if approved == 0 {
+                return Ok(false);
+            }
+
+            // Guest profiles merged into the account still hold a name and phone
+            let guest_ids: Vec<i32> = users::table
+                .filter(users::merged_into_user_id.eq(user_id))
+                .select(users::user_id)
+                .load(conn)?;
+            let account_ids: Vec<i32> = std::iter::once(user_id).chain(guest_ids).collect();
+            let phones: Vec<String> = users::table
+                .filter(users::user_id.eq_any(&account_ids))
+                .select(users::phone)
+                .load(conn)?;
+            diesel::delete(
+                phone_verifications::table.filter(phone_verifications::phone.eq_any(&phones)),
+            )
+            .execute(conn)?;
+
+            // username/phone are unique, so derive placeholders from the id.
+            // "!" is not a valid argon2 hash, so the account can never log in again.
+            for &account_id in &account_ids {
+                diesel::update(users::table.find(account_id))
+                    .set((
+                        users::username.eq(format!("erased-user-{}", account_id)),
+                        users::name.eq(ERASED_NAME),
+                        users::phone.eq(format!("erased-{}", account_id)),
+                        users::password_hash.eq("!"),
+                        users::is_active.eq(false),
+                        users::deactivated_at.eq(Some(Utc::now())),
+                    ))
+                    .execute(conn)?;
+            }
+
+            diesel::update(motorcycles::table.filter(motorcycles::user_id.eq(user_id)))
+                .set((
+                    motorcycles::license_plate.eq(ERASED_LICENSE_PLATE),
+                    motorcycles::engine_number.eq(None::<String>),
+                    motorcycles::vin.eq(None::<String>),
+                    motorcycles::plate_key.eq(None::<String>),
+                    motorcycles::plate_province.eq(None::<String>),
+                ))
+                .execute(conn)?;
+
+            // Photos can show the plate or the customer
+            diesel::update(service_orders::table.filter(service_orders::customer_id.eq(user_id)))
+                .set((
+                    service_orders::before_picture_url.eq(None::<String>),
+                    service_orders::after_picture_url.eq(None::<String>),
+                ))
+                .execute(conn)?;
+
+            // Quoted amounts stay with their orders; notes are free text
+            let order_ids = service_orders::table
+                .filter(service_orders::customer_id.eq(user_id))
+                .select(service_orders::order_id);
+            diesel::update(quotations::table.filter(quotations::order_id.eq_any(order_ids)))
+                .set(quotations::notes.eq(None::<String>))
+                .execute(conn)?;
+
+            diesel::delete(
+                maintenance_reminders::table.filter(maintenance_reminders::user_id.eq(user_id)),
+            )
+            .execute(conn)?;
+            diesel::delete(
+                motorcycle_transfers::table.filter(
+                    motorcycle_transfers::from_user_id
+                        .eq(user_id)
+                        .or(motorcycle_transfers::to_user_id.eq(user_id)),
+                ),
+            )
+            .execute(conn)?;
+            diesel::delete(loyalty_entries::table.filter(loyalty_entries::user_id.eq(user_id)))
+                .execute(conn)?;
+
+            diesel::delete(feedbacks::table.filter(feedbacks::user_id.eq(user_id)))
+                .execute(conn)?;
+            diesel::delete(notifications::table.filter(notifications::user_id.eq(user_id)))
+                .execute(conn)?;
+            diesel::delete(
+                user_line_accounts::table.filter(user_line_accounts::user_id.eq(user_id)),
+            )
+            .execute(conn)?;
+            diesel::delete(refresh_tokens::table.filter(refresh_tokens::user_id.eq(user_id)))
+                .execute(conn)?;
+            diesel::delete(
+                mfa_recovery_codes::table.filter(mfa_recovery_codes::user_id.eq(user_id)),
+            )
+            .execute(conn)?;
+            diesel::delete(user_mfa::table.filter(user_mfa::user_id.eq(user_id))).execute(conn)?;
+
+            Ok(true)
+        })
+        .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,113 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::models::{NewPhoneVerification, PhoneVerificationModel};
+use crate::infrastructure::db::schema::phone_verifications;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+#[derive(Clone)]
+pub struct PhoneVerificationRepository {
+    pool: DbPool,
+}
+
+impl PhoneVerificationRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, new_verification: NewPhoneVerification) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::insert_into(phone_verifications::table)
+            .values(&new_verification)
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    /// Codes sent to this phone since the given time, newest first
+    pub async fn find_recent(
+        &self,
+        phone: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<PhoneVerificationModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        phone_verifications::table
+            .filter(phone_verifications::phone.eq(phone))
+            .filter(phone_verifications::created_at.ge(since))
+            .order(phone_verifications::created_at.desc())
+            .select(PhoneVerificationModel::as_select())
+            .load::<PhoneVerificationModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    /// How many codes have been sent since the given time, from one address or,
+    /// without one, altogether
+    pub async fn count_since(
+        &self,
+        requested_ip: Option<&str>,
+        since: DateTime<Utc>,
+    ) -> Result<i64, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let mut query = phone_verifications::table
+            .filter(phone_verifications::created_at.ge(since))
+            .into_boxed();
+        if let Some(ip) = requested_ip {
+            query = query.filter(phone_verifications::requested_ip.eq(ip));
+        }
+
+        query
+            .count()
+            .get_result(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    /// The newest code for this phone that can still be used
+    pub async fn find_active(
+        &self,
+        phone: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<PhoneVerificationModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        phone_verifications::table
+            .filter(phone_verifications::phone.eq(phone))
+            .filter(phone_verifications::consumed_at.is_null())
+            .filter(phone_verifications::expires_at.gt(now))
+            .order(phone_verifications::created_at.desc())
+            .select(PhoneVerificationModel::as_select())
+            .first::<PhoneVerificationModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn record_attempt(&self, verification_id: i32) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::update(phone_verifications::table.find(verification_id))
+            .set(phone_verifications::attempts.eq(phone_verifications::attempts + 1))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
+    /// Marks the code used. Returns false if it was already used, so the same
+    /// code can't verify two requests racing each other.
+    pub async fn consume(&self, verification_id: i32) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let updated = diesel::update(
+            phone_verifications::table
+                .find(verification_id)
+                .filter(phone_verifications::consumed_at.is_null()),
+        )
+        .set(phone_verifications::consumed_at.eq(Some(Utc::now())))
+        .execute(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+        Ok(updated > 0)
+    }
+}
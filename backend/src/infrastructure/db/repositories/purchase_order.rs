@@ -0,0 +1,570 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::models::{
+    GoodsReceiptLineModel, GoodsReceiptModel, NewGoodsReceipt, NewGoodsReceiptLine,
+    NewPurchaseOrder, NewPurchaseOrderLine, PurchaseOrderLineModel, PurchaseOrderModel,
+    PurchaseOrderStatusEnum, StockMovementReasonEnum, SupplierModel,
+};
+use crate::infrastructure::db::repositories::stock_movement::{StockChange, apply_stock_change};
+use crate::infrastructure::db::schema::{
+    goods_receipt_lines, goods_receipts, purchase_order_lines, purchase_orders, stock_items,
+    suppliers,
+};
+use crate::infrastructure::db::transaction::{TransactionError, rejected};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::sql_types::{Double, Integer, Nullable, Text};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// POs whose outstanding quantities still count as stock on the way
+pub const OPEN_PO_STATUSES: [PurchaseOrderStatusEnum; 3] = [
+    PurchaseOrderStatusEnum::Draft,
+    PurchaseOrderStatusEnum::Sent,
+    PurchaseOrderStatusEnum::PartiallyReceived,
+];
+
+/// Header fields and lines of a draft PO
+pub struct PurchaseOrderDraft {
+    pub supplier_id: i32,
+    /// Where the goods are delivered and booked in
+    pub branch_id: i32,
+    pub expected_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub lines: Vec<PurchaseOrderLineInput>,
+}
+
+pub struct PurchaseOrderLineInput {
+    pub stock_item_id: i32,
+    pub quantity: i32,
+    pub unit_cost: BigDecimal,
+}
+
+pub struct ReceiptLineInput {
+    pub line_id: i32,
+    pub quantity: i32,
+    /// Falls back to the cost agreed on the PO line
+    pub unit_cost: Option<BigDecimal>,
+}
+
+pub struct GoodsReceiptDetail {
+    pub receipt: GoodsReceiptModel,
+    pub lines: Vec<GoodsReceiptLineModel>,
+}
+
+pub struct PurchaseOrderDetail {
+    pub order: PurchaseOrderModel,
+    pub lines: Vec<PurchaseOrderLineModel>,
+    pub receipts: Vec<GoodsReceiptDetail>,
+}
+
+/// How much of an item to buy so the shop stays covered, going by recent usage
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct ReorderSuggestion {
+    #[diesel(sql_type = Integer)]
+    pub stock_item_id: i32,
+    #[diesel(sql_type = Text)]
+    pub name: String,
+    #[diesel(sql_type = Integer)]
+    pub on_hand: i32,
+    #[diesel(sql_type = Integer)]
+    pub on_order: i32,
+    #[diesel(sql_type = Integer)]
+    pub used_recently: i32,
+    #[diesel(sql_type = Integer)]
+    pub suggested_quantity: i32,
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub supplier_id: Option<i32>,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub supplier_name: Option<String>,
+    #[diesel(sql_type = Nullable<Double>)]
+    pub last_unit_cost: Option<f64>,
+}
+
+/// Checks a set of lines before it replaces what's on a draft PO
+fn validate_lines(
+    conn: &mut PgConnection,
+    lines: &[PurchaseOrderLineInput],
+) -> Result<(), TransactionError> {
+    if lines.is_empty() {
+        return Err(rejected("A purchase order needs at least one line"));
+    }
+    let mut seen = HashSet::new();
+    for line in lines {
+        if line.quantity <= 0 {
+            return Err(rejected("Quantities must be positive"));
+        }
+        if line.unit_cost < BigDecimal::zero() {
+            return Err(rejected("Unit cost cannot be negative"));
+        }
+        if !seen.insert(line.stock_item_id) {
+            return Err(rejected("Each stock item can only appear once per order"));
+        }
+    }
+
+    let ids: Vec<i32> = seen.into_iter().collect();
+    let found: i64 = stock_items::table
+        .filter(stock_items::item_id.eq_any(&ids))
+        .filter(stock_items::archived_at.is_null())
+        .count()
+        .get_result(conn)?;
+    if found != ids.len() as i64 {
+        return Err(rejected("Stock item not found"));
+    }
+    Ok(())
+}
+
+fn find_active_supplier(
+    conn: &mut PgConnection,
+    supplier_id: i32,
+) -> Result<SupplierModel, TransactionError> {
+    let supplier = suppliers::table
+        .find(supplier_id)
+        .select(SupplierModel::as_select())
+        .first::<SupplierModel>(conn)
+        .optional()?
+        .ok_or_else(|| rejected("Supplier not found"))?;
+    if !supplier.is_active {
+        return Err(rejected("Supplier is inactive"));
+    }
+    Ok(supplier)
+}
+
+fn insert_lines(
+    conn: &mut PgConnection,
+    po_id: i32,
+    lines: Vec<PurchaseOrderLineInput>,
+) -> QueryResult<Vec<PurchaseOrderLineModel>> {
+    let rows: Vec<NewPurchaseOrderLine> = lines
+        .into_iter()
+        .map(|line| NewPurchaseOrderLine {
+            po_id,
+            stock_item_id: line.stock_item_id,
+            quantity_ordered: line.quantity,
+            unit_cost: line.unit_cost,
+        })
+        .collect();
+    diesel::insert_into(purchase_order_lines::table)
+        .values(&rows)
+        .returning(PurchaseOrderLineModel::as_returning())
+        .get_results(conn)
+}
+
+fn lock_order(conn: &mut PgConnection, po_id: i32) -> Result<PurchaseOrderModel, TransactionError> {
+    purchase_orders::table
+        .find(po_id)
+        .for_update()
+        .select(PurchaseOrderModel::as_select())
+        .first::<PurchaseOrderModel>(conn)
+        .optional()?
+        .ok_or_else(|| rejected("Purchase order not found"))
+}
+
+#[derive(Clone)]
+pub struct PurchaseOrderRepository {
+    pool: DbPool,
+}
+
+impl PurchaseOrderRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Newest first, across the shop or for the given branches
+    pub async fn list(
+        &self,
+        status: Option<PurchaseOrderStatusEnum>,
+        branch_ids: Option<Vec<i32>>,
+    ) -> Result<Vec<PurchaseOrderModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let mut query = purchase_orders::table
+            .select(PurchaseOrderModel::as_select())
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(purchase_orders::status.eq(status));
+        }
+        if let Some(branch_ids) = branch_ids {
+            query = query.filter(purchase_orders::branch_id.eq_any(branch_ids));
+        }
+        query
+            .order(purchase_orders::po_id.desc())
+            .load::<PurchaseOrderModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn find_detail(&self, po_id: i32) -> Result<Option<PurchaseOrderDetail>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let Some(order) = purchase_orders::table
+            .find(po_id)
+            .select(PurchaseOrderModel::as_select())
+            .first::<PurchaseOrderModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())?
+        else {
+            return Ok(None);
+        };
+        let lines = purchase_order_lines::table
+            .filter(purchase_order_lines::po_id.eq(po_id))
+            .order(purchase_order_lines::line_id.asc())
+            .select(PurchaseOrderLineModel::as_select())
+            .load::<PurchaseOrderLineModel>(&mut conn)
+            .map_err(|e| e.to_string())?;
+        let receipts = goods_receipts::table
+            .filter(goods_receipts::po_id.eq(po_id))
+            .order(goods_receipts::receipt_id.asc())
+            .select(GoodsReceiptModel::as_select())
+            .load::<GoodsReceiptModel>(&mut conn)
+            .map_err(|e| e.to_string())?;
+        let ids: Vec<i32> = receipts.iter().map(|r| r.receipt_id).collect();
+        let mut receipt_lines = goods_receipt_lines::table
+            .filter(goods_receipt_lines::receipt_id.eq_any(&ids))
+            .order(goods_receipt_lines::receipt_line_id.asc())
+            .select(GoodsReceiptLineModel::as_select())
+            .load::<GoodsReceiptLineModel>(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let receipts = receipts
+            .into_iter()
+            .map(|receipt| {
+                let (own, rest) = receipt_lines
+                    .drain(..)
+                    .partition(|l| l.receipt_id == receipt.receipt_id);
+                receipt_lines = rest;
+                GoodsReceiptDetail {
+                    receipt,
+                    lines: own,
+                }
+            })
+            .collect();
+
+        Ok(Some(PurchaseOrderDetail {
+            order,
+            lines,
+            receipts,
+        }))
+    }
+
+    pub async fn create(
+        &self,
+        draft: PurchaseOrderDraft,
+        created_by: i32,
+    ) -> Result<(PurchaseOrderModel, Vec<PurchaseOrderLineModel>), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            find_active_supplier(conn, draft.supplier_id)?;
+            validate_lines(conn, &draft.lines)?;
+
+            let order = diesel::insert_into(purchase_orders::table)
+                .values(&NewPurchaseOrder {
+                    supplier_id: draft.supplier_id,
+                    branch_id: draft.branch_id,
+                    expected_date: draft.expected_date,
+                    notes: draft.notes,
+                    created_by: Some(created_by),
+                })
+                .returning(PurchaseOrderModel::as_returning())
+                .get_result::<PurchaseOrderModel>(conn)?;
+            let lines = insert_lines(conn, order.po_id, draft.lines)?;
+            Ok((order, lines))
+        })
+        .map_err(String::from)
+    }
+
+    /// Replaces the header and lines of a PO that hasn't been sent yet
+    pub async fn update_draft(
+        &self,
+        po_id: i32,
+        draft: PurchaseOrderDraft,
+        now: DateTime<Utc>,
+    ) -> Result<(PurchaseOrderModel, Vec<PurchaseOrderLineModel>), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            let order = lock_order(conn, po_id)?;
+            if order.status != PurchaseOrderStatusEnum::Draft {
+                return Err(rejected("Only draft purchase orders can be edited"));
+            }
+            find_active_supplier(conn, draft.supplier_id)?;
+            validate_lines(conn, &draft.lines)?;
+
+            diesel::delete(
+                purchase_order_lines::table.filter(purchase_order_lines::po_id.eq(po_id)),
+            )
+            .execute(conn)?;
+            let lines = insert_lines(conn, po_id, draft.lines)?;
+            let order = diesel::update(purchase_orders::table.find(po_id))
+                .set((
+                    purchase_orders::supplier_id.eq(draft.supplier_id),
+                    purchase_orders::branch_id.eq(draft.branch_id),
+                    purchase_orders::expected_date.eq(draft.expected_date),
+                    purchase_orders::notes.eq(draft.notes),
+                    purchase_orders::updated_at.eq(now),
+                ))
+                .returning(PurchaseOrderModel::as_returning())
+                .get_result::<PurchaseOrderModel>(conn)?;
+            Ok((order, lines))
+        })
+        .map_err(String::from)
+    }
+
+    /// Marks a draft as sent to the supplier. Without an expected date one is
+    /// worked out from the supplier's lead time.
+    pub async fn send(&self, po_id: i32, now: DateTime<Utc>) -> Result<PurchaseOrderModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            let order = lock_order(conn, po_id)?;
+            if order.status != PurchaseOrderStatusEnum::Draft {
+                return Err(rejected("Only draft purchase orders can be sent"));
+            }
+            let supplier = find_active_supplier(conn, order.supplier_id)?;
+            let expected_date = order.expected_date.or_else(|| {
+                now.date_naive()
+                    .checked_add_days(Days::new(supplier.lead_time_days as u64))
+            });
+
+            diesel::update(purchase_orders::table.find(po_id))
+                .set((
+                    purchase_orders::status.eq(PurchaseOrderStatusEnum::Sent),
+                    purchase_orders::expected_date.eq(expected_date),
+                    purchase_orders::sent_at.eq(now),
+                    purchase_orders::updated_at.eq(now),
+                ))
+                .returning(PurchaseOrderModel::as_returning())
+                .get_result::<PurchaseOrderModel>(conn)
+                .map_err(TransactionError::from)
+        })
+        .map_err(String::from)
+    }
+
+    /// Closes a PO that is still open. Anything already received stays in stock;
+    /// only the outstanding quantities stop counting as on order.
+    pub async fn cancel(
+        &self,
+        po_id: i32,
+        now: DateTime<Utc>,
+    ) -> Result<PurchaseOrderModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            let order = lock_order(conn, po_id)?;
+            if !OPEN_PO_STATUSES.contains(&order.status) {
+                return Err(rejected("This purchase order is already closed"));
+            }
+
+            diesel::update(purchase_orders::table.find(po_id))
+                .set((
+                    purchase_orders::status.eq(PurchaseOrderStatusEnum::Cancelled),
+                    purchase_orders::closed_at.eq(now),
+                    purchase_orders::updated_at.eq(now),
+                ))
+                .returning(PurchaseOrderModel::as_returning())
+                .get_result::<PurchaseOrderModel>(conn)
+                .map_err(TransactionError::from)
+        })
+        .map_err(String::from)
+    }
+
+    /// Books a delivery: every line's quantity goes into stock at the cost
+    /// actually paid, and the PO becomes partially or fully received
+    pub async fn receive(
+        &self,
+        po_id: i32,
+        lines: Vec<ReceiptLineInput>,
+        note: Option<String>,
+        received_by: i32,
+        now: DateTime<Utc>,
+    ) -> Result<(PurchaseOrderModel, GoodsReceiptDetail), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            let order = lock_order(conn, po_id)?;
+            if !matches!(
+                order.status,
+                PurchaseOrderStatusEnum::Sent | PurchaseOrderStatusEnum::PartiallyReceived
+            ) {
+                return Err(rejected(
+                    "Goods can only be received against a sent purchase order",
+                ));
+            }
+            if lines.is_empty() {
+                return Err(rejected("A receipt needs at least one line"));
+            }
+
+            let order_lines = purchase_order_lines::table
+                .filter(purchase_order_lines::po_id.eq(po_id))
+                .select(PurchaseOrderLineModel::as_select())
+                .load::<PurchaseOrderLineModel>(conn)?;
+
+            let receipt = diesel::insert_into(goods_receipts::table)
+                .values(&NewGoodsReceipt {
+                    po_id,
+                    received_by: Some(received_by),
+                    note,
+                })
+                .returning(GoodsReceiptModel::as_returning())
+                .get_result::<GoodsReceiptModel>(conn)?;
+
+            let mut seen = HashSet::new();
+            let mut receipt_lines = Vec::with_capacity(lines.len());
+            for input in lines {
+                let line = order_lines
+                    .iter()
+                    .find(|l| l.line_id == input.line_id)
+                    .ok_or_else(|| {
+                        rejected(format!(
+                            "Line {} is not on this purchase order",
+                            input.line_id
+                        ))
+                    })?;
+                if !seen.insert(input.line_id) {
+                    return Err(rejected("Each line can only appear once per receipt"));
+                }
+                if input.quantity <= 0 {
+                    return Err(rejected("Quantities must be positive"));
+                }
+                if line.quantity_received + input.quantity > line.quantity_ordered {
+                    return Err(rejected(format!(
+                        "Line {} would receive more than the {} ordered",
+                        line.line_id, line.quantity_ordered
+                    )));
+                }
+                let unit_cost = input.unit_cost.unwrap_or_else(|| line.unit_cost.clone());
+                if unit_cost < BigDecimal::zero() {
+                    return Err(rejected("Unit cost cannot be negative"));
+                }
+
+                diesel::update(purchase_order_lines::table.find(line.line_id))
+                    .set(
+                        purchase_order_lines::quantity_received
+                            .eq(purchase_order_lines::quantity_received + input.quantity),
+                    )
+                    .execute(conn)?;
+                apply_stock_change(
+                    conn,
+                    StockChange {
+                        stock_item_id: line.stock_item_id,
+                        branch_id: order.branch_id,
+                        delta: input.quantity,
+                        reason: StockMovementReasonEnum::Received,
+                        order_id: None,
+                        actor_id: Some(received_by),
+                        note: Some(format!("PO #{} receipt #{}", po_id, receipt.receipt_id)),
+                        unit_cost: Some(unit_cost.clone()),
+                    },
+                )?;
+                receipt_lines.push(NewGoodsReceiptLine {
+                    receipt_id: receipt.receipt_id,
+                    po_line_id: line.line_id,
+                    stock_item_id: line.stock_item_id,
+                    quantity: input.quantity,
+                    unit_cost,
+                });
+            }
+            let receipt_lines = diesel::insert_into(goods_receipt_lines::table)
+                .values(&receipt_lines)
+                .returning(GoodsReceiptLineModel::as_returning())
+                .get_results::<GoodsReceiptLineModel>(conn)?;
+
+            let outstanding: i64 = purchase_order_lines::table
+                .filter(purchase_order_lines::po_id.eq(po_id))
+                .filter(
+                    purchase_order_lines::quantity_received
+                        .lt(purchase_order_lines::quantity_ordered),
+                )
+                .count()
+                .get_result(conn)?;
+            let (status, closed_at) = if outstanding == 0 {
+                (PurchaseOrderStatusEnum::Received, Some(now))
+            } else {
+                (PurchaseOrderStatusEnum::PartiallyReceived, None)
+            };
+            let order = diesel::update(purchase_orders::table.find(po_id))
+                .set((
+                    purchase_orders::status.eq(status),
+                    purchase_orders::closed_at.eq(closed_at),
+                    purchase_orders::updated_at.eq(now),
+                ))
+                .returning(PurchaseOrderModel::as_returning())
+                .get_result::<PurchaseOrderModel>(conn)?;
+
+            Ok((
+                order,
+                GoodsReceiptDetail {
+                    receipt,
+                    lines: receipt_lines,
+                },
+            ))
+        })
+        .map_err(String::from)
+    }
+
+    /// Items whose stock plus what's already on order won't last through the
+    /// supplier's lead time and `cover_days` more, at the rate they were used
+    /// over the last `usage_days`, or that sit at or below their reorder point.
+    /// The latter get at least their reorder quantity.
+    pub async fn reorder_suggestions(
+        &self,
+        usage_days: i32,
+        cover_days: i32,
+    ) -> Result<Vec<ReorderSuggestion>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::sql_query(
+            "WITH usage AS ( \
+                 SELECT stock_item_id, GREATEST(-SUM(delta), 0)::INT AS used \
+                 FROM stock_movements \
+                 WHERE reason IN ('order_usage', 'order_return') \
+                   AND created_at >= NOW() - make_interval(days => $1) \
+                 GROUP BY stock_item_id \
+             ), on_order AS ( \
+                 SELECT l.stock_item_id, SUM(l.quantity_ordered - l.quantity_received)::INT AS qty \
+                 FROM purchase_order_lines l \
+                 JOIN purchase_orders p ON p.po_id = l.po_id \
+                 WHERE p.status IN ('draft', 'sent', 'partially_received') \
+                 GROUP BY l.stock_item_id \
+             ), last_bought AS ( \
+                 SELECT DISTINCT ON (l.stock_item_id) \
+                        l.stock_item_id, s.supplier_id, s.name, s.lead_time_days, l.unit_cost \
+                 FROM purchase_order_lines l \
+                 JOIN purchase_orders p ON p.po_id = l.po_id \
+                 JOIN suppliers s ON s.supplier_id = p.supplier_id \
+                 WHERE p.status <> 'cancelled' \
+                 ORDER BY l.stock_item_id, l.line_id DESC \
+             ), needs AS ( \
+                 SELECT i.item_id, i.name, i.quantity, i.reorder_point, i.reorder_quantity, \
+                        COALESCE(o.qty, 0) AS on_order, \
+                        COALESCE(u.used, 0) AS used, \
+                        CEIL(COALESCE(u.used, 0)::NUMERIC \
+                             * (COALESCE(b.lead_time_days, 0) + $2) / $1)::INT AS target, \
+                        b.supplier_id, b.name AS supplier_name, b.unit_cost \
+                 FROM stock_items i \
+                 LEFT JOIN usage u ON u.stock_item_id = i.item_id \
+                 LEFT JOIN on_order o ON o.stock_item_id = i.item_id \
+                 LEFT JOIN last_bought b ON b.stock_item_id = i.item_id \
+             ), suggested AS ( \
+                 SELECT needs.*, GREATEST( \
+                            target - quantity - on_order, \
+                            CASE WHEN quantity + on_order <= reorder_point \
+                                 THEN COALESCE(reorder_quantity, reorder_point + 1 - quantity - on_order) \
+                                 ELSE 0 END \
+                        ) AS suggested_quantity \
+                 FROM needs \
+             ) \
+             SELECT item_id AS stock_item_id, name, quantity AS on_hand, on_order, \
+                    used AS used_recently, suggested_quantity, \
+                    supplier_id, supplier_name, unit_cost::FLOAT8 AS last_unit_cost \
+             FROM suggested \
+             WHERE suggested_quantity > 0 \
+             ORDER BY supplier_name NULLS LAST, name",
+        )
+        .bind::<Integer, _>(usage_days)
+        .bind::<Integer, _>(cover_days)
+        .load::<ReorderSuggestion>(&mut conn)
+        .map_err(|e| e.to_string())
+    }
+}
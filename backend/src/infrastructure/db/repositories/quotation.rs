@@ -0,0 +1,350 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::models::{
+    NewQuotation, NewQuotationLine, QuotationLineDecisionEnum, QuotationLineModel, QuotationModel,
+    QuotationStatusEnum, ServiceItemModel, ServiceOrderModel, ServiceOrderStatusEnum,
+};
+use crate::infrastructure::db::repositories::inventory::{remove_item, settle_order_stock};
+use crate::infrastructure::db::repositories::loyalty::{RedemptionScope, refund_redemptions};
+use crate::infrastructure::db::schema::{
+    quotation_lines, quotations, service_items, service_orders,
+};
+use crate::infrastructure::db::transaction::{TransactionError, rejected};
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use std::collections::HashMap;
+
+pub struct IssueQuotation {
+    pub order_id: i32,
+    pub notes: Option<String>,
+    pub valid_until: DateTime<Utc>,
+    pub issued_by: i32,
+}
+
+#[derive(Clone)]
+pub struct QuotationRepository {
+    pool: DbPool,
+}
+
+impl QuotationRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_by_id(&self, quotation_id: i32) -> Result<Option<QuotationModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        quotations::table
+            .find(quotation_id)
+            .select(QuotationModel::as_select())
+            .first::<QuotationModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn find_pending(&self, order_id: i32) -> Result<Option<QuotationModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        quotations::table
+            .filter(quotations::order_id.eq(order_id))
+            .filter(quotations::status.eq(QuotationStatusEnum::Pending))
+            .select(QuotationModel::as_select())
+            .first::<QuotationModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Every version issued for the order, newest first, with its lines
+    pub async fn list_for_order(
+        &self,
+        order_id: i32,
+    ) -> Result<Vec<(QuotationModel, Vec<QuotationLineModel>)>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let versions = quotations::table
+            .filter(quotations::order_id.eq(order_id))
+            .order(quotations::version.desc())
+            .select(QuotationModel::as_select())
+            .load::<QuotationModel>(&mut conn)
+            .map_err(|e| e.to_string())?;
+        let ids: Vec<i32> = versions.iter().map(|q| q.quotation_id).collect();
+        let mut lines = quotation_lines::table
+            .filter(quotation_lines::quotation_id.eq_any(&ids))
+            .order(quotation_lines::line_id.asc())
+            .select(QuotationLineModel::as_select())
+            .load::<QuotationLineModel>(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(versions
+            .into_iter()
+            .map(|quotation| {
+                let (own, rest) = lines
+                    .drain(..)
+                    .partition(|l| l.quotation_id == quotation.quotation_id);
+                lines = rest;
+                (quotation, own)
+            })
+            .collect())
+    }
+
+    /// Snapshots the order's lines into a new quote version and puts the order
+    /// in front of the customer. A still-pending earlier version is superseded;
+    /// lines approved on an earlier version stay approved.
+    pub async fn issue(
+        &self,
+        issue: IssueQuotation,
+    ) -> Result<(QuotationModel, Vec<QuotationLineModel>), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            let order = service_orders::table
+                .find(issue.order_id)
+                .for_update()
+                .select(ServiceOrderModel::as_select())
+                .first::<ServiceOrderModel>(conn)
+                .optional()?
+                .ok_or_else(|| rejected("Order not found"))?;
+            if matches!(
+                order.status,
+                ServiceOrderStatusEnum::Completed
+                    | ServiceOrderStatusEnum::Paid
+                    | ServiceOrderStatusEnum::Cancelled
+            ) {
+                return Err(rejected(
+                    "Quotes can only be issued for orders that are still open",
+                ));
+            }
+
+            let items = service_items::table
+                .filter(service_items::order_id.eq(issue.order_id))
+                .order(service_items::item_id.asc())
+                .select(ServiceItemModel::as_select())
+                .load::<ServiceItemModel>(conn)?;
+            if items.is_empty() {
+                return Err(rejected("Add the work to the order before quoting it"));
+            }
+
+            let previous = quotations::table
+                .filter(quotations::order_id.eq(issue.order_id))
+                .order(quotations::version.desc())
+                .select(QuotationModel::as_select())
+                .load::<QuotationModel>(conn)?;
+            let accepted_ids: Vec<i32> = previous
+                .iter()
+                .filter(|q| q.status == QuotationStatusEnum::Accepted)
+                .map(|q| q.quotation_id)
+                .collect();
+            let approved_items: Vec<i32> = quotation_lines::table
+                .filter(quotation_lines::quotation_id.eq_any(&accepted_ids))
+                .filter(quotation_lines::decision.eq(QuotationLineDecisionEnum::Approved))
+                .filter(quotation_lines::service_item_id.is_not_null())
+                .select(quotation_lines::service_item_id.assume_not_null())
+                .load::<i32>(conn)?;
+
+            // Discounts and work the customer already agreed to aren't up for a decision
+            let locked = |item: &ServiceItemModel| {
+                item.price < BigDecimal::zero() || approved_items.contains(&item.item_id)
+            };
+            if items.iter().all(locked) {
+                return Err(rejected("There is no new work on the order to quote"));
+            }
+
+            diesel::update(
+                quotations::table
+                    .filter(quotations::order_id.eq(issue.order_id))
+                    .filter(quotations::status.eq(QuotationStatusEnum::Pending)),
+            )
+            .set(quotations::status.eq(QuotationStatusEnum::Superseded))
+            .execute(conn)?;
+
+            let total: BigDecimal = items.iter().map(|item| item.price.clone()).sum();
+            let quotation = diesel::insert_into(quotations::table)
+                .values(&NewQuotation {
+                    order_id: issue.order_id,
+                    version: previous.first().map(|q| q.version).unwrap_or(0) + 1,
+                    total: total.clone(),
+                    notes: issue.notes,
+                    valid_until: issue.valid_until,
+                    issued_by: Some(issue.issued_by),
+                })
+                .returning(QuotationModel::as_returning())
+                .get_result::<QuotationModel>(conn)?;
+
+            let new_lines: Vec<NewQuotationLine> = items
+                .iter()
+                .map(|item| {
+                    let locked = locked(item);
+                    NewQuotationLine {
+                        quotation_id: quotation.quotation_id,
+                        service_item_id: Some(item.item_id),
+                        description: item.description.clone(),
+                        price: item.price.clone(),
+                        quantity: item.quantity,
+                        decision: if locked {
+                            QuotationLineDecisionEnum::Approved
+                        } else {
+                            QuotationLineDecisionEnum::Pending
+                        },
+                        locked,
+                    }
+                })
+                .collect();
+            let lines = diesel::insert_into(quotation_lines::table)
+                .values(&new_lines)
+                .returning(QuotationLineModel::as_returning())
+                .get_results::<QuotationLineModel>(conn)?;
+
+            diesel::update(service_orders::table.find(issue.order_id))
+                .set((
+                    service_orders::status.eq(ServiceOrderStatusEnum::OfferSent),
+                    service_orders::total_price.eq(total),
+                ))
+                .execute(conn)?;
+
+            Ok((quotation, lines))
+        })
+        .map_err(String::from)
+    }
+
+    /// Records the customer's line-by-line answer. Declined lines come off the
+    /// order and what's left becomes the repair scope, taking its reserved
+    /// parts out of stock; if nothing is approved the order is cancelled and
+    /// any points and parts it held are returned.
+    pub async fn respond(
+        &self,
+        quotation_id: i32,
+        customer_id: i32,
+        decisions: HashMap<i32, bool>,
+        now: DateTime<Utc>,
+    ) -> Result<(QuotationModel, Vec<QuotationLineModel>), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            let quotation = quotations::table
+                .find(quotation_id)
+                .for_update()
+                .select(QuotationModel::as_select())
+                .first::<QuotationModel>(conn)
+                .optional()?
+                .ok_or_else(|| rejected("Quotation not found"))?;
+            let order = service_orders::table
+                .find(quotation.order_id)
+                .for_update()
+                .select(ServiceOrderModel::as_select())
+                .first::<ServiceOrderModel>(conn)?;
+            if order.customer_id != customer_id {
+                return Err(rejected("Access denied: Not your order"));
+            }
+            if quotation.status != QuotationStatusEnum::Pending {
+                return Err(rejected("This quotation is no longer open"));
+            }
+            if quotation.valid_until <= now {
+                return Err(rejected("This quotation has expired"));
+            }
+            if order.status != ServiceOrderStatusEnum::OfferSent {
+                return Err(rejected("The order is no longer waiting on a quote"));
+            }
+
+            let lines = quotation_lines::table
+                .filter(quotation_lines::quotation_id.eq(quotation_id))
+                .order(quotation_lines::line_id.asc())
+                .select(QuotationLineModel::as_select())
+                .load::<QuotationLineModel>(conn)?;
+            if let Some(unknown) = decisions
+                .keys()
+                .find(|id| !lines.iter().any(|l| l.line_id == **id))
+            {
+                return Err(TransactionError::Rejected(format!(
+                    "Line {} is not part of this quotation",
+                    unknown
+                )));
+            }
+
+            let mut approved_total = BigDecimal::zero();
+            let mut approved_work = false;
+            for line in &lines {
+                let approved = if line.locked {
+                    if decisions.get(&line.line_id) == Some(&false) {
+                        return Err(TransactionError::Rejected(format!(
+                            "\"{}\" was already agreed and can't be declined",
+                            line.description
+                        )));
+                    }
+                    true
+                } else {
+                    *decisions.get(&line.line_id).ok_or_else(|| {
+                        TransactionError::Rejected(format!(
+                            "Please approve or decline \"{}\"",
+                            line.description
+                        ))
+                    })?
+                };
+
+                if approved {
+                    approved_total += line.price.clone();
+                    approved_work |= line.price >= BigDecimal::zero();
+                } else if let Some(item_id) = line.service_item_id {
+                    remove_item(conn, item_id, Some(customer_id))?;
+                }
+                diesel::update(quotation_lines::table.find(line.line_id))
+                    .set(quotation_lines::decision.eq(if approved {
+                        QuotationLineDecisionEnum::Approved
+                    } else {
+                        QuotationLineDecisionEnum::Declined
+                    }))
+                    .execute(conn)?;
+            }
+
+            let (status, order_status) = if approved_work {
+                (
+                    QuotationStatusEnum::Accepted,
+                    ServiceOrderStatusEnum::Repairing,
+                )
+            } else {
+                refund_redemptions(conn, RedemptionScope::Order(order.order_id))?;
+                (
+                    QuotationStatusEnum::Declined,
+                    ServiceOrderStatusEnum::Cancelled,
+                )
+            };
+            settle_order_stock(conn, order.order_id, &order_status, Some(customer_id))?;
+            diesel::update(service_orders::table.find(order.order_id))
+                .set(service_orders::status.eq(order_status))
+                .execute(conn)?;
+            let quotation = diesel::update(quotations::table.find(quotation_id))
+                .set((
+                    quotations::status.eq(status),
+                    quotations::approved_total.eq(Some(approved_total)),
+                    quotations::responded_at.eq(Some(now)),
+                ))
+                .returning(QuotationModel::as_returning())
+                .get_result::<QuotationModel>(conn)?;
+            let lines = quotation_lines::table
+                .filter(quotation_lines::quotation_id.eq(quotation_id))
+                .order(quotation_lines::line_id.asc())
+                .select(QuotationLineModel::as_select())
+                .load::<QuotationLineModel>(conn)?;
+
+            Ok((quotation, lines))
+        })
+        .map_err(String::from)
+    }
+
+    /// Closes the order's open quote when the order moves on without an answer
+    pub async fn close_pending(
+        &self,
+        order_id: i32,
+        status: QuotationStatusEnum,
+    ) -> Result<usize, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::update(
+            quotations::table
+                .filter(quotations::order_id.eq(order_id))
+                .filter(quotations::status.eq(QuotationStatusEnum::Pending)),
+        )
+        .set(quotations::status.eq(status))
+        .execute(&mut conn)
+        .map_err(|e| e.to_string())
+    }
+}
@@ -68,4 +68,14 @@ impl RefreshTokenRepository {
 
         Ok(())
     }
+
+    /// Deletes tokens that expired before the cutoff. Revoked tokens are kept
+    /// until then so that reuse of a stolen token can still be detected.
+    pub async fn delete_expired(&self, cutoff: DateTime<Utc>) -> Result<usize, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::delete(refresh_tokens::table.filter(refresh_tokens::expires_at.lt(cutoff)))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())
+    }
 }
@@ -1,6 +1,6 @@
 use crate::infrastructure::db::connection::DbPool;
 use crate::infrastructure::db::models::ServiceOrderStatusEnum;
-use crate::infrastructure::db::schema::repair_logs;
+use crate::infrastructure::db::schema::{repair_logs, service_orders};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -70,4 +70,17 @@ impl RepairLogRepository {
             .load::<RepairLogModel>(&mut conn)
             .map_err(|e| e.to_string())
     }
+
+    /// Logs across all orders of a bike, oldest first
+    pub async fn get_logs_for_bike(&self, bike_id: i32) -> Result<Vec<RepairLogModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        repair_logs::table
+            .inner_join(service_orders::table)
+            .filter(service_orders::bike_id.eq(bike_id))
+            .order(repair_logs::updated_at.asc())
+            .select(RepairLogModel::as_select())
+            .load::<RepairLogModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
 }
@@ -0,0 +1,146 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::models::ScheduledJobModel;
+use crate::infrastructure::db::schema::scheduled_jobs;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::{Bool, Text};
+
+pub const JOB_STATUS_RUNNING: &str = "running";
+pub const JOB_STATUS_SUCCEEDED: &str = "succeeded";
+pub const JOB_STATUS_FAILED: &str = "failed";
+
+#[derive(QueryableByName)]
+struct AdvisoryLock {
+    #[diesel(sql_type = Bool)]
+    locked: bool,
+}
+
+#[derive(Clone)]
+pub struct ScheduledJobRepository {
+    pool: DbPool,
+}
+
+impl ScheduledJobRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates the job's row on first start. When the schedule in code changed,
+    /// the stored schedule and next run are replaced.
+    pub async fn register(
+        &self,
+        job_name: &str,
+        schedule: &str,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let existing = scheduled_jobs::table
+                .find(job_name)
+                .select(scheduled_jobs::schedule)
+                .for_update()
+                .first::<String>(conn)
+                .optional()?;
+
+            match existing {
+                Some(current) if current == schedule => {}
+                Some(_) => {
+                    diesel::update(scheduled_jobs::table.find(job_name))
+                        .set((
+                            scheduled_jobs::schedule.eq(schedule),
+                            scheduled_jobs::next_run_at.eq(next_run_at),
+                        ))
+                        .execute(conn)?;
+                }
+                None => {
+                    diesel::insert_into(scheduled_jobs::table)
+                        .values((
+                            scheduled_jobs::job_name.eq(job_name),
+                            scheduled_jobs::schedule.eq(schedule),
+                            scheduled_jobs::next_run_at.eq(next_run_at),
+                        ))
+                        .on_conflict_do_nothing()
+                        .execute(conn)?;
+                }
+            }
+            Ok(())
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    pub async fn list(&self) -> Result<Vec<ScheduledJobModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        scheduled_jobs::table
+            .order(scheduled_jobs::job_name.asc())
+            .load::<ScheduledJobModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Claims a due run for this replica. The transaction-scoped advisory lock
+    /// serialises replicas racing for the same job (and works behind PgBouncer
+    /// in transaction mode); moving next_run_at forward is what stops the
+    /// others from running it once the lock is released. Returns false if the
+    /// job isn't due or another replica got there first.
+    pub async fn try_claim(
+        &self,
+        job_name: &str,
+        now: DateTime<Utc>,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let lock = diesel::sql_query(
+                "SELECT pg_try_advisory_xact_lock(hashtext('scheduled_job'), hashtext($1)) AS locked",
+            )
+            .bind::<Text, _>(job_name)
+            .get_result::<AdvisoryLock>(conn)?;
+            if !lock.locked {
+                return Ok(false);
+            }
+
+            let claimed = diesel::update(
+                scheduled_jobs::table
+                    .find(job_name)
+                    .filter(scheduled_jobs::next_run_at.le(now)),
+            )
+            .set((
+                scheduled_jobs::next_run_at.eq(next_run_at),
+                scheduled_jobs::last_started_at.eq(Some(now)),
+                scheduled_jobs::last_status.eq(Some(JOB_STATUS_RUNNING)),
+            ))
+            .execute(conn)?;
+
+            Ok(claimed > 0)
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    pub async fn record_result(
+        &self,
+        job_name: &str,
+        result: &Result<String, String>,
+    ) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let (status, message, failed) = match result {
+            Ok(summary) => (JOB_STATUS_SUCCEEDED, summary, 0),
+            Err(error) => (JOB_STATUS_FAILED, error, 1),
+        };
+
+        diesel::update(scheduled_jobs::table.find(job_name))
+            .set((
+                scheduled_jobs::last_finished_at.eq(Some(Utc::now())),
+                scheduled_jobs::last_status.eq(Some(status)),
+                scheduled_jobs::last_message.eq(Some(message)),
+                scheduled_jobs::run_count.eq(scheduled_jobs::run_count + 1),
+                scheduled_jobs::failure_count.eq(scheduled_jobs::failure_count + failed),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use crate::infrastructure::db::connection::DbPool;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Float4, Int4, Nullable, Text};
+use serde::Serialize;
+
+/// What the counter typed, already broken into the forms each source matches on
+pub struct SearchTerms {
+    /// Free text for names, usernames, brands and models
+    pub text: String,
+    /// Digits only, for phone numbers; None when too short to be useful
+    pub phone_digits: Option<String>,
+    /// Plate without spaces, dashes or dots
+    pub plate: String,
+    /// Exact plate key when the text parses as a full plate
+    pub plate_key: Option<String>,
+    /// Order id when the text looks like "SO-123"
+    pub order_id: Option<i32>,
+}
+
+#[derive(QueryableByName, Debug, Serialize)]
+pub struct SearchHit {
+    /// "user", "motorcycle" or "order"
+    #[diesel(sql_type = Text)]
+    pub kind: String,
+    #[diesel(sql_type = Int4)]
+    pub id: i32,
+    #[diesel(sql_type = Text)]
+    pub title: String,
+    #[diesel(sql_type = Text)]
+    pub subtitle: String,
+    #[diesel(sql_type = Float4)]
+    pub score: f32,
+}
+
+// $1 text, $2 text as a LIKE pattern, $3 phone digits pattern, $4 plate pattern,
+// $5 exact plate key, $6 order id, $7 limit. Exact hits (phone, plate key,
+// order id) score 1; everything else scores by trigram similarity.
+const SEARCH_SQL: &str = r#"
+SELECT * FROM (
+    SELECT 'user'::text AS kind,
+           u.user_id AS id,
+           u.name::text AS title,
+           (u.username || ' · ' || u.phone || ' · '
+               || CASE WHEN u.is_guest THEN 'guest' ELSE u.role::text END)::text AS subtitle,
+           GREATEST(
+               similarity(u.name, $1),
+               similarity(u.username, $1),
+               CASE WHEN regexp_replace(u.phone, '\D', '', 'g') LIKE $3 THEN 1 ELSE 0 END
+           )::real AS score
+    FROM users u
+    WHERE u.merged_into_user_id IS NULL
+      AND (u.name % $1
+           OR u.username % $1
+           OR u.name ILIKE $2
+           OR u.username ILIKE $2
+           OR regexp_replace(u.phone, '\D', '', 'g') LIKE $3)
+
+    UNION ALL
+
+    SELECT 'motorcycle'::text,
+           m.bike_id,
+           m.license_plate::text,
+           (m.brand || ' ' || m.model || ' · ' || u.name)::text,
+           GREATEST(
+               similarity(m.license_plate, $1),
+               similarity(m.brand || ' ' || m.model, $1),
+               CASE WHEN m.plate_key = $5 THEN 1 WHEN m.plate_key LIKE $4 THEN 0.9 ELSE 0 END
+           )::real
+    FROM motorcycles m
+    JOIN users u ON u.user_id = m.user_id
+    WHERE m.archived_at IS NULL
+      AND (m.license_plate % $1
+           OR m.brand % $1
+           OR m.model % $1
+           OR m.brand ILIKE $2
+           OR m.model ILIKE $2
+           OR m.license_plate ILIKE $2
+           OR m.plate_key LIKE $4)
+
+    UNION ALL
+
+    SELECT 'order'::text,
+           o.order_id,
+           ('SO-' || o.order_id)::text,
+           (o.status::text || ' · ' || u.name || COALESCE(' · ' || m.license_plate, ''))::text,
+           1::real
+    FROM service_orders o
+    JOIN users u ON u.user_id = o.customer_id
+    LEFT JOIN motorcycles m ON m.bike_id = o.bike_id
+    WHERE o.order_id = $6
+) hits
+ORDER BY score DESC, kind, id DESC
+LIMIT $7
+"#;
+
+#[derive(Clone)]
+pub struct SearchRepository {
+    pool: DbPool,
+}
+
+impl SearchRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn search(&self, terms: &SearchTerms, limit: i64) -> Result<Vec<SearchHit>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let contains = |value: &str| format!("%{}%", escape_like(value));
+
+        // Absent terms bind NULL, which never matches
+        diesel::sql_query(SEARCH_SQL)
+            .bind::<Text, _>(&terms.text)
+            .bind::<Text, _>(contains(&terms.text))
+            .bind::<Nullable<Text>, _>(terms.phone_digits.as_deref().map(contains))
+            .bind::<Nullable<Text>, _>(
+                Some(terms.plate.as_str())
+                    .filter(|p| !p.is_empty())
+                    .map(contains),
+            )
+            .bind::<Nullable<Text>, _>(terms.plate_key.as_deref())
+            .bind::<Nullable<Int4>, _>(terms.order_id)
+            .bind::<BigInt, _>(limit)
+            .load::<SearchHit>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
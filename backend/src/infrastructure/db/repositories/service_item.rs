@@ -2,8 +2,10 @@ use crate::domain::service::entity::ServiceItem;
 use crate::infrastructure::db::connection::DbPool;
 use crate::infrastructure::db::models::{NewServiceItem, ServiceItemModel};
 use crate::infrastructure::db::schema::service_items;
-use bigdecimal::{BigDecimal, FromPrimitive};
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
 use diesel::prelude::*;
+use diesel::sql_types::Numeric;
+use std::collections::HashMap;
 
 #[derive(Clone)]
 pub struct ServiceItemRepository {
@@ -26,6 +28,8 @@ impl ServiceItemRepository {
             price,
             stock_item_id: item.stock_item_id,
             quantity: item.quantity,
+            unit_cost: None,
+            stock_status: None,
         };
 
         let result = diesel::insert_into(service_items::table)
@@ -55,6 +59,27 @@ impl ServiceItemRepository {
             .collect())
     }
 
+    /// Cost of the parts used on each order, from the costs noted when they
+    /// were taken out of stock
+    pub async fn parts_cost_by_order(&self) -> Result<HashMap<i32, f64>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let rows = service_items::table
+            .filter(service_items::unit_cost.is_not_null())
+            .group_by(service_items::order_id)
+            .select((
+                service_items::order_id,
+                diesel::dsl::sql::<Numeric>("SUM(quantity * unit_cost)"),
+            ))
+            .load::<(i32, BigDecimal)>(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(order_id, cost)| (order_id, cost.to_f64().unwrap_or(0.0)))
+            .collect())
+    }
+
     fn map_model_to_entity(&self, model: ServiceItemModel) -> ServiceItem {
         let price = model.price.to_string().parse::<f64>().unwrap_or(0.0);
 
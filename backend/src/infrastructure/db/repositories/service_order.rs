@@ -1,10 +1,20 @@
+use crate::domain::audit::AuditEvent;
 use crate::domain::service::entity::{OrderStatus, ServiceItem, ServiceOrder};
 use crate::infrastructure::db::connection::DbPool;
 use crate::infrastructure::db::models::{
     NewServiceOrder, ServiceItemModel, ServiceOrderModel, ServiceOrderStatusEnum,
 };
-use crate::infrastructure::db::schema::{notifications, service_items, service_orders};
+use crate::infrastructure::db::repositories::audit_event::insert_event;
+use crate::infrastructure::db::repositories::inventory::{release_order_stock, settle_order_stock};
+use crate::infrastructure::db::repositories::loyalty::{
+    RedemptionScope, claw_back_earned, refund_redemptions,
+};
+use crate::infrastructure::db::schema::{
+    notifications, repair_logs, service_items, service_orders,
+};
+use crate::infrastructure::db::transaction::TransactionError;
 use bigdecimal::{BigDecimal, FromPrimitive};
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 
 #[derive(Clone)]
@@ -40,11 +50,13 @@ impl ServiceOrderRepository {
         let new_order = NewServiceOrder {
             bike_id: order.bike_id,
             customer_id: order.customer_id,
+            branch_id: order.branch_id,
             status: status_enum,
             total_price,
             created_by: creator_id,
             before_picture_url: order.before_picture_url,
             after_picture_url: order.after_picture_url,
+            odometer_km: order.odometer_km,
         };
 
         let result = diesel::insert_into(service_orders::table)
@@ -94,7 +106,14 @@ impl ServiceOrderRepository {
         }
     }
 
-    pub async fn update_order(&self, order: ServiceOrder) -> Result<ServiceOrder, String> {
+    /// Saves the order. A status change also settles its parts: reserved
+    /// stock is taken when work starts and everything is freed on cancellation,
+    /// recorded against `actor_id`.
+    pub async fn update_order(
+        &self,
+        order: ServiceOrder,
+        actor_id: Option<i32>,
+    ) -> Result<ServiceOrder, String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
 
         let order_id = order.id.ok_or("Order ID is required for update")?;
@@ -112,27 +131,39 @@ impl ServiceOrderRepository {
         // Convert f64 to BigDecimal for DB
         let total_price = BigDecimal::from_f64(order.total_price).ok_or("Invalid price")?;
 
-        let target = service_orders::table.find(order_id);
-
-        let result = diesel::update(target)
-            .set((
-                service_orders::status.eq(status_enum),
-                service_orders::total_price.eq(total_price),
-                service_orders::before_picture_url.eq(order.before_picture_url),
-                service_orders::after_picture_url.eq(order.after_picture_url),
-            ))
-            .returning(ServiceOrderModel::as_returning())
-            .get_result::<ServiceOrderModel>(&mut conn)
-            .map_err(|e| e.to_string())?;
+        let result = conn
+            .transaction::<_, TransactionError, _>(|conn| {
+                settle_order_stock(conn, order_id, &status_enum, actor_id)?;
+
+                Ok(diesel::update(service_orders::table.find(order_id))
+                    .set((
+                        service_orders::status.eq(status_enum),
+                        service_orders::total_price.eq(total_price),
+                        service_orders::before_picture_url.eq(order.before_picture_url),
+                        service_orders::after_picture_url.eq(order.after_picture_url),
+                    ))
+                    .returning(ServiceOrderModel::as_returning())
+                    .get_result::<ServiceOrderModel>(conn)?)
+            })
+            .map_err(String::from)?;
 
         Ok(self.map_model_to_entity(result))
     }
 
-    pub async fn list_orders(&self) -> Result<Vec<ServiceOrder>, String> {
+    /// Every order, or only those at the given branches
+    pub async fn list_orders(
+        &self,
+        branch_ids: Option<Vec<i32>>,
+    ) -> Result<Vec<ServiceOrder>, String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
 
-        let results = service_orders::table
+        let mut query = service_orders::table
             .select(ServiceOrderModel::as_select())
+            .into_boxed();
+        if let Some(branch_ids) = branch_ids {
+            query = query.filter(service_orders::branch_id.eq_any(branch_ids));
+        }
+        let results = query
             .load::<ServiceOrderModel>(&mut conn)
             .map_err(|e| e.to_string())?;
 
@@ -160,25 +191,140 @@ impl ServiceOrderRepository {
             .collect())
     }
 
-    pub async fn delete_order(&self, order_id_val: i32) -> Result<(), String> {
+    /// Every order ever opened for a bike, oldest first, with its line items
+    pub async fn list_orders_for_bike(&self, bike_id: i32) -> Result<Vec<ServiceOrder>, String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
 
-        // Delete notifications first (FK: notifications -> service_orders)
-        diesel::delete(notifications::table.filter(notifications::order_id.eq(order_id_val)))
-            .execute(&mut conn)
+        let models = service_orders::table
+            .filter(service_orders::bike_id.eq(bike_id))
+            .order(service_orders::created_at.asc())
+            .select(ServiceOrderModel::as_select())
+            .load::<ServiceOrderModel>(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let order_ids: Vec<i32> = models.iter().map(|m| m.order_id).collect();
+        let item_models = service_items::table
+            .filter(service_items::order_id.eq_any(&order_ids))
+            .order(service_items::item_id.asc())
+            .select(ServiceItemModel::as_select())
+            .load::<ServiceItemModel>(&mut conn)
             .map_err(|e| e.to_string())?;
 
-        // Delete associated service items
-        diesel::delete(service_items::table.filter(service_items::order_id.eq(order_id_val)))
-            .execute(&mut conn)
+        Ok(models
+            .into_iter()
+            .map(|model| {
+                let mut entity = self.map_model_to_entity(model);
+                entity.items = item_models
+                    .iter()
+                    .filter(|m| Some(m.order_id) == entity.id)
+                    .map(|m| ServiceItem {
+                        id: Some(m.item_id),
+                        order_id: m.order_id,
+                        description: m.description.clone(),
+                        price: m.price.to_string().parse::<f64>().unwrap_or(0.0),
+                        stock_item_id: m.stock_item_id,
+                        quantity: m.quantity,
+                    })
+                    .collect();
+                entity
+            })
+            .collect())
+    }
+
+    /// Orders that have sat in `status` since before the cutoff. The last repair
+    /// log entry for that status marks when the order entered it; orders
+    /// without one fall back to their creation time.
+    pub async fn find_stale(
+        &self,
+        status: ServiceOrderStatusEnum,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<ServiceOrder>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let models = service_orders::table
+            .filter(service_orders::status.eq(status.clone()))
+            .filter(service_orders::created_at.lt(cutoff))
+            .select(ServiceOrderModel::as_select())
+            .load::<ServiceOrderModel>(&mut conn)
             .map_err(|e| e.to_string())?;
 
-        // Delete order
-        diesel::delete(service_orders::table.find(order_id_val))
-            .execute(&mut conn)
+        let order_ids: Vec<i32> = models.iter().map(|m| m.order_id).collect();
+        let recent: Vec<i32> = repair_logs::table
+            .filter(repair_logs::order_id.eq_any(&order_ids))
+            .filter(repair_logs::status.eq(status))
+            .filter(repair_logs::updated_at.ge(cutoff))
+            .select(repair_logs::order_id)
+            .load::<i32>(&mut conn)
             .map_err(|e| e.to_string())?;
 
-        Ok(())
+        Ok(models
+            .into_iter()
+            .filter(|m| !recent.contains(&m.order_id))
+            .map(|model| self.map_model_to_entity(model))
+            .collect())
+    }
+
+    /// Cancels the order only if it is still in the expected status, so a
+    /// customer confirming at the same moment wins, and frees its parts.
+    /// Returns whether it changed.
+    pub async fn cancel_if_status(
+        &self,
+        order_id_val: i32,
+        expected: ServiceOrderStatusEnum,
+    ) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            let updated = diesel::update(
+                service_orders::table
+                    .find(order_id_val)
+                    .filter(service_orders::status.eq(expected)),
+            )
+            .set(service_orders::status.eq(ServiceOrderStatusEnum::Cancelled))
+            .execute(conn)?;
+            if updated > 0 {
+                release_order_stock(conn, order_id_val, None)?;
+            }
+            Ok(updated > 0)
+        })
+        .map_err(String::from)
+    }
+
+    /// Removes the order with its items and notifications, putting any stock
+    /// it held back first on behalf of `actor_id`. An order whose parts were
+    /// already fitted is refused until they are taken off it.
+    pub async fn delete_order(
+        &self,
+        order_id_val: i32,
+        actor_id: i32,
+        event: AuditEvent,
+    ) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            release_order_stock(conn, order_id_val, Some(actor_id))?;
+            refund_redemptions(conn, RedemptionScope::Order(order_id_val))?;
+            claw_back_earned(
+                conn,
+                order_id_val,
+                &format!("Order #SO-{} was deleted", order_id_val),
+            )?;
+
+            // Delete notifications first (FK: notifications -> service_orders)
+            diesel::delete(notifications::table.filter(notifications::order_id.eq(order_id_val)))
+                .execute(conn)?;
+
+            // Delete associated service items
+            diesel::delete(service_items::table.filter(service_items::order_id.eq(order_id_val)))
+                .execute(conn)?;
+
+            // Delete order
+            diesel::delete(service_orders::table.find(order_id_val)).execute(conn)?;
+
+            insert_event(conn, event)?;
+            Ok(())
+        })
+        .map_err(String::from)
     }
 
     fn map_model_to_entity(&self, model: ServiceOrderModel) -> ServiceOrder {
@@ -200,12 +346,14 @@ impl ServiceOrderRepository {
             id: Some(model.order_id),
             bike_id: model.bike_id,
             customer_id: model.customer_id,
+            branch_id: model.branch_id,
             status,
             total_price,
             items: Vec::new(),
             created_at: Some(model.created_at),
             before_picture_url: model.before_picture_url,
             after_picture_url: model.after_picture_url,
+            odometer_km: model.odometer_km,
         }
     }
 }
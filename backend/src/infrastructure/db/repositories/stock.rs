@@ -1,9 +1,203 @@
 use crate::domain::service::stock_entity::StockItem;
 use crate::infrastructure::db::connection::DbPool;
-use crate::infrastructure::db::models::{NewStockItem, StockItemModel};
-use crate::infrastructure::db::schema::stock_items;
-use bigdecimal::{BigDecimal, FromPrimitive};
+use crate::infrastructure::db::models::{
+    NewStockItem, PurchaseOrderStatusEnum, StockItemModel, StockMovementReasonEnum,
+};
+use crate::infrastructure::db::repositories::stock_movement::{
+    StockChange, apply_stock_change, lock_stock_level,
+};
+use crate::infrastructure::db::schema::{
+    purchase_order_lines, purchase_orders, stock_alerts, stock_items, stock_levels,
+};
+use crate::infrastructure::db::transaction::{TransactionError, rejected};
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use chrono::Utc;
+use diesel::pg::PgConnection;
 use diesel::prelude::*;
+use diesel::sql_types::{Array, Integer, Jsonb, Nullable, Numeric, Text};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// An item at or below its reorder point. Reorder points are shop-wide, so
+/// this compares the shop's total; `branches` shows where that stock sits.
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct LowStockItem {
+    #[diesel(sql_type = Integer)]
+    pub stock_item_id: i32,
+    #[diesel(sql_type = Text)]
+    pub name: String,
+    #[diesel(sql_type = Integer)]
+    pub quantity: i32,
+    #[diesel(sql_type = Integer)]
+    pub reorder_point: i32,
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub reorder_quantity: Option<i32>,
+    /// Still outstanding on open purchase orders
+    #[diesel(sql_type = Integer)]
+    pub on_order: i32,
+    /// `[{"branch_id", "quantity", "reserved_quantity"}]` for each branch
+    /// holding a level of the item
+    #[diesel(sql_type = Jsonb)]
+    pub branches: serde_json::Value,
+}
+
+/// A low-stock alert that has just been claimed for sending
+#[derive(Debug, QueryableByName)]
+pub struct StockAlert {
+    #[diesel(sql_type = Integer)]
+    pub alert_id: i32,
+    #[diesel(sql_type = Integer)]
+    pub stock_item_id: i32,
+    #[diesel(sql_type = Text)]
+    pub name: String,
+    #[diesel(sql_type = Integer)]
+    pub quantity: i32,
+    #[diesel(sql_type = Integer)]
+    pub reorder_point: i32,
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub reorder_quantity: Option<i32>,
+}
+
+/// An existing item an import saves. `counted_from` is set when the row also
+/// sets the branch's quantity, and holds what the branch held when the file
+/// was checked.
+pub struct ImportedUpdate {
+    pub item: StockItem,
+    pub counted_from: Option<i32>,
+}
+
+/// The item's selling price and cost price as stored
+fn decimals(item: &StockItem) -> Result<(BigDecimal, BigDecimal), String> {
+    let price = BigDecimal::from_f64(item.price).ok_or("Invalid price")?;
+    let cost = BigDecimal::from_f64(item.average_cost)
+        .ok_or("Invalid cost price")?
+        .round(4);
+    Ok((price, cost))
+}
+
+/// Adds the item. It starts empty and the opening quantity is booked at the
+/// branch as its first movement, valued at its cost price.
+fn insert_item(
+    conn: &mut PgConnection,
+    item: &StockItem,
+    price: BigDecimal,
+    cost: BigDecimal,
+    actor_id: i32,
+    branch_id: i32,
+) -> QueryResult<StockItemModel> {
+    let mut created = diesel::insert_into(stock_items::table)
+        .values(&NewStockItem {
+            name: &item.name,
+            price,
+            quantity: 0,
+            reorder_point: item.reorder_point,
+            reorder_quantity: item.reorder_quantity,
+            sku: item.sku.as_deref(),
+            barcode: item.barcode.as_deref(),
+            category: item.category.as_deref(),
+            brand: item.brand.as_deref(),
+            unit: &item.unit,
+            shelf_location: item.shelf_location.as_deref(),
+            average_cost: cost.clone(),
+        })
+        .returning(StockItemModel::as_returning())
+        .get_result::<StockItemModel>(conn)?;
+    let opening = apply_stock_change(
+        conn,
+        StockChange {
+            stock_item_id: created.item_id,
+            branch_id,
+            delta: item.quantity,
+            reason: StockMovementReasonEnum::Opening,
+            order_id: None,
+            actor_id: Some(actor_id),
+            note: None,
+            unit_cost: Some(cost),
+        },
+    )?;
+    created.quantity = opening.quantity_after;
+    Ok(created)
+}
+
+/// Saves an existing item, booking a change to what the branch holds as an
+/// adjustment there
+fn save_item(
+    conn: &mut PgConnection,
+    item: &StockItem,
+    price: BigDecimal,
+    actor_id: i32,
+    note: Option<String>,
+    branch_id: i32,
+) -> QueryResult<StockItemModel> {
+    let item_id = lock_item(conn, item)?;
+    let (current, _) = lock_stock_level(conn, item_id, branch_id)?;
+    adjust_to(
+        conn,
+        item_id,
+        branch_id,
+        item.quantity - current,
+        actor_id,
+        note,
+    )?;
+    save_details(conn, item_id, item, price)
+}
+
+fn lock_item(conn: &mut PgConnection, item: &StockItem) -> QueryResult<i32> {
+    let item_id = item.id.ok_or(diesel::result::Error::NotFound)?;
+    stock_items::table
+        .find(item_id)
+        .for_update()
+        .select(stock_items::item_id)
+        .first::<i32>(conn)
+}
+
+fn adjust_to(
+    conn: &mut PgConnection,
+    item_id: i32,
+    branch_id: i32,
+    delta: i32,
+    actor_id: i32,
+    note: Option<String>,
+) -> QueryResult<()> {
+    apply_stock_change(
+        conn,
+        StockChange {
+            stock_item_id: item_id,
+            branch_id,
+            delta,
+            reason: StockMovementReasonEnum::Adjustment,
+            order_id: None,
+            actor_id: Some(actor_id),
+            note,
+            unit_cost: None,
+        },
+    )?;
+    Ok(())
+}
+
+/// Writes everything about the item except what it holds
+fn save_details(
+    conn: &mut PgConnection,
+    item_id: i32,
+    item: &StockItem,
+    price: BigDecimal,
+) -> QueryResult<StockItemModel> {
+    diesel::update(stock_items::table.find(item_id))
+        .set((
+            stock_items::name.eq(&item.name),
+            stock_items::price.eq(price),
+            stock_items::reorder_point.eq(item.reorder_point),
+            stock_items::reorder_quantity.eq(item.reorder_quantity),
+            stock_items::sku.eq(&item.sku),
+            stock_items::barcode.eq(&item.barcode),
+            stock_items::category.eq(&item.category),
+            stock_items::brand.eq(&item.brand),
+            stock_items::unit.eq(&item.unit),
+            stock_items::shelf_location.eq(&item.shelf_location),
+        ))
+        .returning(StockItemModel::as_returning())
+        .get_result::<StockItemModel>(conn)
+}
 
 #[derive(Clone)]
 pub struct StockItemRepository {
@@ -15,30 +209,96 @@ impl StockItemRepository {
         Self { pool }
     }
 
-    pub async fn create_stock_item(&self, item: StockItem) -> Result<StockItem, String> {
+    /// Adds the item with its opening quantity at the branch
+    pub async fn create_stock_item(
+        &self,
+        item: StockItem,
+        actor_id: i32,
+        branch_id: i32,
+    ) -> Result<StockItem, String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+        let (price, cost) = decimals(&item)?;
 
-        let price = BigDecimal::from_f64(item.price).ok_or("Invalid price")?;
-
-        let new_item = NewStockItem {
-            name: &item.name,
-            price,
-            quantity: item.quantity,
-        };
-
-        let result = diesel::insert_into(stock_items::table)
-            .values(&new_item)
-            .returning(StockItemModel::as_returning())
-            .get_result::<StockItemModel>(&mut conn)
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                insert_item(conn, &item, price, cost, actor_id, branch_id)
+            })
             .map_err(|e| e.to_string())?;
 
         Ok(self.map_model_to_entity(result))
     }
 
+    /// Swaps the items' shop-wide quantities for what the branch holds
+    pub async fn at_branch(
+        &self,
+        items: Vec<StockItem>,
+        branch_id: i32,
+    ) -> Result<Vec<StockItem>, String> {
+        self.at_branches(items, &[branch_id]).await
+    }
+
+    /// Swaps the items' shop-wide quantities for what the branches hold
+    /// between them
+    pub async fn at_branches(
+        &self,
+        mut items: Vec<StockItem>,
+        branch_ids: &[i32],
+    ) -> Result<Vec<StockItem>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+        let item_ids: Vec<i32> = items.iter().filter_map(|item| item.id).collect();
+
+        let mut levels: HashMap<i32, (i32, i32)> = HashMap::new();
+        for (item_id, quantity, reserved) in stock_levels::table
+            .filter(stock_levels::branch_id.eq_any(branch_ids))
+            .filter(stock_levels::stock_item_id.eq_any(&item_ids))
+            .select((
+                stock_levels::stock_item_id,
+                stock_levels::quantity,
+                stock_levels::reserved_quantity,
+            ))
+            .load::<(i32, i32, i32)>(&mut conn)
+            .map_err(|e| e.to_string())?
+        {
+            let level = levels.entry(item_id).or_default();
+            level.0 += quantity;
+            level.1 += reserved;
+        }
+
+        for item in &mut items {
+            let (quantity, reserved) = item
+                .id
+                .and_then(|id| levels.get(&id).copied())
+                .unwrap_or((0, 0));
+            item.quantity = quantity;
+            item.reserved_quantity = reserved;
+            item.available_quantity = quantity - reserved;
+        }
+        Ok(items)
+    }
+
     pub async fn list_stock_items(&self) -> Result<Vec<StockItem>, String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
 
         let results = stock_items::table
+            .filter(stock_items::archived_at.is_null())
+            .select(StockItemModel::as_select())
+            .load::<StockItemModel>(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(results
+            .into_iter()
+            .map(|model| self.map_model_to_entity(model))
+            .collect())
+    }
+
+    /// The stocked items among the given ids; unknown and archived ids are
+    /// skipped
+    pub async fn find_by_ids(&self, item_ids: &[i32]) -> Result<Vec<StockItem>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let results = stock_items::table
+            .filter(stock_items::item_id.eq_any(item_ids))
+            .filter(stock_items::archived_at.is_null())
             .select(StockItemModel::as_select())
             .load::<StockItemModel>(&mut conn)
             .map_err(|e| e.to_string())?;
@@ -62,43 +322,253 @@ impl StockItemRepository {
         Ok(result.map(|model| self.map_model_to_entity(model)))
     }
 
-    pub async fn update_quantity(&self, item_id_val: i32, change: i32) -> Result<(), String> {
+    pub async fn find_by_sku(&self, sku: &str) -> Result<Option<StockItem>, String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
 
-        diesel::update(stock_items::table.find(item_id_val))
-            .set(stock_items::quantity.eq(stock_items::quantity + change))
-            .execute(&mut conn)
+        let result = stock_items::table
+            .filter(stock_items::sku.eq(sku))
+            .filter(stock_items::archived_at.is_null())
+            .select(StockItemModel::as_select())
+            .first::<StockItemModel>(&mut conn)
+            .optional()
             .map_err(|e| e.to_string())?;
 
-        Ok(())
+        Ok(result.map(|model| self.map_model_to_entity(model)))
     }
 
-    pub async fn update_stock_item(&self, item: StockItem) -> Result<StockItem, String> {
+    pub async fn find_by_barcode(&self, barcode: &str) -> Result<Option<StockItem>, String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
-        let item_id = item.id.ok_or("Item ID required for update")?;
-        let price = BigDecimal::from_f64(item.price).ok_or("Invalid price")?;
-
-        let result = diesel::update(stock_items::table.find(item_id))
-            .set((
-                stock_items::name.eq(item.name),
-                stock_items::price.eq(price),
-                stock_items::quantity.eq(item.quantity),
-            ))
-            .returning(StockItemModel::as_returning())
-            .get_result::<StockItemModel>(&mut conn)
+
+        let result = stock_items::table
+            .filter(stock_items::barcode.eq(barcode))
+            .filter(stock_items::archived_at.is_null())
+            .select(StockItemModel::as_select())
+            .first::<StockItemModel>(&mut conn)
+            .optional()
             .map_err(|e| e.to_string())?;
 
-        Ok(self.map_model_to_entity(result))
+        Ok(result.map(|model| self.map_model_to_entity(model)))
+    }
+
+    /// Returns the branch's quantity after the change
+    pub async fn update_quantity(&self, change: StockChange) -> Result<i32, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| apply_stock_change(conn, change))
+            .map(|applied| applied.quantity_after)
+            .map_err(|e| e.to_string())
     }
 
-    pub async fn delete_stock_item(&self, item_id_val: i32) -> Result<(), String> {
+    /// Saves the item; a change to the branch's quantity is booked as an
+    /// adjustment at the current average cost, which only receipts and
+    /// returns move
+    pub async fn update_stock_item(
+        &self,
+        item: StockItem,
+        actor_id: i32,
+        note: Option<String>,
+        branch_id: i32,
+    ) -> Result<StockItem, String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+        item.id.ok_or("Item ID required for update")?;
+        let (price, _) = decimals(&item)?;
 
-        diesel::delete(stock_items::table.find(item_id_val))
-            .execute(&mut conn)
+        let result = conn
+            .transaction::<_, diesel::result::Error, _>(|conn| {
+                save_item(conn, &item, price, actor_id, note, branch_id)
+            })
             .map_err(|e| e.to_string())?;
 
-        Ok(())
+        Ok(self.map_model_to_entity(result))
+    }
+
+    /// Adds and saves a batch of items in one transaction, so either all of
+    /// them land or none do. Quantities are the branch's. A saved item only
+    /// has its quantity set when the import asked for it, booked as an
+    /// adjustment with the given note, and the whole batch is refused if the
+    /// branch's stock of it moved since the file was checked.
+    pub async fn import_items(
+        &self,
+        creates: Vec<StockItem>,
+        updates: Vec<ImportedUpdate>,
+        actor_id: i32,
+        note: &str,
+        branch_id: i32,
+    ) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+        let creates = creates
+            .into_iter()
+            .map(|item| decimals(&item).map(|prices| (item, prices)))
+            .collect::<Result<Vec<_>, String>>()?;
+        let updates = updates
+            .into_iter()
+            .map(|update| decimals(&update.item).map(|(price, _)| (update, price)))
+            .collect::<Result<Vec<_>, String>>()?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            for (item, (price, cost)) in creates {
+                insert_item(conn, &item, price, cost, actor_id, branch_id)?;
+            }
+            for (update, price) in updates {
+                let item = &update.item;
+                let item_id = lock_item(conn, item)?;
+                if let Some(counted_from) = update.counted_from {
+                    let (current, _) = lock_stock_level(conn, item_id, branch_id)?;
+                    if current != counted_from {
+                        return Err(rejected(format!(
+                            "The stock of {} changed from {} to {} since the file was checked. Check the file again before importing.",
+                            item.name, counted_from, current
+                        )));
+                    }
+                    adjust_to(
+                        conn,
+                        item_id,
+                        branch_id,
+                        item.quantity - current,
+                        actor_id,
+                        Some(note.to_string()),
+                    )?;
+                }
+                save_details(conn, item_id, item, price)?;
+            }
+            Ok(())
+        })
+        .map_err(String::from)
+    }
+
+    /// Items at or below their reorder point, furthest below first, with
+    /// what every branch, or only the given ones, holds of them
+    pub async fn find_low_stock(
+        &self,
+        branch_ids: Option<Vec<i32>>,
+    ) -> Result<Vec<LowStockItem>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::sql_query(
+            "SELECT i.item_id AS stock_item_id, i.name, i.quantity, i.reorder_point, \
+                    i.reorder_quantity, \
+                    COALESCE(SUM(l.quantity_ordered - l.quantity_received) \
+                             FILTER (WHERE p.status IN ('draft', 'sent', 'partially_received')), \
+                             0)::INT AS on_order, \
+                    COALESCE((SELECT jsonb_agg(jsonb_build_object( \
+                                  'branch_id', s.branch_id, \
+                                  'quantity', s.quantity, \
+                                  'reserved_quantity', s.reserved_quantity) \
+                                  ORDER BY s.branch_id) \
+                              FROM stock_levels s \
+                              WHERE s.stock_item_id = i.item_id \
+                                AND ($1::INT[] IS NULL OR s.branch_id = ANY($1))), \
+                             '[]'::JSONB) AS branches \
+             FROM stock_items i \
+             LEFT JOIN purchase_order_lines l ON l.stock_item_id = i.item_id \
+             LEFT JOIN purchase_orders p ON p.po_id = l.po_id \
+             WHERE i.archived_at IS NULL \
+               AND i.reorder_point IS NOT NULL AND i.quantity <= i.reorder_point \
+             GROUP BY i.item_id \
+             ORDER BY i.quantity - i.reorder_point, i.name",
+        )
+        .bind::<Nullable<Array<Integer>>, _>(branch_ids)
+        .load::<LowStockItem>(&mut conn)
+        .map_err(|e| e.to_string())
+    }
+
+    /// Alerts that haven't reached anyone yet, oldest first
+    pub async fn pending_alerts(&self) -> Result<Vec<StockAlert>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::sql_query(
+            "SELECT a.alert_id, a.stock_item_id, i.name, a.quantity, a.reorder_point, \
+                    i.reorder_quantity \
+             FROM stock_alerts a \
+             JOIN stock_items i ON i.item_id = a.stock_item_id \
+             WHERE a.notified_at IS NULL \
+             ORDER BY a.alert_id",
+        )
+        .load::<StockAlert>(&mut conn)
+        .map_err(|e| e.to_string())
+    }
+
+    /// Records that the alert went out. False if it had already been marked.
+    pub async fn mark_alert_sent(&self, alert_id: i32) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::update(
+            stock_alerts::table
+                .find(alert_id)
+                .filter(stock_alerts::notified_at.is_null()),
+        )
+        .set(stock_alerts::notified_at.eq(Some(Utc::now())))
+        .execute(&mut conn)
+        .map(|updated| updated > 0)
+        .map_err(|e| e.to_string())
+    }
+
+    /// What the stock on hand is worth at its average cost, across the shop
+    /// or at the given branches
+    pub async fn inventory_value(&self, branch_ids: Option<Vec<i32>>) -> Result<f64, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let value = match branch_ids {
+            None => stock_items::table
+                .filter(stock_items::quantity.gt(0))
+                .select(diesel::dsl::sql::<Nullable<Numeric>>(
+                    "SUM(quantity * average_cost)",
+                ))
+                .first::<Option<BigDecimal>>(&mut conn),
+            Some(branch_ids) => stock_levels::table
+                .inner_join(stock_items::table)
+                .filter(stock_levels::branch_id.eq_any(branch_ids))
+                .filter(stock_levels::quantity.gt(0))
+                .select(diesel::dsl::sql::<Nullable<Numeric>>(
+                    "SUM(stock_levels.quantity * stock_items.average_cost)",
+                ))
+                .first::<Option<BigDecimal>>(&mut conn),
+        }
+        .map_err(|e| e.to_string())?;
+
+        Ok(value.and_then(|v| v.to_f64()).unwrap_or(0.0))
+    }
+
+    /// Takes the item out of the catalogue while keeping its history. Only
+    /// items nobody holds or has on order any more can be archived.
+    pub async fn archive_stock_item(&self, item_id_val: i32) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            let (quantity, reserved) = stock_items::table
+                .find(item_id_val)
+                .filter(stock_items::archived_at.is_null())
+                .for_update()
+                .select((stock_items::quantity, stock_items::reserved_quantity))
+                .first::<(i32, i32)>(conn)
+                .optional()?
+                .ok_or_else(|| rejected("Stock item not found"))?;
+            if quantity != 0 || reserved != 0 {
+                return Err(rejected(format!(
+                    "There are still {} in stock; count them out before archiving the item",
+                    quantity
+                )));
+            }
+            let on_order = purchase_order_lines::table
+                .inner_join(purchase_orders::table)
+                .filter(purchase_order_lines::stock_item_id.eq(item_id_val))
+                .filter(purchase_orders::status.eq_any([
+                    PurchaseOrderStatusEnum::Draft,
+                    PurchaseOrderStatusEnum::Sent,
+                    PurchaseOrderStatusEnum::PartiallyReceived,
+                ]));
+            if diesel::select(diesel::dsl::exists(on_order)).get_result::<bool>(conn)? {
+                return Err(rejected(
+                    "The item is on an open purchase order; close the order before archiving it",
+                ));
+            }
+
+            diesel::update(stock_items::table.find(item_id_val))
+                .set(stock_items::archived_at.eq(Some(Utc::now())))
+                .execute(conn)?;
+            Ok(())
+        })
+        .map_err(String::from)
     }
 
     fn map_model_to_entity(&self, model: StockItemModel) -> StockItem {
@@ -107,6 +577,17 @@ impl StockItemRepository {
             name: model.name,
             price: model.price.to_string().parse::<f64>().unwrap_or(0.0),
             quantity: model.quantity,
+            reserved_quantity: model.reserved_quantity,
+            available_quantity: model.quantity - model.reserved_quantity,
+            reorder_point: model.reorder_point,
+            reorder_quantity: model.reorder_quantity,
+            sku: model.sku,
+            barcode: model.barcode,
+            category: model.category,
+            brand: model.brand,
+            unit: model.unit,
+            shelf_location: model.shelf_location,
+            average_cost: model.average_cost.to_f64().unwrap_or(0.0),
         }
     }
 }
@@ -0,0 +1,362 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::models::{
+    NewStockAlert, NewStockMovement, StockMovementModel, StockMovementReasonEnum,
+};
+use crate::infrastructure::db::schema::{stock_alerts, stock_items, stock_levels, stock_movements};
+use bigdecimal::BigDecimal;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use diesel::sql_types::{Integer, Nullable, Text};
+use serde::Serialize;
+
+/// A change to an item's on-hand quantity at one branch and why it happened
+pub struct StockChange {
+    pub stock_item_id: i32,
+    pub branch_id: i32,
+    pub delta: i32,
+    pub reason: StockMovementReasonEnum,
+    pub order_id: Option<i32>,
+    pub actor_id: Option<i32>,
+    pub note: Option<String>,
+    /// What each unit coming in cost; left out, it comes in at the current
+    /// average cost. Decreases always go out at the average cost.
+    pub unit_cost: Option<BigDecimal>,
+}
+
+/// The branch's quantity of the item after a change, and the cost each unit
+/// moved at
+pub struct AppliedStockChange {
+    pub quantity_after: i32,
+    pub unit_cost: BigDecimal,
+}
+
+/// An item whose counter disagrees with the sum of its movements, either in
+/// total or at one branch
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct StockDiscrepancy {
+    #[diesel(sql_type = Integer)]
+    pub item_id: i32,
+    /// Empty when it's the item's total that is off
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub branch_id: Option<i32>,
+    #[diesel(sql_type = Text)]
+    pub name: String,
+    #[diesel(sql_type = Integer)]
+    pub quantity: i32,
+    #[diesel(sql_type = Integer)]
+    pub ledger_quantity: i32,
+}
+
+/// Weighted average after `incoming` units at `cost` join `on_hand` units at
+/// `average`. When nothing was on hand the new units set the cost outright.
+fn moving_average(
+    on_hand: i32,
+    average: &BigDecimal,
+    incoming: i32,
+    cost: &BigDecimal,
+) -> BigDecimal {
+    if on_hand <= 0 {
+        return cost.clone();
+    }
+    let value = average * BigDecimal::from(on_hand) + cost * BigDecimal::from(incoming);
+    (value / BigDecimal::from(on_hand + incoming)).round(4)
+}
+
+/// Locks what the branch holds of the item, starting it at zero if the branch
+/// never had any. Returns the quantity on hand and the quantity reserved.
+pub fn lock_stock_level(
+    conn: &mut PgConnection,
+    stock_item_id: i32,
+    branch_id: i32,
+) -> QueryResult<(i32, i32)> {
+    diesel::insert_into(stock_levels::table)
+        .values((
+            stock_levels::stock_item_id.eq(stock_item_id),
+            stock_levels::branch_id.eq(branch_id),
+        ))
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+    stock_levels::table
+        .find((stock_item_id, branch_id))
+        .for_update()
+        .select((stock_levels::quantity, stock_levels::reserved_quantity))
+        .first::<(i32, i32)>(conn)
+}
+
+/// Moves the quantity the branch has promised to orders, keeping the item's
+/// total in step
+pub fn adjust_reserved(
+    conn: &mut PgConnection,
+    stock_item_id: i32,
+    branch_id: i32,
+    delta: i32,
+) -> QueryResult<()> {
+    diesel::update(stock_levels::table.find((stock_item_id, branch_id)))
+        .set(stock_levels::reserved_quantity.eq(stock_levels::reserved_quantity + delta))
+        .execute(conn)?;
+    diesel::update(stock_items::table.find(stock_item_id))
+        .set(stock_items::reserved_quantity.eq(stock_items::reserved_quantity + delta))
+        .execute(conn)?;
+    Ok(())
+}
+
+/// Adjusts the branch's quantity and the item's total and appends the
+/// matching movement. Every write to stock_items.quantity and
+/// stock_levels.quantity goes through here, inside the caller's transaction.
+/// Increases move the item's average cost, which is shared by all branches,
+/// towards what they came in at. A decrease that takes the item's total down
+/// to its reorder point also queues a low-stock alert, except mid-transfer.
+pub fn apply_stock_change(
+    conn: &mut PgConnection,
+    change: StockChange,
+) -> QueryResult<AppliedStockChange> {
+    let (quantity_before, average_cost) = stock_items::table
+        .find(change.stock_item_id)
+        .for_update()
+        .select((stock_items::quantity, stock_items::average_cost))
+        .first::<(i32, BigDecimal)>(conn)?;
+    let (branch_before, _) = lock_stock_level(conn, change.stock_item_id, change.branch_id)?;
+    let unit_cost = match change.unit_cost {
+        Some(cost) if change.delta > 0 => cost,
+        _ => average_cost.clone(),
+    };
+    if change.delta == 0 {
+        return Ok(AppliedStockChange {
+            quantity_after: branch_before,
+            unit_cost,
+        });
+    }
+
+    let average_after = if change.delta > 0 {
+        moving_average(quantity_before, &average_cost, change.delta, &unit_cost)
+    } else {
+        average_cost
+    };
+    let (total_after, reorder_point) =
+        diesel::update(stock_items::table.find(change.stock_item_id))
+            .set((
+                stock_items::quantity.eq(quantity_before + change.delta),
+                stock_items::average_cost.eq(average_after),
+            ))
+            .returning((stock_items::quantity, stock_items::reorder_point))
+            .get_result::<(i32, Option<i32>)>(conn)?;
+    let quantity_after =
+        diesel::update(stock_levels::table.find((change.stock_item_id, change.branch_id)))
+            .set(stock_levels::quantity.eq(branch_before + change.delta))
+            .returning(stock_levels::quantity)
+            .get_result::<i32>(conn)?;
+
+    diesel::insert_into(stock_movements::table)
+        .values(&NewStockMovement {
+            stock_item_id: change.stock_item_id,
+            branch_id: change.branch_id,
+            delta: change.delta,
+            reason: change.reason,
+            order_id: change.order_id,
+            actor_id: change.actor_id,
+            note: change.note,
+            quantity_after,
+            unit_cost: Some(unit_cost.clone()),
+        })
+        .execute(conn)?;
+
+    // A transfer's two legs leave the total where it was
+    if let Some(point) = reorder_point
+        && change.reason != StockMovementReasonEnum::Transfer
+        && total_after <= point
+        && total_after - change.delta > point
+    {
+        diesel::insert_into(stock_alerts::table)
+            .values(&NewStockAlert {
+                stock_item_id: change.stock_item_id,
+                quantity: total_after,
+                reorder_point: point,
+            })
+            .execute(conn)?;
+    }
+    Ok(AppliedStockChange {
+        quantity_after,
+        unit_cost,
+    })
+}
+
+#[derive(Clone)]
+pub struct StockMovementRepository {
+    pool: DbPool,
+}
+
+impl StockMovementRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// At every branch, or only the given ones
+    pub async fn list_for_item(
+        &self,
+        stock_item_id: i32,
+        branch_ids: Option<Vec<i32>>,
+        limit: i64,
+    ) -> Result<Vec<StockMovementModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let mut query = stock_movements::table
+            .filter(stock_movements::stock_item_id.eq(stock_item_id))
+            .into_boxed();
+        if let Some(branch_ids) = branch_ids {
+            query = query.filter(stock_movements::branch_id.eq_any(branch_ids));
+        }
+        query
+            .order(stock_movements::movement_id.desc())
+            .limit(limit)
+            .select(StockMovementModel::as_select())
+            .load::<StockMovementModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Recomputes every item's quantity, in total and per branch, from the
+    /// ledger and returns the ones that don't match
+    pub async fn find_discrepancies(&self) -> Result<Vec<StockDiscrepancy>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::sql_query(
+            "SELECT s.item_id, NULL::INT AS branch_id, s.name, s.quantity, \
+                    COALESCE(SUM(m.delta), 0)::INT AS ledger_quantity \
+             FROM stock_items s \
+             LEFT JOIN stock_movements m ON m.stock_item_id = s.item_id \
+             GROUP BY s.item_id, s.name, s.quantity \
+             HAVING s.quantity <> COALESCE(SUM(m.delta), 0) \
+             UNION ALL \
+             SELECT l.stock_item_id, l.branch_id, s.name, l.quantity, \
+                    COALESCE(SUM(m.delta), 0)::INT \
+             FROM stock_levels l \
+             JOIN stock_items s ON s.item_id = l.stock_item_id \
+             LEFT JOIN stock_movements m \
+                    ON m.stock_item_id = l.stock_item_id AND m.branch_id = l.branch_id \
+             GROUP BY l.stock_item_id, l.branch_id, s.name, l.quantity \
+             HAVING l.quantity <> COALESCE(SUM(m.delta), 0) \
+             ORDER BY 1, 2 NULLS FIRST",
+        )
+        .load::<StockDiscrepancy>(&mut conn)
+        .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::db::connection::test_pool;
+    use crate::infrastructure::db::repositories::test_support::{
+        assert_ledger_balanced, branch, decimal, item_state, level, stock_item,
+    };
+
+    fn change(stock_item_id: i32, branch_id: i32, delta: i32, cost: Option<&str>) -> StockChange {
+        StockChange {
+            stock_item_id,
+            branch_id,
+            delta,
+            reason: if delta > 0 {
+                StockMovementReasonEnum::Received
+            } else {
+                StockMovementReasonEnum::Adjustment
+            },
+            order_id: None,
+            actor_id: None,
+            note: None,
+            unit_cost: cost.map(decimal),
+        }
+    }
+
+    #[test]
+    fn moving_average_weights_by_quantity() {
+        let average = moving_average(10, &decimal("100"), 30, &decimal("120"));
+        assert_eq!(average, decimal("115"));
+    }
+
+    #[test]
+    fn moving_average_rounds_to_four_places() {
+        let average = moving_average(2, &decimal("10"), 1, &decimal("11"));
+        assert_eq!(average, decimal("10.3333"));
+    }
+
+    #[test]
+    fn moving_average_starts_over_when_nothing_is_on_hand() {
+        assert_eq!(
+            moving_average(0, &decimal("100"), 5, &decimal("80")),
+            decimal("80")
+        );
+        assert_eq!(
+            moving_average(-3, &decimal("100"), 5, &decimal("80")),
+            decimal("80")
+        );
+    }
+
+    #[test]
+    fn apply_stock_change_keeps_the_ledger_and_costs() {
+        let Some(pool) = test_pool() else {
+            return;
+        };
+        let mut conn = pool.get().unwrap();
+        let conn = &mut *conn;
+        let north = branch(conn);
+        let south = branch(conn);
+        let item = stock_item(conn, "ledger-test", "0");
+
+        let applied = apply_stock_change(conn, change(item, north, 10, Some("100"))).unwrap();
+        assert_eq!(applied.quantity_after, 10);
+        assert_eq!(applied.unit_cost, decimal("100"));
+        apply_stock_change(conn, change(item, south, 30, Some("120"))).unwrap();
+        assert_eq!(item_state(conn, item).2, decimal("115"));
+
+        // Going out never moves the average, and goes out at it
+        let applied = apply_stock_change(conn, change(item, north, -4, Some("999"))).unwrap();
+        assert_eq!(applied.quantity_after, 6);
+        assert_eq!(applied.unit_cost, decimal("115"));
+        assert_eq!(item_state(conn, item).2, decimal("115"));
+
+        // Coming in without a cost comes in at the average
+        let applied = apply_stock_change(conn, change(item, south, 4, None)).unwrap();
+        assert_eq!(applied.unit_cost, decimal("115"));
+        assert_eq!(item_state(conn, item).2, decimal("115"));
+
+        // Nothing moved, nothing booked
+        apply_stock_change(conn, change(item, north, 0, None)).unwrap();
+
+        assert_eq!(level(conn, item, north), (6, 0));
+        assert_eq!(level(conn, item, south), (34, 0));
+        assert_eq!(item_state(conn, item).0, 40);
+        let booked: i64 = stock_movements::table
+            .filter(stock_movements::stock_item_id.eq(item))
+            .count()
+            .get_result(conn)
+            .unwrap();
+        assert_eq!(booked, 4);
+        assert_ledger_balanced(conn, item);
+    }
+
+    #[test]
+    fn apply_stock_change_queues_an_alert_once_on_crossing_the_reorder_point() {
+        let Some(pool) = test_pool() else {
+            return;
+        };
+        let mut conn = pool.get().unwrap();
+        let conn = &mut *conn;
+        let shop = branch(conn);
+        let item = stock_item(conn, "ledger-test", "0");
+        diesel::update(stock_items::table.find(item))
+            .set(stock_items::reorder_point.eq(Some(5)))
+            .execute(conn)
+            .unwrap();
+
+        apply_stock_change(conn, change(item, shop, 8, Some("10"))).unwrap();
+        apply_stock_change(conn, change(item, shop, -3, None)).unwrap();
+        apply_stock_change(conn, change(item, shop, -1, None)).unwrap();
+
+        let alerts: Vec<i32> = stock_alerts::table
+            .filter(stock_alerts::stock_item_id.eq(item))
+            .select(stock_alerts::quantity)
+            .load(conn)
+            .unwrap();
+        assert_eq!(alerts, vec![5]);
+        assert_ledger_balanced(conn, item);
+    }
+}
@@ -0,0 +1,559 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::functions::lower;
+use crate::infrastructure::db::models::{
+    NewStockTake, NewStockTakeLine, StockMovementReasonEnum, StockTakeLineModel, StockTakeModel,
+    StockTakeStatusEnum,
+};
+use crate::infrastructure::db::repositories::stock_movement::{
+    StockChange, apply_stock_change, lock_stock_level,
+};
+use crate::infrastructure::db::schema::{stock_items, stock_levels, stock_take_lines, stock_takes};
+use crate::infrastructure::db::transaction::{TransactionError, rejected};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+pub struct CountInput {
+    pub stock_item_id: i32,
+    pub counted_quantity: i32,
+}
+
+/// A line together with the item it counts
+pub struct StockTakeLineDetail {
+    pub line: StockTakeLineModel,
+    pub name: String,
+    pub sku: Option<String>,
+    pub shelf_location: Option<String>,
+    /// The item's average cost now; posted lines keep their own
+    pub average_cost: BigDecimal,
+}
+
+pub struct StockTakeDetail {
+    pub stock_take: StockTakeModel,
+    pub lines: Vec<StockTakeLineDetail>,
+}
+
+fn lock_session(
+    conn: &mut PgConnection,
+    stock_take_id: i32,
+) -> Result<StockTakeModel, TransactionError> {
+    let session = stock_takes::table
+        .find(stock_take_id)
+        .for_update()
+        .select(StockTakeModel::as_select())
+        .first::<StockTakeModel>(conn)
+        .optional()?
+        .ok_or_else(|| rejected("Stock take not found"))?;
+    if session.status != StockTakeStatusEnum::Open {
+        return Err(rejected("This stock take is already closed"));
+    }
+    Ok(session)
+}
+
+fn load_detail(
+    conn: &mut PgConnection,
+    stock_take_id: i32,
+) -> QueryResult<Option<StockTakeDetail>> {
+    let Some(stock_take) = stock_takes::table
+        .find(stock_take_id)
+        .select(StockTakeModel::as_select())
+        .first::<StockTakeModel>(conn)
+        .optional()?
+    else {
+        return Ok(None);
+    };
+    let lines = stock_take_lines::table
+        .inner_join(stock_items::table)
+        .filter(stock_take_lines::stock_take_id.eq(stock_take_id))
+        .order((
+            stock_items::shelf_location.asc().nulls_last(),
+            stock_items::name.asc(),
+        ))
+        .select((
+            StockTakeLineModel::as_select(),
+            stock_items::name,
+            stock_items::sku,
+            stock_items::shelf_location,
+            stock_items::average_cost,
+        ))
+        .load::<(
+            StockTakeLineModel,
+            String,
+            Option<String>,
+            Option<String>,
+            BigDecimal,
+        )>(conn)?
+        .into_iter()
+        .map(
+            |(line, name, sku, shelf_location, average_cost)| StockTakeLineDetail {
+                line,
+                name,
+                sku,
+                shelf_location,
+                average_cost,
+            },
+        )
+        .collect();
+
+    Ok(Some(StockTakeDetail { stock_take, lines }))
+}
+
+#[derive(Clone)]
+pub struct StockTakeRepository {
+    pool: DbPool,
+}
+
+impl StockTakeRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Newest first, across the shop or at the given branches
+    pub async fn list(
+        &self,
+        status: Option<StockTakeStatusEnum>,
+        branch_ids: Option<Vec<i32>>,
+    ) -> Result<Vec<StockTakeModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let mut query = stock_takes::table
+            .select(StockTakeModel::as_select())
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(stock_takes::status.eq(status));
+        }
+        if let Some(branch_ids) = branch_ids {
+            query = query.filter(stock_takes::branch_id.eq_any(branch_ids));
+        }
+        query
+            .order(stock_takes::stock_take_id.desc())
+            .load::<StockTakeModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn find_detail(&self, stock_take_id: i32) -> Result<Option<StockTakeDetail>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        load_detail(&mut conn, stock_take_id).map_err(|e| e.to_string())
+    }
+
+    /// Starts a count at the branch of every item, or of one category,
+    /// noting what the branch holds of each. An item can only be in one open
+    /// stock take per branch.
+    pub async fn open(
+        &self,
+        category: Option<String>,
+        notes: Option<String>,
+        opened_by: i32,
+        branch_id: i32,
+    ) -> Result<StockTakeModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            // Locking the items keeps two sessions from taking the same item
+            let in_scope = stock_items::table
+                .filter(stock_items::archived_at.is_null())
+                .select(stock_items::item_id)
+                .order(stock_items::item_id.asc());
+            let ids = match &category {
+                Some(category) => in_scope
+                    .filter(
+                        lower(stock_items::category.assume_not_null()).eq(category.to_lowercase()),
+                    )
+                    .for_update()
+                    .load::<i32>(conn)?,
+                None => in_scope.for_update().load::<i32>(conn)?,
+            };
+            if ids.is_empty() {
+                return Err(rejected(match &category {
+                    Some(category) => format!("No stock items in category {}", category),
+                    None => "There are no stock items to count".to_string(),
+                }));
+            }
+
+            let busy: Vec<i32> = stock_take_lines::table
+                .inner_join(stock_takes::table)
+                .filter(stock_takes::status.eq(StockTakeStatusEnum::Open))
+                .filter(stock_takes::branch_id.eq(branch_id))
+                .filter(stock_take_lines::stock_item_id.eq_any(&ids))
+                .select(stock_takes::stock_take_id)
+                .distinct()
+                .load(conn)?;
+            if let Some(other) = busy.first() {
+                return Err(rejected(format!(
+                    "Stock take #{} is still counting some of these items",
+                    other
+                )));
+            }
+
+            let session = diesel::insert_into(stock_takes::table)
+                .values(&NewStockTake {
+                    branch_id,
+                    category,
+                    notes,
+                    opened_by: Some(opened_by),
+                })
+                .returning(StockTakeModel::as_returning())
+                .get_result::<StockTakeModel>(conn)?;
+            let held: HashMap<i32, i32> = stock_levels::table
+                .filter(stock_levels::branch_id.eq(branch_id))
+                .filter(stock_levels::stock_item_id.eq_any(&ids))
+                .select((stock_levels::stock_item_id, stock_levels::quantity))
+                .load::<(i32, i32)>(conn)?
+                .into_iter()
+                .collect();
+            let lines: Vec<NewStockTakeLine> = ids
+                .into_iter()
+                .map(|stock_item_id| NewStockTakeLine {
+                    stock_take_id: session.stock_take_id,
+                    stock_item_id,
+                    system_quantity: held.get(&stock_item_id).copied().unwrap_or(0),
+                })
+                .collect();
+            diesel::insert_into(stock_take_lines::table)
+                .values(&lines)
+                .execute(conn)?;
+            Ok(session)
+        })
+        .map_err(String::from)
+    }
+
+    /// Saves counts against an open stock take. Each count replaces any
+    /// earlier one for the item and takes a fresh note of what the branch
+    /// holds, so several people can count different shelves at once.
+    pub async fn record_counts(
+        &self,
+        stock_take_id: i32,
+        counts: Vec<CountInput>,
+        counted_by: i32,
+        now: DateTime<Utc>,
+    ) -> Result<StockTakeDetail, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            // Shared lock: counters don't block each other, posting waits
+            let session = stock_takes::table
+                .find(stock_take_id)
+                .for_share()
+                .select(StockTakeModel::as_select())
+                .first::<StockTakeModel>(conn)
+                .optional()?
+                .ok_or_else(|| rejected("Stock take not found"))?;
+            if session.status != StockTakeStatusEnum::Open {
+                return Err(rejected("This stock take is already closed"));
+            }
+            if counts.is_empty() {
+                return Err(rejected("Record at least one count"));
+            }
+
+            let mut seen = HashSet::new();
+            for count in counts {
+                if count.counted_quantity < 0 {
+                    return Err(rejected("Counted quantities can't be negative"));
+                }
+                if !seen.insert(count.stock_item_id) {
+                    return Err(rejected("Each item can only be counted once per request"));
+                }
+                let system_quantity = stock_levels::table
+                    .find((count.stock_item_id, session.branch_id))
+                    .select(stock_levels::quantity)
+                    .first::<i32>(conn)
+                    .optional()?
+                    .unwrap_or(0);
+                let updated = diesel::update(
+                    stock_take_lines::table
+                        .filter(stock_take_lines::stock_take_id.eq(stock_take_id))
+                        .filter(stock_take_lines::stock_item_id.eq(count.stock_item_id)),
+                )
+                .set((
+                    stock_take_lines::system_quantity.eq(system_quantity),
+                    stock_take_lines::counted_quantity.eq(Some(count.counted_quantity)),
+                    stock_take_lines::counted_by.eq(Some(counted_by)),
+                    stock_take_lines::counted_at.eq(Some(now)),
+                ))
+                .execute(conn)?;
+                if updated == 0 {
+                    return Err(rejected(format!(
+                        "Stock item {} is not part of this stock take",
+                        count.stock_item_id
+                    )));
+                }
+            }
+
+            load_detail(conn, stock_take_id)?.ok_or_else(|| rejected("Stock take not found"))
+        })
+        .map_err(String::from)
+    }
+
+    /// Books every counted line's variance into the branch's stock as a
+    /// stock-take movement at the item's average cost, which the line keeps
+    /// for the report. Items nobody counted are left alone.
+    pub async fn post(
+        &self,
+        stock_take_id: i32,
+        posted_by: i32,
+        now: DateTime<Utc>,
+    ) -> Result<StockTakeDetail, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            let session = lock_session(conn, stock_take_id)?;
+
+            let lines = stock_take_lines::table
+                .filter(stock_take_lines::stock_take_id.eq(stock_take_id))
+                .filter(stock_take_lines::counted_quantity.is_not_null())
+                .order(stock_take_lines::stock_item_id.asc())
+                .select(StockTakeLineModel::as_select())
+                .load::<StockTakeLineModel>(conn)?;
+            if lines.is_empty() {
+                return Err(rejected("Nothing has been counted yet"));
+            }
+
+            let note = format!("Stock take #{}", stock_take_id);
+            for line in lines {
+                let Some(counted) = line.counted_quantity else {
+                    continue;
+                };
+                let name = stock_items::table
+                    .find(line.stock_item_id)
+                    .for_update()
+                    .select(stock_items::name)
+                    .first::<String>(conn)?;
+                let (on_hand, reserved) =
+                    lock_stock_level(conn, line.stock_item_id, session.branch_id)?;
+                let variance = counted - line.system_quantity;
+                if on_hand + variance < 0 {
+                    return Err(rejected(format!(
+                        "Only {} of {} left, too few to book a variance of {}",
+                        on_hand, name, variance
+                    )));
+                }
+                // The count can't leave less than open orders are holding
+                if on_hand + variance < reserved {
+                    return Err(rejected(format!(
+                        "{} of {} are held for open orders but only {} would be left; \
+                         take them off the orders first",
+                        reserved,
+                        name,
+                        on_hand + variance
+                    )));
+                }
+
+                let applied = apply_stock_change(
+                    conn,
+                    StockChange {
+                        stock_item_id: line.stock_item_id,
+                        branch_id: session.branch_id,
+                        delta: variance,
+                        reason: StockMovementReasonEnum::StockTake,
+                        order_id: None,
+                        actor_id: Some(posted_by),
+                        note: Some(note.clone()),
+                        unit_cost: None,
+                    },
+                )?;
+                diesel::update(stock_take_lines::table.find(line.line_id))
+                    .set(stock_take_lines::unit_cost.eq(Some(applied.unit_cost)))
+                    .execute(conn)?;
+            }
+
+            diesel::update(stock_takes::table.find(stock_take_id))
+                .set((
+                    stock_takes::status.eq(StockTakeStatusEnum::Posted),
+                    stock_takes::posted_by.eq(Some(posted_by)),
+                    stock_takes::closed_at.eq(Some(now)),
+                ))
+                .execute(conn)?;
+
+            load_detail(conn, stock_take_id)?.ok_or_else(|| rejected("Stock take not found"))
+        })
+        .map_err(String::from)
+    }
+
+    /// Drops an open stock take without touching stock
+    pub async fn cancel(
+        &self,
+        stock_take_id: i32,
+        now: DateTime<Utc>,
+    ) -> Result<StockTakeModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            lock_session(conn, stock_take_id)?;
+
+            diesel::update(stock_takes::table.find(stock_take_id))
+                .set((
+                    stock_takes::status.eq(StockTakeStatusEnum::Cancelled),
+                    stock_takes::closed_at.eq(Some(now)),
+                ))
+                .returning(StockTakeModel::as_returning())
+                .get_result::<StockTakeModel>(conn)
+                .map_err(TransactionError::from)
+        })
+        .map_err(String::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::db::connection::test_pool;
+    use crate::infrastructure::db::models::UserRoleEnum;
+    use crate::infrastructure::db::repositories::stock_movement::adjust_reserved;
+    use crate::infrastructure::db::repositories::test_support::{
+        assert_ledger_balanced, branch, decimal, level, stock_item, user,
+    };
+
+    #[tokio::test]
+    async fn posting_books_only_the_counted_variances() {
+        let Some(pool) = test_pool() else {
+            return;
+        };
+        let category = format!("stock-take-test-{}", std::process::id());
+        let (shop, counter, items) = {
+            let mut conn = pool.get().unwrap();
+            let conn = &mut *conn;
+            let shop = branch(conn);
+            let counter = user(conn, UserRoleEnum::Admin);
+            let mut items = Vec::new();
+            for on_hand in [10, 4, 5] {
+                let item = stock_item(conn, &category, "20");
+                apply_stock_change(
+                    conn,
+                    StockChange {
+                        stock_item_id: item,
+                        branch_id: shop,
+                        delta: on_hand,
+                        reason: StockMovementReasonEnum::Opening,
+                        order_id: None,
+                        actor_id: None,
+                        note: None,
+                        unit_cost: Some(decimal("20")),
+                    },
+                )
+                .unwrap();
+                items.push(item);
+            }
+            (shop, counter, items)
+        };
+        let repo = StockTakeRepository::new(pool.clone());
+
+        let session = repo
+            .open(Some(category.clone()), None, counter, shop)
+            .await
+            .unwrap();
+        assert!(
+            repo.open(Some(category), None, counter, shop)
+                .await
+                .is_err(),
+            "the same items can't be in two open stock takes"
+        );
+        repo.record_counts(
+            session.stock_take_id,
+            vec![
+                CountInput {
+                    stock_item_id: items[0],
+                    counted_quantity: 7,
+                },
+                CountInput {
+                    stock_item_id: items[1],
+                    counted_quantity: 6,
+                },
+            ],
+            counter,
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        let posted = repo
+            .post(session.stock_take_id, counter, Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(posted.stock_take.status, StockTakeStatusEnum::Posted);
+        for detail in posted
+            .lines
+            .iter()
+            .filter(|l| l.line.stock_item_id != items[2])
+        {
+            assert_eq!(detail.line.unit_cost, Some(decimal("20")));
+        }
+        assert!(
+            repo.post(session.stock_take_id, counter, Utc::now())
+                .await
+                .is_err(),
+            "a stock take only posts once"
+        );
+
+        let mut conn = pool.get().unwrap();
+        let conn = &mut *conn;
+        assert_eq!(level(conn, items[0], shop), (7, 0));
+        assert_eq!(level(conn, items[1], shop), (6, 0));
+        assert_eq!(level(conn, items[2], shop), (5, 0));
+        for item in items {
+            assert_ledger_balanced(conn, item);
+        }
+    }
+
+    #[tokio::test]
+    async fn a_count_below_what_orders_hold_is_not_posted() {
+        let Some(pool) = test_pool() else {
+            return;
+        };
+        let category = format!("stock-take-held-test-{}", std::process::id());
+        let (shop, counter, item) = {
+            let mut conn = pool.get().unwrap();
+            let conn = &mut *conn;
+            let shop = branch(conn);
+            let counter = user(conn, UserRoleEnum::Admin);
+            let item = stock_item(conn, &category, "20");
+            apply_stock_change(
+                conn,
+                StockChange {
+                    stock_item_id: item,
+                    branch_id: shop,
+                    delta: 5,
+                    reason: StockMovementReasonEnum::Opening,
+                    order_id: None,
+                    actor_id: None,
+                    note: None,
+                    unit_cost: Some(decimal("20")),
+                },
+            )
+            .unwrap();
+            adjust_reserved(conn, item, shop, 3).unwrap();
+            (shop, counter, item)
+        };
+        let repo = StockTakeRepository::new(pool.clone());
+
+        let session = repo
+            .open(Some(category), None, counter, shop)
+            .await
+            .unwrap();
+        repo.record_counts(
+            session.stock_take_id,
+            vec![CountInput {
+                stock_item_id: item,
+                counted_quantity: 2,
+            }],
+            counter,
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        assert!(
+            repo.post(session.stock_take_id, counter, Utc::now())
+                .await
+                .is_err()
+        );
+        repo.cancel(session.stock_take_id, Utc::now())
+            .await
+            .unwrap();
+
+        let mut conn = pool.get().unwrap();
+        let conn = &mut *conn;
+        assert_eq!(level(conn, item, shop), (5, 3));
+        assert_ledger_balanced(conn, item);
+    }
+}
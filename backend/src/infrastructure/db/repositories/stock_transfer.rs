@@ -0,0 +1,270 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::models::{
+    NewStockTransfer, NewStockTransferLine, StockMovementReasonEnum, StockTransferLineModel,
+    StockTransferModel,
+};
+use crate::infrastructure::db::repositories::stock_movement::{
+    StockChange, apply_stock_change, lock_stock_level,
+};
+use crate::infrastructure::db::schema::{stock_items, stock_transfer_lines, stock_transfers};
+use crate::infrastructure::db::transaction::{TransactionError, rejected};
+use diesel::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+pub struct TransferLineInput {
+    pub stock_item_id: i32,
+    pub quantity: i32,
+}
+
+pub struct StockTransferDetail {
+    pub transfer: StockTransferModel,
+    pub lines: Vec<StockTransferLineModel>,
+}
+
+#[derive(Clone)]
+pub struct StockTransferRepository {
+    pool: DbPool,
+}
+
+impl StockTransferRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Newest first; with branches, only transfers leaving or reaching them
+    pub async fn list(
+        &self,
+        branch_ids: Option<Vec<i32>>,
+    ) -> Result<Vec<StockTransferDetail>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let mut query = stock_transfers::table
+            .select(StockTransferModel::as_select())
+            .into_boxed();
+        if let Some(branch_ids) = branch_ids {
+            query = query.filter(
+                stock_transfers::from_branch_id
+                    .eq_any(branch_ids.clone())
+                    .or(stock_transfers::to_branch_id.eq_any(branch_ids)),
+            );
+        }
+        let transfers = query
+            .order(stock_transfers::transfer_id.desc())
+            .load::<StockTransferModel>(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        let ids: Vec<i32> = transfers.iter().map(|t| t.transfer_id).collect();
+        let mut lines: HashMap<i32, Vec<StockTransferLineModel>> = HashMap::new();
+        for line in stock_transfer_lines::table
+            .filter(stock_transfer_lines::transfer_id.eq_any(&ids))
+            .order(stock_transfer_lines::line_id.asc())
+            .select(StockTransferLineModel::as_select())
+            .load::<StockTransferLineModel>(&mut conn)
+            .map_err(|e| e.to_string())?
+        {
+            lines.entry(line.transfer_id).or_default().push(line);
+        }
+
+        Ok(transfers
+            .into_iter()
+            .map(|transfer| StockTransferDetail {
+                lines: lines.remove(&transfer.transfer_id).unwrap_or_default(),
+                transfer,
+            })
+            .collect())
+    }
+
+    /// Moves stock from one branch's shelves to another's. Each line leaves
+    /// as a transfer movement at the item's average cost and arrives at the
+    /// same cost, so the shop's totals and costs don't change. Only stock
+    /// that isn't promised to an order can go.
+    pub async fn create(
+        &self,
+        transfer: NewStockTransfer,
+        lines: Vec<TransferLineInput>,
+    ) -> Result<StockTransferDetail, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            if transfer.from_branch_id == transfer.to_branch_id {
+                return Err(rejected("Pick two different branches"));
+            }
+            if lines.is_empty() {
+                return Err(rejected("A transfer needs at least one line"));
+            }
+            let mut seen = HashSet::new();
+            for line in &lines {
+                if line.quantity <= 0 {
+                    return Err(rejected("Quantities must be positive"));
+                }
+                if !seen.insert(line.stock_item_id) {
+                    return Err(rejected("Each item can only appear once per transfer"));
+                }
+            }
+
+            let from_branch_id = transfer.from_branch_id;
+            let to_branch_id = transfer.to_branch_id;
+            let created_by = transfer.created_by;
+            let header = diesel::insert_into(stock_transfers::table)
+                .values(&transfer)
+                .returning(StockTransferModel::as_returning())
+                .get_result::<StockTransferModel>(conn)?;
+            let note = format!("Transfer #{}", header.transfer_id);
+
+            let mut lines = lines;
+            // Lock items in a fixed order so two transfers never deadlock
+            lines.sort_by_key(|line| line.stock_item_id);
+            let mut rows = Vec::with_capacity(lines.len());
+            for line in lines {
+                let name = stock_items::table
+                    .find(line.stock_item_id)
+                    .filter(stock_items::archived_at.is_null())
+                    .for_update()
+                    .select(stock_items::name)
+                    .first::<String>(conn)
+                    .optional()?
+                    .ok_or_else(|| {
+                        rejected(format!("Stock item {} not found", line.stock_item_id))
+                    })?;
+                let (on_hand, reserved) =
+                    lock_stock_level(conn, line.stock_item_id, from_branch_id)?;
+                if on_hand - reserved < line.quantity {
+                    return Err(rejected(format!(
+                        "Only {} of {} free to send, {} requested",
+                        on_hand - reserved,
+                        name,
+                        line.quantity
+                    )));
+                }
+
+                let sent = apply_stock_change(
+                    conn,
+                    StockChange {
+                        stock_item_id: line.stock_item_id,
+                        branch_id: from_branch_id,
+                        delta: -line.quantity,
+                        reason: StockMovementReasonEnum::Transfer,
+                        order_id: None,
+                        actor_id: created_by,
+                        note: Some(note.clone()),
+                        unit_cost: None,
+                    },
+                )?;
+                apply_stock_change(
+                    conn,
+                    StockChange {
+                        stock_item_id: line.stock_item_id,
+                        branch_id: to_branch_id,
+                        delta: line.quantity,
+                        reason: StockMovementReasonEnum::Transfer,
+                        order_id: None,
+                        actor_id: created_by,
+                        note: Some(note.clone()),
+                        unit_cost: Some(sent.unit_cost.clone()),
+                    },
+                )?;
+                rows.push(NewStockTransferLine {
+                    transfer_id: header.transfer_id,
+                    stock_item_id: line.stock_item_id,
+                    quantity: line.quantity,
+                    unit_cost: sent.unit_cost,
+                });
+            }
+
+            let lines = diesel::insert_into(stock_transfer_lines::table)
+                .values(&rows)
+                .returning(StockTransferLineModel::as_returning())
+                .get_results::<StockTransferLineModel>(conn)?;
+            Ok(StockTransferDetail {
+                transfer: header,
+                lines,
+            })
+        })
+        .map_err(String::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::db::connection::test_pool;
+    use crate::infrastructure::db::repositories::stock_movement::adjust_reserved;
+    use crate::infrastructure::db::repositories::test_support::{
+        assert_ledger_balanced, branch, decimal, item_state, level, stock_item,
+    };
+
+    #[tokio::test]
+    async fn transfer_moves_free_stock_without_changing_totals_or_cost() {
+        let Some(pool) = test_pool() else {
+            return;
+        };
+        let (north, south, item) = {
+            let mut conn = pool.get().unwrap();
+            let conn = &mut *conn;
+            let north = branch(conn);
+            let south = branch(conn);
+            let item = stock_item(conn, "transfer-test", "0");
+            for (branch_id, quantity, cost) in [(north, 10, "30"), (south, 2, "45")] {
+                apply_stock_change(
+                    conn,
+                    StockChange {
+                        stock_item_id: item,
+                        branch_id,
+                        delta: quantity,
+                        reason: StockMovementReasonEnum::Received,
+                        order_id: None,
+                        actor_id: None,
+                        note: None,
+                        unit_cost: Some(decimal(cost)),
+                    },
+                )
+                .unwrap();
+            }
+            adjust_reserved(conn, item, north, 4).unwrap();
+            (north, south, item)
+        };
+        let repo = StockTransferRepository::new(pool.clone());
+
+        let refused = repo
+            .create(
+                NewStockTransfer {
+                    from_branch_id: north,
+                    to_branch_id: south,
+                    note: None,
+                    created_by: None,
+                },
+                vec![TransferLineInput {
+                    stock_item_id: item,
+                    quantity: 7,
+                }],
+            )
+            .await;
+        assert!(refused.is_err(), "reserved stock can't be sent");
+
+        let sent = repo
+            .create(
+                NewStockTransfer {
+                    from_branch_id: north,
+                    to_branch_id: south,
+                    note: None,
+                    created_by: None,
+                },
+                vec![TransferLineInput {
+                    stock_item_id: item,
+                    quantity: 6,
+                }],
+            )
+            .await
+            .unwrap();
+        assert_eq!(sent.lines[0].unit_cost, decimal("32.5"));
+
+        let mut conn = pool.get().unwrap();
+        let conn = &mut *conn;
+        assert_eq!(level(conn, item, north), (4, 4));
+        assert_eq!(level(conn, item, south), (8, 0));
+        let (quantity, reserved, average_cost) = item_state(conn, item);
+        assert_eq!((quantity, reserved), (12, 4));
+        assert_eq!(average_cost, decimal("32.5"));
+        assert_ledger_balanced(conn, item);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::functions::lower;
+use crate::infrastructure::db::models::{SupplierChangeset, SupplierModel};
+use crate::infrastructure::db::schema::suppliers;
+use diesel::prelude::*;
+
+#[derive(Clone)]
+pub struct SupplierRepository {
+    pool: DbPool,
+}
+
+impl SupplierRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<SupplierModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        suppliers::table
+            .order(suppliers::name.asc())
+            .select(SupplierModel::as_select())
+            .load::<SupplierModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn find_by_id(&self, supplier_id: i32) -> Result<Option<SupplierModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        suppliers::table
+            .find(supplier_id)
+            .select(SupplierModel::as_select())
+            .first::<SupplierModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Names are unique regardless of case
+    pub async fn find_by_name(&self, name: &str) -> Result<Option<SupplierModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        suppliers::table
+            .filter(lower(suppliers::name).eq(name.to_lowercase()))
+            .select(SupplierModel::as_select())
+            .first::<SupplierModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn create(&self, supplier: SupplierChangeset) -> Result<SupplierModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::insert_into(suppliers::table)
+            .values(&supplier)
+            .returning(SupplierModel::as_returning())
+            .get_result(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn update(
+        &self,
+        supplier_id: i32,
+        supplier: SupplierChangeset,
+    ) -> Result<SupplierModel, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::update(suppliers::table.find(supplier_id))
+            .set(&supplier)
+            .returning(SupplierModel::as_returning())
+            .get_result(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+}
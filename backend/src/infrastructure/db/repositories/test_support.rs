@@ -0,0 +1,195 @@
+//! Rows the database tests build on, and the checks they share. Everything is
+//! written inside the test pool's transaction and never committed.
+
+use crate::infrastructure::db::models::{
+    NewStockItem, ServiceItemStockStatusEnum, ServiceOrderStatusEnum, UserRoleEnum,
+};
+use crate::infrastructure::db::schema::{
+    branches, service_items, service_orders, stock_items, stock_levels, stock_movements, users,
+};
+use bigdecimal::BigDecimal;
+use diesel::dsl::sum;
+use diesel::pg::PgConnection;
+use diesel::prelude::*;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static NEXT: AtomicU32 = AtomicU32::new(0);
+
+/// A name no other test running at the same time will pick
+fn unique(prefix: &str) -> String {
+    format!(
+        "{}-{}-{}",
+        prefix,
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+pub fn decimal(value: &str) -> BigDecimal {
+    BigDecimal::from_str(value).expect("valid decimal")
+}
+
+pub fn branch(conn: &mut PgConnection) -> i32 {
+    diesel::insert_into(branches::table)
+        .values(branches::name.eq(unique("Test branch")))
+        .returning(branches::branch_id)
+        .get_result(conn)
+        .expect("insert branch")
+}
+
+pub fn user(conn: &mut PgConnection, role: UserRoleEnum) -> i32 {
+    let username = unique("test-user");
+    diesel::insert_into(users::table)
+        .values((
+            users::username.eq(&username),
+            users::password_hash.eq("x"),
+            users::name.eq(&username),
+            users::phone.eq(&username),
+            users::role.eq(role),
+        ))
+        .returning(users::user_id)
+        .get_result(conn)
+        .expect("insert user")
+}
+
+/// An empty item carried at `average_cost`
+pub fn stock_item(conn: &mut PgConnection, category: &str, average_cost: &str) -> i32 {
+    let name = unique("Test part");
+    diesel::insert_into(stock_items::table)
+        .values(&NewStockItem {
+            name: &name,
+            price: decimal("100"),
+            quantity: 0,
+            reorder_point: None,
+            reorder_quantity: None,
+            sku: None,
+            barcode: None,
+            category: Some(category),
+            brand: None,
+            unit: "pcs",
+            shelf_location: None,
+            average_cost: decimal(average_cost),
+        })
+        .returning(stock_items::item_id)
+        .get_result(conn)
+        .expect("insert stock item")
+}
+
+pub fn order(
+    conn: &mut PgConnection,
+    customer_id: i32,
+    branch_id: i32,
+    status: ServiceOrderStatusEnum,
+) -> i32 {
+    diesel::insert_into(service_orders::table)
+        .values((
+            service_orders::customer_id.eq(customer_id),
+            service_orders::created_by.eq(customer_id),
+            service_orders::status.eq(status),
+            service_orders::total_price.eq(decimal("0")),
+            service_orders::branch_id.eq(branch_id),
+        ))
+        .returning(service_orders::order_id)
+        .get_result(conn)
+        .expect("insert order")
+}
+
+/// A part line on the order that already holds `quantity` of the item in the
+/// given way. The caller moves the stock to match.
+pub fn part_line(
+    conn: &mut PgConnection,
+    order_id: i32,
+    stock_item_id: i32,
+    quantity: i32,
+    status: ServiceItemStockStatusEnum,
+    unit_cost: Option<&str>,
+) -> i32 {
+    diesel::insert_into(service_items::table)
+        .values((
+            service_items::order_id.eq(order_id),
+            service_items::description.eq("Test part"),
+            service_items::price.eq(decimal("0")),
+            service_items::stock_item_id.eq(Some(stock_item_id)),
+            service_items::quantity.eq(quantity),
+            service_items::unit_cost.eq(unit_cost.map(decimal)),
+            service_items::stock_status.eq(Some(status)),
+        ))
+        .returning(service_items::item_id)
+        .get_result(conn)
+        .expect("insert service item")
+}
+
+/// The item's (quantity, reserved, average cost)
+pub fn item_state(conn: &mut PgConnection, stock_item_id: i32) -> (i32, i32, BigDecimal) {
+    stock_items::table
+        .find(stock_item_id)
+        .select((
+            stock_items::quantity,
+            stock_items::reserved_quantity,
+            stock_items::average_cost,
+        ))
+        .first(conn)
+        .expect("load stock item")
+}
+
+/// What the branch holds of the item: (quantity, reserved)
+pub fn level(conn: &mut PgConnection, stock_item_id: i32, branch_id: i32) -> (i32, i32) {
+    stock_levels::table
+        .find((stock_item_id, branch_id))
+        .select((stock_levels::quantity, stock_levels::reserved_quantity))
+        .first(conn)
+        .optional()
+        .expect("load stock level")
+        .unwrap_or((0, 0))
+}
+
+/// The item's quantity, in total and at every branch, is the sum of its
+/// movements there, and its reservations add up across branches
+pub fn assert_ledger_balanced(conn: &mut PgConnection, stock_item_id: i32) {
+    let (quantity, reserved, _) = item_state(conn, stock_item_id);
+    let ledger: Option<i64> = stock_movements::table
+        .filter(stock_movements::stock_item_id.eq(stock_item_id))
+        .select(sum(stock_movements::delta))
+        .first(conn)
+        .expect("sum movements");
+    assert_eq!(
+        i64::from(quantity),
+        ledger.unwrap_or(0),
+        "item total is off the ledger"
+    );
+
+    let levels: Vec<(i32, i32, i32)> = stock_levels::table
+        .filter(stock_levels::stock_item_id.eq(stock_item_id))
+        .select((
+            stock_levels::branch_id,
+            stock_levels::quantity,
+            stock_levels::reserved_quantity,
+        ))
+        .load(conn)
+        .expect("load stock levels");
+    for (branch_id, branch_quantity, _) in &levels {
+        let ledger: Option<i64> = stock_movements::table
+            .filter(stock_movements::stock_item_id.eq(stock_item_id))
+            .filter(stock_movements::branch_id.eq(branch_id))
+            .select(sum(stock_movements::delta))
+            .first(conn)
+            .expect("sum branch movements");
+        assert_eq!(
+            i64::from(*branch_quantity),
+            ledger.unwrap_or(0),
+            "branch {} is off the ledger",
+            branch_id
+        );
+    }
+    assert_eq!(
+        quantity,
+        levels.iter().map(|(_, q, _)| q).sum::<i32>(),
+        "branches don't add up to the item total"
+    );
+    assert_eq!(
+        reserved,
+        levels.iter().map(|(_, _, r)| r).sum::<i32>(),
+        "branch reservations don't add up to the item's"
+    );
+}
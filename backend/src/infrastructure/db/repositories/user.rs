@@ -1,8 +1,15 @@
+use crate::domain::audit::AuditEvent;
 use crate::domain::user::entity::{Role, User};
+use crate::domain::value_objects::PhoneNumber;
 use crate::infrastructure::db::connection::DbPool;
 use crate::infrastructure::db::models::{NewUser, UserModel, UserRoleEnum};
+use crate::infrastructure::db::repositories::audit_event::insert_event;
 use crate::infrastructure::db::schema::users;
+use crate::infrastructure::db::transaction::{TransactionError, rejected};
+use chrono::{DateTime, Utc};
+use diesel::dsl::sql;
 use diesel::prelude::*;
+use diesel::sql_types::Text;
 
 #[derive(Clone)]
 pub struct UserRepository {
@@ -29,6 +36,9 @@ impl UserRepository {
             name: &user.name,
             phone: &user.phone,
             role: new_user_role,
+            must_change_password: user.must_change_password,
+            is_guest: user.is_guest,
+            phone_verified_at: user.phone_verified_at,
         };
 
         let result = diesel::insert_into(users::table)
@@ -53,6 +63,58 @@ impl UserRepository {
         Ok(result.map(|model| self.map_model_to_entity(model)))
     }
 
+    pub async fn find_by_phone(&self, phone: &str) -> Result<Option<User>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let result = users::table
+            .filter(users::phone.eq(phone))
+            .filter(users::is_guest.eq(false))
+            .select(UserModel::as_select())
+            .first::<UserModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())?;
+
+        Ok(result.map(|model| self.map_model_to_entity(model)))
+    }
+
+    /// Registered account whose phone matches, however it was typed
+    pub async fn find_account_by_phone(&self, phone: &PhoneNumber) -> Result<Option<User>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let international = format!("66{}", &phone.value()[1..]);
+        let result = users::table
+            .filter(users::is_guest.eq(false))
+            .filter(
+                sql::<Text>("regexp_replace(users.phone, '\\D', '', 'g')")
+                    .eq_any([phone.value().to_string(), international]),
+            )
+            .select(UserModel::as_select())
+            .first::<UserModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())?;
+
+        Ok(result.map(|model| self.map_model_to_entity(model)))
+    }
+
+    /// Guest recorded at the counter with this phone and not yet claimed
+    pub async fn find_open_guest_by_phone(
+        &self,
+        phone: &PhoneNumber,
+    ) -> Result<Option<User>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let result = users::table
+            .filter(users::is_guest.eq(true))
+            .filter(users::merged_into_user_id.is_null())
+            .filter(users::phone.eq(phone.value()))
+            .select(UserModel::as_select())
+            .first::<UserModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())?;
+
+        Ok(result.map(|model| self.map_model_to_entity(model)))
+    }
+
     pub async fn find_by_id(&self, user_id: i32) -> Result<Option<User>, String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
 
@@ -110,6 +172,112 @@ impl UserRepository {
             .collect())
     }
 
+    /// Deactivating the last active admin is refused
+    pub async fn set_active(
+        &self,
+        user_id: i32,
+        active: bool,
+        event: AuditEvent,
+    ) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let deactivated_at = if active {
+            None
+        } else {
+            Some(chrono::Utc::now())
+        };
+
+        conn.transaction::<_, TransactionError, _>(|conn| {
+            if !active {
+                keep_another_admin(conn, user_id)?;
+            }
+            diesel::update(users::table.find(user_id))
+                .set((
+                    users::is_active.eq(active),
+                    users::deactivated_at.eq(deactivated_at),
+                ))
+                .execute(conn)?;
+            insert_event(conn, event)?;
+            Ok(())
+        })
+        .map_err(String::from)
+    }
+
+    /// Changes the user's role, recording the event in the same transaction.
+    /// Taking the role from the last active admin is refused.
+    pub async fn change_role(
+        &self,
+        user_id: i32,
+        role: Role,
+        event: AuditEvent,
+    ) -> Result<User, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let new_role = match role {
+            Role::Admin => UserRoleEnum::Admin,
+            Role::Customer => UserRoleEnum::Customer,
+            Role::Mechanic => UserRoleEnum::Mechanic,
+        };
+
+        let result = conn
+            .transaction::<_, TransactionError, _>(|conn| {
+                if new_role != UserRoleEnum::Admin {
+                    keep_another_admin(conn, user_id)?;
+                }
+                let updated = diesel::update(users::table.find(user_id))
+                    .set(users::role.eq(new_role))
+                    .returning(UserModel::as_returning())
+                    .get_result::<UserModel>(conn)?;
+                insert_event(conn, event)?;
+                Ok(updated)
+            })
+            .map_err(String::from)?;
+
+        Ok(self.map_model_to_entity(result))
+    }
+
+    /// Replaces a one-time password and clears the forced change, but only on
+    /// an active account that still has the change pending. Returns false if
+    /// nothing was changed.
+    pub async fn replace_one_time_password(
+        &self,
+        user_id: i32,
+        password_hash: &str,
+    ) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let updated = diesel::update(
+            users::table
+                .find(user_id)
+                .filter(users::is_active.eq(true))
+                .filter(users::must_change_password.eq(true)),
+        )
+        .set((
+            users::password_hash.eq(password_hash),
+            users::must_change_password.eq(false),
+        ))
+        .execute(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+        Ok(updated > 0)
+    }
+
+    /// Marks the phone as verified, or clears the mark when it changes
+    pub async fn set_phone_verified_at(
+        &self,
+        user_id: i32,
+        verified_at: Option<DateTime<Utc>>,
+    ) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::update(users::table.find(user_id))
+            .set(users::phone_verified_at.eq(verified_at))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+
     pub async fn update_user(&self, user: User) -> Result<User, String> {
         let mut conn = self.pool.get().map_err(|e| e.to_string())?;
 
@@ -156,6 +324,26 @@ impl UserRepository {
             name: model.name,
             phone: model.phone,
             role,
+            is_active: model.is_active,
+            must_change_password: model.must_change_password,
+            is_guest: model.is_guest,
+            phone_verified_at: model.phone_verified_at,
         }
     }
 }
+
+/// Refuses to take the given user out of the active admins when they are the
+/// last one. The active admins stay locked until the transaction ends, so two
+/// admins can't remove each other at the same time.
+fn keep_another_admin(conn: &mut PgConnection, user_id: i32) -> Result<(), TransactionError> {
+    let admins = users::table
+        .filter(users::role.eq(UserRoleEnum::Admin))
+        .filter(users::is_active.eq(true))
+        .select(users::user_id)
+        .for_update()
+        .load::<i32>(conn)?;
+    if admins.len() <= 1 && admins.contains(&user_id) {
+        return Err(rejected("At least one active admin is required"));
+    }
+    Ok(())
+}
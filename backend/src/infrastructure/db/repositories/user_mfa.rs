@@ -0,0 +1,238 @@
+use crate::infrastructure::db::connection::DbPool;
+use crate::infrastructure::db::models::{
+    MfaRecoveryCodeModel, NewMfaRecoveryCode, NewUserMfa, UserMfaModel,
+};
+use crate::infrastructure::db::schema::{mfa_recovery_codes, user_mfa};
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+
+#[derive(Clone)]
+pub struct UserMfaRepository {
+    pool: DbPool,
+}
+
+impl UserMfaRepository {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_by_user_id(&self, user_id: i32) -> Result<Option<UserMfaModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        user_mfa::table
+            .find(user_id)
+            .first::<UserMfaModel>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Starts (or restarts) enrolment with a fresh secret. An already enabled
+    /// enrolment is left alone; callers must disable it first.
+    pub async fn upsert_pending(&self, user_id: i32, totp_secret: String) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let enabled = user_mfa::table
+                .find(user_id)
+                .select(user_mfa::enabled)
+                .for_update()
+                .first::<bool>(conn)
+                .optional()?;
+
+            match enabled {
+                Some(true) => Err(diesel::result::Error::RollbackTransaction),
+                Some(false) => {
+                    diesel::update(user_mfa::table.find(user_id))
+                        .set((
+                            user_mfa::totp_secret.eq(&totp_secret),
+                            user_mfa::last_used_step.eq(None::<i64>),
+                            user_mfa::created_at.eq(Utc::now()),
+                        ))
+                        .execute(conn)?;
+                    Ok(())
+                }
+                None => {
+                    diesel::insert_into(user_mfa::table)
+                        .values(&NewUserMfa {
+                            user_id,
+                            totp_secret: totp_secret.clone(),
+                        })
+                        .execute(conn)?;
+                    Ok(())
+                }
+            }
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::RollbackTransaction => {
+                "Two-factor authentication is already enabled".to_string()
+            }
+            other => other.to_string(),
+        })
+    }
+
+    /// Enables a pending enrolment and replaces any previous recovery codes in
+    /// one go. Fails if it was enabled in the meantime, so a second confirm
+    /// can't swap out the codes the user was just shown.
+    pub async fn enable(
+        &self,
+        user_id: i32,
+        used_step: i64,
+        recovery_code_hashes: Vec<String>,
+    ) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let updated = diesel::update(
+                user_mfa::table
+                    .find(user_id)
+                    .filter(user_mfa::enabled.eq(false)),
+            )
+            .set((
+                user_mfa::enabled.eq(true),
+                user_mfa::last_used_step.eq(Some(used_step)),
+                user_mfa::confirmed_at.eq(Some(Utc::now())),
+            ))
+            .execute(conn)?;
+            if updated == 0 {
+                return Err(diesel::result::Error::RollbackTransaction);
+            }
+
+            diesel::delete(
+                mfa_recovery_codes::table.filter(mfa_recovery_codes::user_id.eq(user_id)),
+            )
+            .execute(conn)?;
+
+            let new_codes: Vec<NewMfaRecoveryCode> = recovery_code_hashes
+                .into_iter()
+                .map(|code_hash| NewMfaRecoveryCode { user_id, code_hash })
+                .collect();
+
+            diesel::insert_into(mfa_recovery_codes::table)
+                .values(&new_codes)
+                .execute(conn)?;
+
+            Ok(())
+        })
+        .map_err(|e| match e {
+            diesel::result::Error::RollbackTransaction => {
+                "Two-factor authentication is already enabled".to_string()
+            }
+            other => other.to_string(),
+        })
+    }
+
+    /// Counts a login code attempt before it is checked. After `max_attempts`
+    /// without a code that worked the step is locked for `lockout`. Returns
+    /// false, without counting, while it is locked.
+    pub async fn record_attempt(
+        &self,
+        user_id: i32,
+        max_attempts: i32,
+        lockout: Duration,
+        now: DateTime<Utc>,
+    ) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let (attempts, locked_until) = user_mfa::table
+                .find(user_id)
+                .for_update()
+                .select((user_mfa::code_attempts, user_mfa::locked_until))
+                .first::<(i32, Option<DateTime<Utc>>)>(conn)?;
+            if locked_until.is_some_and(|until| until > now) {
+                return Ok(false);
+            }
+
+            // A lockout that has run out starts a fresh count
+            let attempts = if locked_until.is_some() {
+                1
+            } else {
+                attempts + 1
+            };
+            diesel::update(user_mfa::table.find(user_id))
+                .set((
+                    user_mfa::code_attempts.eq(attempts),
+                    user_mfa::locked_until.eq((attempts >= max_attempts).then(|| now + lockout)),
+                ))
+                .execute(conn)?;
+            Ok(true)
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    /// Starts the attempt count over once a code has worked
+    pub async fn clear_attempts(&self, user_id: i32) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        diesel::update(user_mfa::table.find(user_id))
+            .set((
+                user_mfa::code_attempts.eq(0),
+                user_mfa::locked_until.eq(None::<DateTime<Utc>>),
+            ))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Records the time step of an accepted code. Returns false when that step
+    /// (or a later one) was already used, so a captured code can't be replayed.
+    pub async fn consume_step(&self, user_id: i32, step: i64) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let updated = diesel::update(
+            user_mfa::table.find(user_id).filter(
+                user_mfa::last_used_step
+                    .is_null()
+                    .or(user_mfa::last_used_step.lt(step)),
+            ),
+        )
+        .set(user_mfa::last_used_step.eq(Some(step)))
+        .execute(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+        Ok(updated > 0)
+    }
+
+    pub async fn find_unused_recovery_codes(
+        &self,
+        user_id: i32,
+    ) -> Result<Vec<MfaRecoveryCodeModel>, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        mfa_recovery_codes::table
+            .filter(mfa_recovery_codes::user_id.eq(user_id))
+            .filter(mfa_recovery_codes::used_at.is_null())
+            .load::<MfaRecoveryCodeModel>(&mut conn)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Returns false if the code was used concurrently
+    pub async fn mark_recovery_code_used(&self, code_id: i32) -> Result<bool, String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        let updated = diesel::update(
+            mfa_recovery_codes::table
+                .find(code_id)
+                .filter(mfa_recovery_codes::used_at.is_null()),
+        )
+        .set(mfa_recovery_codes::used_at.eq(Some(Utc::now())))
+        .execute(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+        Ok(updated > 0)
+    }
+
+    pub async fn delete(&self, user_id: i32) -> Result<(), String> {
+        let mut conn = self.pool.get().map_err(|e| e.to_string())?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::delete(
+                mfa_recovery_codes::table.filter(mfa_recovery_codes::user_id.eq(user_id)),
+            )
+            .execute(conn)?;
+            diesel::delete(user_mfa::table.find(user_id)).execute(conn)?;
+            Ok(())
+        })
+        .map_err(|e| e.to_string())
+    }
+}
@@ -1,6 +1,22 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "erasure_request_status"))]
+    pub struct ErasureRequestStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "loyalty_entry_kind"))]
+    pub struct LoyaltyEntryKind;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "maintenance_reminder_status"))]
+    pub struct MaintenanceReminderStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "motorcycle_transfer_status"))]
+    pub struct MotorcycleTransferStatus;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "notification_channel_enum"))]
     pub struct NotificationChannelEnum;
@@ -13,15 +29,87 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "payment_status_enum"))]
     pub struct PaymentStatusEnum;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "purchase_order_status"))]
+    pub struct PurchaseOrderStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "quotation_line_decision"))]
+    pub struct QuotationLineDecision;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "quotation_status"))]
+    pub struct QuotationStatus;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "service_item_stock_status"))]
+    pub struct ServiceItemStockStatus;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "service_order_status"))]
     pub struct ServiceOrderStatus;
 
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "stock_movement_reason"))]
+    pub struct StockMovementReason;
+
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "stock_take_status"))]
+    pub struct StockTakeStatus;
+
     #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "user_role"))]
     pub struct UserRole;
 }
 
+diesel::table! {
+    audit_events (event_id) {
+        event_id -> Int8,
+        actor_id -> Nullable<Int4>,
+        #[max_length = 64]
+        action -> Varchar,
+        #[max_length = 64]
+        target_type -> Varchar,
+        #[max_length = 64]
+        target_id -> Varchar,
+        before_state -> Nullable<Jsonb>,
+        after_state -> Nullable<Jsonb>,
+        reason -> Nullable<Text>,
+        #[max_length = 64]
+        ip_address -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    branches (branch_id) {
+        branch_id -> Int4,
+        #[max_length = 100]
+        name -> Varchar,
+        address -> Nullable<Text>,
+        #[max_length = 32]
+        phone -> Nullable<Varchar>,
+        is_active -> Bool,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ErasureRequestStatus;
+
+    erasure_requests (request_id) {
+        request_id -> Int4,
+        user_id -> Int4,
+        status -> ErasureRequestStatus,
+        reason -> Nullable<Text>,
+        requested_at -> Timestamptz,
+        reviewed_by -> Nullable<Int4>,
+        reviewed_at -> Nullable<Timestamptz>,
+        review_note -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     feedbacks (feedback_id) {
         feedback_id -> Int4,
@@ -37,6 +125,106 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    goods_receipt_lines (receipt_line_id) {
+        receipt_line_id -> Int4,
+        receipt_id -> Int4,
+        po_line_id -> Int4,
+        stock_item_id -> Int4,
+        quantity -> Int4,
+        unit_cost -> Numeric,
+    }
+}
+
+diesel::table! {
+    goods_receipts (receipt_id) {
+        receipt_id -> Int4,
+        po_id -> Int4,
+        received_by -> Nullable<Int4>,
+        note -> Nullable<Text>,
+        received_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::LoyaltyEntryKind;
+
+    loyalty_entries (entry_id) {
+        entry_id -> Int4,
+        user_id -> Int4,
+        kind -> LoyaltyEntryKind,
+        points -> Int4,
+        remaining -> Int4,
+        spend -> Nullable<Numeric>,
+        order_id -> Nullable<Int4>,
+        service_item_id -> Nullable<Int4>,
+        reason -> Nullable<Text>,
+        created_by -> Nullable<Int4>,
+        expires_at -> Nullable<Timestamptz>,
+        reversed_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::MaintenanceReminderStatus;
+
+    maintenance_reminders (reminder_id) {
+        reminder_id -> Int4,
+        rule_id -> Int4,
+        bike_id -> Int4,
+        user_id -> Int4,
+        status -> MaintenanceReminderStatus,
+        sent_at -> Timestamptz,
+        snoozed_until -> Nullable<Timestamptz>,
+        converted_order_id -> Nullable<Int4>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    maintenance_rules (rule_id) {
+        rule_id -> Int4,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 100]
+        match_keyword -> Varchar,
+        interval_months -> Nullable<Int4>,
+        interval_km -> Nullable<Int4>,
+        is_active -> Bool,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    mfa_recovery_codes (code_id) {
+        code_id -> Int4,
+        user_id -> Int4,
+        #[max_length = 255]
+        code_hash -> Varchar,
+        used_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::MotorcycleTransferStatus;
+
+    motorcycle_transfers (transfer_id) {
+        transfer_id -> Int4,
+        bike_id -> Int4,
+        from_user_id -> Int4,
+        to_user_id -> Int4,
+        initiated_by -> Int4,
+        status -> MotorcycleTransferStatus,
+        created_at -> Timestamptz,
+        responded_at -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     motorcycles (bike_id) {
         bike_id -> Int4,
@@ -47,6 +235,19 @@ diesel::table! {
         #[max_length = 100]
         license_plate -> Varchar,
         user_id -> Int4,
+        year -> Nullable<Int4>,
+        #[max_length = 50]
+        colour -> Nullable<Varchar>,
+        #[max_length = 50]
+        engine_number -> Nullable<Varchar>,
+        #[max_length = 17]
+        vin -> Nullable<Varchar>,
+        mileage -> Int4,
+        archived_at -> Nullable<Timestamptz>,
+        #[max_length = 16]
+        plate_key -> Nullable<Varchar>,
+        #[max_length = 50]
+        plate_province -> Nullable<Varchar>,
     }
 }
 
@@ -58,7 +259,7 @@ diesel::table! {
     notifications (notification_id) {
         notification_id -> Int4,
         user_id -> Int4,
-        order_id -> Int4,
+        order_id -> Nullable<Int4>,
         channel -> NotificationChannelEnum,
         message -> Text,
         sent_at -> Timestamptz,
@@ -83,6 +284,88 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    phone_verifications (verification_id) {
+        verification_id -> Int4,
+        #[max_length = 20]
+        phone -> Varchar,
+        #[max_length = 255]
+        code_hash -> Varchar,
+        attempts -> Int4,
+        expires_at -> Timestamptz,
+        consumed_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        #[max_length = 45]
+        requested_ip -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    purchase_order_lines (line_id) {
+        line_id -> Int4,
+        po_id -> Int4,
+        stock_item_id -> Int4,
+        quantity_ordered -> Int4,
+        quantity_received -> Int4,
+        unit_cost -> Numeric,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::PurchaseOrderStatus;
+
+    purchase_orders (po_id) {
+        po_id -> Int4,
+        supplier_id -> Int4,
+        status -> PurchaseOrderStatus,
+        expected_date -> Nullable<Date>,
+        notes -> Nullable<Text>,
+        created_by -> Nullable<Int4>,
+        sent_at -> Nullable<Timestamptz>,
+        closed_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        branch_id -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::QuotationLineDecision;
+
+    quotation_lines (line_id) {
+        line_id -> Int4,
+        quotation_id -> Int4,
+        service_item_id -> Nullable<Int4>,
+        #[max_length = 255]
+        description -> Varchar,
+        price -> Numeric,
+        quantity -> Int4,
+        decision -> QuotationLineDecision,
+        locked -> Bool,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::QuotationStatus;
+
+    quotations (quotation_id) {
+        quotation_id -> Int4,
+        order_id -> Int4,
+        version -> Int4,
+        status -> QuotationStatus,
+        total -> Numeric,
+        approved_total -> Nullable<Numeric>,
+        notes -> Nullable<Text>,
+        valid_until -> Timestamptz,
+        issued_by -> Nullable<Int4>,
+        responded_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     refresh_tokens (token_id) {
         token_id -> Int4,
@@ -109,6 +392,26 @@ diesel::table! {
 }
 
 diesel::table! {
+    scheduled_jobs (job_name) {
+        #[max_length = 64]
+        job_name -> Varchar,
+        #[max_length = 100]
+        schedule -> Varchar,
+        next_run_at -> Timestamptz,
+        last_started_at -> Nullable<Timestamptz>,
+        last_finished_at -> Nullable<Timestamptz>,
+        #[max_length = 16]
+        last_status -> Nullable<Varchar>,
+        last_message -> Nullable<Text>,
+        run_count -> Int4,
+        failure_count -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ServiceItemStockStatus;
+
     service_items (item_id) {
         item_id -> Int4,
         order_id -> Int4,
@@ -117,6 +420,8 @@ diesel::table! {
         price -> Numeric,
         stock_item_id -> Nullable<Int4>,
         quantity -> Int4,
+        unit_cost -> Nullable<Numeric>,
+        stock_status -> Nullable<ServiceItemStockStatus>,
     }
 }
 
@@ -134,6 +439,32 @@ diesel::table! {
         created_at -> Timestamptz,
         before_picture_url -> Nullable<Text>,
         after_picture_url -> Nullable<Text>,
+        odometer_km -> Nullable<Int4>,
+        branch_id -> Int4,
+    }
+}
+
+diesel::table! {
+    stock_alerts (alert_id) {
+        alert_id -> Int4,
+        stock_item_id -> Int4,
+        quantity -> Int4,
+        reorder_point -> Int4,
+        created_at -> Timestamptz,
+        notified_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    stock_item_compatibilities (compatibility_id) {
+        compatibility_id -> Int4,
+        stock_item_id -> Int4,
+        #[max_length = 255]
+        brand -> Varchar,
+        #[max_length = 255]
+        model -> Nullable<Varchar>,
+        year_from -> Nullable<Int4>,
+        year_to -> Nullable<Int4>,
     }
 }
 
@@ -144,6 +475,128 @@ diesel::table! {
         name -> Varchar,
         price -> Numeric,
         quantity -> Int4,
+        reorder_point -> Nullable<Int4>,
+        reorder_quantity -> Nullable<Int4>,
+        #[max_length = 64]
+        sku -> Nullable<Varchar>,
+        #[max_length = 14]
+        barcode -> Nullable<Varchar>,
+        #[max_length = 100]
+        category -> Nullable<Varchar>,
+        #[max_length = 100]
+        brand -> Nullable<Varchar>,
+        #[max_length = 16]
+        unit -> Varchar,
+        #[max_length = 64]
+        shelf_location -> Nullable<Varchar>,
+        average_cost -> Numeric,
+        reserved_quantity -> Int4,
+        archived_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    stock_levels (stock_item_id, branch_id) {
+        stock_item_id -> Int4,
+        branch_id -> Int4,
+        quantity -> Int4,
+        reserved_quantity -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::StockMovementReason;
+
+    stock_movements (movement_id) {
+        movement_id -> Int4,
+        stock_item_id -> Int4,
+        delta -> Int4,
+        reason -> StockMovementReason,
+        order_id -> Nullable<Int4>,
+        actor_id -> Nullable<Int4>,
+        note -> Nullable<Text>,
+        quantity_after -> Int4,
+        created_at -> Timestamptz,
+        unit_cost -> Nullable<Numeric>,
+        branch_id -> Int4,
+    }
+}
+
+diesel::table! {
+    stock_take_lines (line_id) {
+        line_id -> Int4,
+        stock_take_id -> Int4,
+        stock_item_id -> Int4,
+        system_quantity -> Int4,
+        counted_quantity -> Nullable<Int4>,
+        counted_by -> Nullable<Int4>,
+        counted_at -> Nullable<Timestamptz>,
+        unit_cost -> Nullable<Numeric>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::StockTakeStatus;
+
+    stock_takes (stock_take_id) {
+        stock_take_id -> Int4,
+        status -> StockTakeStatus,
+        #[max_length = 100]
+        category -> Nullable<Varchar>,
+        notes -> Nullable<Text>,
+        opened_by -> Nullable<Int4>,
+        posted_by -> Nullable<Int4>,
+        opened_at -> Timestamptz,
+        closed_at -> Nullable<Timestamptz>,
+        branch_id -> Int4,
+    }
+}
+
+diesel::table! {
+    stock_transfer_lines (line_id) {
+        line_id -> Int4,
+        transfer_id -> Int4,
+        stock_item_id -> Int4,
+        quantity -> Int4,
+        unit_cost -> Numeric,
+    }
+}
+
+diesel::table! {
+    stock_transfers (transfer_id) {
+        transfer_id -> Int4,
+        from_branch_id -> Int4,
+        to_branch_id -> Int4,
+        note -> Nullable<Text>,
+        created_by -> Nullable<Int4>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    suppliers (supplier_id) {
+        supplier_id -> Int4,
+        #[max_length = 255]
+        name -> Varchar,
+        #[max_length = 255]
+        contact_name -> Nullable<Varchar>,
+        #[max_length = 32]
+        phone -> Nullable<Varchar>,
+        #[max_length = 255]
+        email -> Nullable<Varchar>,
+        notes -> Nullable<Text>,
+        lead_time_days -> Int4,
+        is_active -> Bool,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    user_branches (user_id, branch_id) {
+        user_id -> Int4,
+        branch_id -> Int4,
     }
 }
 
@@ -160,6 +613,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    user_mfa (user_id) {
+        user_id -> Int4,
+        #[max_length = 64]
+        totp_secret -> Varchar,
+        enabled -> Bool,
+        last_used_step -> Nullable<Int8>,
+        confirmed_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        code_attempts -> Int4,
+        locked_until -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::UserRole;
@@ -176,32 +643,108 @@ diesel::table! {
         phone -> Varchar,
         role -> UserRole,
         created_at -> Timestamptz,
+        is_active -> Bool,
+        deactivated_at -> Nullable<Timestamptz>,
+        must_change_password -> Bool,
+        is_guest -> Bool,
+        phone_verified_at -> Nullable<Timestamptz>,
+        merged_into_user_id -> Nullable<Int4>,
+        all_branches -> Bool,
     }
 }
 
+diesel::joinable!(audit_events -> users (actor_id));
 diesel::joinable!(feedbacks -> users (user_id));
+diesel::joinable!(goods_receipt_lines -> goods_receipts (receipt_id));
+diesel::joinable!(goods_receipt_lines -> purchase_order_lines (po_line_id));
+diesel::joinable!(goods_receipt_lines -> stock_items (stock_item_id));
+diesel::joinable!(goods_receipts -> purchase_orders (po_id));
+diesel::joinable!(goods_receipts -> users (received_by));
+diesel::joinable!(loyalty_entries -> service_items (service_item_id));
+diesel::joinable!(loyalty_entries -> service_orders (order_id));
+diesel::joinable!(loyalty_entries -> users (user_id));
+diesel::joinable!(maintenance_reminders -> maintenance_rules (rule_id));
+diesel::joinable!(maintenance_reminders -> motorcycles (bike_id));
+diesel::joinable!(maintenance_reminders -> users (user_id));
+diesel::joinable!(mfa_recovery_codes -> users (user_id));
+diesel::joinable!(motorcycle_transfers -> motorcycles (bike_id));
 diesel::joinable!(motorcycles -> users (user_id));
 diesel::joinable!(notifications -> service_orders (order_id));
 diesel::joinable!(notifications -> users (user_id));
 diesel::joinable!(payments -> service_orders (order_id));
+diesel::joinable!(purchase_order_lines -> purchase_orders (po_id));
+diesel::joinable!(purchase_order_lines -> stock_items (stock_item_id));
+diesel::joinable!(purchase_orders -> branches (branch_id));
+diesel::joinable!(purchase_orders -> suppliers (supplier_id));
+diesel::joinable!(purchase_orders -> users (created_by));
+diesel::joinable!(quotation_lines -> quotations (quotation_id));
+diesel::joinable!(quotation_lines -> service_items (service_item_id));
+diesel::joinable!(quotations -> service_orders (order_id));
+diesel::joinable!(quotations -> users (issued_by));
 diesel::joinable!(refresh_tokens -> users (user_id));
 diesel::joinable!(repair_logs -> service_orders (order_id));
 diesel::joinable!(repair_logs -> users (mechanic_id));
 diesel::joinable!(service_items -> service_orders (order_id));
 diesel::joinable!(service_items -> stock_items (stock_item_id));
+diesel::joinable!(service_orders -> branches (branch_id));
 diesel::joinable!(service_orders -> motorcycles (bike_id));
+diesel::joinable!(stock_alerts -> stock_items (stock_item_id));
+diesel::joinable!(stock_item_compatibilities -> stock_items (stock_item_id));
+diesel::joinable!(stock_levels -> branches (branch_id));
+diesel::joinable!(stock_levels -> stock_items (stock_item_id));
+diesel::joinable!(stock_movements -> branches (branch_id));
+diesel::joinable!(stock_movements -> service_orders (order_id));
+diesel::joinable!(stock_movements -> stock_items (stock_item_id));
+diesel::joinable!(stock_movements -> users (actor_id));
+diesel::joinable!(stock_take_lines -> stock_items (stock_item_id));
+diesel::joinable!(stock_take_lines -> stock_takes (stock_take_id));
+diesel::joinable!(stock_take_lines -> users (counted_by));
+diesel::joinable!(stock_takes -> branches (branch_id));
+diesel::joinable!(stock_transfer_lines -> stock_items (stock_item_id));
+diesel::joinable!(stock_transfer_lines -> stock_transfers (transfer_id));
+diesel::joinable!(stock_transfers -> users (created_by));
+diesel::joinable!(user_branches -> branches (branch_id));
+diesel::joinable!(user_branches -> users (user_id));
 diesel::joinable!(user_line_accounts -> users (user_id));
+diesel::joinable!(user_mfa -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    audit_events,
+    branches,
+    erasure_requests,
     feedbacks,
+    goods_receipt_lines,
+    goods_receipts,
+    loyalty_entries,
+    maintenance_reminders,
+    maintenance_rules,
+    mfa_recovery_codes,
+    motorcycle_transfers,
     motorcycles,
     notifications,
     payments,
+    phone_verifications,
+    purchase_order_lines,
+    purchase_orders,
+    quotation_lines,
+    quotations,
     refresh_tokens,
     repair_logs,
+    scheduled_jobs,
     service_items,
     service_orders,
+    stock_alerts,
+    stock_item_compatibilities,
     stock_items,
+    stock_levels,
+    stock_movements,
+    stock_take_lines,
+    stock_takes,
+    stock_transfer_lines,
+    stock_transfers,
+    suppliers,
+    user_branches,
     user_line_accounts,
+    user_mfa,
     users,
 );
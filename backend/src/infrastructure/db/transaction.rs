@@ -0,0 +1,25 @@
+/// Failure inside a transaction: either the database or a business rule that
+/// rolled it back, whose message goes back to the caller as it is
+pub enum TransactionError {
+    Db(diesel::result::Error),
+    Rejected(String),
+}
+
+impl From<diesel::result::Error> for TransactionError {
+    fn from(e: diesel::result::Error) -> Self {
+        Self::Db(e)
+    }
+}
+
+impl From<TransactionError> for String {
+    fn from(e: TransactionError) -> Self {
+        match e {
+            TransactionError::Db(e) => e.to_string(),
+            TransactionError::Rejected(message) => message,
+        }
+    }
+}
+
+pub fn rejected(message: impl Into<String>) -> TransactionError {
+    TransactionError::Rejected(message.into())
+}
@@ -1,3 +1,4 @@
 pub mod composite;
 pub mod line;
+pub mod sms;
 pub mod web;
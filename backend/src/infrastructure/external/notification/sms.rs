@@ -0,0 +1,65 @@
+use crate::domain::notification::gateway::SmsGateway;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+use std::env;
+
+/// Hands messages to an SMS provider's HTTP endpoint as `{ "to", "message" }`.
+/// Without SMS_WEBHOOK_URL (local development) messages are dropped, and only
+/// the fact that one was dropped is logged since texts carry login codes.
+pub struct WebhookSmsGateway {
+    client: Client,
+    webhook_url: Option<String>,
+    webhook_token: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SmsRequest<'a> {
+    to: &'a str,
+    message: &'a str,
+}
+
+impl WebhookSmsGateway {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            webhook_url: env::var("SMS_WEBHOOK_URL").ok(),
+            webhook_token: env::var("SMS_WEBHOOK_TOKEN").ok(),
+        }
+    }
+}
+
+impl Default for WebhookSmsGateway {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl SmsGateway for WebhookSmsGateway {
+    async fn send_sms(&self, phone: &str, message: &str) -> Result<(), String> {
+        let Some(url) = &self.webhook_url else {
+            tracing::warn!("SMS_WEBHOOK_URL is not set; SMS not sent");
+            return Ok(());
+        };
+
+        let mut request = self
+            .client
+            .post(url)
+            .json(&SmsRequest { to: phone, message });
+        if let Some(token) = &self.webhook_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("SMS request failed: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("SMS provider error: {} - {}", status, error_text));
+        }
+        Ok(())
+    }
+}
@@ -20,7 +20,7 @@ impl NotificationGateway for WebNotificationGateway {
     async fn send_notification(&self, message: NotificationMessage) -> Result<(), String> {
         let new_notif = NewNotification {
             user_id: message.user_id,
-            order_id: message.order_id.unwrap_or(0),
+            order_id: message.order_id,
             channel: NotificationChannelEnum::Web,
             message: format!("{}\n{}", message.title, message.body),
             status: NotificationStatusEnum::Sent,
@@ -1,11 +1,17 @@
 pub mod auth;
 
 use self::auth::AuthUser;
+use crate::domain::audit::AuditActor;
+use crate::domain::user::entity::Role;
+use crate::infrastructure::http::routes::ErrorResponse;
 use axum::{
-    Extension, RequestPartsExt,
-    extract::FromRequestParts,
+    Extension, Json, RequestPartsExt,
+    extract::{ConnectInfo, FromRequestParts},
     http::{StatusCode, request::Parts},
+    response::{IntoResponse, Response},
 };
+use std::convert::Infallible;
+use std::net::SocketAddr;
 
 impl<S> FromRequestParts<S> for AuthUser
 where
@@ -22,3 +28,78 @@ where
         Ok(user)
     }
 }
+
+/// The address a request came from, for requests that needn't be signed in
+pub struct ClientIp(pub Option<String>);
+
+fn client_ip(parts: &Parts) -> Option<String> {
+    // Behind Railway's proxy the socket address is the proxy's, so take the
+    // hop the proxy appended itself. Earlier entries come from the client
+    // and can be made up.
+    let forwarded = parts
+        .headers
+        .get("x-forwarded-for")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|v| v.rsplit(',').next())
+        .map(|ip| ip.trim().to_string())
+        .filter(|ip| !ip.is_empty());
+    forwarded.or_else(|| {
+        parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string())
+    })
+}
+
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(ClientIp(client_ip(parts)))
+    }
+}
+
+impl<S> FromRequestParts<S> for AuditActor
+where
+    S: Send + Sync,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+
+        Ok(AuditActor {
+            user_id: user.user_id,
+            ip_address: client_ip(parts),
+        })
+    }
+}
+
+/// A signed-in admin, for the purchasing endpoints. Anyone else is turned
+/// away before the handler runs.
+pub struct PurchasingAdmin(pub AuthUser);
+
+impl<S> FromRequestParts<S> for PurchasingAdmin
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        if user.role != Role::Admin {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(ErrorResponse::from("Only admins can manage purchasing")),
+            )
+                .into_response());
+        }
+
+        Ok(PurchasingAdmin(user))
+    }
+}
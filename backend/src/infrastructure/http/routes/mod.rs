@@ -1,30 +1,70 @@
 use crate::application::state::AppState;
 use crate::application::use_cases::add_stock_item::AddStockItemCommand;
+use crate::application::use_cases::branch::{
+    BranchAccess, BranchCommand, BranchFilter, SetUserBranchesCommand,
+};
+use crate::application::use_cases::confirm_mfa::ConfirmMfaCommand;
 use crate::application::use_cases::connect_line::ConnectLineCommand;
+use crate::application::use_cases::create_guest_customer::CreateGuestCustomerCommand;
 use crate::application::use_cases::create_service_order::CreateServiceOrderCommand;
-use crate::application::use_cases::login::LoginCommand;
+use crate::application::use_cases::create_staff::CreateStaffCommand;
+use crate::application::use_cases::disable_mfa::DisableMfaCommand;
+use crate::application::use_cases::export_personal_data::export_to_zip;
+use crate::application::use_cases::list_audit_events::AuditEventQuery;
+use crate::application::use_cases::list_erasure_requests::ErasureRequestQuery;
+use crate::application::use_cases::list_maintenance_reminders::SnoozeReminderCommand;
+use crate::application::use_cases::list_stock_movements::StockMovementQuery;
+use crate::application::use_cases::login::{
+    CompletePasswordChangeCommand, LoginCommand, VerifyMfaCommand,
+};
 use crate::application::use_cases::logout::LogoutCommand;
+use crate::application::use_cases::loyalty::{AdjustPointsCommand, RedeemPointsCommand};
+use crate::application::use_cases::manage_maintenance_rules::MaintenanceRuleCommand;
+use crate::application::use_cases::manage_suppliers::SupplierCommand;
+use crate::application::use_cases::part_compatibility::SetCompatibilityCommand;
 use crate::application::use_cases::process_payment::ProcessPaymentCommand;
 use crate::application::use_cases::promote_user::PromoteUserCommand;
+use crate::application::use_cases::purchase_order::{
+    CancelPurchaseOrderCommand, PurchaseOrderCommand, PurchaseOrderQuery, ReceiveGoodsCommand,
+    ReorderQuery,
+};
+use crate::application::use_cases::quotation::{IssueQuotationCommand, RespondQuotationCommand};
 use crate::application::use_cases::refresh_token::RefreshTokenCommand;
 use crate::application::use_cases::register_user::RegisterUserCommand;
+use crate::application::use_cases::request_erasure::RequestErasureCommand;
+use crate::application::use_cases::review_erasure_request::ReviewErasureCommand;
+use crate::application::use_cases::search::SearchQuery;
+use crate::application::use_cases::stock_catalogue::{
+    CatalogueExportQuery, CatalogueFormat, CatalogueImportQuery,
+};
+use crate::application::use_cases::stock_take::{
+    CancelStockTakeCommand, CountSheet, OpenStockTakeCommand, RecordCountsCommand, StockTakeQuery,
+};
+use crate::application::use_cases::stock_transfer::{StockTransferCommand, StockTransferQuery};
 use crate::application::use_cases::submit_feedback::SubmitFeedbackCommand;
+use crate::application::use_cases::transfer_motorcycle::InitiateTransferCommand;
 use crate::application::use_cases::update_order_photos::UpdateOrderPhotosCommand;
 use crate::application::use_cases::update_order_status::UpdateOrderStatusCommand;
 use crate::application::use_cases::update_profile::UpdateProfileCommand;
 use crate::application::use_cases::update_stock_item::UpdateStockItemCommand;
 use crate::application::use_cases::use_stock_item::UseStockItemCommand;
+use crate::application::use_cases::verify_phone::{ConfirmPhoneCommand, RequestPhoneCodeCommand};
 
+use crate::domain::audit::AuditActor;
+use crate::domain::motorcycle::MotorcycleDetails;
+use crate::domain::service::stock_entity::PublicStockItem;
 use crate::domain::user::entity::Role;
+use crate::infrastructure::db::repositories::motorcycle::MotorcycleRemoval;
 use crate::infrastructure::http::middleware::auth::AuthUser;
+use crate::infrastructure::http::middleware::{ClientIp, PurchasingAdmin};
 use axum::{
     Router,
     extract::{Json, Multipart, Query, State},
-    http::StatusCode,
+    http::{StatusCode, header},
     response::IntoResponse,
-    routing::{delete, get, post},
+    routing::{delete, get, post, put},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tower_http::services::ServeDir;
 
@@ -49,6 +89,67 @@ impl From<&str> for ErrorResponse {
 
 // Handlers
 
+/// Why the user can't act on this branch, if they can't. Customers aren't
+/// tied to branches, so only staff are checked.
+async fn branch_denied(state: &AppState, user: &AuthUser, branch_id: i32) -> Option<String> {
+    if user.role == Role::Customer {
+        return None;
+    }
+    state
+        .branch_use_case
+        .check(user.user_id, &user.role, branch_id)
+        .await
+        .err()
+}
+
+/// Staff only work on their own branches' orders. A missing order is left
+/// for the handler to report.
+async fn order_branch_denied(state: &AppState, user: &AuthUser, order_id: i32) -> Option<String> {
+    let order = state
+        .get_service_order_detail_use_case
+        .execute(order_id)
+        .await
+        .ok()?;
+    branch_denied(state, user, order.branch_id).await
+}
+
+/// Branch set-up and who works where is for admins who aren't tied to
+/// particular branches
+async fn branch_admin_denied(state: &AppState, user: &AuthUser) -> Option<String> {
+    if user.role != Role::Admin {
+        return Some("Only admins can manage branches".to_string());
+    }
+    match state.branch_use_case.access(user.user_id, &user.role).await {
+        Ok(BranchAccess::All) => None,
+        Ok(BranchAccess::Only(_)) => {
+            Some("Only admins working across all branches can manage branches".to_string())
+        }
+        Err(e) => Some(e),
+    }
+}
+
+async fn purchase_order_branch_denied(
+    state: &AppState,
+    user: &AuthUser,
+    po_id: i32,
+) -> Option<String> {
+    let po = state.purchase_order_use_case.get(po_id).await.ok()?;
+    branch_denied(state, user, po.branch_id).await
+}
+
+async fn stock_take_branch_denied(
+    state: &AppState,
+    user: &AuthUser,
+    stock_take_id: i32,
+) -> Option<String> {
+    let branch_id = state
+        .stock_take_use_case
+        .branch_of(stock_take_id)
+        .await
+        .ok()?;
+    branch_denied(state, user, branch_id).await
+}
+
 async fn register_user(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<RegisterUserCommand>,
@@ -60,6 +161,36 @@ async fn register_user(
     }
 }
 
+async fn request_phone_code(
+    State(state): State<Arc<AppState>>,
+    ClientIp(ip): ClientIp,
+    Json(payload): Json<RequestPhoneCodeCommand>,
+) -> impl IntoResponse {
+    match state.verify_phone_use_case.request_code(payload, ip).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "message": "Verification code sent" })),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn confirm_phone(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<ConfirmPhoneCommand>,
+) -> impl IntoResponse {
+    match state
+        .verify_phone_use_case
+        .confirm(user.user_id, payload)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
 async fn login(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<LoginCommand>,
@@ -71,6 +202,90 @@ async fn login(
     }
 }
 
+async fn complete_password_change(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CompletePasswordChangeCommand>,
+) -> impl IntoResponse {
+    match state.login_use_case.complete_password_change(payload).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::UNAUTHORIZED, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn verify_mfa(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<VerifyMfaCommand>,
+) -> impl IntoResponse {
+    match state.login_use_case.verify_mfa(payload).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::UNAUTHORIZED, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+// Mandatory enrollment happens before the user has a session, so these
+// endpoints authenticate with the MFA challenge token from /login instead.
+#[derive(Deserialize)]
+struct MfaChallengeEnrollRequest {
+    challenge_token: String,
+}
+
+#[derive(Deserialize)]
+struct MfaChallengeConfirmRequest {
+    challenge_token: String,
+    code: String,
+}
+
+async fn enroll_mfa_with_challenge(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<MfaChallengeEnrollRequest>,
+) -> impl IntoResponse {
+    let claims = match state.jwt_service.verify_mfa_token(&payload.challenge_token) {
+        Ok(data) => data.claims,
+        Err(e) => return (StatusCode::UNAUTHORIZED, Json(ErrorResponse::from(e))).into_response(),
+    };
+
+    match state.enroll_mfa_use_case.execute(claims.user_id).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn confirm_mfa_with_challenge(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<MfaChallengeConfirmRequest>,
+) -> impl IntoResponse {
+    let claims = match state.jwt_service.verify_mfa_token(&payload.challenge_token) {
+        Ok(data) => data.claims,
+        Err(e) => return (StatusCode::UNAUTHORIZED, Json(ErrorResponse::from(e))).into_response(),
+    };
+
+    let recovery = match state
+        .confirm_mfa_use_case
+        .execute(claims.user_id, ConfirmMfaCommand { code: payload.code })
+        .await
+    {
+        Ok(result) => result,
+        Err(e) => return (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    };
+
+    // The code just proved possession of the authenticator, so log straight in
+    match state.login_use_case.start_session(claims.user_id).await {
+        Ok(session) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "recovery_codes": recovery.recovery_codes,
+                "session": session,
+            })),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
 async fn refresh_token(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<RefreshTokenCommand>,
@@ -100,6 +315,7 @@ async fn logout(
 async fn promote_user(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
+    actor: AuditActor,
     Json(payload): Json<PromoteUserCommand>,
 ) -> impl IntoResponse {
     if user.role != Role::Admin {
@@ -110,123 +326,124 @@ async fn promote_user(
             .into_response();
     }
 
-    match state.promote_user_use_case.execute(payload).await {
+    match state.promote_user_use_case.execute(payload, &actor).await {
         Ok(result) => (StatusCode::OK, Json(result)).into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn submit_feedback(
+async fn create_staff(
     State(state): State<Arc<AppState>>,
-    Json(payload): Json<SubmitFeedbackCommand>,
+    user: AuthUser,
+    actor: AuditActor,
+    Json(payload): Json<CreateStaffCommand>,
 ) -> impl IntoResponse {
-    tracing::info!("Submitting feedback from: {}", payload.email);
-    match state.submit_feedback_use_case.execute(payload).await {
-        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
-        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
-    }
-}
-
-async fn list_feedbacks(State(state): State<Arc<AppState>>, user: AuthUser) -> impl IntoResponse {
     if user.role != Role::Admin {
         return (
             StatusCode::FORBIDDEN,
-            Json(ErrorResponse::from("Only admins can view feedback")),
+            Json(ErrorResponse::from("Only admins can create staff accounts")),
         )
             .into_response();
     }
 
-    match state.list_feedbacks_use_case.execute().await {
-        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::from(e)),
-        )
-            .into_response(),
+    match state.create_staff_use_case.execute(payload, &actor).await {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn delete_feedback(
+async fn create_guest_customer(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    axum::extract::Path(id): axum::extract::Path<i32>,
+    actor: AuditActor,
+    Json(payload): Json<CreateGuestCustomerCommand>,
 ) -> impl IntoResponse {
-    if user.role != Role::Admin {
+    if user.role == Role::Customer {
         return (
             StatusCode::FORBIDDEN,
-            Json(ErrorResponse::from("Only admins can delete feedback")),
+            Json(ErrorResponse::from(
+                "Only staff can record walk-in customers",
+            )),
         )
             .into_response();
     }
 
-    match state.delete_feedback_use_case.execute(id).await {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+    match state
+        .create_guest_customer_use_case
+        .execute(&actor, payload)
+        .await
+    {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn create_service_order(
+async fn deactivate_user(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    Json(payload): Json<CreateServiceOrderCommand>,
+    actor: AuditActor,
+    axum::extract::Path(user_id): axum::extract::Path<i32>,
 ) -> impl IntoResponse {
-    tracing::info!("User {} creating service order", user.user_id);
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can deactivate users")),
+        )
+            .into_response();
+    }
+
     match state
-        .create_service_order_use_case
-        .execute(payload, user.user_id)
+        .set_user_active_use_case
+        .deactivate(user_id, &actor)
         .await
     {
-        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "message": "User deactivated" })),
+        )
+            .into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn process_payment(
+async fn reactivate_user(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    Json(payload): Json<ProcessPaymentCommand>,
+    actor: AuditActor,
+    axum::extract::Path(user_id): axum::extract::Path<i32>,
 ) -> impl IntoResponse {
-    // Basic verification: Customer can only pay for their own order
-    if user.role == Role::Customer {
-        match state
-            .get_service_order_detail_use_case
-            .execute(payload.order_id)
-            .await
-        {
-            Ok(order) if order.customer_id != user.user_id => {
-                return (
-                    StatusCode::FORBIDDEN,
-                    Json(ErrorResponse::from("Access denied: Not your order")),
-                )
-                    .into_response();
-            }
-            Err(_) => {
-                return (
-                    StatusCode::NOT_FOUND,
-                    Json(ErrorResponse::from("Order not found")),
-                )
-                    .into_response();
-            }
-            _ => {}
-        }
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can reactivate users")),
+        )
+            .into_response();
     }
 
-    match state.process_payment_use_case.execute(payload).await {
-        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+    match state
+        .set_user_active_use_case
+        .reactivate(user_id, &actor)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "message": "User reactivated" })),
+        )
+            .into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn list_users(State(state): State<Arc<AppState>>, user: AuthUser) -> impl IntoResponse {
-    if user.role != Role::Admin {
+async fn list_branches(State(state): State<Arc<AppState>>, user: AuthUser) -> impl IntoResponse {
+    if user.role == Role::Customer {
         return (
             StatusCode::FORBIDDEN,
-            Json(ErrorResponse::from("Only admins can list users")),
+            Json(ErrorResponse::from("Only staff can view branches")),
         )
             .into_response();
     }
 
-    match state.list_users_use_case.execute().await {
+    match state.branch_use_case.list().await {
         Ok(result) => (StatusCode::OK, Json(result)).into_response(),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -236,85 +453,87 @@ async fn list_users(State(state): State<Arc<AppState>>, user: AuthUser) -> impl
     }
 }
 
-async fn list_service_orders(
+async fn create_branch(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
+    actor: AuditActor,
+    Json(payload): Json<BranchCommand>,
 ) -> impl IntoResponse {
-    let result = match user.role {
-        Role::Customer => {
-            state
-                .list_service_orders_use_case
-                .execute_for_customer(user.user_id)
-                .await
-        }
-        _ => state.list_service_orders_use_case.execute_all().await,
-    };
+    if let Some(e) = branch_admin_denied(&state, &user).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
 
-    match result {
-        Ok(orders) => (StatusCode::OK, Json(orders)).into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::from(e)),
-        )
-            .into_response(),
+    match state.branch_use_case.create(payload, &actor).await {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn update_order_status(
+async fn update_branch(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    Json(payload): Json<UpdateOrderStatusCommand>,
+    actor: AuditActor,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+    Json(payload): Json<BranchCommand>,
 ) -> impl IntoResponse {
-    match state
-        .update_order_status_use_case
-        .execute(payload, user.user_id, user.role)
-        .await
-    {
+    if let Some(e) = branch_admin_denied(&state, &user).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state.branch_use_case.update(id, payload, &actor).await {
         Ok(result) => (StatusCode::OK, Json(result)).into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn update_order_photos(
+async fn get_user_branches(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    Json(payload): Json<UpdateOrderPhotosCommand>,
+    axum::extract::Path(id): axum::extract::Path<i32>,
 ) -> impl IntoResponse {
-    if user.role != Role::Mechanic && user.role != Role::Admin {
+    if user.role != Role::Admin && user.user_id != id {
         return (
             StatusCode::FORBIDDEN,
             Json(ErrorResponse::from(
-                "Only mechanics or admins can update photos",
+                "Only admins can view other staff's branches",
             )),
         )
             .into_response();
     }
 
-    match state.update_order_photos_use_case.execute(payload).await {
-        Ok(_) => StatusCode::OK.into_response(),
-        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    match state.branch_use_case.user_branches(id).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
     }
 }
 
-#[derive(serde::Deserialize)]
-pub struct StatsQuery {
-    pub days: Option<i64>,
-}
-
-async fn get_dashboard_stats(
+async fn set_user_branches(
     State(state): State<Arc<AppState>>,
-    Query(query): Query<StatsQuery>,
     user: AuthUser,
+    actor: AuditActor,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+    Json(payload): Json<SetUserBranchesCommand>,
 ) -> impl IntoResponse {
-    if user.role != Role::Admin {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(ErrorResponse::from("Only admins can access statistics")),
-        )
-            .into_response();
+    if let Some(e) = branch_admin_denied(&state, &user).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state
+        .branch_use_case
+        .set_user_branches(id, payload, &actor)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
+}
 
-    match state.get_dashboard_stats_use_case.execute(query.days).await {
+async fn get_my_loyalty(State(state): State<Arc<AppState>>, user: AuthUser) -> impl IntoResponse {
+    match state.loyalty_use_case.summary(user.user_id).await {
         Ok(result) => (StatusCode::OK, Json(result)).into_response(),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -324,40 +543,108 @@ async fn get_dashboard_stats(
     }
 }
 
-async fn get_profile(State(state): State<Arc<AppState>>, user: AuthUser) -> impl IntoResponse {
-    match state.get_profile_use_case.execute(user.user_id).await {
-        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
-        Err(e) => (StatusCode::NOT_FOUND, Json(ErrorResponse::from(e))).into_response(),
-    }
-}
+async fn get_user_loyalty(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(user_id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    if user.role == Role::Customer {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only staff can view another customer's points",
+            )),
+        )
+            .into_response();
+    }
 
-async fn update_profile(
+    match state.loyalty_use_case.summary_for(user_id).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn adjust_loyalty_points(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    Json(payload): Json<UpdateProfileCommand>,
+    actor: AuditActor,
+    axum::extract::Path(user_id): axum::extract::Path<i32>,
+    Json(payload): Json<AdjustPointsCommand>,
 ) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can adjust points")),
+        )
+            .into_response();
+    }
+
     match state
-        .update_profile_use_case
-        .execute(user.user_id, payload)
+        .loyalty_use_case
+        .adjust(user_id, payload, &actor)
         .await
     {
-        Ok(_) => (
-            StatusCode::OK,
-            Json(serde_json::json!({ "message": "Profile updated" })),
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn redeem_loyalty_points(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(order_id): axum::extract::Path<i32>,
+    Json(payload): Json<RedeemPointsCommand>,
+) -> impl IntoResponse {
+    match state
+        .loyalty_use_case
+        .redeem(order_id, payload, user.user_id, user.role)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn issue_quotation(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(order_id): axum::extract::Path<i32>,
+    Json(payload): Json<IssueQuotationCommand>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can send quotations")),
         )
-            .into_response(),
+            .into_response();
+    }
+
+    if let Some(e) = order_branch_denied(&state, &user, order_id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state
+        .quotation_use_case
+        .issue(order_id, payload, user.user_id)
+        .await
+    {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn connect_line(
+async fn list_quotations(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    Json(payload): Json<ConnectLineCommand>,
+    axum::extract::Path(order_id): axum::extract::Path<i32>,
 ) -> impl IntoResponse {
+    if let Some(e) = order_branch_denied(&state, &user, order_id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
     match state
-        .connect_line_use_case
-        .execute(user.user_id, payload)
+        .quotation_use_case
+        .list(order_id, user.user_id, user.role)
         .await
     {
         Ok(result) => (StatusCode::OK, Json(result)).into_response(),
@@ -365,290 +652,1922 @@ async fn connect_line(
     }
 }
 
-async fn disconnect_line(State(state): State<Arc<AppState>>, user: AuthUser) -> impl IntoResponse {
-    tracing::info!("User {} disconnecting LINE account", user.user_id);
-    match state.disconnect_line_use_case.execute(user.user_id).await {
+async fn respond_to_quotation(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(quotation_id): axum::extract::Path<i32>,
+    Json(payload): Json<RespondQuotationCommand>,
+) -> impl IntoResponse {
+    match state
+        .quotation_use_case
+        .respond(quotation_id, payload, user.user_id)
+        .await
+    {
         Ok(result) => (StatusCode::OK, Json(result)).into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn get_service_order_detail(
+async fn submit_feedback(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SubmitFeedbackCommand>,
+) -> impl IntoResponse {
+    tracing::info!("Submitting feedback from: {}", payload.email);
+    match state.submit_feedback_use_case.execute(payload).await {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn list_feedbacks(State(state): State<Arc<AppState>>, user: AuthUser) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can view feedback")),
+        )
+            .into_response();
+    }
+
+    match state.list_feedbacks_use_case.execute().await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+async fn delete_feedback(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    axum::extract::Path(order_id): axum::extract::Path<i32>,
+    actor: AuditActor,
+    axum::extract::Path(id): axum::extract::Path<i32>,
 ) -> impl IntoResponse {
-    tracing::info!(
-        "User {} ({:?}) requesting order {} details",
-        user.user_id,
-        user.role,
-        order_id
-    );
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can delete feedback")),
+        )
+            .into_response();
+    }
 
-    if let Some(err_msg) = match user.role {
-        Role::Admin | Role::Mechanic => None,
-        Role::Customer => {
-            // Check if order belongs to customer
-            match state
-                .get_service_order_detail_use_case
-                .execute(order_id)
-                .await
-            {
-                Ok(order) if order.customer_id != user.user_id => {
-                    Some("Access denied: Not your order")
-                }
-                Err(_) => Some("Order not found"),
-                _ => None,
-            }
-        }
-    } {
-        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(err_msg))).into_response();
+    match state.delete_feedback_use_case.execute(id, &actor).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
+}
 
+async fn create_service_order(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<CreateServiceOrderCommand>,
+) -> impl IntoResponse {
+    tracing::info!("User {} creating service order", user.user_id);
     match state
-        .get_service_order_detail_use_case
-        .execute(order_id)
+        .create_service_order_use_case
+        .execute(payload, user.user_id, &user.role)
         .await
     {
-        Ok(order) => {
-            tracing::info!("Order {} details retrieved successfully", order_id);
-            (StatusCode::OK, Json(order)).into_response()
-        }
-        Err(e) => {
-            tracing::warn!("Failed to get order {} details: {}", order_id, e);
-            (StatusCode::NOT_FOUND, Json(ErrorResponse::from(e))).into_response()
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn process_payment(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<ProcessPaymentCommand>,
+) -> impl IntoResponse {
+    // Basic verification: Customer can only pay for their own order
+    if user.role == Role::Customer {
+        match state
+            .get_service_order_detail_use_case
+            .execute(payload.order_id)
+            .await
+        {
+            Ok(order) if order.customer_id != user.user_id => {
+                return (
+                    StatusCode::FORBIDDEN,
+                    Json(ErrorResponse::from("Access denied: Not your order")),
+                )
+                    .into_response();
+            }
+            Err(_) => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(ErrorResponse::from("Order not found")),
+                )
+                    .into_response();
+            }
+            _ => {}
         }
     }
+
+    match state.process_payment_use_case.execute(payload).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
 }
 
-#[derive(serde::Deserialize)]
-struct DeleteServiceOrderBody {
-    reason: Option<String>,
+async fn list_users(State(state): State<Arc<AppState>>, user: AuthUser) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can list users")),
+        )
+            .into_response();
+    }
+
+    match state.list_users_use_case.execute().await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
 }
 
-async fn delete_service_order(
+async fn search(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<SearchQuery>,
     user: AuthUser,
-    axum::extract::Path(order_id): axum::extract::Path<i32>,
-    Json(payload): Json<DeleteServiceOrderBody>,
 ) -> impl IntoResponse {
-    if user.role != Role::Admin {
+    if user.role == Role::Customer {
         return (
             StatusCode::FORBIDDEN,
-            Json(ErrorResponse::from("Only admins can delete orders")),
+            Json(ErrorResponse::from("Only staff can search")),
         )
             .into_response();
     }
 
-    let reason = payload
-        .reason
-        .unwrap_or_else(|| "No reason provided".to_string());
+    match state.search_use_case.execute(&user.role, query).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn list_service_orders(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(filter): Query<BranchFilter>,
+) -> impl IntoResponse {
+    let result = match user.role {
+        Role::Customer => {
+            state
+                .list_service_orders_use_case
+                .execute_for_customer(user.user_id)
+                .await
+        }
+        _ => match state
+            .branch_use_case
+            .scope(user.user_id, &user.role, filter.branch_id)
+            .await
+        {
+            Ok(branch_ids) => {
+                state
+                    .list_service_orders_use_case
+                    .execute_all(branch_ids)
+                    .await
+            }
+            Err(e) => {
+                return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+            }
+        },
+    };
 
+    match result {
+        Ok(orders) => (StatusCode::OK, Json(orders)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+async fn update_order_status(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<UpdateOrderStatusCommand>,
+) -> impl IntoResponse {
+    if let Some(e) = order_branch_denied(&state, &user, payload.order_id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state
+        .update_order_status_use_case
+        .execute(payload, user.user_id, user.role)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn update_order_photos(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<UpdateOrderPhotosCommand>,
+) -> impl IntoResponse {
+    if user.role != Role::Mechanic && user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only mechanics or admins can update photos",
+            )),
+        )
+            .into_response();
+    }
+
+    if let Some(e) = order_branch_denied(&state, &user, payload.order_id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state
+        .update_order_photos_use_case
+        .execute(payload, user.user_id)
+        .await
+    {
+        Ok(_) => StatusCode::OK.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub struct StatsQuery {
+    pub days: Option<i64>,
+    pub branch_id: Option<i32>,
+}
+
+async fn get_dashboard_stats(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StatsQuery>,
+    user: AuthUser,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can access statistics")),
+        )
+            .into_response();
+    }
+
+    let branch_ids = match state
+        .branch_use_case
+        .scope(user.user_id, &user.role, query.branch_id)
+        .await
+    {
+        Ok(branch_ids) => branch_ids,
+        Err(e) => return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response(),
+    };
+
+    match state
+        .get_dashboard_stats_use_case
+        .execute(query.days, branch_ids)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+async fn get_profile(State(state): State<Arc<AppState>>, user: AuthUser) -> impl IntoResponse {
+    match state.get_profile_use_case.execute(user.user_id).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn update_profile(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<UpdateProfileCommand>,
+) -> impl IntoResponse {
+    match state
+        .update_profile_use_case
+        .execute(user.user_id, payload)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "message": "Profile updated" })),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn enroll_mfa(State(state): State<Arc<AppState>>, user: AuthUser) -> impl IntoResponse {
+    match state.enroll_mfa_use_case.execute(user.user_id).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn confirm_mfa(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<ConfirmMfaCommand>,
+) -> impl IntoResponse {
+    match state
+        .confirm_mfa_use_case
+        .execute(user.user_id, payload)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn disable_mfa(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<DisableMfaCommand>,
+) -> impl IntoResponse {
+    match state
+        .disable_mfa_use_case
+        .execute(user.user_id, payload)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "message": "Two-factor authentication disabled" })),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn connect_line(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<ConnectLineCommand>,
+) -> impl IntoResponse {
+    match state
+        .connect_line_use_case
+        .execute(user.user_id, payload)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn disconnect_line(State(state): State<Arc<AppState>>, user: AuthUser) -> impl IntoResponse {
+    tracing::info!("User {} disconnecting LINE account", user.user_id);
+    match state.disconnect_line_use_case.execute(user.user_id).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn get_service_order_detail(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(order_id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    tracing::info!(
+        "User {} ({:?}) requesting order {} details",
+        user.user_id,
+        user.role,
+        order_id
+    );
+
+    if let Some(err_msg) = match user.role {
+        Role::Admin | Role::Mechanic => order_branch_denied(&state, &user, order_id).await,
+        Role::Customer => {
+            // Check if order belongs to customer
+            match state
+                .get_service_order_detail_use_case
+                .execute(order_id)
+                .await
+            {
+                Ok(order) if order.customer_id != user.user_id => {
+                    Some("Access denied: Not your order".to_string())
+                }
+                Err(_) => Some("Order not found".to_string()),
+                _ => None,
+            }
+        }
+    } {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(err_msg))).into_response();
+    }
+
+    match state
+        .get_service_order_detail_use_case
+        .execute(order_id)
+        .await
+    {
+        Ok(order) => {
+            tracing::info!("Order {} details retrieved successfully", order_id);
+            (StatusCode::OK, Json(order)).into_response()
+        }
+        Err(e) => {
+            tracing::warn!("Failed to get order {} details: {}", order_id, e);
+            (StatusCode::NOT_FOUND, Json(ErrorResponse::from(e))).into_response()
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct DeleteServiceOrderBody {
+    reason: Option<String>,
+}
+
+async fn delete_service_order(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    actor: AuditActor,
+    axum::extract::Path(order_id): axum::extract::Path<i32>,
+    Json(payload): Json<DeleteServiceOrderBody>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can delete orders")),
+        )
+            .into_response();
+    }
+
+    let reason = payload
+        .reason
+        .unwrap_or_else(|| "No reason provided".to_string());
+
+    if let Some(e) = order_branch_denied(&state, &user, order_id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state
+        .delete_service_order_use_case
+        .execute(order_id, reason, &actor)
+        .await
+    {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn add_service_item(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<crate::application::use_cases::add_service_item::AddServiceItemCommand>,
+) -> impl IntoResponse {
+    if user.role != Role::Mechanic && user.role != Role::Admin {
+        tracing::warn!(
+            "User {} ({:?}) attempted to add service item without permission",
+            user.user_id,
+            user.role
+        );
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only mechanics or admins can add service items",
+            )),
+        )
+            .into_response();
+    }
+
+    tracing::info!(
+        "User {} ({:?}) adding service item to order {}",
+        user.user_id,
+        user.role,
+        payload.order_id
+    );
+
+    if let Some(e) = order_branch_denied(&state, &user, payload.order_id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state
+        .add_service_item_use_case
+        .execute(payload, user.user_id)
+        .await
+    {
+        Ok(item) => {
+            tracing::info!("Service item added successfully: {:?}", item.id);
+            (StatusCode::CREATED, Json(item)).into_response()
+        }
+        Err(e) => {
+            tracing::error!("Failed to add service item: {}", e);
+            (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response()
+        }
+    }
+}
+
+async fn remove_service_item(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(item_id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    if user.role != Role::Mechanic && user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only mechanics or admins can remove service items",
+            )),
+        )
+            .into_response();
+    }
+
+    let order_id = match state.remove_service_item_use_case.order_id(item_id).await {
+        Ok(Some(order_id)) => order_id,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::from("Service item not found")),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::from(e)),
+            )
+                .into_response();
+        }
+    };
+    if let Some(e) = order_branch_denied(&state, &user, order_id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state
+        .remove_service_item_use_case
+        .execute(item_id, user.user_id)
+        .await
+    {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn upload_file(mut multipart: Multipart) -> impl IntoResponse {
+    let mut file_url = String::new();
+
+    while let Ok(Some(field)) = multipart.next_field().await {
+        let name = field.name().unwrap_or("file").to_string();
+        if name == "file" {
+            let file_name = field.file_name().unwrap_or("upload.jpg").to_string();
+            let content_type = field.content_type().unwrap_or("image/jpeg").to_string();
+
+            // Validate content type is image
+            if !content_type.starts_with("image/") {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse::from("Only image files are allowed")),
+                )
+                    .into_response();
+            }
+
+            let data = match field.bytes().await {
+                Ok(data) => data,
+                Err(e) => {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(ErrorResponse::from(format!("Failed to read file: {}", e))),
+                    )
+                        .into_response();
+                }
+            };
+
+            let extension = std::path::Path::new(&file_name)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("jpg");
+
+            let new_filename = format!("{}.{}", uuid::Uuid::new_v4(), extension);
+            let upload_dir = "uploads";
+
+            if let Err(e) = std::fs::create_dir_all(upload_dir) {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse::from(format!(
+                        "Failed to create upload directory: {}",
+                        e
+                    ))),
+                )
+                    .into_response();
+            }
+
+            let path = std::path::Path::new(upload_dir).join(&new_filename);
+            if let Err(e) = std::fs::write(&path, data) {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse::from(format!("Failed to save file: {}", e))),
+                )
+                    .into_response();
+            }
+
+            file_url = format!("/uploads/{}", new_filename);
+            break; // Currently only handles one file
+        }
+    }
+
+    if file_url.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::from("No file provided")),
+        )
+            .into_response();
+    }
+
+    (StatusCode::OK, Json(serde_json::json!({ "url": file_url }))).into_response()
+}
+
+// Router
+
+async fn add_stock_item(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<AddStockItemCommand>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can manage stock")),
+        )
+            .into_response();
+    }
+
+    match state
+        .add_stock_item_use_case
+        .execute(payload, user.user_id, &user.role)
+        .await
+    {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn update_stock_item(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    actor: AuditActor,
+    Json(payload): Json<UpdateStockItemCommand>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can manage stock")),
+        )
+            .into_response();
+    }
+
+    match state
+        .update_stock_item_use_case
+        .execute(payload, &actor, &user.role)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn delete_stock_item(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(item_id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can manage stock")),
+        )
+            .into_response();
+    }
+
+    match state.delete_stock_item_use_case.execute(item_id).await {
+        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn list_stock_items(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(filter): Query<BranchFilter>,
+) -> impl IntoResponse {
+    let branch_ids = match state
+        .branch_use_case
+        .scope(user.user_id, &user.role, filter.branch_id)
+        .await
+    {
+        Ok(branch_ids) => branch_ids,
+        Err(e) => return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response(),
+    };
+
+    match state.list_stock_items_use_case.execute(branch_ids).await {
+        Ok(items) if user.role == Role::Admin => (StatusCode::OK, Json(items)).into_response(),
+        Ok(items) => {
+            let items: Vec<PublicStockItem> = items.into_iter().map(Into::into).collect();
+            (StatusCode::OK, Json(items)).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+async fn get_stock_item_by_barcode(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(code): axum::extract::Path<String>,
+    Query(filter): Query<BranchFilter>,
+) -> impl IntoResponse {
+    if user.role == Role::Customer {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only staff can look up stock by barcode",
+            )),
+        )
+            .into_response();
+    }
+
+    match state
+        .get_stock_item_by_barcode_use_case
+        .execute(&code, filter.branch_id, user.user_id, &user.role)
+        .await
+    {
+        Ok(Some(item)) if user.role == Role::Admin => (StatusCode::OK, Json(item)).into_response(),
+        Ok(Some(item)) => (StatusCode::OK, Json(PublicStockItem::from(item))).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::from("No stock item has this barcode")),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn list_stock_movements(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(item_id): axum::extract::Path<i32>,
+    Query(query): Query<StockMovementQuery>,
+) -> impl IntoResponse {
+    if user.role == Role::Customer {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only staff can view stock movements")),
+        )
+            .into_response();
+    }
+
+    match state
+        .list_stock_movements_use_case
+        .execute(item_id, query, user.user_id, &user.role)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn check_stock_consistency(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only admins can check stock consistency",
+            )),
+        )
+            .into_response();
+    }
+
+    match state.check_stock_consistency_use_case.execute().await {
+        Ok(discrepancies) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "consistent": discrepancies.is_empty(),
+                "discrepancies": discrepancies,
+            })),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+async fn low_stock_report(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(filter): Query<BranchFilter>,
+) -> impl IntoResponse {
+    if user.role == Role::Customer {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only staff can view stock levels")),
+        )
+            .into_response();
+    }
+
+    let branch_ids = match state
+        .branch_use_case
+        .scope(user.user_id, &user.role, filter.branch_id)
+        .await
+    {
+        Ok(branch_ids) => branch_ids,
+        Err(e) => return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response(),
+    };
+
+    match state.low_stock_use_case.report(branch_ids).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+async fn export_stock_catalogue(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(query): Query<CatalogueExportQuery>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can export the catalogue")),
+        )
+            .into_response();
+    }
+
+    let branch_ids = match state
+        .branch_use_case
+        .scope(user.user_id, &user.role, query.branch_id)
+        .await
+    {
+        Ok(branch_ids) => branch_ids,
+        Err(e) => return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response(),
+    };
+
+    let format = query.format.unwrap_or(CatalogueFormat::Csv);
+    match state
+        .stock_catalogue_use_case
+        .export(format, branch_ids)
+        .await
+    {
+        Ok(bytes) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, format.content_type().to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"stock-catalogue.{}\"",
+                        format.extension()
+                    ),
+                ),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+/// Takes the spreadsheet from the `file` field of a multipart upload
+async fn import_stock_catalogue(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    actor: AuditActor,
+    Query(query): Query<CatalogueImportQuery>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can import the catalogue")),
+        )
+            .into_response();
+    }
+
+    let mut upload = None;
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse::from(format!("Failed to read upload: {}", e))),
+                )
+                    .into_response();
+            }
+        };
+        if field.name() != Some("file") {
+            continue;
+        }
+        let file_name = field.file_name().unwrap_or_default().to_string();
+        match field.bytes().await {
+            Ok(data) => upload = Some((file_name, data)),
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ErrorResponse::from(format!("Failed to read file: {}", e))),
+                )
+                    .into_response();
+            }
+        }
+    }
+    let Some((file_name, data)) = upload else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::from("Attach the spreadsheet as \"file\"")),
+        )
+            .into_response();
+    };
+    let Some(format) = query
+        .format
+        .or_else(|| CatalogueFormat::from_file_name(&file_name))
+    else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::from("Upload a .csv or .xlsx file")),
+        )
+            .into_response();
+    };
+
+    match state
+        .stock_catalogue_use_case
+        .import(&data, &query, format, &actor, &user.role)
+        .await
+    {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn suggest_reorder(
+    State(state): State<Arc<AppState>>,
+    _: PurchasingAdmin,
+    Query(query): Query<ReorderQuery>,
+) -> impl IntoResponse {
+    match state.purchase_order_use_case.suggest_reorder(query).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn list_suppliers(
+    State(state): State<Arc<AppState>>,
+    _: PurchasingAdmin,
+) -> impl IntoResponse {
+    match state.manage_suppliers_use_case.list().await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+async fn create_supplier(
+    State(state): State<Arc<AppState>>,
+    _: PurchasingAdmin,
+    actor: AuditActor,
+    Json(payload): Json<SupplierCommand>,
+) -> impl IntoResponse {
+    match state
+        .manage_suppliers_use_case
+        .create(payload, &actor)
+        .await
+    {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn update_supplier(
+    State(state): State<Arc<AppState>>,
+    _: PurchasingAdmin,
+    actor: AuditActor,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+    Json(payload): Json<SupplierCommand>,
+) -> impl IntoResponse {
+    match state
+        .manage_suppliers_use_case
+        .update(id, payload, &actor)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn list_purchase_orders(
+    State(state): State<Arc<AppState>>,
+    PurchasingAdmin(user): PurchasingAdmin,
+    Query(query): Query<PurchaseOrderQuery>,
+) -> impl IntoResponse {
+    match state
+        .purchase_order_use_case
+        .list(query, user.user_id, &user.role)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+async fn create_purchase_order(
+    State(state): State<Arc<AppState>>,
+    PurchasingAdmin(user): PurchasingAdmin,
+    Json(payload): Json<PurchaseOrderCommand>,
+) -> impl IntoResponse {
+    match state
+        .purchase_order_use_case
+        .create(payload, user.user_id, &user.role)
+        .await
+    {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn get_purchase_order(
+    State(state): State<Arc<AppState>>,
+    PurchasingAdmin(user): PurchasingAdmin,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    if let Some(e) = purchase_order_branch_denied(&state, &user, id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state.purchase_order_use_case.get(id).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn update_purchase_order(
+    State(state): State<Arc<AppState>>,
+    PurchasingAdmin(user): PurchasingAdmin,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+    Json(payload): Json<PurchaseOrderCommand>,
+) -> impl IntoResponse {
+    if let Some(e) = purchase_order_branch_denied(&state, &user, id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state
+        .purchase_order_use_case
+        .update(id, payload, user.user_id, &user.role)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn send_purchase_order(
+    State(state): State<Arc<AppState>>,
+    PurchasingAdmin(user): PurchasingAdmin,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    if let Some(e) = purchase_order_branch_denied(&state, &user, id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state.purchase_order_use_case.send(id).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn cancel_purchase_order(
+    State(state): State<Arc<AppState>>,
+    PurchasingAdmin(user): PurchasingAdmin,
+    actor: AuditActor,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+    Json(payload): Json<CancelPurchaseOrderCommand>,
+) -> impl IntoResponse {
+    if let Some(e) = purchase_order_branch_denied(&state, &user, id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state
+        .purchase_order_use_case
+        .cancel(id, payload, &actor)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn receive_goods(
+    State(state): State<Arc<AppState>>,
+    PurchasingAdmin(user): PurchasingAdmin,
+    actor: AuditActor,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+    Json(payload): Json<ReceiveGoodsCommand>,
+) -> impl IntoResponse {
+    if let Some(e) = purchase_order_branch_denied(&state, &user, id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state
+        .purchase_order_use_case
+        .receive(id, payload, &actor)
+        .await
+    {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn list_stock_takes(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(query): Query<StockTakeQuery>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can manage stock takes")),
+        )
+            .into_response();
+    }
+
+    match state
+        .stock_take_use_case
+        .list(query, user.user_id, &user.role)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+async fn open_stock_take(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<OpenStockTakeCommand>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can manage stock takes")),
+        )
+            .into_response();
+    }
+
+    match state
+        .stock_take_use_case
+        .open(payload, user.user_id, &user.role)
+        .await
+    {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+/// The count sheet, ordered by shelf location. Only admins see the expected
+/// quantities and costs.
+async fn get_stock_take(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    if user.role != Role::Mechanic && user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only mechanics or admins can count stock",
+            )),
+        )
+            .into_response();
+    }
+
+    if let Some(e) = stock_take_branch_denied(&state, &user, id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state.stock_take_use_case.get(id).await {
+        Ok(result) if user.role == Role::Admin => (StatusCode::OK, Json(result)).into_response(),
+        Ok(result) => (StatusCode::OK, Json(CountSheet::from(result))).into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn record_stock_take_counts(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+    Json(payload): Json<RecordCountsCommand>,
+) -> impl IntoResponse {
+    if user.role != Role::Mechanic && user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only mechanics or admins can count stock",
+            )),
+        )
+            .into_response();
+    }
+
+    if let Some(e) = stock_take_branch_denied(&state, &user, id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state
+        .stock_take_use_case
+        .record_counts(id, payload, user.user_id)
+        .await
+    {
+        Ok(result) if user.role == Role::Admin => (StatusCode::OK, Json(result)).into_response(),
+        Ok(result) => (StatusCode::OK, Json(CountSheet::from(result))).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn get_stock_take_variance(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can manage stock takes")),
+        )
+            .into_response();
+    }
+
+    if let Some(e) = stock_take_branch_denied(&state, &user, id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state.stock_take_use_case.variance_report(id).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn post_stock_take(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    actor: AuditActor,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can manage stock takes")),
+        )
+            .into_response();
+    }
+
+    if let Some(e) = stock_take_branch_denied(&state, &user, id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state.stock_take_use_case.post(id, &actor).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn cancel_stock_take(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    actor: AuditActor,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+    Json(payload): Json<CancelStockTakeCommand>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can manage stock takes")),
+        )
+            .into_response();
+    }
+
+    if let Some(e) = stock_take_branch_denied(&state, &user, id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state.stock_take_use_case.cancel(id, payload, &actor).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn list_stock_transfers(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Query(query): Query<StockTransferQuery>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can transfer stock")),
+        )
+            .into_response();
+    }
+
+    match state
+        .stock_transfer_use_case
+        .list(query, user.user_id, &user.role)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn create_stock_transfer(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    actor: AuditActor,
+    Json(payload): Json<StockTransferCommand>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can transfer stock")),
+        )
+            .into_response();
+    }
+
+    match state
+        .stock_transfer_use_case
+        .create(payload, &actor, &user.role)
+        .await
+    {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn get_stock_compatibility(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(item_id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    if user.role == Role::Customer {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only staff can view part compatibility",
+            )),
+        )
+            .into_response();
+    }
+
+    match state.part_compatibility_use_case.tags(item_id).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn set_stock_compatibility(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    actor: AuditActor,
+    axum::extract::Path(item_id): axum::extract::Path<i32>,
+    Json(payload): Json<SetCompatibilityCommand>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can manage stock")),
+        )
+            .into_response();
+    }
+
+    match state
+        .part_compatibility_use_case
+        .set_tags(item_id, payload, &actor)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+/// Parts that fit the order's motorcycle, closest fit first
+async fn suggest_order_parts(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(order_id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    if user.role != Role::Mechanic && user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only mechanics or admins can pick parts",
+            )),
+        )
+            .into_response();
+    }
+
+    if let Some(e) = order_branch_denied(&state, &user, order_id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state.part_compatibility_use_case.suggest(order_id).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn use_stock_item(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<UseStockItemCommand>,
+) -> impl IntoResponse {
+    if user.role != Role::Mechanic && user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only mechanics or admins can use stock items",
+            )),
+        )
+            .into_response();
+    }
+
+    if let Some(e) = order_branch_denied(&state, &user, payload.order_id).await {
+        return (StatusCode::FORBIDDEN, Json(ErrorResponse::from(e))).into_response();
+    }
+
+    match state
+        .use_stock_item_use_case
+        .execute(payload, user.user_id)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn get_jwks(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (StatusCode::OK, Json(state.jwt_service.jwks())).into_response()
+}
+
+async fn list_notifications(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> impl IntoResponse {
+    match state
+        .list_notifications_use_case
+        .execute(user.user_id)
+        .await
+    {
+        Ok(notifications) => (StatusCode::OK, Json(notifications)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+async fn mark_notification_read(
+    State(state): State<Arc<AppState>>,
+    _user: AuthUser,
+    axum::extract::Path(notification_id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    match state
+        .mark_notification_read_use_case
+        .execute(notification_id)
+        .await
+    {
+        Ok(_) => StatusCode::OK.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn list_audit_events(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AuditEventQuery>,
+    user: AuthUser,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from("Only admins can view the audit trail")),
+        )
+            .into_response();
+    }
+
+    match state.list_audit_events_use_case.execute(query).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ExportQuery {
+    pub format: Option<String>,
+}
+
+async fn export_personal_data(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ExportQuery>,
+    user: AuthUser,
+) -> impl IntoResponse {
+    let export = match state
+        .export_personal_data_use_case
+        .execute(user.user_id)
+        .await
+    {
+        Ok(export) => export,
+        Err(e) => return (StatusCode::NOT_FOUND, Json(ErrorResponse::from(e))).into_response(),
+    };
+
+    if query.format.as_deref() != Some("zip") {
+        return (StatusCode::OK, Json(export)).into_response();
+    }
+
+    match export_to_zip(&export) {
+        Ok(bytes) => (
+            StatusCode::OK,
+            [
+                (header::CONTENT_TYPE, "application/zip".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"personal-data-{}.zip\"",
+                        user.user_id
+                    ),
+                ),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+async fn request_erasure(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<RequestErasureCommand>,
+) -> impl IntoResponse {
+    match state
+        .request_erasure_use_case
+        .execute(user.user_id, payload)
+        .await
+    {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn list_erasure_requests(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ErasureRequestQuery>,
+    user: AuthUser,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only admins can review erasure requests",
+            )),
+        )
+            .into_response();
+    }
+
+    match state.list_erasure_requests_use_case.execute(query).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+async fn approve_erasure_request(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    actor: AuditActor,
+    axum::extract::Path(request_id): axum::extract::Path<i32>,
+    Json(payload): Json<ReviewErasureCommand>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only admins can review erasure requests",
+            )),
+        )
+            .into_response();
+    }
+
+    match state
+        .review_erasure_request_use_case
+        .approve(request_id, payload, &actor)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "message": "Personal data erased" })),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn reject_erasure_request(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    actor: AuditActor,
+    axum::extract::Path(request_id): axum::extract::Path<i32>,
+    Json(payload): Json<ReviewErasureCommand>,
+) -> impl IntoResponse {
+    if user.role != Role::Admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::from(
+                "Only admins can review erasure requests",
+            )),
+        )
+            .into_response();
+    }
+
+    match state
+        .review_erasure_request_use_case
+        .reject(request_id, payload, &actor)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "message": "Erasure request rejected" })),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn list_my_motorcycles(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> impl IntoResponse {
+    match state.list_motorcycles_use_case.execute(user.user_id).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
+    }
+}
+
+async fn register_motorcycle(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    Json(payload): Json<MotorcycleDetails>,
+) -> impl IntoResponse {
+    match state
+        .register_motorcycle_use_case
+        .execute(user.user_id, payload)
+        .await
+    {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn update_motorcycle(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+    Json(payload): Json<MotorcycleDetails>,
+) -> impl IntoResponse {
+    match state
+        .update_motorcycle_use_case
+        .execute(user.user_id, id, payload)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn delete_motorcycle(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    match state
+        .delete_motorcycle_use_case
+        .execute(user.user_id, id)
+        .await
+    {
+        Ok(MotorcycleRemoval::Deleted) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "message": "Motorcycle deleted" })),
+        )
+            .into_response(),
+        Ok(MotorcycleRemoval::Archived) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "message": "Motorcycle removed from your garage; its service history is kept"
+            })),
+        )
+            .into_response(),
+        Err(e) => (StatusCode::CONFLICT, Json(ErrorResponse::from(e))).into_response(),
+    }
+}
+
+async fn get_motorcycle_history(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
     match state
-        .delete_service_order_use_case
-        .execute(order_id, reason)
+        .get_motorcycle_history_use_case
+        .execute(id, user.user_id, &user.role)
         .await
     {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
-        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn add_service_item(
+async fn list_scheduled_jobs(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    Json(payload): Json<crate::application::use_cases::add_service_item::AddServiceItemCommand>,
 ) -> impl IntoResponse {
-    if user.role != Role::Mechanic && user.role != Role::Admin {
-        tracing::warn!(
-            "User {} ({:?}) attempted to add service item without permission",
-            user.user_id,
-            user.role
-        );
+    if user.role != Role::Admin {
         return (
             StatusCode::FORBIDDEN,
-            Json(ErrorResponse::from(
-                "Only mechanics or admins can add service items",
-            )),
+            Json(ErrorResponse::from("Only admins can view scheduled jobs")),
         )
             .into_response();
     }
 
-    tracing::info!(
-        "User {} ({:?}) adding service item to order {}",
-        user.user_id,
-        user.role,
-        payload.order_id
-    );
-
-    match state.add_service_item_use_case.execute(payload).await {
-        Ok(item) => {
-            tracing::info!("Service item added successfully: {:?}", item.id);
-            (StatusCode::CREATED, Json(item)).into_response()
-        }
-        Err(e) => {
-            tracing::error!("Failed to add service item: {}", e);
-            (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response()
-        }
+    match state.list_scheduled_jobs_use_case.execute().await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
+        )
+            .into_response(),
     }
 }
 
-async fn remove_service_item(
+async fn list_maintenance_rules(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    axum::extract::Path(item_id): axum::extract::Path<i32>,
 ) -> impl IntoResponse {
-    if user.role != Role::Mechanic && user.role != Role::Admin {
+    if user.role != Role::Admin {
         return (
             StatusCode::FORBIDDEN,
             Json(ErrorResponse::from(
-                "Only mechanics or admins can remove service items",
+                "Only admins can manage maintenance rules",
             )),
         )
             .into_response();
     }
 
-    match state.remove_service_item_use_case.execute(item_id).await {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
-        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
-    }
-}
-
-async fn upload_file(mut multipart: Multipart) -> impl IntoResponse {
-    let mut file_url = String::new();
-
-    while let Ok(Some(field)) = multipart.next_field().await {
-        let name = field.name().unwrap_or("file").to_string();
-        if name == "file" {
-            let file_name = field.file_name().unwrap_or("upload.jpg").to_string();
-            let content_type = field.content_type().unwrap_or("image/jpeg").to_string();
-
-            // Validate content type is image
-            if !content_type.starts_with("image/") {
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(ErrorResponse::from("Only image files are allowed")),
-                )
-                    .into_response();
-            }
-
-            let data = match field.bytes().await {
-                Ok(data) => data,
-                Err(e) => {
-                    return (
-                        StatusCode::BAD_REQUEST,
-                        Json(ErrorResponse::from(format!("Failed to read file: {}", e))),
-                    )
-                        .into_response();
-                }
-            };
-
-            let extension = std::path::Path::new(&file_name)
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .unwrap_or("jpg");
-
-            let new_filename = format!("{}.{}", uuid::Uuid::new_v4(), extension);
-            let upload_dir = "uploads";
-
-            if let Err(e) = std::fs::create_dir_all(upload_dir) {
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::from(format!(
-                        "Failed to create upload directory: {}",
-                        e
-                    ))),
-                )
-                    .into_response();
-            }
-
-            let path = std::path::Path::new(upload_dir).join(&new_filename);
-            if let Err(e) = std::fs::write(&path, data) {
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse::from(format!("Failed to save file: {}", e))),
-                )
-                    .into_response();
-            }
-
-            file_url = format!("/uploads/{}", new_filename);
-            break; // Currently only handles one file
-        }
-    }
-
-    if file_url.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse::from("No file provided")),
+    match state.manage_maintenance_rules_use_case.list().await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
         )
-            .into_response();
+            .into_response(),
     }
-
-    (StatusCode::OK, Json(serde_json::json!({ "url": file_url }))).into_response()
 }
 
-// Router
-
-async fn add_stock_item(
+async fn create_maintenance_rule(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    Json(payload): Json<AddStockItemCommand>,
+    actor: AuditActor,
+    Json(payload): Json<MaintenanceRuleCommand>,
 ) -> impl IntoResponse {
     if user.role != Role::Admin {
         return (
             StatusCode::FORBIDDEN,
-            Json(ErrorResponse::from("Only admins can manage stock")),
+            Json(ErrorResponse::from(
+                "Only admins can manage maintenance rules",
+            )),
         )
             .into_response();
     }
 
-    match state.add_stock_item_use_case.execute(payload).await {
+    match state
+        .manage_maintenance_rules_use_case
+        .create(payload, &actor)
+        .await
+    {
         Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn update_stock_item(
+async fn update_maintenance_rule(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    Json(payload): Json<UpdateStockItemCommand>,
+    actor: AuditActor,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+    Json(payload): Json<MaintenanceRuleCommand>,
 ) -> impl IntoResponse {
     if user.role != Role::Admin {
         return (
             StatusCode::FORBIDDEN,
-            Json(ErrorResponse::from("Only admins can manage stock")),
+            Json(ErrorResponse::from(
+                "Only admins can manage maintenance rules",
+            )),
         )
             .into_response();
     }
 
-    match state.update_stock_item_use_case.execute(payload).await {
+    match state
+        .manage_maintenance_rules_use_case
+        .update(id, payload, &actor)
+        .await
+    {
         Ok(result) => (StatusCode::OK, Json(result)).into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn delete_stock_item(
+async fn list_my_reminders(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    axum::extract::Path(item_id): axum::extract::Path<i32>,
 ) -> impl IntoResponse {
-    if user.role != Role::Admin {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(ErrorResponse::from("Only admins can manage stock")),
+    match state
+        .list_maintenance_reminders_use_case
+        .execute(user.user_id)
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::from(e)),
         )
-            .into_response();
+            .into_response(),
     }
+}
 
-    match state.delete_stock_item_use_case.execute(item_id).await {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
+async fn snooze_reminder(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+    Json(payload): Json<SnoozeReminderCommand>,
+) -> impl IntoResponse {
+    match state
+        .list_maintenance_reminders_use_case
+        .snooze(user.user_id, id, payload)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "message": "Reminder snoozed" })),
+        )
+            .into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn list_stock_items(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    match state.list_stock_items_use_case.execute().await {
-        Ok(items) => (StatusCode::OK, Json(items)).into_response(),
+async fn list_my_transfers(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+) -> impl IntoResponse {
+    match state.transfer_motorcycle_use_case.list(user.user_id).await {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse::from(e)),
@@ -657,56 +2576,76 @@ async fn list_stock_items(State(state): State<Arc<AppState>>) -> impl IntoRespon
     }
 }
 
-async fn use_stock_item(
+async fn initiate_transfer(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
-    Json(payload): Json<UseStockItemCommand>,
+    actor: AuditActor,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+    Json(payload): Json<InitiateTransferCommand>,
 ) -> impl IntoResponse {
-    if user.role != Role::Mechanic && user.role != Role::Admin {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(ErrorResponse::from(
-                "Only mechanics or admins can use stock items",
-            )),
-        )
-            .into_response();
+    match state
+        .transfer_motorcycle_use_case
+        .initiate(&actor, &user.role, id, payload)
+        .await
+    {
+        Ok(result) => (StatusCode::CREATED, Json(result)).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
+}
 
-    match state.use_stock_item_use_case.execute(payload).await {
-        Ok(_) => StatusCode::OK.into_response(),
+async fn accept_transfer(
+    State(state): State<Arc<AppState>>,
+    user: AuthUser,
+    axum::extract::Path(id): axum::extract::Path<i32>,
+) -> impl IntoResponse {
+    match state
+        .transfer_motorcycle_use_case
+        .accept(user.user_id, id)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "message": "Motorcycle added to your garage" })),
+        )
+            .into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn list_notifications(
+async fn decline_transfer(
     State(state): State<Arc<AppState>>,
     user: AuthUser,
+    axum::extract::Path(id): axum::extract::Path<i32>,
 ) -> impl IntoResponse {
     match state
-        .list_notifications_use_case
-        .execute(user.user_id)
+        .transfer_motorcycle_use_case
+        .decline(user.user_id, id)
         .await
     {
-        Ok(notifications) => (StatusCode::OK, Json(notifications)).into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::from(e)),
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "message": "Transfer declined" })),
         )
             .into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
 
-async fn mark_notification_read(
+async fn cancel_transfer(
     State(state): State<Arc<AppState>>,
-    _user: AuthUser,
-    axum::extract::Path(notification_id): axum::extract::Path<i32>,
+    user: AuthUser,
+    axum::extract::Path(id): axum::extract::Path<i32>,
 ) -> impl IntoResponse {
     match state
-        .mark_notification_read_use_case
-        .execute(notification_id)
+        .transfer_motorcycle_use_case
+        .cancel(user.user_id, &user.role, id)
         .await
     {
-        Ok(_) => StatusCode::OK.into_response(),
+        Ok(_) => (
+            StatusCode::OK,
+            Json(serde_json::json!({ "message": "Transfer cancelled" })),
+        )
+            .into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, Json(ErrorResponse::from(e))).into_response(),
     }
 }
@@ -719,6 +2658,11 @@ pub fn create_router() -> Router<Arc<AppState>> {
     let public_routes = Router::new()
         .route("/register", post(register_user))
         .route("/login", post(login))
+        .route("/auth/phone/code", post(request_phone_code))
+        .route("/auth/password/change", post(complete_password_change))
+        .route("/auth/mfa/verify", post(verify_mfa))
+        .route("/auth/mfa/enroll", post(enroll_mfa_with_challenge))
+        .route("/auth/mfa/confirm", post(confirm_mfa_with_challenge))
         .route("/auth/refresh", post(refresh_token))
         .route("/auth/logout", post(logout))
         .route("/feedback", post(submit_feedback))
@@ -744,6 +2688,16 @@ pub fn create_router() -> Router<Arc<AppState>> {
             "/orders/{id}",
             get(get_service_order_detail).delete(delete_service_order),
         )
+        .route(
+            "/orders/{id}/loyalty-redemption",
+            post(redeem_loyalty_points),
+        )
+        .route(
+            "/orders/{id}/quotations",
+            get(list_quotations).post(issue_quotation),
+        )
+        .route("/orders/{id}/suggested-parts", get(suggest_order_parts))
+        .route("/quotations/{id}/respond", post(respond_to_quotation))
         .route("/orders/photos", post(update_order_photos))
         .route("/orders/items", post(add_service_item))
         .route("/orders/items/{id}", delete(remove_service_item))
@@ -755,20 +2709,116 @@ pub fn create_router() -> Router<Arc<AppState>> {
                 .put(update_stock_item),
         )
         .route("/stock/{id}", delete(delete_stock_item))
+        .route("/stock/{id}/movements", get(list_stock_movements))
+        .route(
+            "/stock/{id}/compatibility",
+            get(get_stock_compatibility).put(set_stock_compatibility),
+        )
+        .route("/stock/consistency", get(check_stock_consistency))
+        .route("/stock/reorder-suggestions", get(suggest_reorder))
+        .route("/stock/low-stock", get(low_stock_report))
+        .route("/stock/by-barcode/{code}", get(get_stock_item_by_barcode))
+        .route("/stock/export", get(export_stock_catalogue))
+        .route("/stock/import", post(import_stock_catalogue))
+        .route(
+            "/stock/transfers",
+            get(list_stock_transfers).post(create_stock_transfer),
+        )
+        .route("/suppliers", get(list_suppliers).post(create_supplier))
+        .route("/suppliers/{id}", put(update_supplier))
+        .route(
+            "/purchase-orders",
+            get(list_purchase_orders).post(create_purchase_order),
+        )
+        .route(
+            "/purchase-orders/{id}",
+            get(get_purchase_order).put(update_purchase_order),
+        )
+        .route("/purchase-orders/{id}/send", post(send_purchase_order))
+        .route("/purchase-orders/{id}/cancel", post(cancel_purchase_order))
+        .route("/purchase-orders/{id}/receipts", post(receive_goods))
+        .route("/stock-takes", get(list_stock_takes).post(open_stock_take))
+        .route("/stock-takes/{id}", get(get_stock_take))
+        .route("/stock-takes/{id}/counts", put(record_stock_take_counts))
+        .route("/stock-takes/{id}/variance", get(get_stock_take_variance))
+        .route("/stock-takes/{id}/post", post(post_stock_take))
+        .route("/stock-takes/{id}/cancel", post(cancel_stock_take))
         .route("/feedback", get(list_feedbacks))
         .route("/feedback/{id}", delete(delete_feedback))
         .route("/payments", post(process_payment))
         .route("/users", get(list_users))
+        .route("/guests", post(create_guest_customer))
+        .route("/search", get(search))
+        .route("/users/{id}/deactivate", post(deactivate_user))
+        .route("/users/{id}/reactivate", post(reactivate_user))
+        .route(
+            "/users/{id}/branches",
+            get(get_user_branches).put(set_user_branches),
+        )
+        .route("/branches", get(list_branches).post(create_branch))
+        .route("/branches/{id}", put(update_branch))
+        .route("/users/{id}/loyalty", get(get_user_loyalty))
+        .route(
+            "/users/{id}/loyalty/adjustments",
+            post(adjust_loyalty_points),
+        )
+        .route("/admin/staff", post(create_staff))
         .route("/stats", get(get_dashboard_stats))
+        .route("/admin/audit", get(list_audit_events))
+        .route("/admin/erasure-requests", get(list_erasure_requests))
+        .route("/admin/jobs", get(list_scheduled_jobs))
+        .route(
+            "/admin/maintenance-rules",
+            get(list_maintenance_rules).post(create_maintenance_rule),
+        )
+        .route(
+            "/admin/maintenance-rules/{id}",
+            put(update_maintenance_rule),
+        )
+        .route(
+            "/admin/erasure-requests/{id}/approve",
+            post(approve_erasure_request),
+        )
+        .route(
+            "/admin/erasure-requests/{id}/reject",
+            post(reject_erasure_request),
+        )
         .route("/me", get(get_profile).put(update_profile))
+        .route("/me/phone/verify", post(confirm_phone))
+        .route("/me/loyalty", get(get_my_loyalty))
+        .route("/me/mfa/enroll", post(enroll_mfa))
+        .route("/me/mfa/confirm", post(confirm_mfa))
+        .route("/me/mfa/disable", post(disable_mfa))
+        .route("/me/export", get(export_personal_data))
+        .route("/me/erasure-request", post(request_erasure))
+        .route("/me/reminders", get(list_my_reminders))
+        .route("/me/reminders/{id}/snooze", post(snooze_reminder))
+        .route(
+            "/me/motorcycles",
+            get(list_my_motorcycles).post(register_motorcycle),
+        )
+        .route(
+            "/me/motorcycles/{id}",
+            put(update_motorcycle).delete(delete_motorcycle),
+        )
+        .route("/motorcycles/{id}/history", get(get_motorcycle_history))
+        .route("/motorcycles/{id}/transfer", post(initiate_transfer))
+        .route("/me/transfers", get(list_my_transfers))
+        .route("/transfers/{id}/accept", post(accept_transfer))
+        .route("/transfers/{id}/decline", post(decline_transfer))
+        .route("/transfers/{id}/cancel", post(cancel_transfer))
         .route("/notifications", get(list_notifications))
         .route("/notifications/{id}/read", post(mark_notification_read))
         .route("/line/connect", post(connect_line))
         .route("/line/disconnect", post(disconnect_line))
         .layer(auth_middleware);
 
+    // Public keys for services that verify our access tokens themselves
+    let well_known_routes = Router::new().route("/.well-known/jwks.json", get(get_jwks));
+
     Router::new()
         .merge(health_route)
+        .merge(well_known_routes)
         .nest("/api", public_routes.merge(protected_routes))
         .nest_service("/uploads", ServeDir::new("uploads"))
 }
@@ -0,0 +1,143 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+    OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
+use rsa::RsaPublicKey;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::traits::PublicKeyParts;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+// Key files inside JWT_KEYS_DIR:
+//   <kid>.public.pem  -> accepted for verification and published in the JWKS
+//   <kid>.private.pem -> only needed for the key named by JWT_SIGNING_KEY_ID
+const PUBLIC_KEY_SUFFIX: &str = ".public.pem";
+const PRIVATE_KEY_SUFFIX: &str = ".private.pem";
+
+/// Asymmetric signing key plus every public key we still accept, indexed by `kid`.
+/// Rotating means dropping a new key pair into the directory and pointing
+/// JWT_SIGNING_KEY_ID at it; the old public key stays until its tokens expire.
+pub struct KeyRing {
+    pub algorithm: Algorithm,
+    pub signing_kid: String,
+    pub encoding_key: EncodingKey,
+    pub verification_keys: HashMap<String, DecodingKey>,
+    pub jwks: JwkSet,
+}
+
+impl KeyRing {
+    pub fn load(algorithm: Algorithm, keys_dir: &str, signing_kid: &str) -> Result<Self, String> {
+        let dir = Path::new(keys_dir);
+        let entries = fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read JWT key directory {}: {}", keys_dir, e))?;
+
+        let mut verification_keys = HashMap::new();
+        let mut jwks = Vec::new();
+
+        for entry in entries {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            let file_name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+
+            let Some(kid) = file_name.strip_suffix(PUBLIC_KEY_SUFFIX) else {
+                continue;
+            };
+
+            let pem = fs::read(&path)
+                .map_err(|e| format!("Failed to read public key {}: {}", file_name, e))?;
+            let (decoding_key, parameters) = load_public_key(algorithm, &pem)
+                .map_err(|e| format!("Invalid public key {}: {}", file_name, e))?;
+
+            jwks.push(Jwk {
+                common: CommonParameters {
+                    public_key_use: Some(PublicKeyUse::Signature),
+                    key_algorithm: Some(key_algorithm(algorithm)),
+                    key_id: Some(kid.to_string()),
+                    ..Default::default()
+                },
+                algorithm: parameters,
+            });
+            verification_keys.insert(kid.to_string(), decoding_key);
+        }
+
+        if !verification_keys.contains_key(signing_kid) {
+            return Err(format!(
+                "Signing key '{}' has no {}{} in {}",
+                signing_kid, signing_kid, PUBLIC_KEY_SUFFIX, keys_dir
+            ));
+        }
+
+        let private_pem = fs::read(dir.join(format!("{}{}", signing_kid, PRIVATE_KEY_SUFFIX)))
+            .map_err(|e| format!("Failed to read private key for '{}': {}", signing_kid, e))?;
+        let encoding_key = match algorithm {
+            Algorithm::EdDSA => EncodingKey::from_ed_pem(&private_pem),
+            _ => EncodingKey::from_rsa_pem(&private_pem),
+        }
+        .map_err(|e| format!("Invalid private key for '{}': {}", signing_kid, e))?;
+
+        // Stable order so the published document doesn't shuffle between restarts
+        jwks.sort_by(|a, b| a.common.key_id.cmp(&b.common.key_id));
+
+        Ok(Self {
+            algorithm,
+            signing_kid: signing_kid.to_string(),
+            encoding_key,
+            verification_keys,
+            jwks: JwkSet { keys: jwks },
+        })
+    }
+}
+
+pub fn parse_algorithm(value: &str) -> Result<Algorithm, String> {
+    match value {
+        "HS256" => Ok(Algorithm::HS256),
+        "EdDSA" => Ok(Algorithm::EdDSA),
+        "RS256" => Ok(Algorithm::RS256),
+        other => Err(format!(
+            "Unsupported JWT_ALGORITHM '{}' (expected HS256, EdDSA or RS256)",
+            other
+        )),
+    }
+}
+
+fn key_algorithm(algorithm: Algorithm) -> KeyAlgorithm {
+    match algorithm {
+        Algorithm::EdDSA => KeyAlgorithm::EdDSA,
+        _ => KeyAlgorithm::RS256,
+    }
+}
+
+fn load_public_key(
+    algorithm: Algorithm,
+    pem: &[u8],
+) -> Result<(DecodingKey, AlgorithmParameters), String> {
+    match algorithm {
+        Algorithm::EdDSA => {
+            let key = DecodingKey::from_ed_pem(pem).map_err(|e| e.to_string())?;
+            let parameters = AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(key.as_bytes()),
+            });
+            Ok((key, parameters))
+        }
+        _ => {
+            let key = DecodingKey::from_rsa_pem(pem).map_err(|e| e.to_string())?;
+            // from_rsa_pem leaves us with the PKCS#1 body, which is exactly (n, e)
+            let public_key =
+                RsaPublicKey::from_pkcs1_der(key.as_bytes()).map_err(|e| e.to_string())?;
+            let parameters = AlgorithmParameters::RSA(RSAKeyParameters {
+                key_type: RSAKeyType::RSA,
+                n: URL_SAFE_NO_PAD.encode(public_key.n().to_bytes_be()),
+                e: URL_SAFE_NO_PAD.encode(public_key.e().to_bytes_be()),
+            });
+            Ok((key, parameters))
+        }
+    }
+}
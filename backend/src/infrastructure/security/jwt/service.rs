@@ -1,33 +1,67 @@
 use crate::domain::user::entity::Role;
-use jsonwebtoken::{DecodingKey, EncodingKey, Header, TokenData, Validation, decode, encode};
+use crate::infrastructure::security::jwt::keys::{KeyRing, parse_algorithm};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{
+    Algorithm, DecodingKey, EncodingKey, Header, TokenData, Validation, decode, decode_header,
+    encode,
+};
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+const CHALLENGE_EXPIRATION: usize = 300; // 5 minutes
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenUse {
+    Access,
+    Refresh,
+    // Short-lived proof that the password was checked; only redeemable for an MFA step
+    Mfa,
+    // Same, but for replacing a one-time password before the first session
+    PasswordChange,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String, // username
     pub user_id: i32,
     pub role: Role,
     pub exp: usize,
+    // Absent on tokens issued before key rotation support; those are told apart by secret instead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_use: Option<TokenUse>,
 }
 
 use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct JwtService {
-    encoding_key: Arc<EncodingKey>,
-    decoding_key: Arc<DecodingKey>,
-    refresh_encoding_key: Arc<EncodingKey>,
-    refresh_decoding_key: Arc<DecodingKey>,
+    // Set when JWT_ALGORITHM is EdDSA/RS256; signs everything with a `kid` header
+    key_ring: Option<Arc<KeyRing>>,
+    // HS256 secrets. Required in HS256 mode, optional otherwise so tokens issued
+    // before switching to asymmetric keys stay valid until they expire.
+    encoding_key: Option<Arc<EncodingKey>>,
+    decoding_key: Option<Arc<DecodingKey>>,
+    refresh_encoding_key: Option<Arc<EncodingKey>>,
+    refresh_decoding_key: Option<Arc<DecodingKey>>,
     expiration: usize,
     refresh_expiration: usize,
 }
 
+impl Default for JwtService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl JwtService {
     pub fn new() -> Self {
-        let secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
-        let refresh_secret = env::var("REFRESH_SECRET").unwrap_or_else(|_| secret.clone());
+        let algorithm =
+            parse_algorithm(&env::var("JWT_ALGORITHM").unwrap_or_else(|_| "HS256".to_string()))
+                .unwrap_or_else(|e| panic!("{}", e));
+        let secret = env::var("JWT_SECRET").ok();
+        let refresh_secret = env::var("REFRESH_SECRET").ok().or_else(|| secret.clone());
         let expiration = env::var("JWT_EXPIRATION")
             .unwrap_or_else(|_| "3600".to_string())
             .parse::<usize>()
@@ -37,11 +71,41 @@ impl JwtService {
             .parse::<usize>()
             .expect("REFRESH_EXPIRATION must be a number");
 
+        let key_ring = if algorithm == Algorithm::HS256 {
+            if secret.is_none() {
+                panic!("JWT_SECRET must be set");
+            }
+            None
+        } else {
+            let keys_dir = env::var("JWT_KEYS_DIR")
+                .expect("JWT_KEYS_DIR must be set for asymmetric JWT signing");
+            let signing_kid = env::var("JWT_SIGNING_KEY_ID")
+                .expect("JWT_SIGNING_KEY_ID must be set for asymmetric JWT signing");
+            let ring = KeyRing::load(algorithm, &keys_dir, &signing_kid)
+                .unwrap_or_else(|e| panic!("{}", e));
+            tracing::info!(
+                "JWT signing with {:?} key '{}' ({} verification keys)",
+                algorithm,
+                signing_kid,
+                ring.verification_keys.len()
+            );
+            Some(Arc::new(ring))
+        };
+
         Self {
-            encoding_key: Arc::new(EncodingKey::from_secret(secret.as_bytes())),
-            decoding_key: Arc::new(DecodingKey::from_secret(secret.as_bytes())),
-            refresh_encoding_key: Arc::new(EncodingKey::from_secret(refresh_secret.as_bytes())),
-            refresh_decoding_key: Arc::new(DecodingKey::from_secret(refresh_secret.as_bytes())),
+            key_ring,
+            encoding_key: secret
+                .as_ref()
+                .map(|s| Arc::new(EncodingKey::from_secret(s.as_bytes()))),
+            decoding_key: secret
+                .as_ref()
+                .map(|s| Arc::new(DecodingKey::from_secret(s.as_bytes()))),
+            refresh_encoding_key: refresh_secret
+                .as_ref()
+                .map(|s| Arc::new(EncodingKey::from_secret(s.as_bytes()))),
+            refresh_decoding_key: refresh_secret
+                .as_ref()
+                .map(|s| Arc::new(DecodingKey::from_secret(s.as_bytes()))),
             expiration,
             refresh_expiration,
         }
@@ -53,51 +117,160 @@ impl JwtService {
         username: &str,
         role: Role,
     ) -> Result<String, String> {
-        let expiration = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| e.to_string())?
-            .as_secs() as usize
-            + self.expiration;
+        let claims =
+            self.build_claims(user_id, username, role, self.expiration, TokenUse::Access)?;
+        self.sign(&claims, self.encoding_key.as_deref())
+    }
 
-        let claims = Claims {
-            sub: username.to_string(),
+    pub fn verify_token(&self, token: &str) -> Result<TokenData<Claims>, String> {
+        let token_data = self.verify(token, self.decoding_key.as_deref())?;
+        if !matches!(token_data.claims.token_use, None | Some(TokenUse::Access)) {
+            return Err("Only access tokens can be used for API access".to_string());
+        }
+        Ok(token_data)
+    }
+
+    pub fn generate_refresh_token(
+        &self,
+        user_id: i32,
+        username: &str,
+        role: Role,
+    ) -> Result<String, String> {
+        let claims = self.build_claims(
             user_id,
+            username,
             role,
-            exp: expiration,
-        };
+            self.refresh_expiration,
+            TokenUse::Refresh,
+        )?;
+        self.sign(&claims, self.refresh_encoding_key.as_deref())
+    }
 
-        encode(&Header::default(), &claims, &self.encoding_key).map_err(|e| e.to_string())
+    pub fn verify_refresh_token(&self, token: &str) -> Result<TokenData<Claims>, String> {
+        let token_data = self.verify(token, self.refresh_decoding_key.as_deref())?;
+        if !matches!(token_data.claims.token_use, None | Some(TokenUse::Refresh)) {
+            return Err("Only refresh tokens can be used to refresh a session".to_string());
+        }
+        Ok(token_data)
     }
 
-    pub fn verify_token(&self, token: &str) -> Result<TokenData<Claims>, String> {
-        decode::<Claims>(token, &self.decoding_key, &Validation::default())
-            .map_err(|e| e.to_string())
+    pub fn generate_mfa_token(
+        &self,
+        user_id: i32,
+        username: &str,
+        role: Role,
+    ) -> Result<String, String> {
+        self.generate_challenge(user_id, username, role, TokenUse::Mfa)
     }
 
-    pub fn generate_refresh_token(
+    pub fn verify_mfa_token(&self, token: &str) -> Result<TokenData<Claims>, String> {
+        self.verify_challenge(token, TokenUse::Mfa)
+    }
+
+    pub fn generate_password_change_token(
         &self,
         user_id: i32,
         username: &str,
         role: Role,
     ) -> Result<String, String> {
+        self.generate_challenge(user_id, username, role, TokenUse::PasswordChange)
+    }
+
+    pub fn verify_password_change_token(&self, token: &str) -> Result<TokenData<Claims>, String> {
+        self.verify_challenge(token, TokenUse::PasswordChange)
+    }
+
+    /// Public verification keys for `/.well-known/jwks.json`. Empty in HS256 mode,
+    /// since the shared secret must never be published.
+    pub fn jwks(&self) -> JwkSet {
+        match &self.key_ring {
+            Some(ring) => ring.jwks.clone(),
+            None => JwkSet { keys: Vec::new() },
+        }
+    }
+
+    fn generate_challenge(
+        &self,
+        user_id: i32,
+        username: &str,
+        role: Role,
+        token_use: TokenUse,
+    ) -> Result<String, String> {
+        let claims = self.build_claims(user_id, username, role, CHALLENGE_EXPIRATION, token_use)?;
+        self.sign(&claims, self.encoding_key.as_deref())
+    }
+
+    fn verify_challenge(
+        &self,
+        token: &str,
+        token_use: TokenUse,
+    ) -> Result<TokenData<Claims>, String> {
+        let token_data = self.verify(token, self.decoding_key.as_deref())?;
+        if token_data.claims.token_use != Some(token_use) {
+            return Err("Invalid challenge token".to_string());
+        }
+        Ok(token_data)
+    }
+
+    fn build_claims(
+        &self,
+        user_id: i32,
+        username: &str,
+        role: Role,
+        lifetime: usize,
+        token_use: TokenUse,
+    ) -> Result<Claims, String> {
         let expiration = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map_err(|e| e.to_string())?
             .as_secs() as usize
-            + self.refresh_expiration;
+            + lifetime;
 
-        let claims = Claims {
+        Ok(Claims {
             sub: username.to_string(),
             user_id,
             role,
             exp: expiration,
-        };
+            token_use: Some(token_use),
+        })
+    }
 
-        encode(&Header::default(), &claims, &self.refresh_encoding_key).map_err(|e| e.to_string())
+    fn sign(&self, claims: &Claims, secret: Option<&EncodingKey>) -> Result<String, String> {
+        match &self.key_ring {
+            Some(ring) => {
+                let mut header = Header::new(ring.algorithm);
+                header.kid = Some(ring.signing_kid.clone());
+                encode(&header, claims, &ring.encoding_key).map_err(|e| e.to_string())
+            }
+            None => {
+                let secret = secret.ok_or("JWT secret is not configured")?;
+                encode(&Header::default(), claims, secret).map_err(|e| e.to_string())
+            }
+        }
     }
 
-    pub fn verify_refresh_token(&self, token: &str) -> Result<TokenData<Claims>, String> {
-        decode::<Claims>(token, &self.refresh_decoding_key, &Validation::default())
-            .map_err(|e| e.to_string())
+    fn verify(
+        &self,
+        token: &str,
+        secret: Option<&DecodingKey>,
+    ) -> Result<TokenData<Claims>, String> {
+        let header = decode_header(token).map_err(|e| e.to_string())?;
+
+        match (header.kid, &self.key_ring) {
+            (Some(kid), Some(ring)) => {
+                let key = ring
+                    .verification_keys
+                    .get(&kid)
+                    .ok_or_else(|| format!("Unknown signing key '{}'", kid))?;
+                // Pin the algorithm so a token can't pick a weaker one for itself
+                decode::<Claims>(token, key, &Validation::new(ring.algorithm))
+                    .map_err(|e| e.to_string())
+            }
+            (Some(kid), None) => Err(format!("Unknown signing key '{}'", kid)),
+            (None, _) => {
+                let secret = secret.ok_or("Token has no key id")?;
+                decode::<Claims>(token, secret, &Validation::default()).map_err(|e| e.to_string())
+            }
+        }
     }
 }
@@ -0,0 +1,99 @@
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use std::env;
+use std::time::{SystemTime, UNIX_EPOCH};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+// Standard authenticator app settings (Google Authenticator, Authy, 1Password, ...)
+const TOTP_DIGITS: usize = 6;
+const TOTP_STEP: u64 = 30;
+// Codes from the previous/next step are accepted to tolerate clock drift
+const TOTP_SKEW: i64 = 1;
+
+pub const RECOVERY_CODE_COUNT: usize = 10;
+// No 0/O or 1/I so codes survive being read aloud or copied by hand
+const RECOVERY_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+pub fn generate_totp_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+/// otpauth:// URI for authenticator apps; the frontend renders it as a QR code
+pub fn provisioning_uri(secret: &str, account_name: &str) -> Result<String, String> {
+    Ok(build_totp(secret, account_name)?.get_url())
+}
+
+/// Returns the time step the code belongs to, or None if it doesn't match
+pub fn verify_totp_code(secret: &str, code: &str) -> Result<Option<i64>, String> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(None);
+    }
+
+    let totp = build_totp(secret, "")?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| e.to_string())?
+        .as_secs() as i64;
+    let current_step = now / TOTP_STEP as i64;
+
+    for step in (current_step - TOTP_SKEW)..=(current_step + TOTP_SKEW) {
+        let expected = totp.generate(step as u64 * TOTP_STEP);
+        if constant_time_eq(expected.as_bytes(), code.as_bytes()) {
+            return Ok(Some(step));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Plaintext recovery codes in XXXXX-XXXXX form. Shown to the user once, stored hashed.
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let mut bytes = [0u8; 10];
+            OsRng.fill_bytes(&mut bytes);
+            let chars: String = bytes
+                .iter()
+                .map(|b| RECOVERY_CODE_ALPHABET[*b as usize % RECOVERY_CODE_ALPHABET.len()] as char)
+                .collect();
+            format!("{}-{}", &chars[..5], &chars[5..])
+        })
+        .collect()
+}
+
+/// Recovery codes are compared case-insensitively and with or without the dash
+pub fn normalize_recovery_code(code: &str) -> String {
+    let compact: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if compact.len() == 10 {
+        format!("{}-{}", &compact[..5], &compact[5..])
+    } else {
+        compact
+    }
+}
+
+fn build_totp(secret: &str, account_name: &str) -> Result<TOTP, String> {
+    let bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| format!("Invalid TOTP secret: {:?}", e))?;
+    let issuer = env::var("MFA_ISSUER").unwrap_or_else(|_| "Motorcycle Service".to_string());
+
+    // ':' separates issuer and account in the otpauth label
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        0,
+        TOTP_STEP,
+        bytes,
+        Some(issuer.replace(':', "")),
+        account_name.replace(':', ""),
+    )
+    .map_err(|e| e.to_string())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
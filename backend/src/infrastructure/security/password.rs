@@ -1,8 +1,16 @@
 use argon2::{
     Argon2,
-    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
+    password_hash::{
+        PasswordHash, PasswordHasher, PasswordVerifier, SaltString,
+        rand_core::{OsRng, RngCore},
+    },
 };
 
+// Readable alphabet for passwords handed over in person (no 0/O, 1/l/I)
+const ONE_TIME_PASSWORD_ALPHABET: &[u8] =
+    b"abcdefghijkmnpqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const ONE_TIME_PASSWORD_LENGTH: usize = 12;
+
 pub fn hash_password(password: &str) -> Result<String, String> {
     let salt = SaltString::generate(&mut OsRng);
     let argon2 = Argon2::default();
@@ -23,3 +31,18 @@ pub fn verify_password(hash: &str, password: &str) -> Result<bool, String> {
         .verify_password(password.as_bytes(), &parsed_hash)
         .is_ok())
 }
+
+/// Temporary password for admin-created accounts; must be replaced at first login
+pub fn generate_one_time_password() -> String {
+    let mut bytes = [0u8; ONE_TIME_PASSWORD_LENGTH];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+        .iter()
+        .map(|b| ONE_TIME_PASSWORD_ALPHABET[*b as usize % ONE_TIME_PASSWORD_ALPHABET.len()] as char)
+        .collect()
+}
+
+/// Six-digit code texted to a phone to prove the holder has it
+pub fn generate_phone_code() -> String {
+    format!("{:06}", OsRng.next_u32() % 1_000_000)
+}
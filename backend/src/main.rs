@@ -4,40 +4,104 @@ use backend::application::state::AppState;
 use backend::application::use_cases::submit_feedback::SubmitFeedbackUseCase;
 use backend::infrastructure::db::repositories::feedback::FeedbackRepository;
 
+use backend::application::scheduler::JobScheduler;
+use backend::application::scheduler::jobs::{
+    ExpireStaleOrdersJob, LowStockDigestJob, LoyaltyExpiryJob, MaintenanceRemindersJob,
+    RefreshTokenCleanupJob, StockConsistencyJob,
+};
 use backend::application::use_cases::add_service_item::AddServiceItemUseCase;
+use backend::application::use_cases::branch::BranchUseCase;
+use backend::application::use_cases::check_stock_consistency::CheckStockConsistencyUseCase;
+use backend::application::use_cases::confirm_mfa::ConfirmMfaUseCase;
 use backend::application::use_cases::connect_line::ConnectLineUseCase;
+use backend::application::use_cases::create_guest_customer::CreateGuestCustomerUseCase;
 use backend::application::use_cases::create_service_order::CreateServiceOrderUseCase;
+use backend::application::use_cases::create_staff::CreateStaffUseCase;
 use backend::application::use_cases::delete_feedback::DeleteFeedbackUseCase;
+use backend::application::use_cases::delete_motorcycle::DeleteMotorcycleUseCase;
 use backend::application::use_cases::delete_service_order::DeleteServiceOrderUseCase;
+use backend::application::use_cases::disable_mfa::DisableMfaUseCase;
 use backend::application::use_cases::disconnect_line::DisconnectLineUseCase;
+use backend::application::use_cases::enroll_mfa::EnrollMfaUseCase;
+use backend::application::use_cases::expire_stale_orders::{
+    ExpireStaleOrdersUseCase, ExpiryWindows,
+};
+use backend::application::use_cases::export_personal_data::ExportPersonalDataUseCase;
 use backend::application::use_cases::get_dashboard_stats::GetDashboardStatsUseCase;
+use backend::application::use_cases::get_motorcycle_history::GetMotorcycleHistoryUseCase;
 use backend::application::use_cases::get_profile::GetProfileUseCase;
 use backend::application::use_cases::get_service_order_detail::GetServiceOrderDetailUseCase;
+use backend::application::use_cases::get_stock_item_by_barcode::GetStockItemByBarcodeUseCase;
+use backend::application::use_cases::list_audit_events::ListAuditEventsUseCase;
+use backend::application::use_cases::list_erasure_requests::ListErasureRequestsUseCase;
 use backend::application::use_cases::list_feedbacks::ListFeedbacksUseCase;
+use backend::application::use_cases::list_maintenance_reminders::ListMaintenanceRemindersUseCase;
+use backend::application::use_cases::list_motorcycles::ListMotorcyclesUseCase;
+use backend::application::use_cases::list_scheduled_jobs::ListScheduledJobsUseCase;
 use backend::application::use_cases::list_service_orders::ListServiceOrdersUseCase;
+use backend::application::use_cases::list_stock_movements::ListStockMovementsUseCase;
 use backend::application::use_cases::list_users::ListUsersUseCase;
 use backend::application::use_cases::login::LoginUseCase;
 use backend::application::use_cases::logout::LogoutUseCase;
+use backend::application::use_cases::low_stock::LowStockUseCase;
+use backend::application::use_cases::loyalty::LoyaltyUseCase;
+use backend::application::use_cases::manage_maintenance_rules::ManageMaintenanceRulesUseCase;
+use backend::application::use_cases::manage_suppliers::ManageSuppliersUseCase;
+use backend::application::use_cases::part_compatibility::PartCompatibilityUseCase;
 use backend::application::use_cases::process_payment::ProcessPaymentUseCase;
 use backend::application::use_cases::promote_user::PromoteUserUseCase;
+use backend::application::use_cases::purchase_order::PurchaseOrderUseCase;
+use backend::application::use_cases::quotation::QuotationUseCase;
 use backend::application::use_cases::refresh_token::RefreshTokenUseCase;
+use backend::application::use_cases::register_motorcycle::RegisterMotorcycleUseCase;
 use backend::application::use_cases::register_user::RegisterUserUseCase;
 use backend::application::use_cases::remove_service_item::RemoveServiceItemUseCase;
+use backend::application::use_cases::request_erasure::RequestErasureUseCase;
+use backend::application::use_cases::review_erasure_request::ReviewErasureRequestUseCase;
+use backend::application::use_cases::search::SearchUseCase;
+use backend::application::use_cases::send_maintenance_reminders::SendMaintenanceRemindersUseCase;
+use backend::application::use_cases::set_user_active::SetUserActiveUseCase;
+use backend::application::use_cases::stock_catalogue::StockCatalogueUseCase;
+use backend::application::use_cases::stock_take::StockTakeUseCase;
+use backend::application::use_cases::stock_transfer::StockTransferUseCase;
+use backend::application::use_cases::transfer_motorcycle::TransferMotorcycleUseCase;
+use backend::application::use_cases::update_motorcycle::UpdateMotorcycleUseCase;
 use backend::application::use_cases::update_order_photos::UpdateOrderPhotosUseCase;
 use backend::application::use_cases::update_order_status::UpdateOrderStatusUseCase;
 use backend::application::use_cases::update_profile::UpdateProfileUseCase;
-use backend::domain::notification::gateway::NotificationGateway;
+use backend::application::use_cases::verify_phone::VerifyPhoneUseCase;
+use backend::domain::notification::gateway::{NotificationGateway, SmsGateway};
 use backend::domain::payment::gateway::PaymentGateway;
 use backend::infrastructure::db::connection::establish_connection;
+use backend::infrastructure::db::repositories::audit_event::AuditEventRepository;
+use backend::infrastructure::db::repositories::branch::BranchRepository;
+use backend::infrastructure::db::repositories::erasure_request::ErasureRequestRepository;
+use backend::infrastructure::db::repositories::guest_customer::GuestCustomerRepository;
 use backend::infrastructure::db::repositories::inventory::InventoryRepository;
+use backend::infrastructure::db::repositories::loyalty::LoyaltyRepository;
+use backend::infrastructure::db::repositories::maintenance::MaintenanceRepository;
 use backend::infrastructure::db::repositories::motorcycle::MotorcycleRepository;
+use backend::infrastructure::db::repositories::motorcycle_transfer::MotorcycleTransferRepository;
+use backend::infrastructure::db::repositories::part_compatibility::PartCompatibilityRepository;
+use backend::infrastructure::db::repositories::personal_data::PersonalDataRepository;
+use backend::infrastructure::db::repositories::phone_verification::PhoneVerificationRepository;
+use backend::infrastructure::db::repositories::purchase_order::PurchaseOrderRepository;
+use backend::infrastructure::db::repositories::quotation::QuotationRepository;
 use backend::infrastructure::db::repositories::refresh_token::RefreshTokenRepository;
 use backend::infrastructure::db::repositories::repair_log::RepairLogRepository;
+use backend::infrastructure::db::repositories::scheduled_job::ScheduledJobRepository;
+use backend::infrastructure::db::repositories::search::SearchRepository;
 use backend::infrastructure::db::repositories::service_item::ServiceItemRepository;
 use backend::infrastructure::db::repositories::service_order::ServiceOrderRepository;
+use backend::infrastructure::db::repositories::stock_movement::StockMovementRepository;
+use backend::infrastructure::db::repositories::stock_take::StockTakeRepository;
+use backend::infrastructure::db::repositories::stock_transfer::StockTransferRepository;
+use backend::infrastructure::db::repositories::supplier::SupplierRepository;
 use backend::infrastructure::db::repositories::user::UserRepository;
 use backend::infrastructure::db::repositories::user_line_account::UserLineAccountRepository;
+use backend::infrastructure::db::repositories::user_mfa::UserMfaRepository;
 use backend::infrastructure::external::notification::line::LineNotificationGateway;
+use backend::infrastructure::external::notification::sms::WebhookSmsGateway;
 use backend::infrastructure::external::payment::omise::OmiseGateway;
 use backend::infrastructure::security::jwt::service::JwtService;
 use std::net::SocketAddr;
@@ -76,6 +140,25 @@ async fn main() {
         );
     let feedback_repository = FeedbackRepository::new(pool.clone());
     let repair_log_repository = RepairLogRepository::new(pool.clone());
+    let user_mfa_repository = UserMfaRepository::new(pool.clone());
+    let audit_event_repository = AuditEventRepository::new(pool.clone());
+    let erasure_request_repository = ErasureRequestRepository::new(pool.clone());
+    let personal_data_repository = PersonalDataRepository::new(pool.clone());
+    let maintenance_repository = MaintenanceRepository::new(pool.clone());
+    let motorcycle_transfer_repository = MotorcycleTransferRepository::new(pool.clone());
+    let scheduled_job_repository = ScheduledJobRepository::new(pool.clone());
+    let search_repository = SearchRepository::new(pool.clone());
+    let guest_customer_repository = GuestCustomerRepository::new(pool.clone());
+    let phone_verification_repository = PhoneVerificationRepository::new(pool.clone());
+    let loyalty_repository = LoyaltyRepository::new(pool.clone());
+    let quotation_repository = QuotationRepository::new(pool.clone());
+    let stock_movement_repository = StockMovementRepository::new(pool.clone());
+    let supplier_repository = SupplierRepository::new(pool.clone());
+    let purchase_order_repository = PurchaseOrderRepository::new(pool.clone());
+    let stock_take_repository = StockTakeRepository::new(pool.clone());
+    let branch_repository = BranchRepository::new(pool.clone());
+    let stock_transfer_repository = StockTransferRepository::new(pool.clone());
+    let part_compatibility_repository = PartCompatibilityRepository::new(pool.clone());
 
     // Gateways
     let omise_gateway: Arc<dyn PaymentGateway + Send + Sync> = Arc::new(OmiseGateway::new());
@@ -92,28 +175,97 @@ async fn main() {
             web_gateway,
         ]));
 
+    let sms_gateway: Arc<dyn SmsGateway + Send + Sync> = Arc::new(WebhookSmsGateway::new());
+
     // Services
     let jwt_service = JwtService::new();
+    // When set, admins can't get a session without a second factor
+    let mfa_required_for_admins = std::env::var("MFA_REQUIRED_FOR_ADMINS")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+    let frontend_url =
+        std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
+    // Job schedules use cron syntax with a seconds field, evaluated in UTC
+    let schedule = |name: &str, default: &str| std::env::var(name).unwrap_or(default.to_string());
+    let maintenance_reminder_schedule = schedule("MAINTENANCE_REMINDER_SCHEDULE", "0 0 2 * * *");
+    let expire_orders_schedule = schedule("EXPIRE_ORDERS_SCHEDULE", "0 15 * * * *");
+    let token_cleanup_schedule = schedule("TOKEN_CLEANUP_SCHEDULE", "0 30 3 * * *");
+    let loyalty_expiry_schedule = schedule("LOYALTY_EXPIRY_SCHEDULE", "0 45 2 * * *");
+    let stock_check_schedule = schedule("STOCK_CHECK_SCHEDULE", "0 0 4 * * *");
+    let low_stock_digest_schedule = schedule("LOW_STOCK_DIGEST_SCHEDULE", "0 0 1 * * *");
+    // Days an order may wait on the customer before it's cancelled automatically
+    let days = |name: &str, default: i64| {
+        std::env::var(name)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default)
+    };
+    let expiry_windows = ExpiryWindows {
+        quote_days: days("QUOTE_EXPIRY_DAYS", 7),
+        booking_days: days("BOOKING_EXPIRY_DAYS", 14),
+    };
 
     // Use Cases
-    let register_user_use_case = RegisterUserUseCase::new(user_repository.clone());
-    let promote_user_use_case = PromoteUserUseCase::new(user_repository.clone());
+    let verify_phone_use_case = VerifyPhoneUseCase::new(
+        phone_verification_repository,
+        user_repository.clone(),
+        guest_customer_repository,
+        sms_gateway,
+        audit_event_repository.clone(),
+    );
+    let loyalty_use_case = LoyaltyUseCase::new(
+        loyalty_repository,
+        service_order_repository.clone(),
+        user_repository.clone(),
+        audit_event_repository.clone(),
+    );
+    let register_user_use_case =
+        RegisterUserUseCase::new(user_repository.clone(), verify_phone_use_case.clone());
+    let create_guest_customer_use_case =
+        CreateGuestCustomerUseCase::new(user_repository.clone(), audit_event_repository.clone());
     let login_use_case = LoginUseCase::new(
         user_repository.clone(),
         user_line_account_repository.clone(),
         refresh_token_repository.clone(),
+        user_mfa_repository.clone(),
         jwt_service.clone(),
         line_gateway.clone(),
+        mfa_required_for_admins,
     );
     let logout_use_case = LogoutUseCase::new(refresh_token_repository.clone());
-    let refresh_token_use_case =
-        RefreshTokenUseCase::new(refresh_token_repository, jwt_service.clone());
+    let refresh_token_use_case = RefreshTokenUseCase::new(
+        refresh_token_repository.clone(),
+        user_repository.clone(),
+        jwt_service.clone(),
+    );
+    let promote_user_use_case = PromoteUserUseCase::new(
+        user_repository.clone(),
+        user_line_account_repository.clone(),
+        branch_repository.clone(),
+        notification_gateway.clone(),
+    );
+    let create_staff_use_case = CreateStaffUseCase::new(
+        user_repository.clone(),
+        audit_event_repository.clone(),
+        branch_repository.clone(),
+    );
+    let set_user_active_use_case =
+        SetUserActiveUseCase::new(user_repository.clone(), refresh_token_repository.clone());
+    let branch_use_case = BranchUseCase::new(branch_repository.clone(), user_repository.clone());
     let create_service_order_use_case = CreateServiceOrderUseCase::new(
         service_order_repository.clone(),
         motorcycle_repository.clone(),
         user_line_account_repository.clone(),
         user_repository.clone(),
         notification_gateway.clone(),
+        maintenance_repository.clone(),
+        branch_use_case.clone(),
+    );
+    let low_stock_use_case = LowStockUseCase::new(
+        stock_item_repository.clone(),
+        user_repository.clone(),
+        user_line_account_repository.clone(),
+        notification_gateway.clone(),
     );
     let process_payment_use_case = ProcessPaymentUseCase::new(
         service_order_repository.clone(),
@@ -122,6 +274,7 @@ async fn main() {
         omise_gateway,
         notification_gateway.clone(),
         repair_log_repository.clone(),
+        loyalty_use_case.clone(),
     );
     let list_users_use_case = ListUsersUseCase::new(user_repository.clone());
     let list_service_orders_use_case =
@@ -131,21 +284,49 @@ async fn main() {
         user_line_account_repository.clone(),
         user_repository.clone(),
         notification_gateway.clone(),
-        repair_log_repository,
+        repair_log_repository.clone(),
+        loyalty_use_case.clone(),
+        quotation_repository.clone(),
+        low_stock_use_case.clone(),
+    );
+    let quotation_use_case = QuotationUseCase::new(
+        quotation_repository.clone(),
+        service_order_repository.clone(),
+        repair_log_repository.clone(),
+        update_order_status_use_case.clone(),
+        user_line_account_repository.clone(),
+        notification_gateway.clone(),
+        expiry_windows.quote_days,
+        low_stock_use_case.clone(),
     );
     let get_profile_use_case = GetProfileUseCase::new(
         user_repository.clone(),
         user_line_account_repository.clone(),
+        user_mfa_repository.clone(),
+    );
+    let enroll_mfa_use_case =
+        EnrollMfaUseCase::new(user_repository.clone(), user_mfa_repository.clone());
+    let confirm_mfa_use_case = ConfirmMfaUseCase::new(user_mfa_repository.clone());
+    let disable_mfa_use_case = DisableMfaUseCase::new(
+        user_repository.clone(),
+        user_mfa_repository,
+        mfa_required_for_admins,
+    );
+    let connect_line_use_case = ConnectLineUseCase::new(
+        user_line_account_repository.clone(),
+        line_gateway.clone(),
+        user_repository.clone(),
+        verify_phone_use_case.clone(),
     );
-    let connect_line_use_case =
-        ConnectLineUseCase::new(user_line_account_repository.clone(), line_gateway.clone());
     let disconnect_line_use_case = DisconnectLineUseCase::new(user_line_account_repository.clone());
     let get_dashboard_stats_use_case = GetDashboardStatsUseCase::new(
         service_order_repository.clone(),
         user_repository.clone(),
-        motorcycle_repository,
+        motorcycle_repository.clone(),
+        service_item_repository.clone(),
+        stock_item_repository.clone(),
     );
-    let update_profile_use_case = UpdateProfileUseCase::new(user_repository);
+    let update_profile_use_case = UpdateProfileUseCase::new(user_repository.clone());
     let update_order_photos_use_case =
         UpdateOrderPhotosUseCase::new(service_order_repository.clone());
     let get_service_order_detail_use_case =
@@ -161,27 +342,74 @@ async fn main() {
     );
     let submit_feedback_use_case = SubmitFeedbackUseCase::new(feedback_repository.clone());
     let list_feedbacks_use_case = ListFeedbacksUseCase::new(feedback_repository.clone());
-    let delete_feedback_use_case = DeleteFeedbackUseCase::new(feedback_repository.clone());
+    let delete_feedback_use_case =
+        DeleteFeedbackUseCase::new(feedback_repository.clone(), audit_event_repository.clone());
 
     let add_stock_item_use_case =
         backend::application::use_cases::add_stock_item::AddStockItemUseCase::new(
             stock_item_repository.clone(),
+            branch_use_case.clone(),
         );
     let list_stock_items_use_case =
         backend::application::use_cases::list_stock_items::ListStockItemsUseCase::new(
             stock_item_repository.clone(),
         );
+    let get_stock_item_by_barcode_use_case =
+        GetStockItemByBarcodeUseCase::new(stock_item_repository.clone(), branch_use_case.clone());
     let update_stock_item_use_case =
         backend::application::use_cases::update_stock_item::UpdateStockItemUseCase::new(
             stock_item_repository.clone(),
+            audit_event_repository.clone(),
+            low_stock_use_case.clone(),
+            branch_use_case.clone(),
         );
+    let stock_catalogue_use_case = StockCatalogueUseCase::new(
+        stock_item_repository.clone(),
+        audit_event_repository.clone(),
+        low_stock_use_case.clone(),
+        branch_use_case.clone(),
+    );
+    let stock_take_use_case = StockTakeUseCase::new(
+        stock_take_repository,
+        audit_event_repository.clone(),
+        low_stock_use_case.clone(),
+        branch_use_case.clone(),
+    );
+    let stock_transfer_use_case = StockTransferUseCase::new(
+        stock_transfer_repository,
+        audit_event_repository.clone(),
+        branch_use_case.clone(),
+    );
     let delete_stock_item_use_case =
         backend::application::use_cases::delete_stock_item::DeleteStockItemUseCase::new(
             stock_item_repository.clone(),
         );
+    let list_stock_movements_use_case = ListStockMovementsUseCase::new(
+        stock_movement_repository.clone(),
+        stock_item_repository.clone(),
+        branch_use_case.clone(),
+    );
+    let check_stock_consistency_use_case =
+        CheckStockConsistencyUseCase::new(stock_movement_repository);
+    let manage_suppliers_use_case =
+        ManageSuppliersUseCase::new(supplier_repository, audit_event_repository.clone());
+    let purchase_order_use_case = PurchaseOrderUseCase::new(
+        purchase_order_repository,
+        audit_event_repository.clone(),
+        branch_use_case.clone(),
+    );
+    let part_compatibility_use_case = PartCompatibilityUseCase::new(
+        part_compatibility_repository,
+        stock_item_repository.clone(),
+        service_order_repository.clone(),
+        motorcycle_repository.clone(),
+        audit_event_repository.clone(),
+    );
     let use_stock_item_use_case =
         backend::application::use_cases::use_stock_item::UseStockItemUseCase::new(
             inventory_repository.clone(),
+            low_stock_use_case.clone(),
+            part_compatibility_use_case.clone(),
         );
     let remove_service_item_use_case = RemoveServiceItemUseCase::new(inventory_repository.clone());
 
@@ -194,6 +422,56 @@ async fn main() {
             notification_repository.clone(),
         );
 
+    let export_personal_data_use_case =
+        ExportPersonalDataUseCase::new(personal_data_repository.clone());
+    let request_erasure_use_case =
+        RequestErasureUseCase::new(user_repository.clone(), erasure_request_repository.clone());
+    let list_erasure_requests_use_case =
+        ListErasureRequestsUseCase::new(erasure_request_repository.clone());
+    let review_erasure_request_use_case = ReviewErasureRequestUseCase::new(
+        erasure_request_repository,
+        personal_data_repository,
+        service_order_repository.clone(),
+        audit_event_repository.clone(),
+    );
+    let list_audit_events_use_case = ListAuditEventsUseCase::new(audit_event_repository.clone());
+
+    let manage_maintenance_rules_use_case = ManageMaintenanceRulesUseCase::new(
+        maintenance_repository.clone(),
+        audit_event_repository.clone(),
+    );
+    let list_maintenance_reminders_use_case =
+        ListMaintenanceRemindersUseCase::new(maintenance_repository.clone());
+    let send_maintenance_reminders_use_case = SendMaintenanceRemindersUseCase::new(
+        maintenance_repository,
+        user_line_account_repository.clone(),
+        notification_gateway.clone(),
+        frontend_url.clone(),
+    );
+
+    let search_use_case = SearchUseCase::new(search_repository);
+    let list_scheduled_jobs_use_case =
+        ListScheduledJobsUseCase::new(scheduled_job_repository.clone());
+
+    let list_motorcycles_use_case = ListMotorcyclesUseCase::new(motorcycle_repository.clone());
+    let register_motorcycle_use_case =
+        RegisterMotorcycleUseCase::new(motorcycle_repository.clone());
+    let update_motorcycle_use_case = UpdateMotorcycleUseCase::new(motorcycle_repository.clone());
+    let delete_motorcycle_use_case = DeleteMotorcycleUseCase::new(motorcycle_repository.clone());
+    let transfer_motorcycle_use_case = TransferMotorcycleUseCase::new(
+        motorcycle_repository.clone(),
+        motorcycle_transfer_repository,
+        user_repository.clone(),
+        user_line_account_repository.clone(),
+        notification_gateway.clone(),
+        audit_event_repository.clone(),
+    );
+    let get_motorcycle_history_use_case = GetMotorcycleHistoryUseCase::new(
+        motorcycle_repository,
+        service_order_repository.clone(),
+        repair_log_repository,
+    );
+
     let app_state = Arc::new(AppState {
         submit_feedback_use_case,
         list_feedbacks_use_case,
@@ -225,12 +503,44 @@ async fn main() {
         mark_notification_read_use_case,
         delete_service_order_use_case,
         remove_service_item_use_case,
+        enroll_mfa_use_case,
+        confirm_mfa_use_case,
+        disable_mfa_use_case,
+        list_audit_events_use_case,
+        export_personal_data_use_case,
+        request_erasure_use_case,
+        list_erasure_requests_use_case,
+        review_erasure_request_use_case,
+        create_staff_use_case,
+        set_user_active_use_case,
+        list_motorcycles_use_case,
+        register_motorcycle_use_case,
+        update_motorcycle_use_case,
+        delete_motorcycle_use_case,
+        get_motorcycle_history_use_case,
+        manage_maintenance_rules_use_case,
+        list_maintenance_reminders_use_case,
+        transfer_motorcycle_use_case,
+        list_scheduled_jobs_use_case,
+        search_use_case,
+        create_guest_customer_use_case,
+        verify_phone_use_case,
+        loyalty_use_case: loyalty_use_case.clone(),
+        quotation_use_case,
+        list_stock_movements_use_case,
+        check_stock_consistency_use_case: check_stock_consistency_use_case.clone(),
+        manage_suppliers_use_case,
+        purchase_order_use_case,
+        low_stock_use_case: low_stock_use_case.clone(),
+        get_stock_item_by_barcode_use_case,
+        stock_catalogue_use_case,
+        stock_take_use_case,
+        stock_transfer_use_case,
+        part_compatibility_use_case,
+        branch_use_case,
         jwt_service: jwt_service.clone(),
     });
 
-    let frontend_url =
-        std::env::var("FRONTEND_URL").unwrap_or_else(|_| "http://localhost:3000".to_string());
-
     let allowed_origins: Vec<HeaderValue> = vec![
         "http://localhost:3000".parse().unwrap(),
         frontend_url
@@ -249,6 +559,53 @@ async fn main() {
         ])
         .allow_headers(Any);
 
+    // Background jobs
+    let expire_stale_orders_use_case = ExpireStaleOrdersUseCase::new(
+        service_order_repository.clone(),
+        user_line_account_repository.clone(),
+        notification_gateway.clone(),
+        audit_event_repository.clone(),
+        loyalty_use_case.clone(),
+        quotation_repository,
+        expiry_windows,
+    );
+    JobScheduler::new(scheduled_job_repository)
+        .add(
+            &maintenance_reminder_schedule,
+            Arc::new(MaintenanceRemindersJob::new(
+                send_maintenance_reminders_use_case,
+            )),
+        )
+        .expect("Invalid MAINTENANCE_REMINDER_SCHEDULE")
+        .add(
+            &expire_orders_schedule,
+            Arc::new(ExpireStaleOrdersJob::new(expire_stale_orders_use_case)),
+        )
+        .expect("Invalid EXPIRE_ORDERS_SCHEDULE")
+        .add(
+            &token_cleanup_schedule,
+            Arc::new(RefreshTokenCleanupJob::new(
+                refresh_token_repository.clone(),
+            )),
+        )
+        .expect("Invalid TOKEN_CLEANUP_SCHEDULE")
+        .add(
+            &loyalty_expiry_schedule,
+            Arc::new(LoyaltyExpiryJob::new(loyalty_use_case)),
+        )
+        .expect("Invalid LOYALTY_EXPIRY_SCHEDULE")
+        .add(
+            &stock_check_schedule,
+            Arc::new(StockConsistencyJob::new(check_stock_consistency_use_case)),
+        )
+        .expect("Invalid STOCK_CHECK_SCHEDULE")
+        .add(
+            &low_stock_digest_schedule,
+            Arc::new(LowStockDigestJob::new(low_stock_use_case)),
+        )
+        .expect("Invalid LOW_STOCK_DIGEST_SCHEDULE")
+        .start();
+
     let app = backend::infrastructure::http::routes::create_router()
         .layer(cors)
         .layer(Extension(jwt_service)) // Inject JwtService for middleware
@@ -263,5 +620,10 @@ async fn main() {
     tracing::info!("Server running on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }